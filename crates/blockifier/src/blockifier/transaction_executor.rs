@@ -19,7 +19,7 @@ use crate::state::state_api::{StateReader, StateResult};
 use crate::transaction::errors::TransactionExecutionError;
 use crate::transaction::objects::{TransactionExecutionInfo, TransactionInfoCreator};
 use crate::transaction::transaction_execution::Transaction;
-use crate::transaction::transactions::{ExecutableTransaction, ExecutionFlags};
+use crate::transaction::transactions::ExecutionFlags;
 
 #[cfg(test)]
 #[path = "transaction_executor_test.rs"]
@@ -104,16 +104,17 @@ impl<S: StateReader> TransactionExecutor<S> {
         // Executing a single transaction cannot be done in a concurrent mode.
         let execution_flags =
             ExecutionFlags { charge_fee: tx_charge_fee, validate: true, concurrency_mode: false };
-        let tx_execution_result =
-            tx.execute_raw(&mut transactional_state, &self.block_context, execution_flags);
+        let tx_execution_result = tx.execute_raw_and_summarize(
+            &mut transactional_state,
+            &self.block_context,
+            execution_flags,
+        );
         match tx_execution_result {
-            Ok(tx_execution_info) => {
-                let tx_state_changes_keys =
-                    transactional_state.get_actual_state_changes()?.into_keys();
+            Ok((tx_execution_info, tx_summary)) => {
                 self.bouncer.try_update(
                     &transactional_state,
-                    &tx_state_changes_keys,
-                    &tx_execution_info.summarize(),
+                    &tx_summary.state_changes_keys,
+                    &tx_summary.execution_summary,
                     &tx_execution_info.receipt.resources,
                 )?;
                 transactional_state.commit();
@@ -15,7 +15,7 @@ use crate::blockifier::transaction_executor::{
 };
 use crate::bouncer::{Bouncer, BouncerWeights};
 use crate::context::BlockContext;
-use crate::state::cached_state::CachedState;
+use crate::state::cached_state::{CachedState, TransactionalState};
 use crate::state::state_api::StateReader;
 use crate::test_utils::contracts::FeatureContract;
 use crate::test_utils::declare::declare_tx;
@@ -30,6 +30,7 @@ use crate::test_utils::{
     DEFAULT_STRK_L1_GAS_PRICE,
 };
 use crate::transaction::errors::TransactionExecutionError;
+use crate::transaction::objects::TransactionInfoCreator;
 use crate::transaction::test_utils::{
     account_invoke_tx,
     block_context,
@@ -40,6 +41,7 @@ use crate::transaction::test_utils::{
     TestInitData,
 };
 use crate::transaction::transaction_execution::Transaction;
+use crate::transaction::transactions::ExecutionFlags;
 fn tx_executor_test_body<S: StateReader>(
     state: CachedState<S>,
     block_context: BlockContext,
@@ -244,6 +246,51 @@ fn test_l1_handler(block_context: BlockContext) {
     tx_executor_test_body(state, block_context, tx, expected_bouncer_weights);
 }
 
+#[rstest]
+fn test_execution_summary_reused_by_bouncer(block_context: BlockContext) {
+    let test_contract = FeatureContract::TestContract(CairoVersion::Cairo1);
+    let tx = Transaction::L1Handler(l1handler_tx(
+        Fee(1908000000000000),
+        test_contract.get_instance_address(0),
+    ));
+
+    // Execute the transaction on its own, and update a bouncer with the returned summary.
+    let mut state = test_state(&block_context.chain_info, BALANCE, &[(test_contract, 1)]);
+    let mut transactional_state = TransactionalState::create_transactional(&mut state);
+    let execution_flags = ExecutionFlags {
+        charge_fee: tx.create_tx_info().enforce_fee(),
+        validate: true,
+        concurrency_mode: false,
+    };
+    let (tx_execution_info, tx_summary) = tx
+        .execute_raw_and_summarize(&mut transactional_state, &block_context, execution_flags)
+        .unwrap();
+    assert_eq!(tx_summary.execution_summary, tx_execution_info.summarize());
+    assert_eq!(
+        tx_summary.state_changes_keys,
+        transactional_state.get_actual_state_changes().unwrap().into_keys()
+    );
+    let mut expected_bouncer = Bouncer::new(block_context.bouncer_config.clone());
+    expected_bouncer
+        .try_update(
+            &transactional_state,
+            &tx_summary.state_changes_keys,
+            &tx_summary.execution_summary,
+            &tx_execution_info.receipt.resources,
+        )
+        .unwrap();
+
+    // The executor updates its bouncer with the same summary.
+    let state = test_state(&block_context.chain_info, BALANCE, &[(test_contract, 1)]);
+    let mut tx_executor =
+        TransactionExecutor::new(state, block_context, TransactionExecutorConfig::default());
+    tx_executor.execute(&tx).unwrap();
+    assert_eq!(
+        tx_executor.bouncer.get_accumulated_weights(),
+        expected_bouncer.get_accumulated_weights()
+    );
+}
+
 #[rstest]
 #[case::happy_flow(BouncerWeights::empty(), 10)]
 #[should_panic(expected = "BlockFull: Transaction cannot be added to the current block, block \
@@ -6,6 +6,7 @@ use papyrus_config::dumping::{append_sub_config_name, ser_param, SerializeConfig
 use papyrus_config::{ParamPath, ParamPrivacyInput, SerializedParam};
 use serde::{Deserialize, Serialize};
 use starknet_api::core::ClassHash;
+use thiserror::Error;
 
 use crate::blockifier::transaction_executor::{
     TransactionExecutorError,
@@ -16,7 +17,6 @@ use crate::fee::gas_usage::get_onchain_data_segment_length;
 use crate::fee::resources::TransactionResources;
 use crate::state::cached_state::{StateChangesKeys, StorageEntry};
 use crate::state::state_api::StateReader;
-use crate::transaction::errors::TransactionExecutionError;
 use crate::transaction::objects::{ExecutionResourcesTraits, TransactionExecutionResult};
 use crate::utils::usize_from_u64;
 
@@ -58,19 +58,35 @@ impl BouncerConfig {
         self.block_max_capacity.has_room(weights)
     }
 
-    pub fn within_max_capacity_or_err(
-        &self,
-        weights: BouncerWeights,
-    ) -> TransactionExecutionResult<()> {
-        if self.block_max_capacity.has_room(weights) {
+    pub fn within_max_capacity(&self, weights: BouncerWeights) -> Result<(), TransactionTooLarge> {
+        if self.has_room(weights) {
             Ok(())
         } else {
-            Err(TransactionExecutionError::TransactionTooLarge {
+            Err(TransactionTooLarge {
                 max_capacity: Box::new(self.block_max_capacity),
                 tx_size: Box::new(weights),
             })
         }
     }
+
+    pub fn within_max_capacity_or_err(
+        &self,
+        weights: BouncerWeights,
+    ) -> TransactionExecutionResult<()> {
+        Ok(self.within_max_capacity(weights)?)
+    }
+}
+
+/// The weights of a transaction exceed the capacity of an empty block, hence it can not be
+/// included in any block.
+#[derive(Clone, Debug, PartialEq, Error)]
+#[error(
+    "Transaction size exceeds the maximum block capacity. Max block capacity: {max_capacity}, \
+     transaction size: {tx_size}."
+)]
+pub struct TransactionTooLarge {
+    pub max_capacity: Box<BouncerWeights>,
+    pub tx_size: Box<BouncerWeights>,
 }
 
 impl SerializeConfig for BouncerConfig {
@@ -576,6 +592,10 @@ pub fn get_particia_update_resources(n_visited_storage_entries: usize) -> Execut
     }
 }
 
+/// Checks whether a transaction with the given execution summary, resources and state changes fits
+/// an empty block. This is the check `Transaction::execute_raw` runs after execution; it has no
+/// side effects, so it can also be used to decide on a transaction's inclusion before committing
+/// it.
 pub fn verify_tx_weights_within_max_capacity<S: StateReader>(
     state_reader: &S,
     tx_execution_summary: &ExecutionSummary,
@@ -591,5 +611,5 @@ pub fn verify_tx_weights_within_max_capacity<S: StateReader>(
         tx_state_changes_keys,
     )?;
 
-    bouncer_config.within_max_capacity_or_err(tx_weights)
+    Ok(bouncer_config.within_max_capacity(tx_weights)?)
 }
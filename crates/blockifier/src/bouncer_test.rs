@@ -5,22 +5,29 @@ use cairo_vm::types::builtin_name::BuiltinName;
 use cairo_vm::vm::runners::cairo_runner::ExecutionResources;
 use rstest::rstest;
 use starknet_api::transaction::fields::Fee;
-use starknet_api::{class_hash, contract_address, storage_key};
+use starknet_api::{class_hash, contract_address, invoke_tx_args, storage_key};
 
 use super::BouncerConfig;
 use crate::blockifier::transaction_executor::TransactionExecutorError;
 use crate::bouncer::{
+    get_tx_weights,
     verify_tx_weights_within_max_capacity,
     Bouncer,
     BouncerWeights,
     BuiltinCount,
+    TransactionTooLarge,
 };
 use crate::context::BlockContext;
 use crate::execution::call_info::ExecutionSummary;
 use crate::fee::resources::{ComputationResources, TransactionResources};
 use crate::state::cached_state::{StateChangesKeys, TransactionalState};
+use crate::test_utils::contracts::FeatureContract;
 use crate::test_utils::initial_test_state::test_state;
+use crate::test_utils::{create_trivial_calldata, CairoVersion, BALANCE};
 use crate::transaction::errors::TransactionExecutionError;
+use crate::transaction::test_utils::account_invoke_tx;
+use crate::transaction::transaction_execution::Transaction;
+use crate::transaction::transactions::ExecutionFlags;
 
 #[test]
 fn test_block_weights_has_room() {
@@ -285,3 +292,120 @@ fn test_bouncer_try_update(#[case] added_ecdsa: usize, #[case] scenario: &'stati
         _ => panic!("Unexpected scenario: {}", scenario),
     }
 }
+
+#[test]
+fn test_within_max_capacity_oversized_summary() {
+    let block_context = BlockContext::create_for_account_testing();
+    let state = test_state(block_context.chain_info(), Fee(0), &[]);
+    let execution_summary = ExecutionSummary {
+        visited_storage_entries: (0..100_u32)
+            .map(|i| (contract_address!(i), storage_key!(i)))
+            .collect(),
+        ..Default::default()
+    };
+    let tx_resources = TransactionResources::default();
+    let tx_state_changes_keys = StateChangesKeys::default();
+    let tx_weights = get_tx_weights(
+        &state,
+        &execution_summary.executed_class_hashes,
+        execution_summary.visited_storage_entries.len(),
+        &tx_resources,
+        &tx_state_changes_keys,
+    )
+    .unwrap();
+
+    let fitting_config = BouncerConfig {
+        block_max_capacity: BouncerWeights { n_steps: tx_weights.n_steps, ..BouncerWeights::max() },
+    };
+    let exceeded_config = BouncerConfig {
+        block_max_capacity: BouncerWeights {
+            n_steps: tx_weights.n_steps - 1,
+            ..BouncerWeights::max()
+        },
+    };
+
+    for (bouncer_config, expected_result) in [
+        (fitting_config, Ok(())),
+        (
+            exceeded_config.clone(),
+            Err(TransactionTooLarge {
+                max_capacity: Box::new(exceeded_config.block_max_capacity),
+                tx_size: Box::new(tx_weights),
+            }),
+        ),
+    ] {
+        assert_eq!(bouncer_config.within_max_capacity(tx_weights), expected_result);
+        let preflight_result = verify_tx_weights_within_max_capacity(
+            &state,
+            &execution_summary,
+            &tx_resources,
+            &tx_state_changes_keys,
+            &bouncer_config,
+        );
+        assert_eq!(preflight_result.is_ok(), expected_result.is_ok());
+    }
+}
+
+/// The pre-flight check on the summary of an execution agrees with the check done during the
+/// execution itself.
+#[test]
+fn test_preflight_capacity_check_agrees_with_execution() {
+    let account = FeatureContract::AccountWithoutValidations(CairoVersion::Cairo1);
+    let test_contract = FeatureContract::TestContract(CairoVersion::Cairo0);
+    let block_context = BlockContext::create_for_account_testing();
+    let mut state =
+        test_state(block_context.chain_info(), BALANCE, &[(account, 1), (test_contract, 1)]);
+    let tx = Transaction::Account(account_invoke_tx(invoke_tx_args! {
+        sender_address: account.get_instance_address(0),
+        calldata: create_trivial_calldata(test_contract.get_instance_address(0)),
+    }));
+    let execution_flags =
+        ExecutionFlags { charge_fee: false, validate: true, concurrency_mode: false };
+
+    // Execute with no capacity limit, and compute the weights of the transaction.
+    let mut transactional_state = TransactionalState::create_transactional(&mut state);
+    let (tx_execution_info, tx_summary) = tx
+        .execute_raw_and_summarize(&mut transactional_state, &block_context, execution_flags)
+        .unwrap();
+    let tx_weights = get_tx_weights(
+        &transactional_state,
+        &tx_summary.execution_summary.executed_class_hashes,
+        tx_summary.execution_summary.visited_storage_entries.len(),
+        &tx_execution_info.receipt.resources,
+        &tx_summary.state_changes_keys,
+    )
+    .unwrap();
+    let bouncer_config = BouncerConfig {
+        block_max_capacity: BouncerWeights {
+            n_steps: tx_weights.n_steps - 1,
+            ..BouncerWeights::max()
+        },
+    };
+    let preflight_result = verify_tx_weights_within_max_capacity(
+        &transactional_state,
+        &tx_summary.execution_summary,
+        &tx_execution_info.receipt.resources,
+        &tx_summary.state_changes_keys,
+        &bouncer_config,
+    );
+    transactional_state.abort();
+
+    // Execute again, with a capacity the transaction exceeds.
+    let block_context = BlockContext { bouncer_config: bouncer_config.clone(), ..block_context };
+    let mut transactional_state = TransactionalState::create_transactional(&mut state);
+    let execution_result =
+        tx.execute_raw_and_summarize(&mut transactional_state, &block_context, execution_flags);
+    transactional_state.abort();
+
+    let expected_max_capacity = bouncer_config.block_max_capacity;
+    assert_matches!(
+        preflight_result,
+        Err(TransactionExecutionError::TransactionTooLarge { max_capacity, tx_size })
+            if *max_capacity == expected_max_capacity && *tx_size == tx_weights
+    );
+    assert_matches!(
+        execution_result,
+        Err(TransactionExecutionError::TransactionTooLarge { max_capacity, tx_size })
+            if *max_capacity == expected_max_capacity && *tx_size == tx_weights
+    );
+}
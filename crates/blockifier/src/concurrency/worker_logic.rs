@@ -15,20 +15,15 @@ use crate::concurrency::utils::lock_mutex_in_array;
 use crate::concurrency::versioned_state::ThreadSafeVersionedState;
 use crate::concurrency::TxIndex;
 use crate::context::BlockContext;
-use crate::state::cached_state::{
-    ContractClassMapping,
-    StateChanges,
-    StateMaps,
-    TransactionalState,
-};
+use crate::state::cached_state::{ContractClassMapping, StateMaps, TransactionalState};
 use crate::state::state_api::{StateReader, UpdatableState};
 use crate::transaction::objects::{
     TransactionExecutionInfo,
     TransactionExecutionResult,
     TransactionInfoCreator,
 };
-use crate::transaction::transaction_execution::Transaction;
-use crate::transaction::transactions::{ExecutableTransaction, ExecutionFlags};
+use crate::transaction::transaction_execution::{Transaction, TransactionSummary};
+use crate::transaction::transactions::ExecutionFlags;
 
 #[cfg(test)]
 #[path = "worker_logic_test.rs"]
@@ -44,6 +39,8 @@ pub struct ExecutionTaskOutput {
     pub contract_classes: ContractClassMapping,
     pub visited_pcs: HashMap<ClassHash, HashSet<usize>>,
     pub result: TransactionExecutionResult<TransactionExecutionInfo>,
+    // The summary of a successful execution, reused when committing the transaction.
+    pub summary: Option<TransactionSummary>,
 }
 
 pub struct WorkerExecutor<'a, S: StateReader> {
@@ -137,12 +134,15 @@ impl<'a, S: StateReader> WorkerExecutor<'a, S> {
             TransactionalState::create_transactional(&mut tx_versioned_state);
         let execution_flags =
             ExecutionFlags { charge_fee: tx_charge_fee, validate: true, concurrency_mode: true };
-        let execution_result =
-            tx.execute_raw(&mut transactional_state, self.block_context, execution_flags);
+        let execution_result = tx.execute_raw_and_summarize(
+            &mut transactional_state,
+            self.block_context,
+            execution_flags,
+        );
 
         // Update the versioned state and store the transaction execution output.
         let execution_output_inner = match execution_result {
-            Ok(_) => {
+            Ok((tx_execution_info, summary)) => {
                 let tx_reads_writes = transactional_state.cache.take();
                 let writes = tx_reads_writes.to_state_diff();
                 let contract_classes = transactional_state.class_hash_to_class.take();
@@ -154,16 +154,18 @@ impl<'a, S: StateReader> WorkerExecutor<'a, S> {
                     writes,
                     contract_classes,
                     visited_pcs,
-                    result: execution_result,
+                    result: Ok(tx_execution_info),
+                    summary: Some(summary),
                 }
             }
-            Err(_) => ExecutionTaskOutput {
+            Err(error) => ExecutionTaskOutput {
                 reads: transactional_state.cache.take().initial_reads,
                 // Failed transaction - ignore the writes and visited PCs.
                 writes: StateMaps::default(),
                 contract_classes: HashMap::default(),
                 visited_pcs: HashMap::default(),
-                result: execution_result,
+                result: Err(error),
+                summary: None,
             },
         };
         let mut execution_output = lock_mutex_in_array(&self.execution_outputs, tx_index);
@@ -231,26 +233,20 @@ impl<'a, S: StateReader> WorkerExecutor<'a, S> {
 
         // Execution is final.
         let mut execution_output = lock_mutex_in_array(&self.execution_outputs, tx_index);
-        let writes = &execution_output.as_ref().expect(EXECUTION_OUTPUTS_UNWRAP_ERROR).writes;
-        // TODO(Yoni): get rid of this clone.
-        let mut tx_state_changes_keys = StateChanges::from(writes.clone()).into_keys();
-        let tx_result =
-            &mut execution_output.as_mut().expect(EXECUTION_OUTPUTS_UNWRAP_ERROR).result;
+        let ExecutionTaskOutput { result: tx_result, summary: tx_summary, .. } =
+            execution_output.as_mut().expect(EXECUTION_OUTPUTS_UNWRAP_ERROR);
 
         if let Ok(tx_execution_info) = tx_result.as_mut() {
             let tx_context = self.block_context.to_tx_context(&self.chunk[tx_index]);
-            // Add the deleted sequencer balance key to the storage keys.
-            let concurrency_mode = true;
-            tx_state_changes_keys.update_sequencer_key_in_storage(
-                &tx_context,
-                tx_execution_info,
-                concurrency_mode,
-            );
+            // The summary was computed by the final execution, and its state changes keys include
+            // the deleted sequencer balance key.
+            let tx_summary =
+                tx_summary.as_ref().expect("A successful execution should have a summary.");
             // Ask the bouncer if there is room for the transaction in the block.
             let bouncer_result = self.bouncer.lock().expect("Bouncer lock failed.").try_update(
                 &tx_versioned_state,
-                &tx_state_changes_keys,
-                &tx_execution_info.summarize(),
+                &tx_summary.state_changes_keys,
+                &tx_summary.execution_summary,
                 &tx_execution_info.receipt.resources,
             );
             if let Err(error) = bouncer_result {
@@ -5,6 +5,7 @@ use papyrus_config::{ParamPath, ParamPrivacyInput, SerializedParam};
 use serde::{Deserialize, Serialize};
 use starknet_api::block::GasPriceVector;
 use starknet_api::core::{ChainId, ContractAddress};
+use starknet_api::execution_resources::GasAmount;
 use starknet_api::transaction::fields::{
     AllResourceBounds,
     GasVectorComputationMode,
@@ -61,6 +62,13 @@ impl TransactionContext {
             }) => l2_gas.max_amount.0,
         }
     }
+
+    /// Returns the minimal initial Sierra gas needed to start running the transaction: the OS
+    /// cost of a transaction and the initial budget of an entry point.
+    pub fn minimal_initial_sierra_gas(&self) -> GasAmount {
+        let gas_costs = &self.block_context.versioned_constants.os_constants.gas_costs;
+        GasAmount(gas_costs.transaction_gas_cost + gas_costs.entry_point_initial_budget)
+    }
 }
 
 #[derive(Clone, Debug)]
@@ -90,6 +90,16 @@ impl RunnableContractClass {
         }
     }
 
+    /// Returns the major version of the class: 0 for Cairo 0 classes, and 1 for Cairo 1 classes.
+    pub fn class_version(&self) -> u8 {
+        match self {
+            Self::V0(_) => 0,
+            Self::V1(_) => 1,
+            #[cfg(feature = "cairo_native")]
+            Self::V1Native(_) => 1,
+        }
+    }
+
     pub fn estimate_casm_hash_computation_resources(&self) -> ExecutionResources {
         match self {
             Self::V0(class) => class.estimate_casm_hash_computation_resources(),
@@ -26,7 +26,6 @@ use starknet_types_core::felt::{Felt, FromStrError};
 use thiserror::Error;
 
 use crate::blockifier::block::BlockInfo;
-use crate::context::TransactionContext;
 use crate::execution::call_info::{CallInfo, OrderedEvent, OrderedL2ToL1Message};
 use crate::execution::common_hints::{
     extended_builtin_hint_processor,
@@ -383,7 +382,7 @@ impl<'a> DeprecatedSyscallHintProcessor<'a> {
         vm: &mut VirtualMachine,
     ) -> DeprecatedSyscallResult<Relocatable> {
         let tx_signature_start_ptr = self.get_or_allocate_tx_signature_segment(vm)?;
-        let TransactionContext { block_context, tx_info } = self.context.tx_context.as_ref();
+        let tx_info = &self.context.tx_context.tx_info;
         let tx_signature_length = tx_info.signature().0.len();
         let tx_info: Vec<MaybeRelocatable> = vec![
             tx_info.signed_version().0.into(),
@@ -392,7 +391,7 @@ impl<'a> DeprecatedSyscallHintProcessor<'a> {
             tx_signature_length.into(),
             tx_signature_start_ptr.into(),
             tx_info.transaction_hash().0.into(),
-            Felt::from_hex(block_context.chain_info.chain_id.as_hex().as_str())?.into(),
+            self.context.chain_id_felt.into(),
             tx_info.nonce().0.into(),
         ];
 
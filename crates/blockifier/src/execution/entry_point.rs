@@ -1,6 +1,6 @@
 use std::cell::RefCell;
 use std::cmp::min;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::sync::Arc;
 
 use cairo_vm::vm::runners::cairo_runner::{ExecutionResources, ResourceTracker, RunResources};
@@ -16,7 +16,7 @@ use starknet_api::transaction::fields::{
     ValidResourceBounds,
 };
 use starknet_api::transaction::TransactionVersion;
-use starknet_types_core::felt::Felt;
+use starknet_types_core::felt::{Felt, FromStrError};
 
 use crate::abi::abi_utils::selector_from_name;
 use crate::abi::constants;
@@ -36,7 +36,12 @@ use crate::state::state_api::{State, StateResult};
 use crate::transaction::objects::{HasRelatedFeeType, TransactionInfo};
 use crate::transaction::transaction_types::TransactionType;
 use crate::utils::usize_from_u64;
-use crate::versioned_constants::{GasCosts, VersionedConstants};
+use crate::versioned_constants::{
+    GasCosts,
+    OsConstants,
+    VersionedConstants,
+    OS_RESOURCES_OF_ALL_TX_TYPES,
+};
 
 #[cfg(test)]
 #[path = "entry_point_test.rs"]
@@ -121,12 +126,12 @@ impl CallEntryPoint {
         context: &mut EntryPointExecutionContext,
         remaining_gas: &mut u64,
     ) -> EntryPointExecutionResult<CallInfo> {
-        let tx_context = &context.tx_context;
         let mut decrement_when_dropped = RecursionDepthGuard::new(
             context.current_recursion_depth.clone(),
             context.versioned_constants().max_recursion_depth,
         );
         decrement_when_dropped.try_increment_and_check_depth()?;
+        context.touch_contract(self.storage_address)?;
 
         // Validate contract is deployed.
         let storage_class_hash = state.get_class_hash_at(self.storage_address)?;
@@ -139,7 +144,7 @@ impl CallEntryPoint {
             None => storage_class_hash, // If not given, take the storage contract class hash.
         };
         // Hack to prevent version 0 attack on argent accounts.
-        if tx_context.tx_info.version() == TransactionVersion::ZERO
+        if context.tx_context.tx_info.version() == TransactionVersion::ZERO
             && class_hash
                 == ClassHash(
                     Felt::from_hex(FAULTY_CLASS_HASH).expect("A class hash must be a felt."),
@@ -216,12 +221,18 @@ pub struct EntryPointExecutionContext {
     // We use `Arc` to avoid the clone of this potentially large object, as inner calls
     // are created during execution.
     pub tx_context: Arc<TransactionContext>,
+    /// The chain id as a felt, as exposed to contracts by `get_execution_info`.
+    pub chain_id_felt: Felt,
     // VM execution limits.
     pub vm_run_resources: RunResources,
     /// Used for tracking events order during the current execution.
     pub n_emitted_events: usize,
     /// Used for tracking L2-to-L1 messages order during the current execution.
     pub n_sent_messages_to_l1: usize,
+    /// Used for bounding the number of inner calls performed during the current execution.
+    pub n_inner_calls: usize,
+    /// Used for bounding the number of distinct contracts touched during the current execution.
+    pub touched_contracts: HashSet<ContractAddress>,
     // Managed by dedicated guard object.
     current_recursion_depth: Arc<RefCell<usize>>,
 
@@ -239,28 +250,56 @@ impl EntryPointExecutionContext {
         tx_context: Arc<TransactionContext>,
         mode: ExecutionMode,
         limit_steps_by_resources: bool,
-    ) -> Self {
+    ) -> Result<Self, FromStrError> {
+        let chain_id_felt =
+            Felt::from_hex(tx_context.block_context.chain_info.chain_id.as_hex().as_str())?;
         let max_steps = Self::max_steps(&tx_context, &mode, limit_steps_by_resources);
-        Self {
+        Ok(Self {
+            chain_id_felt,
             vm_run_resources: RunResources::new(max_steps),
             n_emitted_events: 0,
             n_sent_messages_to_l1: 0,
+            n_inner_calls: 0,
+            touched_contracts: HashSet::new(),
             tx_context: tx_context.clone(),
             current_recursion_depth: Default::default(),
             execution_mode: mode,
             tracked_resource_stack: vec![],
             revert_infos: ExecutionRevertInfo(vec![]),
+        })
+    }
+
+    /// Records that the given contract was touched during the current execution. Fails if this
+    /// exceeds the number of distinct contracts a single execution may touch.
+    pub fn touch_contract(
+        &mut self,
+        contract_address: ContractAddress,
+    ) -> EntryPointExecutionResult<()> {
+        if self.touched_contracts.contains(&contract_address) {
+            return Ok(());
+        }
+        if let Some(max_n_touched_contracts) = self.versioned_constants().max_n_touched_contracts {
+            if self.touched_contracts.len() >= max_n_touched_contracts {
+                return Err(EntryPointExecutionError::TouchedContractsLimitExceeded {
+                    max_n_touched_contracts,
+                });
+            }
         }
+        self.touched_contracts.insert(contract_address);
+        Ok(())
     }
 
     pub fn new_validate(
         tx_context: Arc<TransactionContext>,
         limit_steps_by_resources: bool,
-    ) -> Self {
+    ) -> Result<Self, FromStrError> {
         Self::new(tx_context, ExecutionMode::Validate, limit_steps_by_resources)
     }
 
-    pub fn new_invoke(tx_context: Arc<TransactionContext>, limit_steps_by_resources: bool) -> Self {
+    pub fn new_invoke(
+        tx_context: Arc<TransactionContext>,
+        limit_steps_by_resources: bool,
+    ) -> Result<Self, FromStrError> {
         Self::new(tx_context, ExecutionMode::Execute, limit_steps_by_resources)
     }
 
@@ -376,8 +415,11 @@ impl EntryPointExecutionContext {
             .map(|call_info| call_info.charged_resources.vm_resources.n_steps)
             .unwrap_or_default();
 
-        let overhead_steps =
-            self.versioned_constants().os_resources_for_tx_type(tx_type, calldata_length).n_steps;
+        let overhead_steps = self
+            .versioned_constants()
+            .os_resources_for_tx_type(tx_type, calldata_length)
+            .expect(OS_RESOURCES_OF_ALL_TX_TYPES)
+            .n_steps;
         self.subtract_steps(validate_steps + overhead_steps)
     }
 
@@ -406,6 +448,94 @@ impl EntryPointExecutionContext {
 
         Ok(())
     }
+
+    /// Reverts a failed inner call, given the number of revert infos before it was executed: undoes
+    /// its state changes, rolls back the event and L2-to-L1 message ordering counters it advanced,
+    /// and deletes its events and messages, so that the orders in the surviving calls remain
+    /// contiguous.
+    pub fn revert_failed_call(
+        &mut self,
+        revert_idx: usize,
+        state: &mut dyn State,
+        call_info: &mut CallInfo,
+    ) -> StateResult<()> {
+        self.revert(revert_idx, state)?;
+
+        let mut stack: Vec<&mut CallInfo> = vec![call_info];
+        while let Some(call_info) = stack.pop() {
+            call_info.execution.events.clear();
+            call_info.execution.l2_to_l1_messages.clear();
+            // Add inner calls that did not fail to the stack.
+            // The events and l2_to_l1_messages of the failed calls were already cleared.
+            stack.extend(
+                call_info.inner_calls.iter_mut().filter(|call_info| !call_info.execution.failed),
+            );
+        }
+
+        Ok(())
+    }
+}
+
+/// Builds an [`EntryPointExecutionContext`]. By default, the context is in execute mode, the
+/// number of steps is limited by the resource bounds of the transaction, and the gas costs are
+/// those of the versioned constants of the block.
+pub struct EntryPointExecutionContextBuilder {
+    tx_context: Arc<TransactionContext>,
+    execution_mode: ExecutionMode,
+    limit_steps_by_resources: bool,
+    gas_costs: Option<GasCosts>,
+}
+
+impl EntryPointExecutionContextBuilder {
+    pub fn new(tx_context: Arc<TransactionContext>) -> Self {
+        Self {
+            tx_context,
+            execution_mode: ExecutionMode::Execute,
+            limit_steps_by_resources: true,
+            gas_costs: None,
+        }
+    }
+
+    /// The execution mode affects the syscalls available to the executed code and the block info
+    /// it observes.
+    pub fn execution_mode(mut self, execution_mode: ExecutionMode) -> Self {
+        self.execution_mode = execution_mode;
+        self
+    }
+
+    pub fn limit_steps_by_resources(mut self, limit_steps_by_resources: bool) -> Self {
+        self.limit_steps_by_resources = limit_steps_by_resources;
+        self
+    }
+
+    /// Overrides the gas costs of the versioned constants, for this context only.
+    pub fn gas_costs(mut self, gas_costs: GasCosts) -> Self {
+        self.gas_costs = Some(gas_costs);
+        self
+    }
+
+    pub fn build(self) -> Result<EntryPointExecutionContext, FromStrError> {
+        let tx_context = match self.gas_costs {
+            None => self.tx_context,
+            Some(gas_costs) => {
+                let mut tx_context = TransactionContext::clone(&self.tx_context);
+                let versioned_constants = &mut tx_context.block_context.versioned_constants;
+                versioned_constants.os_constants = Arc::new(OsConstants {
+                    gas_costs,
+                    validate_rounding_consts: versioned_constants
+                        .os_constants
+                        .validate_rounding_consts
+                        .clone(),
+                });
+                Arc::new(tx_context)
+            }
+        };
+        EntryPointExecutionContext::new(
+            tx_context,
+            self.execution_mode,
+            self.limit_steps_by_resources,
+        )
+    }
 }
 
 pub fn execute_constructor_entry_point(
@@ -416,6 +546,10 @@ pub fn execute_constructor_entry_point(
     calldata: Calldata,
     remaining_gas: &mut u64,
 ) -> ConstructorEntryPointExecutionResult<CallInfo> {
+    // Deploying a contract touches it, even if it has no constructor.
+    context
+        .touch_contract(ctor_context.storage_address)
+        .map_err(|error| ConstructorEntryPointExecutionError::new(error, &ctor_context, None))?;
     // Ensure the class is declared (by reading it).
     let contract_class =
         state.get_compiled_contract_class(ctor_context.class_hash).map_err(|error| {
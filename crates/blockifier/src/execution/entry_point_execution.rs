@@ -368,31 +368,82 @@ fn maybe_fill_holes(
 }
 
 /// Calculates the total gas for fee in the current call + subtree.
-#[allow(dead_code)]
-fn to_gas_for_fee(
+#[cfg_attr(not(feature = "cairo_native"), allow(dead_code))]
+pub(crate) fn to_gas_for_fee(
     tracked_resource: &TrackedResource,
     gas_consumed: u64,
     inner_calls: &[CallInfo],
-) -> GasAmount {
+) -> Result<GasAmount, PostExecutionError> {
     // The Sierra gas consumed in this specific call is `gas_consumed`
     // (= total gas of self + subtree), minus the sum of all inner calls Sierra gas consumed.
     // To compute the total Sierra gas to charge (of self + subtree), if the tracked resource is
     // Sierra gas, we add this amount to the total gas to charge for in the subtree:
     // gas_for_fee = gas_consumed - subtree_gas_consumed + subtree_gas_to_fee.
-    GasAmount(match tracked_resource {
+    let underflow_error = || PostExecutionError::MalformedReturnData {
+        error_message: format!(
+            "Gas for fee underflowed: inner calls consumed more gas than the call ({gas_consumed})."
+        ),
+    };
+    Ok(GasAmount(match tracked_resource {
         // If the tracked resource is CairoSteps, then all tracked resources of all calls in
         // the subtree are also CairoSteps. Thus, the total gas to charge in this subtree is zero.
         TrackedResource::CairoSteps => 0,
-        TrackedResource::SierraGas => gas_consumed
-            .checked_sub(
-                inner_calls
-                    .iter()
-                    .map(|call| call.execution.gas_consumed - call.charged_resources.gas_for_fee.0)
-                    .sum::<u64>(),
-            )
-            .expect("gas_for_fee unexpectedly underflowed."),
+        TrackedResource::SierraGas => {
+            let mut subtree_gas_not_for_fee: u64 = 0;
+            for call in inner_calls {
+                let call_gas_not_for_fee = call
+                    .execution
+                    .gas_consumed
+                    .checked_sub(call.charged_resources.gas_for_fee.0)
+                    .ok_or_else(underflow_error)?;
+                subtree_gas_not_for_fee = subtree_gas_not_for_fee
+                    .checked_add(call_gas_not_for_fee)
+                    .ok_or_else(underflow_error)?;
+            }
+            gas_consumed.checked_sub(subtree_gas_not_for_fee).ok_or_else(underflow_error)?
+        }
+    }))
+}
+
+/// Verifies the gas charged for fee by a Sierra gas call against `inner_calls_gas_charged`, the
+/// gas the syscall handler actually deducted from the call's budget for its inner calls, rather
+/// than against the gas the inner calls report to have consumed.
+#[cfg_attr(not(feature = "cairo_native"), allow(dead_code))]
+pub(crate) fn reconcile_gas_for_fee(
+    call_info: &CallInfo,
+    inner_calls_gas_charged: u64,
+) -> Result<(), PostExecutionError> {
+    let gas_for_fee = call_info.charged_resources.gas_for_fee;
+    let expected_gas_for_fee = match call_info.tracked_resource {
+        TrackedResource::CairoSteps => Some(0),
+        TrackedResource::SierraGas => {
+            let inner_calls_gas_for_fee = call_info
+                .inner_calls
+                .iter()
+                .map(|call| call.charged_resources.gas_for_fee.0)
+                .try_fold(0_u64, u64::checked_add);
+            call_info
+                .execution
+                .gas_consumed
+                .checked_sub(inner_calls_gas_charged)
+                .zip(inner_calls_gas_for_fee)
+                .and_then(|(own_gas, inner_calls_gas_for_fee)| {
+                    own_gas.checked_add(inner_calls_gas_for_fee)
+                })
+        }
+    };
+    if expected_gas_for_fee == Some(gas_for_fee.0) {
+        return Ok(());
+    }
+    Err(PostExecutionError::MalformedReturnData {
+        error_message: format!(
+            "Gas for fee does not match the gas charged by the syscall handler: {gas_for_fee} vs. \
+             gas consumed {}, of which {inner_calls_gas_charged} charged for inner calls.",
+            call_info.execution.gas_consumed
+        ),
     })
 }
+
 pub fn finalize_execution(
     mut runner: CairoRunner,
     mut syscall_handler: SyscallHintProcessor<'_>,
@@ -433,8 +484,8 @@ pub fn finalize_execution(
     }
     *syscall_handler.resources += &vm_resources_without_inner_calls;
     // Take into account the syscall resources of the current call.
-    *syscall_handler.resources +=
-        &versioned_constants.get_additional_os_syscall_resources(&syscall_handler.syscall_counter);
+    *syscall_handler.resources += &versioned_constants
+        .get_additional_os_syscall_resources(&syscall_handler.syscall_counter)?;
 
     syscall_handler.finalize();
 
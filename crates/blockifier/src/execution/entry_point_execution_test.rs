@@ -1,8 +1,10 @@
+use assert_matches::assert_matches;
 use starknet_api::execution_resources::GasAmount;
 
 use crate::execution::call_info::{CallExecution, CallInfo, ChargedResources};
 use crate::execution::contract_class::TrackedResource;
-use crate::execution::entry_point_execution::to_gas_for_fee;
+use crate::execution::entry_point_execution::{reconcile_gas_for_fee, to_gas_for_fee};
+use crate::execution::errors::PostExecutionError;
 
 #[test]
 /// Verifies that every call from the inner most to the outer has the expected gas_for_fee for the
@@ -20,7 +22,7 @@ fn test_gas_for_fee() {
         (TrackedResource::SierraGas, 3, 1),
     ] {
         assert_eq!(
-            to_gas_for_fee(&tracked_resource, gas_consumed, &inner_calls).0,
+            to_gas_for_fee(&tracked_resource, gas_consumed, &inner_calls).unwrap().0,
             expected_gas_for_fee
         );
         inner_calls = vec![CallInfo {
@@ -38,7 +40,10 @@ fn test_gas_for_fee() {
     // Second branch - 1 call.
     let (tracked_resource, gas_consumed, expected_gas_for_fee) =
         (TrackedResource::CairoSteps, 4, 0);
-    assert_eq!(to_gas_for_fee(&tracked_resource, gas_consumed, &[]).0, expected_gas_for_fee);
+    assert_eq!(
+        to_gas_for_fee(&tracked_resource, gas_consumed, &[]).unwrap().0,
+        expected_gas_for_fee
+    );
 
     inner_calls.push(CallInfo {
         execution: CallExecution { gas_consumed, ..Default::default() },
@@ -51,5 +56,59 @@ fn test_gas_for_fee() {
     });
 
     // Outer call.
-    assert_eq!(to_gas_for_fee(&TrackedResource::SierraGas, 8, &inner_calls).0, 2);
+    assert_eq!(to_gas_for_fee(&TrackedResource::SierraGas, 8, &inner_calls).unwrap().0, 2);
+}
+
+/// Verifies that inner calls reporting more gas than the call consumed fail the gas for fee
+/// calculation instead of underflowing.
+#[test]
+fn test_gas_for_fee_underflow() {
+    let inner_calls = [CallInfo {
+        execution: CallExecution { gas_consumed: 9, ..Default::default() },
+        tracked_resource: TrackedResource::CairoSteps,
+        ..Default::default()
+    }];
+    assert_matches!(
+        to_gas_for_fee(&TrackedResource::SierraGas, 8, &inner_calls),
+        Err(PostExecutionError::MalformedReturnData { .. })
+    );
+}
+
+/// Verifies that a gas for fee that disagrees with the gas the syscall handler charged for the
+/// inner calls fails the reconciliation, even when it agrees with the gas the inner calls report.
+#[test]
+fn test_reconcile_gas_for_fee() {
+    // An inner call that is not charged for its gas.
+    let inner_call = CallInfo {
+        execution: CallExecution { gas_consumed: 3, ..Default::default() },
+        tracked_resource: TrackedResource::CairoSteps,
+        ..Default::default()
+    };
+    let call_info = |gas_for_fee| CallInfo {
+        execution: CallExecution { gas_consumed: 8, ..Default::default() },
+        tracked_resource: TrackedResource::SierraGas,
+        inner_calls: vec![inner_call.clone()],
+        charged_resources: ChargedResources {
+            gas_for_fee: GasAmount(gas_for_fee),
+            ..Default::default()
+        },
+        ..Default::default()
+    };
+
+    assert_matches!(reconcile_gas_for_fee(&call_info(5), 3), Ok(()));
+    // Charging for the gas consumed by the inner call.
+    assert_matches!(
+        reconcile_gas_for_fee(&call_info(8), 3),
+        Err(PostExecutionError::MalformedReturnData { .. })
+    );
+    // The inner call reports less gas than the handler charged for it.
+    assert_matches!(
+        reconcile_gas_for_fee(&call_info(5), 4),
+        Err(PostExecutionError::MalformedReturnData { .. })
+    );
+    // The handler charged more gas for the inner calls than the call consumed.
+    assert_matches!(
+        reconcile_gas_for_fee(&call_info(5), 9),
+        Err(PostExecutionError::MalformedReturnData { .. })
+    );
 }
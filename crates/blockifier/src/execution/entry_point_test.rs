@@ -1,24 +1,45 @@
 use std::collections::HashSet;
+use std::sync::Arc;
 
 use cairo_vm::types::builtin_name::BuiltinName;
+use cairo_vm::vm::runners::cairo_runner::ExecutionResources;
 use num_bigint::BigInt;
 use pretty_assertions::assert_eq;
-use starknet_api::core::EntryPointSelector;
+use starknet_api::core::{ChainId, EntryPointSelector};
 use starknet_api::execution_utils::format_panic_data;
 use starknet_api::transaction::fields::{Calldata, Fee};
 use starknet_api::{calldata, felt, storage_key};
+use starknet_types_core::felt::Felt;
 
 use crate::abi::abi_utils::{get_storage_var_address, selector_from_name};
-use crate::context::ChainInfo;
+use crate::abi::constants;
+use crate::context::{BlockContext, ChainInfo, TransactionContext};
 use crate::execution::call_info::{CallExecution, CallInfo};
-use crate::execution::entry_point::CallEntryPoint;
-use crate::retdata;
+use crate::execution::common_hints::ExecutionMode;
+use crate::execution::entry_point::{
+    CallEntryPoint,
+    EntryPointExecutionContext,
+    EntryPointExecutionContextBuilder,
+};
 use crate::state::cached_state::CachedState;
 use crate::test_utils::contracts::FeatureContract;
 use crate::test_utils::dict_state_reader::DictStateReader;
 use crate::test_utils::initial_test_state::test_state;
-use crate::test_utils::{trivial_external_entry_point_new, CairoVersion, BALANCE};
-use crate::versioned_constants::VersionedConstants;
+#[cfg(feature = "cairo_native")]
+use crate::test_utils::create_calldata;
+use crate::test_utils::{
+    trivial_external_entry_point_new,
+    CairoVersion,
+    BALANCE,
+    CURRENT_BLOCK_NUMBER,
+};
+use crate::transaction::objects::{
+    CurrentTransactionInfo,
+    DeprecatedTransactionInfo,
+    TransactionInfo,
+};
+use crate::versioned_constants::{GasCosts, VersionedConstants};
+use crate::{check_entry_point_execution_error_for_custom_hint, retdata};
 
 #[test]
 fn test_call_info_iteration() {
@@ -536,3 +557,183 @@ fn test_cairo1_entry_point_segment_arena() {
         2
     );
 }
+
+fn tx_context_with_chain_id(chain_id: ChainId) -> Arc<TransactionContext> {
+    let mut block_context = BlockContext::create_for_testing();
+    block_context.chain_info.chain_id = chain_id;
+    Arc::new(TransactionContext {
+        block_context,
+        tx_info: TransactionInfo::Current(CurrentTransactionInfo::create_for_testing()),
+    })
+}
+
+#[test]
+fn test_context_caches_chain_id_felt() {
+    let chain_id = ChainId::create_for_testing();
+    let context =
+        EntryPointExecutionContext::new_invoke(tx_context_with_chain_id(chain_id.clone()), false)
+            .unwrap();
+    assert_eq!(context.chain_id_felt, Felt::from_hex(chain_id.as_hex().as_str()).unwrap());
+
+    // A chain id longer than 32 characters does not fit in a felt.
+    let oversized_chain_id = ChainId::Other("A".repeat(33));
+    assert!(
+        EntryPointExecutionContext::new_invoke(tx_context_with_chain_id(oversized_chain_id), false)
+            .is_err()
+    );
+}
+
+fn tx_context_for_testing() -> Arc<TransactionContext> {
+    Arc::new(TransactionContext {
+        block_context: BlockContext::create_for_testing(),
+        tx_info: TransactionInfo::Deprecated(DeprecatedTransactionInfo::default()),
+    })
+}
+
+#[test]
+fn test_context_builder_validate_mode_rejects_get_block_hash() {
+    let test_contract = FeatureContract::TestContract(CairoVersion::Cairo1);
+    let chain_info = &ChainInfo::create_for_testing();
+    let mut state = test_state(chain_info, BALANCE, &[(test_contract, 1)]);
+    let block_number = felt!(CURRENT_BLOCK_NUMBER - constants::STORED_BLOCK_HASH_BUFFER);
+    let entry_point_call = CallEntryPoint {
+        entry_point_selector: selector_from_name("test_get_block_hash"),
+        calldata: calldata![block_number],
+        ..trivial_external_entry_point_new(test_contract)
+    };
+
+    let mut context = EntryPointExecutionContextBuilder::new(tx_context_for_testing())
+        .execution_mode(ExecutionMode::Validate)
+        .limit_steps_by_resources(false)
+        .build()
+        .unwrap();
+    assert_eq!(context.execution_mode, ExecutionMode::Validate);
+
+    let mut remaining_gas = entry_point_call.initial_gas;
+    let error = entry_point_call
+        .execute(&mut state, &mut ExecutionResources::default(), &mut context, &mut remaining_gas)
+        .unwrap_err();
+    check_entry_point_execution_error_for_custom_hint!(
+        &error,
+        "Unauthorized syscall get_block_hash in execution mode Validate.",
+    );
+}
+
+#[test]
+fn test_context_builder_overrides_gas_costs() {
+    let tx_context = tx_context_for_testing();
+    let default_context =
+        EntryPointExecutionContextBuilder::new(tx_context.clone()).build().unwrap();
+    assert_eq!(default_context.execution_mode, ExecutionMode::Execute);
+
+    let step_gas_cost = default_context.gas_costs().step_gas_cost + 1;
+    let context = EntryPointExecutionContextBuilder::new(tx_context.clone())
+        .gas_costs(GasCosts { step_gas_cost, ..Default::default() })
+        .build()
+        .unwrap();
+    assert_eq!(context.gas_costs().step_gas_cost, step_gas_cost);
+    // The given transaction context is not modified.
+    assert_eq!(
+        tx_context.block_context.versioned_constants.os_constants.gas_costs.step_gas_cost,
+        default_context.gas_costs().step_gas_cost
+    );
+}
+
+#[test]
+fn test_event_ordering_after_reverted_inner_call() {
+    let test_contract = FeatureContract::TestContract(CairoVersion::Cairo1);
+    let chain_info = &ChainInfo::create_for_testing();
+    let mut state = test_state(chain_info, BALANCE, &[(test_contract, 1)]);
+    let mut context = EntryPointExecutionContextBuilder::new(tx_context_for_testing())
+        .limit_steps_by_resources(false)
+        .build()
+        .unwrap();
+    let mut resources = ExecutionResources::default();
+    let emit_events_call = |n_events: u64| CallEntryPoint {
+        entry_point_selector: selector_from_name("test_emit_events"),
+        calldata: calldata![felt!(n_events), felt!(1_u8), felt!(2_u8), felt!(1_u8), felt!(3_u8)],
+        ..trivial_external_entry_point_new(test_contract)
+    };
+    let mut execute = |call: CallEntryPoint, context: &mut EntryPointExecutionContext| {
+        let mut remaining_gas = call.initial_gas;
+        let revert_idx = context.revert_infos.0.len();
+        let mut call_info =
+            call.execute(&mut state, &mut resources, context, &mut remaining_gas).unwrap();
+        if call_info.execution.failed {
+            context.revert_failed_call(revert_idx, &mut state, &mut call_info).unwrap();
+        }
+        call_info
+    };
+
+    let first_call_info = execute(emit_events_call(1), &mut context);
+
+    // Emits an event and sends a message to L1, then reverts.
+    let reverted_call = CallEntryPoint {
+        entry_point_selector: selector_from_name("test_revert_helper"),
+        calldata: calldata![test_contract.get_class_hash().0],
+        ..trivial_external_entry_point_new(test_contract)
+    };
+    let reverted_call_info = execute(reverted_call, &mut context);
+    assert!(reverted_call_info.execution.failed);
+    assert!(reverted_call_info.execution.events.is_empty());
+    assert!(reverted_call_info.execution.l2_to_l1_messages.is_empty());
+    assert_eq!(context.n_emitted_events, 1);
+    assert_eq!(context.n_sent_messages_to_l1, 0);
+
+    let last_call_info = execute(emit_events_call(2), &mut context);
+
+    let event_orders: Vec<usize> = first_call_info
+        .execution
+        .events
+        .iter()
+        .chain(last_call_info.execution.events.iter())
+        .map(|event| event.order)
+        .collect();
+    assert_eq!(event_orders, vec![0, 1, 2]);
+}
+
+/// The native syscall handler reverts a failed inner call the same way the VM does.
+#[cfg(feature = "cairo_native")]
+#[test]
+fn test_event_ordering_after_reverted_inner_call_in_native() {
+    let test_contract = FeatureContract::TestContract(CairoVersion::Native);
+    let chain_info = &ChainInfo::create_for_testing();
+    let mut state = test_state(chain_info, BALANCE, &[(test_contract, 1)]);
+    let mut context = EntryPointExecutionContextBuilder::new(tx_context_for_testing())
+        .limit_steps_by_resources(false)
+        .build()
+        .unwrap();
+    let mut resources = ExecutionResources::default();
+    let mut execute = |call: CallEntryPoint| {
+        let mut remaining_gas = call.initial_gas;
+        call.execute(&mut state, &mut resources, &mut context, &mut remaining_gas).unwrap()
+    };
+
+    // The outer call catches the revert of the inner call, which emits an event and sends a message
+    // to L1 before reverting.
+    let outer_call_info = execute(CallEntryPoint {
+        entry_point_selector: selector_from_name("test_call_contract_revert"),
+        calldata: create_calldata(
+            test_contract.get_instance_address(0),
+            "test_revert_helper",
+            &[test_contract.get_class_hash().0],
+        ),
+        ..trivial_external_entry_point_new(test_contract)
+    });
+    assert!(!outer_call_info.execution.failed);
+    let [inner_call_info] = &outer_call_info.inner_calls[..] else {
+        panic!("Expected one inner call, got {:?}", outer_call_info.inner_calls);
+    };
+    assert!(inner_call_info.execution.failed);
+    assert!(inner_call_info.execution.events.is_empty());
+    assert!(inner_call_info.execution.l2_to_l1_messages.is_empty());
+
+    let emit_events_call_info = execute(CallEntryPoint {
+        entry_point_selector: selector_from_name("test_emit_events"),
+        calldata: calldata![felt!(2_u8), felt!(1_u8), felt!(2_u8), felt!(1_u8), felt!(3_u8)],
+        ..trivial_external_entry_point_new(test_contract)
+    });
+    let event_orders: Vec<usize> =
+        emit_events_call_info.execution.events.iter().map(|event| event.order).collect();
+    assert_eq!(event_orders, vec![0, 1]);
+}
@@ -19,6 +19,7 @@ use crate::execution::stack_trace::Cairo1RevertSummary;
 #[cfg(feature = "cairo_native")]
 use crate::execution::syscalls::hint_processor::SyscallExecutionError;
 use crate::state::errors::StateError;
+use crate::versioned_constants::VersionedConstantsError;
 
 // TODO(AlonH, 21/12/2022): Implement Display for all types that appear in errors.
 
@@ -72,6 +73,8 @@ pub enum PostExecutionError {
     VirtualMachineError(#[from] VirtualMachineError),
     #[error("Malformed return data : {error_message}.")]
     MalformedReturnData { error_message: String },
+    #[error(transparent)]
+    VersionedConstantsError(#[from] VersionedConstantsError),
 }
 
 impl From<RunnerError> for PostExecutionError {
@@ -104,6 +107,10 @@ pub enum EntryPointExecutionError {
     RecursionDepthExceeded,
     #[error(transparent)]
     StateError(#[from] StateError),
+    #[error(
+        "Execution failed due to touching more than {max_n_touched_contracts} distinct contracts."
+    )]
+    TouchedContractsLimitExceeded { max_n_touched_contracts: usize },
     #[error(transparent)]
     TraceError(#[from] TraceError),
 }
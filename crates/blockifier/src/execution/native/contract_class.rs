@@ -13,6 +13,9 @@ use crate::execution::contract_class::{ContractClassV1, EntryPointsByType, HasSe
 use crate::execution::entry_point::CallEntryPoint;
 use crate::execution::errors::PreExecutionError;
 use crate::execution::native::utils::contract_entrypoint_to_entrypoint_selector;
+/// A natively compiled contract class. Cloning is cheap and shares the compiled executor, so a
+/// class that is cached by its hash (e.g., in the `GlobalContractCache` shared by the block
+/// builder) is compiled once and reused by all the calls to it, across transactions and blocks.
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub struct NativeContractClassV1(pub Arc<NativeContractClassV1Inner>);
 impl Deref for NativeContractClassV1 {
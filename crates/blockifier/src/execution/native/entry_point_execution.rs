@@ -1,7 +1,6 @@
 use cairo_native::execution_result::ContractExecutionResult;
 use cairo_vm::vm::runners::cairo_runner::ExecutionResources;
 use num_traits::ToPrimitive;
-use starknet_api::execution_resources::GasAmount;
 
 use crate::execution::call_info::{CallExecution, CallInfo, ChargedResources, Retdata};
 use crate::execution::contract_class::TrackedResource;
@@ -10,9 +9,11 @@ use crate::execution::entry_point::{
     EntryPointExecutionContext,
     EntryPointExecutionResult,
 };
+use crate::execution::entry_point_execution::{reconcile_gas_for_fee, to_gas_for_fee};
 use crate::execution::errors::{EntryPointExecutionError, PostExecutionError};
 use crate::execution::native::contract_class::NativeContractClassV1;
 use crate::execution::native::syscall_handler::NativeSyscallHandler;
+use crate::execution::native::utils::validate_return_values_length;
 use crate::state::state_api::State;
 
 pub fn execute_entry_point_call(
@@ -65,9 +66,18 @@ fn create_callinfo(
         .into());
     }
 
+    if let Some(max_retdata_length) =
+        syscall_handler.context.versioned_constants().max_native_retdata_length
+    {
+        validate_return_values_length(&call_result.return_values, max_retdata_length)?;
+    }
+
     let gas_consumed = syscall_handler.call.initial_gas - remaining_gas;
 
-    Ok(CallInfo {
+    let gas_for_fee =
+        to_gas_for_fee(&TrackedResource::SierraGas, gas_consumed, &syscall_handler.inner_calls)?;
+
+    let call_info = CallInfo {
         call: syscall_handler.call,
         execution: CallExecution {
             retdata: Retdata(call_result.return_values),
@@ -78,7 +88,7 @@ fn create_callinfo(
         },
         charged_resources: ChargedResources {
             vm_resources: ExecutionResources::default(),
-            gas_for_fee: GasAmount(gas_consumed),
+            gas_for_fee,
         },
         inner_calls: syscall_handler.inner_calls,
         storage_read_values: syscall_handler.read_values,
@@ -89,5 +99,12 @@ fn create_callinfo(
         accessed_contract_addresses: Default::default(),
         read_class_hash_values: Default::default(),
         tracked_resource: TrackedResource::SierraGas,
-    })
+    };
+
+    // A mismatch means the fee charged for the call drifted from its actual gas consumption.
+    let reconciliation = reconcile_gas_for_fee(&call_info, syscall_handler.inner_calls_gas_charged);
+    debug_assert!(reconciliation.is_ok(), "{reconciliation:?}");
+    reconciliation?;
+
+    Ok(call_info)
 }
@@ -47,12 +47,19 @@ use crate::execution::entry_point::{
 use crate::execution::errors::EntryPointExecutionError;
 use crate::execution::execution_utils::execute_deployment;
 use crate::execution::native::utils::{calculate_resource_bounds, default_tx_v2_info};
-use crate::execution::syscalls::exceeds_event_size_limit;
 use crate::execution::syscalls::hint_processor::{
     SyscallExecutionError,
     BLOCK_NUMBER_OUT_OF_RANGE_ERROR,
-    INVALID_INPUT_LENGTH_ERROR,
     OUT_OF_GAS_ERROR,
+    TOO_MANY_INNER_CALLS_ERROR,
+};
+use crate::execution::syscalls::{
+    exceeds_event_size_limit,
+    keccak_rounds_and_gas_cost,
+    rounded_block_info_for_validate,
+    sha256_process_block_in_place,
+    SyscallSelector,
+    KECCAK_FULL_RATE_IN_WORDS,
 };
 use crate::state::state_api::State;
 use crate::transaction::objects::TransactionInfo;
@@ -68,6 +75,8 @@ pub struct NativeSyscallHandler<'state> {
     pub events: Vec<OrderedEvent>,
     pub l2_to_l1_messages: Vec<OrderedL2ToL1Message>,
     pub inner_calls: Vec<CallInfo>,
+    // The gas deducted from the remaining gas of this call for executing its inner calls.
+    pub inner_calls_gas_charged: u64,
 
     // Additional information gathered during execution.
     pub read_values: Vec<Felt>,
@@ -92,6 +101,7 @@ impl<'state> NativeSyscallHandler<'state> {
             events: Vec::new(),
             l2_to_l1_messages: Vec::new(),
             inner_calls: Vec::new(),
+            inner_calls_gas_charged: 0,
             read_values: Vec::new(),
             accessed_keys: HashSet::new(),
             unrecoverable_error: None,
@@ -103,23 +113,48 @@ impl<'state> NativeSyscallHandler<'state> {
         entry_point: CallEntryPoint,
         remaining_gas: &mut u128,
     ) -> SyscallResult<Retdata> {
+        // Bound the size of the call tree kept in memory for the entire execution.
+        let n_inner_calls = self.context.n_inner_calls;
+        if self
+            .context
+            .versioned_constants()
+            .max_n_inner_calls
+            .is_some_and(|max_n_inner_calls| n_inner_calls >= max_n_inner_calls)
+        {
+            let too_many_inner_calls_felt = Felt::from_hex(TOO_MANY_INNER_CALLS_ERROR)
+                .expect("Converting TOO_MANY_INNER_CALLS_ERROR to Felt should not fail.");
+            let error =
+                SyscallExecutionError::SyscallError { error_data: vec![too_many_inner_calls_felt] };
+            return Err(self.handle_error(remaining_gas, error));
+        }
+        self.context.n_inner_calls += 1;
+
         let mut remaining_gas_u64 =
             u64::try_from(*remaining_gas).expect("Failed to convert gas to u64.");
-        let call_info = entry_point
+        let initial_gas_u64 = remaining_gas_u64;
+        let revert_idx = self.context.revert_infos.0.len();
+        let mut call_info = entry_point
             .execute(self.state, self.resources, self.context, &mut remaining_gas_u64)
             .map_err(|e| self.handle_error(remaining_gas, e.into()))?;
+        self.inner_calls_gas_charged += initial_gas_u64 - remaining_gas_u64;
         let retdata = call_info.execution.retdata.clone();
-
-        if call_info.execution.failed {
-            let error = SyscallExecutionError::SyscallError { error_data: retdata.0 };
-            return Err(self.handle_error(remaining_gas, error));
+        let failed = call_info.execution.failed;
+        if failed {
+            self.context
+                .revert_failed_call(revert_idx, self.state, &mut call_info)
+                .map_err(|e| self.handle_error(remaining_gas, e.into()))?;
         }
 
         // TODO(Noa, 1/11/2024): remove this once the gas type is u64.
         // Change the remaining gas value.
         *remaining_gas = u128::from(remaining_gas_u64);
 
+        // Like in the VM, the failed call is kept in the call tree, with its changes reverted.
         self.inner_calls.push(call_info);
+        if failed {
+            let error = SyscallExecutionError::SyscallError { error_data: retdata.0 };
+            return Err(self.handle_error(remaining_gas, error));
+        }
 
         Ok(retdata)
     }
@@ -153,6 +188,22 @@ impl<'state> NativeSyscallHandler<'state> {
         Ok(())
     }
 
+    /// Fails a syscall that is not implemented yet. Panics, unless unsupported syscalls are
+    /// configured to be recoverable.
+    fn unsupported_syscall<T>(
+        &mut self,
+        remaining_gas: &mut u128,
+        selector: SyscallSelector,
+    ) -> SyscallResult<T> {
+        if !self.context.versioned_constants().unsupported_syscalls_are_recoverable {
+            panic!(
+                "The {selector:?} syscall is not supported by the native syscall handler, and \
+                 unsupported syscalls are configured to be unrecoverable."
+            );
+        }
+        Err(self.handle_error(remaining_gas, SyscallExecutionError::UnsupportedSyscall(selector)))
+    }
+
     fn handle_error(
         &mut self,
         remaining_gas: &mut u128,
@@ -189,60 +240,39 @@ impl<'state> NativeSyscallHandler<'state> {
     fn get_tx_info_v1(&self) -> TxInfo {
         let tx_info = &self.context.tx_context.tx_info;
         TxInfo {
-            version: tx_info.version().0,
+            version: tx_info.signed_version().0,
             account_contract_address: Felt::from(tx_info.sender_address()),
             max_fee: tx_info.max_fee_for_execution_info_syscall().0,
             signature: tx_info.signature().0,
             transaction_hash: tx_info.transaction_hash().0,
-            chain_id: Felt::from_hex(
-                &self.context.tx_context.block_context.chain_info.chain_id.as_hex(),
-            )
-            .expect("Failed to convert the chain_id to hex."),
+            chain_id: self.context.chain_id_felt,
             nonce: tx_info.nonce().0,
         }
     }
 
     fn get_block_info(&self) -> BlockInfo {
         let block_info = &self.context.tx_context.block_context.block_info;
-        if self.context.execution_mode == ExecutionMode::Validate {
-            let versioned_constants = self.context.versioned_constants();
-            let block_number = block_info.block_number.0;
-            let block_timestamp = block_info.block_timestamp.0;
-            // Round down to the nearest multiple of validate_block_number_rounding.
-            let validate_block_number_rounding =
-                versioned_constants.get_validate_block_number_rounding();
-            let rounded_block_number =
-                (block_number / validate_block_number_rounding) * validate_block_number_rounding;
-            // Round down to the nearest multiple of validate_timestamp_rounding.
-            let validate_timestamp_rounding = versioned_constants.get_validate_timestamp_rounding();
-            let rounded_timestamp =
-                (block_timestamp / validate_timestamp_rounding) * validate_timestamp_rounding;
-            BlockInfo {
-                block_number: rounded_block_number,
-                block_timestamp: rounded_timestamp,
-                sequencer_address: Felt::ZERO,
-            }
+        let block_info = if self.context.execution_mode == ExecutionMode::Validate {
+            rounded_block_info_for_validate(block_info, self.context.versioned_constants())
         } else {
-            BlockInfo {
-                block_number: block_info.block_number.0,
-                block_timestamp: block_info.block_timestamp.0,
-                sequencer_address: Felt::from(block_info.sequencer_address),
-            }
+            block_info.clone()
+        };
+        BlockInfo {
+            block_number: block_info.block_number.0,
+            block_timestamp: block_info.block_timestamp.0,
+            sequencer_address: Felt::from(block_info.sequencer_address),
         }
     }
 
     fn get_tx_info_v2(&self) -> SyscallResult<TxV2Info> {
         let tx_info = &self.context.tx_context.tx_info;
         let native_tx_info = TxV2Info {
-            version: tx_info.version().0,
+            version: tx_info.signed_version().0,
             account_contract_address: Felt::from(tx_info.sender_address()),
             max_fee: tx_info.max_fee_for_execution_info_syscall().0,
             signature: tx_info.signature().0,
             transaction_hash: tx_info.transaction_hash().0,
-            chain_id: Felt::from_hex(
-                &self.context.tx_context.block_context.chain_info.chain_id.as_hex(),
-            )
-            .expect("Failed to convert the chain_id to hex."),
+            chain_id: self.context.chain_id_felt,
             nonce: tx_info.nonce().0,
             ..default_tx_v2_info()
         };
@@ -414,7 +444,11 @@ impl<'state> StarknetSyscallHandler for &mut NativeSyscallHandler<'state> {
         match contract_class {
             RunnableContractClass::V0(_) => Err(self.handle_error(
                 remaining_gas,
-                SyscallExecutionError::ForbiddenClassReplacement { class_hash },
+                SyscallExecutionError::ForbiddenClassReplacement {
+                    storage_address: self.call.storage_address,
+                    class_hash,
+                    class_version: contract_class.class_version(),
+                },
             )),
             RunnableContractClass::V1(_) | RunnableContractClass::V1Native(_) => {
                 self.state
@@ -598,27 +632,13 @@ impl<'state> StarknetSyscallHandler for &mut NativeSyscallHandler<'state> {
     }
 
     fn keccak(&mut self, input: &[u64], remaining_gas: &mut u128) -> SyscallResult<U256> {
+        // Covers the syscall invocation only; the rounds are charged below, as in the VM.
         self.pre_execute_syscall(remaining_gas, self.context.gas_costs().keccak_gas_cost)?;
 
-        const KECCAK_FULL_RATE_IN_WORDS: usize = 17;
-
-        let input_length = input.len();
-        let (n_rounds, remainder) = num_integer::div_rem(input_length, KECCAK_FULL_RATE_IN_WORDS);
-
-        if remainder != 0 {
-            return Err(self.handle_error(
-                remaining_gas,
-                SyscallExecutionError::SyscallError {
-                    error_data: vec![Felt::from_hex(INVALID_INPUT_LENGTH_ERROR).unwrap()],
-                },
-            ));
-        }
-
-        // TODO(Ori, 1/2/2024): Write an indicative expect message explaining why the conversion
-        // works.
-        let n_rounds_as_u128 = u128::try_from(n_rounds).expect("Failed to convert usize to u128.");
-        let gas_cost =
-            n_rounds_as_u128 * u128::from(self.context.gas_costs().keccak_round_cost_gas_cost);
+        let gas_cost = match keccak_rounds_and_gas_cost(input.len(), self.context.gas_costs()) {
+            Ok((_n_rounds, gas_cost)) => u128::from(gas_cost),
+            Err(error) => return Err(self.handle_error(remaining_gas, error)),
+        };
 
         if gas_cost > *remaining_gas {
             return Err(self.handle_error(
@@ -648,88 +668,88 @@ impl<'state> StarknetSyscallHandler for &mut NativeSyscallHandler<'state> {
         &mut self,
         _x: U256,
         _y: U256,
-        _remaining_gas: &mut u128,
+        remaining_gas: &mut u128,
     ) -> SyscallResult<Option<Secp256k1Point>> {
-        todo!("Implement secp256k1_new syscall.");
+        self.unsupported_syscall(remaining_gas, SyscallSelector::Secp256k1New)
     }
 
     fn secp256k1_add(
         &mut self,
         _p0: Secp256k1Point,
         _p1: Secp256k1Point,
-        _remaining_gas: &mut u128,
+        remaining_gas: &mut u128,
     ) -> SyscallResult<Secp256k1Point> {
-        todo!("Implement secp256k1_add syscall.");
+        self.unsupported_syscall(remaining_gas, SyscallSelector::Secp256k1Add)
     }
 
     fn secp256k1_mul(
         &mut self,
         _p: Secp256k1Point,
         _m: U256,
-        _remaining_gas: &mut u128,
+        remaining_gas: &mut u128,
     ) -> SyscallResult<Secp256k1Point> {
-        todo!("Implement secp256k1_mul syscall.");
+        self.unsupported_syscall(remaining_gas, SyscallSelector::Secp256k1Mul)
     }
 
     fn secp256k1_get_point_from_x(
         &mut self,
         _x: U256,
         _y_parity: bool,
-        _remaining_gas: &mut u128,
+        remaining_gas: &mut u128,
     ) -> SyscallResult<Option<Secp256k1Point>> {
-        todo!("Implement secp256k1_get_point_from_x syscall.");
+        self.unsupported_syscall(remaining_gas, SyscallSelector::Secp256k1GetPointFromX)
     }
 
     fn secp256k1_get_xy(
         &mut self,
         _p: Secp256k1Point,
-        _remaining_gas: &mut u128,
+        remaining_gas: &mut u128,
     ) -> SyscallResult<(U256, U256)> {
-        todo!("Implement secp256k1_get_xy syscall.");
+        self.unsupported_syscall(remaining_gas, SyscallSelector::Secp256k1GetXy)
     }
 
     fn secp256r1_new(
         &mut self,
         _x: U256,
         _y: U256,
-        _remaining_gas: &mut u128,
+        remaining_gas: &mut u128,
     ) -> SyscallResult<Option<Secp256r1Point>> {
-        todo!("Implement secp256r1_new syscall.");
+        self.unsupported_syscall(remaining_gas, SyscallSelector::Secp256r1New)
     }
 
     fn secp256r1_add(
         &mut self,
         _p0: Secp256r1Point,
         _p1: Secp256r1Point,
-        _remaining_gas: &mut u128,
+        remaining_gas: &mut u128,
     ) -> SyscallResult<Secp256r1Point> {
-        todo!("Implement secp256r1_add syscall.");
+        self.unsupported_syscall(remaining_gas, SyscallSelector::Secp256r1Add)
     }
 
     fn secp256r1_mul(
         &mut self,
         _p: Secp256r1Point,
         _m: U256,
-        _remaining_gas: &mut u128,
+        remaining_gas: &mut u128,
     ) -> SyscallResult<Secp256r1Point> {
-        todo!("Implement secp256r1_mul syscall.");
+        self.unsupported_syscall(remaining_gas, SyscallSelector::Secp256r1Mul)
     }
 
     fn secp256r1_get_point_from_x(
         &mut self,
         _x: U256,
         _y_parity: bool,
-        _remaining_gas: &mut u128,
+        remaining_gas: &mut u128,
     ) -> SyscallResult<Option<Secp256r1Point>> {
-        todo!("Implement secp256r1_get_point_from_x syscall.");
+        self.unsupported_syscall(remaining_gas, SyscallSelector::Secp256r1GetPointFromX)
     }
 
     fn secp256r1_get_xy(
         &mut self,
         _p: Secp256r1Point,
-        _remaining_gas: &mut u128,
+        remaining_gas: &mut u128,
     ) -> SyscallResult<(U256, U256)> {
-        todo!("Implement secp256r1_get_xy syscall.");
+        self.unsupported_syscall(remaining_gas, SyscallSelector::Secp256r1GetXy)
     }
 
     fn sha256_process_block(
@@ -743,14 +763,7 @@ impl<'state> StarknetSyscallHandler for &mut NativeSyscallHandler<'state> {
             self.context.gas_costs().sha256_process_block_gas_cost,
         )?;
 
-        let data_as_bytes = sha2::digest::generic_array::GenericArray::from_exact_iter(
-            current_block.iter().flat_map(|x| x.to_be_bytes()),
-        )
-        .expect(
-            "u32.to_be_bytes() returns 4 bytes, and data.len() == 16. So data contains 64 bytes.",
-        );
-
-        sha2::compress256(prev_state, &[data_as_bytes]);
+        sha256_process_block_in_place(prev_state, current_block);
 
         Ok(())
     }
@@ -4,6 +4,7 @@ use starknet_api::core::EntryPointSelector;
 use starknet_api::transaction::fields::{Resource, ValidResourceBounds};
 use starknet_types_core::felt::Felt;
 
+use crate::execution::errors::PostExecutionError;
 use crate::transaction::objects::CurrentTransactionInfo;
 
 pub fn contract_entrypoint_to_entrypoint_selector(
@@ -25,6 +26,25 @@ pub fn encode_str_as_felts(msg: &str) -> Vec<Felt> {
     encoding
 }
 
+/// Sanity check on the return values produced by the native executor; a result exceeding the
+/// configured bound can only originate from an executor bug.
+pub fn validate_return_values_length(
+    return_values: &[Felt],
+    max_retdata_length: usize,
+) -> Result<(), PostExecutionError> {
+    if return_values.len() > max_retdata_length {
+        return Err(PostExecutionError::MalformedReturnData {
+            error_message: format!(
+                "Unexpected return data length. Length exceeds the allowed maximum: {} > {}",
+                return_values.len(),
+                max_retdata_length
+            ),
+        });
+    }
+
+    Ok(())
+}
+
 pub fn default_tx_v2_info() -> TxV2Info {
     TxV2Info {
         version: Default::default(),
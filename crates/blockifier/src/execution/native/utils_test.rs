@@ -1,3 +1,4 @@
+use assert_matches::assert_matches;
 use cairo_lang_starknet_classes::contract_class::ContractEntryPoint;
 use num_bigint::BigUint;
 use pretty_assertions::assert_eq;
@@ -5,9 +6,11 @@ use starknet_api::core::EntryPointSelector;
 use starknet_api::execution_utils::format_panic_data;
 use starknet_types_core::felt::Felt;
 
+use crate::execution::errors::PostExecutionError;
 use crate::execution::native::utils::{
     contract_entrypoint_to_entrypoint_selector,
     encode_str_as_felts,
+    validate_return_values_length,
 };
 
 #[test]
@@ -52,3 +55,20 @@ fn test_encode_large_str() {
          0x676572206e6f7420736f206d75636800000000000000000000000000000000 ('ger not so much'))"
     );
 }
+
+#[test]
+fn test_validate_return_values_length() {
+    const MAX_RETDATA_LENGTH: usize = 3;
+
+    let return_values = vec![Felt::ONE; MAX_RETDATA_LENGTH];
+    validate_return_values_length(&return_values, MAX_RETDATA_LENGTH).unwrap();
+
+    let oversized_return_values = vec![Felt::ONE; MAX_RETDATA_LENGTH + 1];
+    let error =
+        validate_return_values_length(&oversized_return_values, MAX_RETDATA_LENGTH).unwrap_err();
+    assert_matches!(
+        error,
+        PostExecutionError::MalformedReturnData { error_message }
+        if error_message.contains("4 > 3")
+    );
+}
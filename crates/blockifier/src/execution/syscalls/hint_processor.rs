@@ -61,6 +61,7 @@ use crate::execution::syscalls::{
     keccak,
     library_call,
     replace_class,
+    rounded_block_info_for_validate,
     send_message_to_l1,
     sha_256_process_block,
     storage_read,
@@ -86,8 +87,15 @@ pub enum SyscallExecutionError {
     BadSyscallPointer { expected_ptr: Relocatable, actual_ptr: Relocatable },
     #[error(transparent)]
     EmitEventError(#[from] EmitEventError),
-    #[error("Cannot replace V1 class hash with V0 class hash: {class_hash}.")]
-    ForbiddenClassReplacement { class_hash: ClassHash },
+    #[error(
+        "Cannot replace V1 class hash with V0 class hash: {class_hash} (class version: \
+         {class_version}), in contract: {storage_address}."
+    )]
+    ForbiddenClassReplacement {
+        storage_address: ContractAddress,
+        class_hash: ClassHash,
+        class_version: u8,
+    },
     #[error(transparent)]
     FromStr(#[from] FromStrError),
     #[error("Invalid address domain: {address_domain}.")]
@@ -116,6 +124,8 @@ pub enum SyscallExecutionError {
     InvalidSyscallSelector(Felt),
     #[error("Unauthorized syscall {syscall_name} in execution mode {execution_mode}.")]
     InvalidSyscallInExecutionMode { syscall_name: String, execution_mode: ExecutionMode },
+    #[error("Unsupported syscall: {0:?}.")]
+    UnsupportedSyscall(SyscallSelector),
     #[error(transparent)]
     MathError(#[from] cairo_vm::types::errors::math_errors::MathError),
     #[error(transparent)]
@@ -203,6 +213,9 @@ pub const ENTRYPOINT_NOT_FOUND_ERROR: &str =
 // "ENTRYPOINT_FAILED";
 pub const ENTRYPOINT_FAILED_ERROR: &str =
     "0x000000000000000000000000000000454e545259504f494e545f4641494c4544";
+// "Too many inner calls";
+pub const TOO_MANY_INNER_CALLS_ERROR: &str =
+    "0x000000000000000000000000546f6f206d616e7920696e6e65722063616c6c73";
 // "Invalid input length";
 pub const INVALID_INPUT_LENGTH_ERROR: &str =
     "0x000000000000000000000000496e76616c696420696e707574206c656e677468";
@@ -591,28 +604,16 @@ impl<'a> SyscallHintProcessor<'a> {
         vm: &mut VirtualMachine,
     ) -> SyscallResult<Relocatable> {
         let block_info = &self.context.tx_context.block_context.block_info;
-        let block_timestamp = block_info.block_timestamp.0;
-        let block_number = block_info.block_number.0;
-        let versioned_constants = self.context.versioned_constants();
-        let block_data: Vec<Felt> = if self.is_validate_mode() {
-            // Round down to the nearest multiple of validate_block_number_rounding.
-            let validate_block_number_rounding =
-                versioned_constants.get_validate_block_number_rounding();
-            let rounded_block_number =
-                (block_number / validate_block_number_rounding) * validate_block_number_rounding;
-            // Round down to the nearest multiple of validate_timestamp_rounding.
-            let validate_timestamp_rounding = versioned_constants.get_validate_timestamp_rounding();
-            let rounded_timestamp =
-                (block_timestamp / validate_timestamp_rounding) * validate_timestamp_rounding;
-
-            vec![Felt::from(rounded_block_number), Felt::from(rounded_timestamp), Felt::ZERO]
+        let block_info = if self.is_validate_mode() {
+            rounded_block_info_for_validate(block_info, self.context.versioned_constants())
         } else {
-            vec![
-                Felt::from(block_number),
-                Felt::from(block_timestamp),
-                *block_info.sequencer_address.0.key(),
-            ]
+            block_info.clone()
         };
+        let block_data = vec![
+            Felt::from(block_info.block_number.0),
+            Felt::from(block_info.block_timestamp.0),
+            *block_info.sequencer_address.0.key(),
+        ];
         let (block_info_segment_start_ptr, _) = self.allocate_data_segment(vm, &block_data)?;
 
         Ok(block_info_segment_start_ptr)
@@ -641,10 +642,7 @@ impl<'a> SyscallHintProcessor<'a> {
             tx_signature_start_ptr.into(),
             tx_signature_end_ptr.into(),
             (tx_info).transaction_hash().0.into(),
-            Felt::from_hex(
-                self.context.tx_context.block_context.chain_info.chain_id.as_hex().as_str(),
-            )?
-            .into(),
+            self.context.chain_id_felt.into(),
             (tx_info).nonce().0.into(),
         ];
 
@@ -807,9 +805,25 @@ pub fn execute_inner_call(
     syscall_handler: &mut SyscallHintProcessor<'_>,
     remaining_gas: &mut u64,
 ) -> SyscallResult<ReadOnlySegment> {
+    // Bound the size of the call tree kept in memory for the entire execution.
+    let n_inner_calls = syscall_handler.context.n_inner_calls;
+    if syscall_handler
+        .context
+        .versioned_constants()
+        .max_n_inner_calls
+        .is_some_and(|max_n_inner_calls| n_inner_calls >= max_n_inner_calls)
+    {
+        let too_many_inner_calls_felt =
+            Felt::from_hex(TOO_MANY_INNER_CALLS_ERROR).map_err(SyscallExecutionError::from)?;
+        return Err(SyscallExecutionError::SyscallError {
+            error_data: vec![too_many_inner_calls_felt],
+        });
+    }
+    syscall_handler.context.n_inner_calls += 1;
+
     let revert_idx = syscall_handler.context.revert_infos.0.len();
 
-    let call_info = call.execute(
+    let mut call_info = call.execute(
         syscall_handler.state,
         syscall_handler.resources,
         syscall_handler.context,
@@ -818,23 +832,15 @@ pub fn execute_inner_call(
 
     let mut raw_retdata = call_info.execution.retdata.0.clone();
     let failed = call_info.execution.failed;
+    if failed {
+        syscall_handler.context.revert_failed_call(
+            revert_idx,
+            syscall_handler.state,
+            &mut call_info,
+        )?;
+    }
     syscall_handler.inner_calls.push(call_info);
     if failed {
-        syscall_handler.context.revert(revert_idx, syscall_handler.state)?;
-
-        // Delete events and l2_to_l1_messages from the reverted call.
-        let reverted_call = &mut syscall_handler.inner_calls.last_mut().unwrap();
-        let mut stack: Vec<&mut CallInfo> = vec![reverted_call];
-        while let Some(call_info) = stack.pop() {
-            call_info.execution.events.clear();
-            call_info.execution.l2_to_l1_messages.clear();
-            // Add inner calls that did not fail to the stack.
-            // The events and l2_to_l1_messages of the failed calls were already cleared.
-            stack.extend(
-                call_info.inner_calls.iter_mut().filter(|call_info| !call_info.execution.failed),
-            );
-        }
-
         raw_retdata
             .push(Felt::from_hex(ENTRYPOINT_FAILED_ERROR).map_err(SyscallExecutionError::from)?);
         return Err(SyscallExecutionError::SyscallError { error_data: raw_retdata });
@@ -1,7 +1,7 @@
 use cairo_vm::types::relocatable::{MaybeRelocatable, Relocatable};
 use cairo_vm::vm::vm_core::VirtualMachine;
 use num_traits::ToPrimitive;
-use starknet_api::block::{BlockHash, BlockNumber};
+use starknet_api::block::{BlockHash, BlockNumber, BlockTimestamp};
 use starknet_api::contract_class::EntryPointType;
 use starknet_api::core::{
     calculate_contract_address,
@@ -30,6 +30,7 @@ use self::hint_processor::{
     BLOCK_NUMBER_OUT_OF_RANGE_ERROR,
 };
 use crate::abi::constants;
+use crate::blockifier::block::BlockInfo;
 use crate::execution::call_info::{MessageToL1, OrderedEvent, OrderedL2ToL1Message};
 use crate::execution::deprecated_syscalls::DeprecatedSyscallSelector;
 use crate::execution::entry_point::{CallEntryPoint, CallType, ConstructorContext};
@@ -42,7 +43,7 @@ use crate::execution::execution_utils::{
 };
 use crate::execution::syscalls::hint_processor::{INVALID_INPUT_LENGTH_ERROR, OUT_OF_GAS_ERROR};
 use crate::transaction::account_transaction::is_cairo1;
-use crate::versioned_constants::{EventLimits, VersionedConstants};
+use crate::versioned_constants::{EventLimits, GasCosts, VersionedConstants};
 
 pub mod hint_processor;
 mod secp;
@@ -445,6 +446,28 @@ pub fn get_execution_info(
     Ok(GetExecutionInfoResponse { execution_info_ptr })
 }
 
+/// Returns the block info exposed to the syscalls in validate mode: the block number and timestamp
+/// are rounded down to the nearest multiple of their validate rounding factors, and the sequencer
+/// address is zeroed.
+pub fn rounded_block_info_for_validate(
+    block_info: &BlockInfo,
+    versioned_constants: &VersionedConstants,
+) -> BlockInfo {
+    let validate_block_number_rounding = versioned_constants.get_validate_block_number_rounding();
+    let rounded_block_number = (block_info.block_number.0 / validate_block_number_rounding)
+        * validate_block_number_rounding;
+    let validate_timestamp_rounding = versioned_constants.get_validate_timestamp_rounding();
+    let rounded_timestamp =
+        (block_info.block_timestamp.0 / validate_timestamp_rounding) * validate_timestamp_rounding;
+
+    BlockInfo {
+        block_number: BlockNumber(rounded_block_number),
+        block_timestamp: BlockTimestamp(rounded_timestamp),
+        sequencer_address: ContractAddress::default(),
+        ..block_info.clone()
+    }
+}
+
 // LibraryCall syscall.
 
 #[derive(Debug, Eq, PartialEq)]
@@ -513,7 +536,11 @@ pub fn replace_class(
     let class = syscall_handler.state.get_compiled_contract_class(class_hash)?;
 
     if !is_cairo1(&class) {
-        return Err(SyscallExecutionError::ForbiddenClassReplacement { class_hash });
+        return Err(SyscallExecutionError::ForbiddenClassReplacement {
+            storage_address: syscall_handler.storage_address(),
+            class_hash,
+            class_version: class.class_version(),
+        });
     }
     syscall_handler.state.set_class_hash_at(syscall_handler.storage_address(), class_hash)?;
     Ok(ReplaceClassResponse {})
@@ -660,15 +687,15 @@ impl SyscallResponse for KeccakResponse {
     }
 }
 
-pub fn keccak(
-    request: KeccakRequest,
-    vm: &mut VirtualMachine,
-    syscall_handler: &mut SyscallHintProcessor<'_>,
-    remaining_gas: &mut u64,
-) -> SyscallResult<KeccakResponse> {
-    let input_length = (request.input_end - request.input_start)?;
+pub const KECCAK_FULL_RATE_IN_WORDS: usize = 17;
 
-    const KECCAK_FULL_RATE_IN_WORDS: usize = 17;
+/// Returns the number of keccak rounds needed to hash an input of the given length (in words), and
+/// the gas cost of these rounds. The rounds are charged on top of `keccak_gas_cost`, which only
+/// covers the syscall invocation; this is the case both in the VM and in native execution.
+pub fn keccak_rounds_and_gas_cost(
+    input_length: usize,
+    gas_costs: &GasCosts,
+) -> SyscallResult<(usize, u64)> {
     let (n_rounds, remainder) = num_integer::div_rem(input_length, KECCAK_FULL_RATE_IN_WORDS);
 
     if remainder != 0 {
@@ -681,7 +708,19 @@ pub fn keccak(
 
     // TODO(Ori, 1/2/2024): Write an indicative expect message explaining why the conversion works.
     let n_rounds_as_u64 = u64::try_from(n_rounds).expect("Failed to convert usize to u64.");
-    let gas_cost = n_rounds_as_u64 * syscall_handler.context.gas_costs().keccak_round_cost_gas_cost;
+    Ok((n_rounds, n_rounds_as_u64 * gas_costs.keccak_round_cost_gas_cost))
+}
+
+pub fn keccak(
+    request: KeccakRequest,
+    vm: &mut VirtualMachine,
+    syscall_handler: &mut SyscallHintProcessor<'_>,
+    remaining_gas: &mut u64,
+) -> SyscallResult<KeccakResponse> {
+    let input_length = (request.input_end - request.input_start)?;
+
+    let (n_rounds, gas_cost) =
+        keccak_rounds_and_gas_cost(input_length, syscall_handler.context.gas_costs())?;
     if gas_cost > *remaining_gas {
         let out_of_gas_error =
             Felt::from_hex(OUT_OF_GAS_ERROR).map_err(SyscallExecutionError::from)?;
@@ -745,28 +784,37 @@ impl SyscallResponse for Sha256ProcessBlockResponse {
     }
 }
 
+pub const SHA256_BLOCK_SIZE: usize = 16;
+pub const SHA256_STATE_SIZE: usize = 8;
+
+/// Updates the given SHA-256 state in place with a single block of the message. Processing the
+/// blocks of a padded message in order, starting from the initial state, yields its digest.
+pub fn sha256_process_block_in_place(
+    state: &mut [u32; SHA256_STATE_SIZE],
+    block: &[u32; SHA256_BLOCK_SIZE],
+) {
+    let block_as_bytes = sha2::digest::generic_array::GenericArray::from_exact_iter(
+        block.iter().flat_map(|word| word.to_be_bytes()),
+    )
+    .expect(
+        "u32.to_be_bytes() returns 4 bytes, and block.len() == 16. So block contains 64 bytes.",
+    );
+
+    sha2::compress256(state, &[block_as_bytes]);
+}
+
 pub fn sha_256_process_block(
     request: Sha256ProcessBlockRequest,
     vm: &mut VirtualMachine,
     syscall_handler: &mut SyscallHintProcessor<'_>,
     _remaining_gas: &mut u64,
 ) -> SyscallResult<Sha256ProcessBlockResponse> {
-    const SHA256_BLOCK_SIZE: usize = 16;
-
     let data = vm.get_integer_range(request.input_start, SHA256_BLOCK_SIZE)?;
-    const SHA256_STATE_SIZE: usize = 8;
     let prev_state = vm.get_integer_range(request.state_ptr, SHA256_STATE_SIZE)?;
 
-    let data_as_bytes =
-        sha2::digest::generic_array::GenericArray::from_exact_iter(data.iter().flat_map(|felt| {
-            felt.to_bigint()
-                .to_u32()
-                .expect("libfunc should ensure the input is an [u32; 16].")
-                .to_be_bytes()
-        }))
-        .expect(
-            "u32.to_be_bytes() returns 4 bytes, and data.len() == 16. So data contains 64 bytes.",
-        );
+    let block: [u32; SHA256_BLOCK_SIZE] = core::array::from_fn(|i| {
+        data[i].to_bigint().to_u32().expect("libfunc should ensure the input is an [u32; 16].")
+    });
 
     let mut state_as_words: [u32; SHA256_STATE_SIZE] = core::array::from_fn(|i| {
         prev_state[i].to_bigint().to_u32().expect(
@@ -774,7 +822,9 @@ pub fn sha_256_process_block(
         )
     });
 
-    sha2::compress256(&mut state_as_words, &[data_as_bytes]);
+    // Unlike the native syscall, the previous state is left untouched, and the new state is written
+    // to a new location in the sha256 segment.
+    sha256_process_block_in_place(&mut state_as_words, &block);
 
     let segment = syscall_handler.sha256_segment_end_ptr.unwrap_or(vm.add_memory_segment());
 
@@ -1,20 +1,26 @@
 use core::panic;
 use std::sync::Arc;
 
+use cairo_vm::vm::runners::cairo_runner::ExecutionResources;
 use itertools::Itertools;
 use pretty_assertions::assert_eq;
 use rstest::rstest;
+#[cfg(feature = "cairo_native")]
+use starknet_api::execution_resources::GasAmount;
 use starknet_api::execution_utils::format_panic_data;
 use starknet_api::felt;
 use starknet_api::transaction::fields::Calldata;
+use starknet_types_core::felt::Felt;
 use test_case::test_case;
 
 use super::constants::REQUIRED_GAS_CALL_CONTRACT_TEST;
 use crate::abi::abi_utils::selector_from_name;
-use crate::context::ChainInfo;
+use crate::context::{BlockContext, ChainInfo, TransactionContext};
 use crate::execution::call_info::CallExecution;
+use crate::execution::common_hints::ExecutionMode;
 use crate::execution::contract_class::TrackedResource;
-use crate::execution::entry_point::CallEntryPoint;
+use crate::execution::entry_point::{CallEntryPoint, EntryPointExecutionContext};
+use crate::execution::syscalls::hint_processor::TOO_MANY_INNER_CALLS_ERROR;
 use crate::retdata;
 use crate::state::state_api::StateReader;
 use crate::test_utils::contracts::FeatureContract;
@@ -27,6 +33,7 @@ use crate::test_utils::{
     CompilerBasedVersion,
     BALANCE,
 };
+use crate::transaction::objects::{CurrentTransactionInfo, TransactionInfo};
 
 // TODO: Add test for native once reverts are supported.
 #[test]
@@ -191,6 +198,36 @@ fn test_tracked_resources_fn(
     assert_eq!(execution.inner_calls.first().unwrap().tracked_resource, expected_inner_resource);
 }
 
+/// A Native call is charged only for its own gas and that of its Sierra gas inner calls; the gas of
+/// a Cairo0 inner call is charged through its VM resources.
+#[cfg(feature = "cairo_native")]
+#[test]
+fn test_native_gas_for_fee_with_cairo0_inner_call() {
+    let outer_version = CompilerBasedVersion::CairoVersion(CairoVersion::Native);
+    let inner_version = CompilerBasedVersion::CairoVersion(CairoVersion::Cairo0);
+    let outer_contract = outer_version.get_test_contract();
+    let inner_contract = inner_version.get_test_contract();
+    let chain_info = &ChainInfo::create_for_testing();
+    let mut state = test_state(chain_info, BALANCE, &[(outer_contract, 1), (inner_contract, 1)]);
+
+    let entry_point_call = CallEntryPoint {
+        entry_point_selector: selector_from_name("test_call_contract"),
+        calldata: build_recurse_calldata(&[inner_version]),
+        ..trivial_external_entry_point_new(outer_contract)
+    };
+    let call_info = entry_point_call.execute_directly(&mut state).unwrap();
+
+    let [inner_call] = &call_info.inner_calls[..] else {
+        panic!("Expected one inner call, got {:?}", call_info.inner_calls);
+    };
+    assert_eq!(inner_call.tracked_resource, TrackedResource::CairoSteps);
+    assert_eq!(inner_call.charged_resources.gas_for_fee, GasAmount(0));
+    assert_eq!(
+        call_info.charged_resources.gas_for_fee,
+        GasAmount(call_info.execution.gas_consumed - inner_call.execution.gas_consumed)
+    );
+}
+
 #[test_case(CompilerBasedVersion::CairoVersion(CairoVersion::Cairo0), CompilerBasedVersion::CairoVersion(CairoVersion::Cairo1); "Cairo0_and_Cairo1")]
 #[test_case(CompilerBasedVersion::OldCairo1, CompilerBasedVersion::CairoVersion(CairoVersion::Cairo1); "OldCairo1_and_Cairo1")]
 #[cfg_attr(
@@ -240,3 +277,96 @@ fn test_tracked_resources_nested(
     let second_inner_call_info = execution.inner_calls.get(1).unwrap();
     assert_eq!(second_inner_call_info.tracked_resource, TrackedResource::SierraGas);
 }
+
+#[cfg_attr(
+    feature = "cairo_native",
+    test_case(CairoVersion::Native; "Native")
+)]
+#[test_case(CairoVersion::Cairo1; "VM")]
+fn test_call_contract_exceeds_max_inner_calls(cairo_version: CairoVersion) {
+    let test_contract = FeatureContract::TestContract(cairo_version);
+    let chain_info = &ChainInfo::create_for_testing();
+    let mut state = test_state(chain_info, BALANCE, &[(test_contract, 1)]);
+
+    // Forbid inner calls altogether.
+    let mut block_context = BlockContext::create_for_testing();
+    block_context.versioned_constants.max_n_inner_calls = Some(0);
+    let tx_context = TransactionContext {
+        block_context,
+        tx_info: TransactionInfo::Current(CurrentTransactionInfo::create_for_testing()),
+    };
+    let mut context =
+        EntryPointExecutionContext::new(Arc::new(tx_context), ExecutionMode::Execute, false)
+            .unwrap();
+
+    let entry_point_call = CallEntryPoint {
+        entry_point_selector: selector_from_name("test_call_contract"),
+        calldata: build_recurse_calldata(&[CompilerBasedVersion::CairoVersion(cairo_version)]),
+        ..trivial_external_entry_point_new(test_contract)
+    };
+    let mut remaining_gas = entry_point_call.initial_gas;
+    let call_info = entry_point_call
+        .execute(&mut state, &mut ExecutionResources::default(), &mut context, &mut remaining_gas)
+        .unwrap();
+
+    assert!(call_info.execution.failed);
+    assert!(call_info.inner_calls.is_empty());
+    assert!(
+        call_info
+            .execution
+            .retdata
+            .0
+            .contains(&Felt::from_hex(TOO_MANY_INNER_CALLS_ERROR).unwrap())
+    );
+}
+
+#[cfg_attr(
+    feature = "cairo_native",
+    test_case(CairoVersion::Native; "Native")
+)]
+#[test_case(CairoVersion::Cairo1; "VM")]
+fn test_call_contract_exceeds_max_touched_contracts(cairo_version: CairoVersion) {
+    let test_contract = FeatureContract::TestContract(cairo_version);
+    let chain_info = &ChainInfo::create_for_testing();
+    let mut state = test_state(chain_info, BALANCE, &[(test_contract, 3)]);
+
+    // The first instance calls the second, which calls the third.
+    let max_n_touched_contracts = 2;
+    let mut block_context = BlockContext::create_for_testing();
+    block_context.versioned_constants.max_n_touched_contracts = Some(max_n_touched_contracts);
+    let tx_context = TransactionContext {
+        block_context,
+        tx_info: TransactionInfo::Current(CurrentTransactionInfo::create_for_testing()),
+    };
+    let mut context =
+        EntryPointExecutionContext::new(Arc::new(tx_context), ExecutionMode::Execute, false)
+            .unwrap();
+
+    let innermost_calldata = create_calldata(
+        test_contract.get_instance_address(2),
+        "test_storage_read_write",
+        &[
+            felt!(123_u16), // Calldata: address.
+            felt!(45_u8),   // Calldata: value.
+        ],
+    );
+    let calldata = create_calldata(
+        test_contract.get_instance_address(1),
+        "test_call_contract",
+        &innermost_calldata.0,
+    );
+    let entry_point_call = CallEntryPoint {
+        entry_point_selector: selector_from_name("test_call_contract"),
+        calldata,
+        ..trivial_external_entry_point_new(test_contract)
+    };
+    let mut remaining_gas = entry_point_call.initial_gas;
+    let error = entry_point_call
+        .execute(&mut state, &mut ExecutionResources::default(), &mut context, &mut remaining_gas)
+        .unwrap_err();
+
+    assert!(error.to_string().contains(&format!(
+        "Execution failed due to touching more than {max_n_touched_contracts} distinct contracts."
+    )));
+    assert_eq!(context.touched_contracts.len(), max_n_touched_contracts);
+}
@@ -1,7 +1,11 @@
+use std::sync::Arc;
+
+#[cfg(feature = "cairo_native")]
+use cairo_native::starknet::StarknetSyscallHandler;
 use cairo_vm::Felt252;
 use num_traits::Pow;
-use starknet_api::block::GasPrice;
-use starknet_api::core::ChainId;
+use starknet_api::block::{BlockNumber, BlockTimestamp, GasPrice};
+use starknet_api::core::{ChainId, ContractAddress};
 use starknet_api::data_availability::DataAvailabilityMode;
 use starknet_api::execution_resources::GasAmount;
 use starknet_api::transaction::fields::{
@@ -20,9 +24,12 @@ use starknet_types_core::felt::Felt;
 use test_case::test_case;
 
 use crate::abi::abi_utils::selector_from_name;
-use crate::context::ChainInfo;
+use crate::context::{BlockContext, ChainInfo, TransactionContext};
 use crate::execution::common_hints::ExecutionMode;
 use crate::execution::entry_point::CallEntryPoint;
+use crate::execution::syscalls::rounded_block_info_for_validate;
+#[cfg(feature = "cairo_native")]
+use crate::execution::syscalls::syscall_tests::utils::run_native_syscalls_given_tx_context;
 use crate::test_utils::contracts::FeatureContract;
 use crate::test_utils::initial_test_state::test_state;
 use crate::test_utils::{
@@ -41,6 +48,7 @@ use crate::transaction::objects::{
     DeprecatedTransactionInfo,
     TransactionInfo,
 };
+use crate::versioned_constants::{GasCosts, OsConstants, ValidateRoundingConsts};
 
 #[cfg_attr(
     feature = "cairo_native",
@@ -102,6 +110,26 @@ use crate::transaction::objects::{
         "Native: Execute execution mode: block info should be as usual. Transaction V3."
     )
 )]
+#[cfg_attr(
+    feature = "cairo_native",
+    test_case(
+        FeatureContract::TestContract(CairoVersion::Native),
+        ExecutionMode::Execute,
+        TransactionVersion::ONE,
+        true;
+        "Native: Execute execution mode: block info should be as usual. Transaction V1. Query."
+    )
+)]
+#[cfg_attr(
+    feature = "cairo_native",
+    test_case(
+        FeatureContract::TestContract(CairoVersion::Native),
+        ExecutionMode::Execute,
+        TransactionVersion::THREE,
+        true;
+        "Native: Execute execution mode: block info should be as usual. Transaction V3. Query."
+    )
+)]
 #[test_case(
     FeatureContract::TestContract(CairoVersion::Cairo1),
     ExecutionMode::Validate,
@@ -330,3 +358,156 @@ fn str_to_32_bytes_in_hex(s: &str) -> String {
         s.as_bytes().iter().fold(String::new(), |s, byte| s + (&format!("{:02x}", byte)));
     [prefix, &padding_zeros, &word_in_hex].into_iter().collect()
 }
+
+fn block_context_for_validate_rounding(
+    validate_block_number_rounding: u64,
+    validate_timestamp_rounding: u64,
+    block_number: u64,
+    block_timestamp: u64,
+) -> BlockContext {
+    let mut block_context = BlockContext::create_for_testing();
+    block_context.block_info.block_number = BlockNumber(block_number);
+    block_context.block_info.block_timestamp = BlockTimestamp(block_timestamp);
+    block_context.versioned_constants.os_constants = Arc::new(OsConstants {
+        gas_costs: GasCosts::create_for_testing_from_subset("{}"),
+        validate_rounding_consts: ValidateRoundingConsts {
+            validate_block_number_rounding,
+            validate_timestamp_rounding,
+        },
+    });
+    block_context
+}
+
+#[test_case(1, 1, 2001, 1072023, 2001, 1072023; "no rounding")]
+#[test_case(
+    100,
+    3600,
+    CURRENT_BLOCK_NUMBER,
+    CURRENT_BLOCK_TIMESTAMP,
+    CURRENT_BLOCK_NUMBER_FOR_VALIDATE,
+    CURRENT_BLOCK_TIMESTAMP_FOR_VALIDATE;
+    "default rounding"
+)]
+#[test_case(10, 60, 2000, 1069200, 2000, 1069200; "already rounded")]
+#[test_case(1000, 86400, 999, 86399, 0, 0; "below the rounding factors")]
+#[test_case(7, 13, 100, 100, 98, 91; "non-round rounding factors")]
+fn test_validate_block_info_rounding(
+    validate_block_number_rounding: u64,
+    validate_timestamp_rounding: u64,
+    block_number: u64,
+    block_timestamp: u64,
+    expected_block_number: u64,
+    expected_block_timestamp: u64,
+) {
+    let block_context = block_context_for_validate_rounding(
+        validate_block_number_rounding,
+        validate_timestamp_rounding,
+        block_number,
+        block_timestamp,
+    );
+
+    // The block info written by the VM syscall handler in validate mode.
+    let block_info = rounded_block_info_for_validate(
+        block_context.block_info(),
+        block_context.versioned_constants(),
+    );
+    assert_eq!(block_info.block_number, BlockNumber(expected_block_number));
+    assert_eq!(block_info.block_timestamp, BlockTimestamp(expected_block_timestamp));
+    assert_eq!(block_info.sequencer_address, ContractAddress::default());
+
+    // The native syscall handler exposes the same block info.
+    #[cfg(feature = "cairo_native")]
+    {
+        // The syscalls are implemented for a mutable reference to the handler.
+        let tx_context = TransactionContext {
+            block_context,
+            tx_info: TransactionInfo::Current(CurrentTransactionInfo::create_for_testing()),
+        };
+        let (native_block_info, _) = run_native_syscalls_given_tx_context(
+            tx_context,
+            ExecutionMode::Validate,
+            |mut syscall_handler, remaining_gas| {
+                syscall_handler.get_execution_info(remaining_gas).unwrap().block_info
+            },
+        );
+        assert_eq!(native_block_info.block_number, block_info.block_number.0);
+        assert_eq!(native_block_info.block_timestamp, block_info.block_timestamp.0);
+        assert_eq!(native_block_info.sequencer_address, *block_info.sequencer_address.0.key());
+    }
+}
+
+#[cfg(feature = "cairo_native")]
+fn current_tx_info_with_unsupported_fields(only_query: bool) -> TransactionInfo {
+    TransactionInfo::Current(CurrentTransactionInfo {
+        common_fields: CommonAccountFields {
+            version: TransactionVersion::THREE,
+            only_query,
+            ..Default::default()
+        },
+        tip: Tip(7),
+        nonce_data_availability_mode: DataAvailabilityMode::L1,
+        fee_data_availability_mode: DataAvailabilityMode::L2,
+        paymaster_data: PaymasterData(vec![felt!(1_u8), felt!(2_u8)]),
+        account_deployment_data: AccountDeploymentData(vec![felt!(3_u8)]),
+        ..CurrentTransactionInfo::create_for_testing()
+    })
+}
+
+#[cfg(feature = "cairo_native")]
+fn deprecated_tx_info(only_query: bool) -> TransactionInfo {
+    TransactionInfo::Deprecated(DeprecatedTransactionInfo {
+        common_fields: CommonAccountFields {
+            version: TransactionVersion::ONE,
+            only_query,
+            ..Default::default()
+        },
+        max_fee: Fee(42),
+    })
+}
+
+/// The native syscall handler reports the same transaction info as the VM's, which writes the
+/// signed version, the execution info max fee, and zeros for the fields unsupported by deprecated
+/// transactions.
+#[cfg(feature = "cairo_native")]
+#[test_case(current_tx_info_with_unsupported_fields(false); "Current")]
+#[test_case(current_tx_info_with_unsupported_fields(true); "Current. Query")]
+#[test_case(deprecated_tx_info(false); "Deprecated")]
+#[test_case(deprecated_tx_info(true); "Deprecated. Query")]
+fn test_native_tx_v2_info_matches_vm(tx_info: TransactionInfo) {
+    let tx_context =
+        TransactionContext { block_context: BlockContext::create_for_testing(), tx_info };
+    let (native_tx_info, _) = run_native_syscalls_given_tx_context(
+        tx_context.clone(),
+        ExecutionMode::Execute,
+        // The syscalls are implemented for a mutable reference to the handler.
+        |mut syscall_handler, remaining_gas| {
+            syscall_handler.get_execution_info_v2(remaining_gas).unwrap().tx_info
+        },
+    );
+
+    let tx_info = &tx_context.tx_info;
+    assert_eq!(native_tx_info.version, tx_info.signed_version().0);
+    assert_eq!(native_tx_info.account_contract_address, *tx_info.sender_address().0.key());
+    assert_eq!(native_tx_info.max_fee, tx_info.max_fee_for_execution_info_syscall().0);
+    assert_eq!(native_tx_info.transaction_hash, tx_info.transaction_hash().0);
+    assert_eq!(native_tx_info.nonce, tx_info.nonce().0);
+
+    let native_unsupported_fields = (
+        Felt::from(native_tx_info.tip),
+        native_tx_info.paymaster_data,
+        Felt::from(native_tx_info.nonce_data_availability_mode),
+        Felt::from(native_tx_info.fee_data_availability_mode),
+        native_tx_info.account_deployment_data,
+    );
+    let vm_unsupported_fields = match tx_info {
+        TransactionInfo::Current(context) => (
+            Felt::from(context.tip.0),
+            context.paymaster_data.0.clone(),
+            Felt::from(context.nonce_data_availability_mode),
+            Felt::from(context.fee_data_availability_mode),
+            context.account_deployment_data.0.clone(),
+        ),
+        TransactionInfo::Deprecated(_) => (Felt::ZERO, vec![], Felt::ZERO, Felt::ZERO, vec![]),
+    };
+    assert_eq!(native_unsupported_fields, vm_unsupported_fields);
+}
@@ -1,14 +1,29 @@
+use assert_matches::assert_matches;
+#[cfg(feature = "cairo_native")]
+use cairo_native::starknet::{StarknetSyscallHandler, U256};
+#[cfg(feature = "cairo_native")]
+use cairo_vm::types::relocatable::MaybeRelocatable;
+#[cfg(feature = "cairo_native")]
+use num_traits::ToPrimitive;
 use starknet_api::transaction::fields::Calldata;
+use starknet_types_core::felt::Felt;
 use test_case::test_case;
 
 use crate::abi::abi_utils::selector_from_name;
 use crate::context::ChainInfo;
 use crate::execution::call_info::CallExecution;
 use crate::execution::entry_point::CallEntryPoint;
+use crate::execution::syscalls::hint_processor::{SyscallExecutionError, INVALID_INPUT_LENGTH_ERROR};
+#[cfg(feature = "cairo_native")]
+use crate::execution::syscalls::syscall_tests::utils::{run_native_syscalls, run_vm_syscalls};
+#[cfg(feature = "cairo_native")]
+use crate::execution::syscalls::SyscallSelector;
+use crate::execution::syscalls::{keccak_rounds_and_gas_cost, KECCAK_FULL_RATE_IN_WORDS};
 use crate::retdata;
 use crate::test_utils::contracts::FeatureContract;
 use crate::test_utils::initial_test_state::test_state;
 use crate::test_utils::{trivial_external_entry_point_new, CairoVersion, BALANCE};
+use crate::versioned_constants::VersionedConstants;
 
 #[test_case(FeatureContract::TestContract(CairoVersion::Cairo1), 254910; "VM")]
 #[cfg_attr(
@@ -31,3 +46,94 @@ fn test_keccak(test_contract: FeatureContract, expected_gas: u64) {
         CallExecution { gas_consumed: expected_gas, ..CallExecution::from_retdata(retdata![]) }
     );
 }
+
+#[test_case(0, 0; "empty input")]
+#[test_case(KECCAK_FULL_RATE_IN_WORDS, 1; "single full rate")]
+#[test_case(2 * KECCAK_FULL_RATE_IN_WORDS, 2; "two full rates")]
+#[test_case(10 * KECCAK_FULL_RATE_IN_WORDS, 10; "ten full rates")]
+fn test_keccak_rounds_gas_cost(input_length: usize, expected_n_rounds: usize) {
+    let gas_costs = &VersionedConstants::create_for_testing().os_constants.gas_costs;
+
+    let (n_rounds, gas_cost) = keccak_rounds_and_gas_cost(input_length, gas_costs).unwrap();
+
+    assert_eq!(n_rounds, expected_n_rounds);
+    let expected_n_rounds = u64::try_from(expected_n_rounds).unwrap();
+    assert_eq!(gas_cost, expected_n_rounds * gas_costs.keccak_round_cost_gas_cost);
+}
+
+#[test]
+fn test_keccak_rounds_gas_cost_misaligned_input() {
+    let gas_costs = &VersionedConstants::create_for_testing().os_constants.gas_costs;
+
+    assert_matches!(
+        keccak_rounds_and_gas_cost(KECCAK_FULL_RATE_IN_WORDS + 1, gas_costs),
+        Err(SyscallExecutionError::SyscallError { error_data })
+            if error_data == vec![Felt::from_hex(INVALID_INPUT_LENGTH_ERROR).unwrap()]
+    );
+}
+
+// Executes the keccak syscall on the given input with the native syscall handler, and returns its
+// result along with the gas it consumed.
+#[cfg(feature = "cairo_native")]
+fn execute_native_keccak(input: &[u64]) -> (Result<U256, Vec<Felt>>, u128) {
+    // The syscalls are implemented for a mutable reference to the handler.
+    run_native_syscalls(|mut syscall_handler, remaining_gas| {
+        syscall_handler.keccak(input, remaining_gas)
+    })
+}
+
+// Same as `execute_native_keccak`, with the VM syscall handler.
+#[cfg(feature = "cairo_native")]
+fn execute_vm_keccak(input: &[u64]) -> (Result<U256, Vec<Felt>>, u128) {
+    let (result, consumed_gas) = run_vm_syscalls(|runner| {
+        let input_start = runner.vm.add_memory_segment();
+        let input: Vec<MaybeRelocatable> =
+            input.iter().map(|&word| Felt::from(word).into()).collect();
+        let input_end = runner.vm.load_data(input_start, &input).unwrap();
+        let response =
+            runner.execute(SyscallSelector::Keccak, vec![input_start.into(), input_end.into()])?;
+        let read_u128 =
+            |ptr| runner.vm.get_integer(ptr).unwrap().to_u128().expect("Expected a u128 result.");
+        Ok(U256 { lo: read_u128(response), hi: read_u128((response + 1).unwrap()) })
+    });
+    (result, u128::from(consumed_gas))
+}
+
+#[cfg(feature = "cairo_native")]
+fn keccak_test_input(input_length: usize) -> Vec<u64> {
+    (0..input_length).map(|i| u64::try_from(i).unwrap() * 0x0101_0101).collect()
+}
+
+/// The native keccak syscall computes the same hash as the VM's, and charges the same gas: the
+/// syscall gas cost, excluding the pre-charged base cost, and the cost of the rounds.
+#[cfg(feature = "cairo_native")]
+#[test_case(0; "empty input")]
+#[test_case(KECCAK_FULL_RATE_IN_WORDS; "single full rate")]
+#[test_case(2 * KECCAK_FULL_RATE_IN_WORDS; "two full rates")]
+#[test_case(10 * KECCAK_FULL_RATE_IN_WORDS; "ten full rates")]
+fn test_keccak_native_gas_matches_vm(input_length: usize) {
+    let input = keccak_test_input(input_length);
+
+    let (vm_result, vm_consumed_gas) = execute_vm_keccak(&input);
+    let (native_result, native_consumed_gas) = execute_native_keccak(&input);
+
+    assert!(vm_result.is_ok());
+    assert_eq!(native_result, vm_result);
+    assert_eq!(native_consumed_gas, vm_consumed_gas);
+}
+
+/// On a misaligned input, the VM fails the syscall after charging its gas cost (excluding the
+/// pre-charged base cost), and does not charge for any round. The native syscall does the same.
+#[cfg(feature = "cairo_native")]
+#[test_case(1; "shorter than full rate")]
+#[test_case(KECCAK_FULL_RATE_IN_WORDS + 1; "longer than full rate")]
+fn test_keccak_native_misaligned_input_matches_vm(input_length: usize) {
+    let input = keccak_test_input(input_length);
+
+    let (vm_result, vm_consumed_gas) = execute_vm_keccak(&input);
+    let (native_result, native_consumed_gas) = execute_native_keccak(&input);
+
+    assert_eq!(vm_result, Err(vec![Felt::from_hex(INVALID_INPUT_LENGTH_ERROR).unwrap()]));
+    assert_eq!(native_result, vm_result);
+    assert_eq!(native_consumed_gas, vm_consumed_gas);
+}
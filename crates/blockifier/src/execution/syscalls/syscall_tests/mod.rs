@@ -14,3 +14,7 @@ mod secp;
 mod send_message_to_l1;
 mod sha256;
 mod storage_read_write;
+#[cfg(feature = "cairo_native")]
+mod unsupported_syscall;
+#[cfg(feature = "cairo_native")]
+mod utils;
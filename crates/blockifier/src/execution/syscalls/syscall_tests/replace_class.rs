@@ -1,3 +1,5 @@
+#[cfg(feature = "cairo_native")]
+use assert_matches::assert_matches;
 use pretty_assertions::assert_eq;
 use starknet_api::{calldata, felt};
 use test_case::test_case;
@@ -6,6 +8,9 @@ use crate::abi::abi_utils::selector_from_name;
 use crate::context::ChainInfo;
 use crate::execution::call_info::CallExecution;
 use crate::execution::entry_point::CallEntryPoint;
+#[cfg(feature = "cairo_native")]
+use crate::execution::errors::EntryPointExecutionError;
+use crate::execution::syscalls::hint_processor::SyscallExecutionError;
 use crate::state::state_api::StateReader;
 use crate::test_utils::contracts::FeatureContract;
 use crate::test_utils::initial_test_state::test_state;
@@ -52,7 +57,45 @@ fn cairo0_class_hash(test_contract: FeatureContract) {
     };
     let error = entry_point_call.execute_directly(&mut state).unwrap_err();
 
-    assert!(error.to_string().contains("Cannot replace V1 class hash with V0 class hash"));
+    let expected_error = SyscallExecutionError::ForbiddenClassReplacement {
+        storage_address: test_contract.get_instance_address(0),
+        class_hash: v0_class_hash,
+        class_version: 0,
+    };
+    assert!(error.to_string().contains(&expected_error.to_string()));
+}
+
+#[cfg(feature = "cairo_native")]
+#[test]
+fn cairo0_class_hash_native_error_context() {
+    let test_contract = FeatureContract::TestContract(CairoVersion::Native);
+    let empty_contract_cairo0 = FeatureContract::Empty(CairoVersion::Cairo0);
+    let mut state = test_state(
+        &ChainInfo::create_for_testing(),
+        BALANCE,
+        &[(test_contract, 1), (empty_contract_cairo0, 0)],
+    );
+    let v0_class_hash = empty_contract_cairo0.get_class_hash();
+
+    let entry_point_call = CallEntryPoint {
+        calldata: calldata![v0_class_hash.0],
+        entry_point_selector: selector_from_name("test_replace_class"),
+        ..trivial_external_entry_point_new(test_contract)
+    };
+    let error = entry_point_call.execute_directly(&mut state).unwrap_err();
+
+    let EntryPointExecutionError::NativeUnrecoverableError(error) = error else {
+        panic!("Expected a native unrecoverable error, got: {error:?}.");
+    };
+    assert_matches!(
+        *error,
+        SyscallExecutionError::ForbiddenClassReplacement {
+            storage_address,
+            class_hash,
+            class_version: 0,
+        } if storage_address == test_contract.get_instance_address(0)
+            && class_hash == v0_class_hash
+    );
 }
 
 #[cfg_attr(
@@ -1,15 +1,37 @@
+#[cfg(feature = "cairo_native")]
+use cairo_native::starknet::StarknetSyscallHandler;
+#[cfg(feature = "cairo_native")]
+use cairo_vm::types::relocatable::MaybeRelocatable;
+#[cfg(feature = "cairo_native")]
+use num_traits::ToPrimitive;
+use sha2::{Digest, Sha256};
 use starknet_api::transaction::fields::Calldata;
+#[cfg(feature = "cairo_native")]
+use starknet_types_core::felt::Felt;
 use test_case::test_case;
 
 use crate::abi::abi_utils::selector_from_name;
 use crate::context::ChainInfo;
 use crate::execution::call_info::CallExecution;
 use crate::execution::entry_point::CallEntryPoint;
+#[cfg(feature = "cairo_native")]
+use crate::execution::syscalls::syscall_tests::utils::{run_native_syscalls, run_vm_syscalls};
+#[cfg(feature = "cairo_native")]
+use crate::execution::syscalls::SyscallSelector;
+use crate::execution::syscalls::{
+    sha256_process_block_in_place,
+    SHA256_BLOCK_SIZE,
+    SHA256_STATE_SIZE,
+};
 use crate::retdata;
 use crate::test_utils::contracts::FeatureContract;
 use crate::test_utils::initial_test_state::test_state;
 use crate::test_utils::{trivial_external_entry_point_new, CairoVersion, BALANCE};
 
+const SHA256_INITIAL_STATE: [u32; SHA256_STATE_SIZE] = [
+    0x6a09e667, 0xbb67ae85, 0x3c6ef372, 0xa54ff53a, 0x510e527f, 0x9b05688c, 0x1f83d9ab, 0x5be0cd19,
+];
+
 #[cfg_attr(
     feature = "cairo_native",
     test_case(FeatureContract::TestContract(CairoVersion::Native), 890255; "Native")
@@ -31,3 +53,96 @@ fn test_sha256(test_contract: FeatureContract, gas_consumed: u64) {
         CallExecution { gas_consumed, ..CallExecution::from_retdata(retdata![]) }
     );
 }
+
+/// Pads the message as specified by SHA-256, and splits it into blocks of big-endian words.
+fn sha256_padded_blocks(message: &[u8]) -> Vec<[u32; SHA256_BLOCK_SIZE]> {
+    let message_length_in_bits = u64::try_from(message.len() * 8).unwrap();
+    let mut padded_message = message.to_vec();
+    padded_message.push(0x80);
+    while padded_message.len() % 64 != 56 {
+        padded_message.push(0);
+    }
+    padded_message.extend(message_length_in_bits.to_be_bytes());
+
+    padded_message
+        .chunks_exact(64)
+        .map(|block| {
+            core::array::from_fn(|i| {
+                u32::from_be_bytes(block[4 * i..4 * (i + 1)].try_into().unwrap())
+            })
+        })
+        .collect()
+}
+
+fn state_to_digest(state: &[u32; SHA256_STATE_SIZE]) -> Vec<u8> {
+    state.iter().flat_map(|word| word.to_be_bytes()).collect()
+}
+
+fn test_message(length: usize) -> Vec<u8> {
+    (0..length).map(|i| u8::try_from(i % 251).unwrap()).collect()
+}
+
+/// Chaining the block processing over a padded message, updating the state in place as the VM
+/// does between calls, yields the message digest.
+#[test_case(0; "empty message")]
+#[test_case(55; "single block")]
+#[test_case(56; "padding overflows to a second block")]
+#[test_case(1000; "many blocks")]
+fn test_sha256_process_block_chaining(message_length: usize) {
+    let message = test_message(message_length);
+
+    let mut state = SHA256_INITIAL_STATE;
+    for block in sha256_padded_blocks(&message) {
+        sha256_process_block_in_place(&mut state, &block);
+    }
+
+    assert_eq!(state_to_digest(&state), Sha256::digest(&message).to_vec());
+}
+
+/// Chaining the native syscall over a multi-block message, which updates `prev_state` in place,
+/// yields the same final state as chaining the VM's, which writes each new state to a new location,
+/// and charges the same gas.
+#[cfg(feature = "cairo_native")]
+#[test_case(1; "single block")]
+#[test_case(16; "many blocks")]
+fn test_sha256_native_chaining_matches_vm(n_blocks: usize) {
+    // Leaves room for the padding in the last block.
+    let message = test_message(n_blocks * 64 - 9);
+    let blocks = sha256_padded_blocks(&message);
+    assert_eq!(blocks.len(), n_blocks);
+
+    let (vm_state, vm_consumed_gas) = run_vm_syscalls(|runner| {
+        let mut state_ptr = runner.vm.add_memory_segment();
+        runner.vm.load_data(state_ptr, &words_to_memory(&SHA256_INITIAL_STATE)).unwrap();
+        for block in &blocks {
+            let input_start = runner.vm.add_memory_segment();
+            runner.vm.load_data(input_start, &words_to_memory(block)).unwrap();
+            let response = runner
+                .execute(
+                    SyscallSelector::Sha256ProcessBlock,
+                    vec![state_ptr.into(), input_start.into()],
+                )
+                .unwrap();
+            state_ptr = runner.vm.get_relocatable(response).unwrap();
+        }
+        let state = runner.vm.get_integer_range(state_ptr, SHA256_STATE_SIZE).unwrap();
+        core::array::from_fn(|i| state[i].to_u32().unwrap())
+    });
+    let (native_state, native_consumed_gas) =
+        run_native_syscalls(|mut syscall_handler, remaining_gas| {
+            let mut state = SHA256_INITIAL_STATE;
+            for block in &blocks {
+                syscall_handler.sha256_process_block(&mut state, block, remaining_gas).unwrap();
+            }
+            state
+        });
+
+    assert_eq!(state_to_digest(&vm_state), Sha256::digest(&message).to_vec());
+    assert_eq!(native_state, vm_state);
+    assert_eq!(native_consumed_gas, u128::from(vm_consumed_gas));
+}
+
+#[cfg(feature = "cairo_native")]
+fn words_to_memory(words: &[u32]) -> Vec<MaybeRelocatable> {
+    words.iter().map(|&word| Felt::from(word).into()).collect()
+}
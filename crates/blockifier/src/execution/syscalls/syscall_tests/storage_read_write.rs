@@ -10,6 +10,7 @@ use crate::execution::syscalls::syscall_tests::constants::REQUIRED_GAS_STORAGE_R
 use crate::retdata;
 use crate::state::state_api::StateReader;
 use crate::test_utils::contracts::FeatureContract;
+use crate::test_utils::execution_diff::{run_both_and_compare, ExecutionDiff};
 use crate::test_utils::initial_test_state::test_state;
 use crate::test_utils::{trivial_external_entry_point_new, CairoVersion, BALANCE};
 
@@ -45,3 +46,35 @@ fn test_storage_read_write(test_contract: FeatureContract, expected_gas: u64) {
         state.get_storage_at(storage_address, StorageKey::try_from(key).unwrap()).unwrap();
     assert_eq!(value_from_state, value);
 }
+
+#[test]
+fn test_storage_read_write_matches_vm() {
+    assert_storage_read_write_matches_vm(FeatureContract::TestContract(CairoVersion::Cairo1));
+}
+
+// Native consumes 25920 gas where the VM consumes REQUIRED_GAS_STORAGE_READ_WRITE_TEST, see
+// test_storage_read_write.
+#[cfg(feature = "cairo_native")]
+#[ignore = "Native gas accounting of the storage syscalls does not match the VM yet."]
+#[test]
+fn test_storage_read_write_native_matches_vm() {
+    assert_storage_read_write_matches_vm(FeatureContract::TestContract(CairoVersion::Native));
+}
+
+fn assert_storage_read_write_matches_vm(test_contract: FeatureContract) {
+    let entry_point_call = CallEntryPoint {
+        calldata: calldata![felt!(1234_u16), felt!(18_u8)],
+        entry_point_selector: selector_from_name("test_storage_read_write"),
+        ..trivial_external_entry_point_new(test_contract)
+    };
+    let vm_contract = FeatureContract::TestContract(CairoVersion::Cairo1);
+
+    assert_eq!(
+        run_both_and_compare(
+            entry_point_call,
+            (test_contract, vm_contract),
+            &ChainInfo::create_for_testing()
+        ),
+        ExecutionDiff::default()
+    );
+}
@@ -0,0 +1,45 @@
+use std::sync::Arc;
+
+use cairo_vm::vm::runners::cairo_runner::ExecutionResources;
+
+use crate::abi::abi_utils::selector_from_name;
+use crate::context::{BlockContext, ChainInfo, TransactionContext};
+use crate::execution::entry_point::{CallEntryPoint, EntryPointExecutionContext};
+use crate::test_utils::contracts::FeatureContract;
+use crate::test_utils::initial_test_state::test_state;
+use crate::test_utils::{trivial_external_entry_point_new, CairoVersion, BALANCE};
+use crate::transaction::objects::{CurrentTransactionInfo, TransactionInfo};
+use crate::versioned_constants::{VersionedConstants, VersionedConstantsOverrides};
+
+fn execution_context() -> EntryPointExecutionContext {
+    let mut block_context = BlockContext::create_for_testing();
+    block_context.versioned_constants =
+        VersionedConstants::get_versioned_constants(VersionedConstantsOverrides {
+            unsupported_syscalls_are_recoverable: true,
+            ..Default::default()
+        });
+    let tx_context = TransactionContext {
+        block_context,
+        tx_info: TransactionInfo::Current(CurrentTransactionInfo::create_for_testing()),
+    };
+    EntryPointExecutionContext::new_invoke(Arc::new(tx_context), false).unwrap()
+}
+
+/// The native syscall handler does not implement the secp256k1 syscalls yet.
+#[test]
+fn test_unsupported_syscall_in_native() {
+    let test_contract = FeatureContract::TestContract(CairoVersion::Native);
+    let chain_info = &ChainInfo::create_for_testing();
+    let mut state = test_state(chain_info, BALANCE, &[(test_contract, 1)]);
+    let mut context = execution_context();
+    let entry_point_call = CallEntryPoint {
+        entry_point_selector: selector_from_name("test_secp256k1"),
+        ..trivial_external_entry_point_new(test_contract)
+    };
+
+    let mut remaining_gas = entry_point_call.initial_gas;
+    let error = entry_point_call
+        .execute(&mut state, &mut ExecutionResources::default(), &mut context, &mut remaining_gas)
+        .unwrap_err();
+    assert_eq!(error.to_string(), "Unsupported syscall: Secp256k1New.");
+}
@@ -0,0 +1,141 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use cairo_lang_casm::hints::StarknetHint;
+use cairo_lang_casm::operand::{CellRef, Register, ResOperand};
+use cairo_vm::types::relocatable::{MaybeRelocatable, Relocatable};
+use cairo_vm::vm::runners::cairo_runner::ExecutionResources;
+use cairo_vm::vm::vm_core::VirtualMachine;
+use num_traits::ToPrimitive;
+use starknet_types_core::felt::Felt;
+
+use crate::context::{BlockContext, ChainInfo, TransactionContext};
+use crate::execution::common_hints::ExecutionMode;
+use crate::execution::entry_point::{EntryPointExecutionContextBuilder, EntryPointRevertInfo};
+use crate::execution::execution_utils::ReadOnlySegments;
+use crate::execution::native::syscall_handler::NativeSyscallHandler;
+use crate::execution::syscalls::hint_processor::SyscallHintProcessor;
+use crate::execution::syscalls::SyscallSelector;
+use crate::test_utils::contracts::FeatureContract;
+use crate::test_utils::initial_test_state::test_state;
+use crate::test_utils::{trivial_external_entry_point_new, CairoVersion, BALANCE};
+use crate::transaction::objects::{CurrentTransactionInfo, TransactionInfo};
+
+/// Runs the given syscalls with a native syscall handler of a call to the test contract, starting
+/// with the default initial gas. Returns their result along with the gas they consumed.
+pub fn run_native_syscalls<R>(
+    syscalls: impl FnOnce(&mut NativeSyscallHandler<'_>, &mut u128) -> R,
+) -> (R, u128) {
+    let tx_context = TransactionContext {
+        block_context: BlockContext::create_for_testing(),
+        tx_info: TransactionInfo::Current(CurrentTransactionInfo::create_for_testing()),
+    };
+    run_native_syscalls_given_tx_context(tx_context, ExecutionMode::Execute, syscalls)
+}
+
+/// Same as [`run_native_syscalls`], in the given transaction context and execution mode.
+pub fn run_native_syscalls_given_tx_context<R>(
+    tx_context: TransactionContext,
+    execution_mode: ExecutionMode,
+    syscalls: impl FnOnce(&mut NativeSyscallHandler<'_>, &mut u128) -> R,
+) -> (R, u128) {
+    let test_contract = FeatureContract::TestContract(CairoVersion::Native);
+    let mut state = test_state(&ChainInfo::create_for_testing(), BALANCE, &[(test_contract, 1)]);
+    let mut context = EntryPointExecutionContextBuilder::new(Arc::new(tx_context))
+        .execution_mode(execution_mode)
+        .build()
+        .unwrap();
+    let initial_gas = u128::from(context.gas_costs().default_initial_gas_cost);
+    let mut resources = ExecutionResources::default();
+    let mut syscall_handler = NativeSyscallHandler::new(
+        trivial_external_entry_point_new(test_contract),
+        &mut state,
+        &mut resources,
+        &mut context,
+    );
+
+    let mut remaining_gas = initial_gas;
+    let result = syscalls(&mut syscall_handler, &mut remaining_gas);
+    (result, initial_gas - remaining_gas)
+}
+
+/// Executes syscalls with a VM syscall handler the way the VM does on a syscall hint: the request
+/// is written to the syscall segment, and the response is read from it.
+pub struct VmSyscallRunner<'a> {
+    pub vm: VirtualMachine,
+    pub syscall_handler: SyscallHintProcessor<'a>,
+    pub remaining_gas: u64,
+}
+
+impl VmSyscallRunner<'_> {
+    /// Executes the syscall with the given selector and request. Returns a pointer to its response,
+    /// or its error data if it failed.
+    pub fn execute(
+        &mut self,
+        selector: SyscallSelector,
+        request: Vec<MaybeRelocatable>,
+    ) -> Result<Relocatable, Vec<Felt>> {
+        let mut syscall: Vec<MaybeRelocatable> = vec![
+            Felt::from_bytes_be_slice(format!("{selector:?}").as_bytes()).into(),
+            Felt::from(self.remaining_gas).into(),
+        ];
+        syscall.extend(request);
+        let response_ptr = self.vm.load_data(self.syscall_handler.syscall_ptr, &syscall).unwrap();
+        // The syscall pointer operand is not used by the handler.
+        let hint = StarknetHint::SystemCall {
+            system: ResOperand::Deref(CellRef { register: Register::FP, offset: 0 }),
+        };
+        self.syscall_handler.execute_next_syscall(&mut self.vm, &hint).unwrap();
+
+        let read_felt = |vm: &VirtualMachine, offset: usize| {
+            vm.get_integer((response_ptr + offset).unwrap()).unwrap().into_owned()
+        };
+        self.remaining_gas = read_felt(&self.vm, 0).to_u64().unwrap();
+        if read_felt(&self.vm, 1) == Felt::ZERO {
+            return Ok((response_ptr + 2).unwrap());
+        }
+        let error_start = self.vm.get_relocatable((response_ptr + 2).unwrap()).unwrap();
+        let error_end = self.vm.get_relocatable((response_ptr + 3).unwrap()).unwrap();
+        let error_data =
+            self.vm.get_integer_range(error_start, (error_end - error_start).unwrap()).unwrap();
+        Err(error_data.into_iter().map(|felt| felt.into_owned()).collect())
+    }
+}
+
+/// Runs the given syscalls with a VM syscall handler of a call to the test contract, starting with
+/// the default initial gas. Returns their result along with the gas they consumed.
+pub fn run_vm_syscalls<R>(syscalls: impl FnOnce(&mut VmSyscallRunner<'_>) -> R) -> (R, u64) {
+    let test_contract = FeatureContract::TestContract(CairoVersion::Cairo1);
+    let mut state = test_state(&ChainInfo::create_for_testing(), BALANCE, &[(test_contract, 1)]);
+    let tx_context = TransactionContext {
+        block_context: BlockContext::create_for_testing(),
+        tx_info: TransactionInfo::Current(CurrentTransactionInfo::create_for_testing()),
+    };
+    let mut context = EntryPointExecutionContextBuilder::new(Arc::new(tx_context)).build().unwrap();
+    let call = trivial_external_entry_point_new(test_contract);
+    // Pushed on execution of the call, and taken by the syscall handler.
+    context.revert_infos.0.push(EntryPointRevertInfo::new(
+        call.storage_address,
+        test_contract.get_class_hash(),
+        0,
+        0,
+    ));
+    let initial_gas = context.gas_costs().default_initial_gas_cost;
+    let mut resources = ExecutionResources::default();
+    let hints = HashMap::new();
+    let mut vm = VirtualMachine::new(false);
+    let initial_syscall_ptr = vm.add_memory_segment();
+    let syscall_handler = SyscallHintProcessor::new(
+        &mut state,
+        &mut resources,
+        &mut context,
+        initial_syscall_ptr,
+        call,
+        &hints,
+        ReadOnlySegments::default(),
+    );
+
+    let mut runner = VmSyscallRunner { vm, syscall_handler, remaining_gas: initial_gas };
+    let result = syscalls(&mut runner);
+    (result, initial_gas - runner.remaining_gas)
+}
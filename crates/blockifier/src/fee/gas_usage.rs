@@ -10,6 +10,7 @@ use crate::fee::eth_gas_constants;
 use crate::state::cached_state::StateChangesCount;
 use crate::transaction::account_transaction::AccountTransaction;
 use crate::utils::u64_from_usize;
+use crate::versioned_constants::OS_RESOURCES_OF_ALL_TX_TYPES;
 
 #[cfg(test)]
 #[path = "gas_usage_test.rs"]
@@ -187,9 +188,11 @@ pub fn estimate_minimal_gas_vector(
     };
 
     let data_segment_length = get_onchain_data_segment_length(&state_changes_by_account_tx);
-    let os_steps_for_type =
-        versioned_constants.os_resources_for_tx_type(&tx.tx_type(), tx.calldata_length()).n_steps
-            + versioned_constants.os_kzg_da_resources(data_segment_length).n_steps;
+    let os_steps_for_type = versioned_constants
+        .os_resources_for_tx_type(&tx.tx_type(), tx.calldata_length())
+        .expect(OS_RESOURCES_OF_ALL_TX_TYPES)
+        .n_steps
+        + versioned_constants.os_kzg_da_resources(data_segment_length).n_steps;
 
     let resources = ExecutionResources { n_steps: os_steps_for_type, ..Default::default() };
     let da_gas_cost = get_da_gas_cost(&state_changes_by_account_tx, block_info.use_kzg_da);
@@ -17,6 +17,7 @@ use crate::fee::eth_gas_constants;
 use crate::fee::fee_utils::get_fee_by_gas_vector;
 use crate::fee::gas_usage::{get_da_gas_cost, get_message_segment_length};
 use crate::fee::resources::{
+    ArchivalDataGasBreakdown,
     ComputationResources,
     StarknetResources,
     StateResources,
@@ -79,6 +80,55 @@ fn starknet_resources() -> StarknetResources {
     StarknetResources::new(2_usize, 3_usize, 4_usize, state_resources, 6.into(), execution_summary)
 }
 
+#[rstest]
+fn test_archival_data_gas_breakdown(
+    versioned_constants: &VersionedConstants,
+    starknet_resources: StarknetResources,
+) {
+    let breakdown_and_total = |mode: GasVectorComputationMode| {
+        let breakdown = starknet_resources.archival_data_gas_breakdown(versioned_constants, &mode);
+        let archival_gas_costs = versioned_constants.get_archival_data_gas_costs(&mode);
+        // 2 calldata felts and 3 signature felts, 4 code bytes, and 3 event keys and 5 data words.
+        let expected_breakdown = ArchivalDataGasBreakdown {
+            calldata_and_signature: (archival_gas_costs.gas_per_data_felt * 5_u64)
+                .to_integer()
+                .into(),
+            code: (archival_gas_costs.gas_per_code_byte * 4_u64).to_integer().into(),
+            events: (archival_gas_costs.gas_per_data_felt
+                * (archival_gas_costs.event_key_factor * 3_u64 + 5_u64))
+                .to_integer()
+                .into(),
+        };
+        assert_eq!(breakdown, expected_breakdown);
+        let total = breakdown.total();
+        assert_eq!(
+            total,
+            GasAmount(breakdown.calldata_and_signature.0 + breakdown.code.0 + breakdown.events.0)
+        );
+        (breakdown, total)
+    };
+
+    let (_, l2_gas_total) = breakdown_and_total(GasVectorComputationMode::All);
+    let (_, l1_gas_total) = breakdown_and_total(GasVectorComputationMode::NoL2Gas);
+
+    // The totals are the archival data part of the Starknet resources gas vector, in L2 gas units
+    // when L2 gas is computed, and in L1 gas units otherwise.
+    assert_eq!(
+        starknet_resources
+            .archival_data
+            .to_gas_vector(versioned_constants, &GasVectorComputationMode::All),
+        GasVector::from_l2_gas(l2_gas_total)
+    );
+    assert_eq!(
+        starknet_resources
+            .archival_data
+            .to_gas_vector(versioned_constants, &GasVectorComputationMode::NoL2Gas),
+        GasVector::from_l1_gas(l1_gas_total)
+    );
+    // L2 gas units are cheaper than L1 gas units.
+    assert!(l2_gas_total > l1_gas_total);
+}
+
 #[rstest]
 fn test_get_event_gas_cost(
     versioned_constants: &VersionedConstants,
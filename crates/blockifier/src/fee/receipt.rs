@@ -15,6 +15,7 @@ use crate::state::cached_state::StateChanges;
 use crate::transaction::account_transaction::AccountTransaction;
 use crate::transaction::objects::HasRelatedFeeType;
 use crate::transaction::transaction_types::TransactionType;
+use crate::versioned_constants::OS_RESOURCES_OF_ALL_TX_TYPES;
 
 #[cfg(test)]
 #[path = "receipt_test.rs"]
@@ -73,11 +74,15 @@ impl TransactionReceipt {
         );
 
         let cairo_resources = (execution_resources
-            + &tx_context.block_context.versioned_constants.get_additional_os_tx_resources(
-                tx_type,
-                &starknet_resources,
-                tx_context.block_context.block_info.use_kzg_da,
-            ))
+            + &tx_context
+                .block_context
+                .versioned_constants
+                .get_additional_os_tx_resources(
+                    tx_type,
+                    &starknet_resources,
+                    tx_context.block_context.block_info.use_kzg_da,
+                )
+                .expect(OS_RESOURCES_OF_ALL_TX_TYPES))
             .filter_unused_builtins();
 
         let tx_resources = TransactionResources {
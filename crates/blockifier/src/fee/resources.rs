@@ -137,6 +137,16 @@ impl StarknetResources {
             })
         })
     }
+
+    /// Returns the gas cost of the archival data resources, broken down by component. The amounts
+    /// are given in L1/L2 gas units, depending on the mode.
+    pub fn archival_data_gas_breakdown(
+        &self,
+        versioned_constants: &VersionedConstants,
+        mode: &GasVectorComputationMode,
+    ) -> ArchivalDataGasBreakdown {
+        self.archival_data.gas_breakdown(versioned_constants, mode)
+    }
 }
 
 #[cfg_attr(feature = "transaction_serde", derive(serde::Serialize, serde::Deserialize))]
@@ -182,6 +192,30 @@ pub struct ArchivalDataResources {
     code_size: usize,
 }
 
+/// The gas cost of a transaction's archival data, by component, in L1/L2 gas units.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct ArchivalDataGasBreakdown {
+    pub calldata_and_signature: GasAmount,
+    pub code: GasAmount,
+    pub events: GasAmount,
+}
+
+impl ArchivalDataGasBreakdown {
+    pub fn total(&self) -> GasAmount {
+        [self.calldata_and_signature, self.code, self.events].into_iter().fold(
+            GasAmount::ZERO,
+            |accumulator, cost| {
+                accumulator.checked_add(cost).unwrap_or_else(|| {
+                    panic!(
+                        "Archival data resources to gas vector overflowed: tried to add \
+                         {accumulator:?} gas to {cost:?} gas.",
+                    )
+                })
+            },
+        )
+    }
+}
+
 impl ArchivalDataResources {
     /// Returns the cost of the transaction's archival data, for example, calldata, signature, code,
     /// and events.
@@ -190,6 +224,19 @@ impl ArchivalDataResources {
         versioned_constants: &VersionedConstants,
         mode: &GasVectorComputationMode,
     ) -> GasVector {
+        let gas_amount = self.gas_breakdown(versioned_constants, mode).total();
+        match mode {
+            GasVectorComputationMode::All => GasVector::from_l2_gas(gas_amount),
+            GasVectorComputationMode::NoL2Gas => GasVector::from_l1_gas(gas_amount),
+        }
+    }
+
+    /// Returns the cost of each component of the transaction's archival data.
+    pub fn gas_breakdown(
+        &self,
+        versioned_constants: &VersionedConstants,
+        mode: &GasVectorComputationMode,
+    ) -> ArchivalDataGasBreakdown {
         let archival_gas_costs = match mode {
             // Computation is in L2 gas units.
             GasVectorComputationMode::All => &versioned_constants.archival_data_gas_costs,
@@ -198,23 +245,10 @@ impl ArchivalDataResources {
                 &versioned_constants.deprecated_l2_resource_gas_costs
             }
         };
-        let gas_amount = [
-            self.get_calldata_and_signature_gas_cost(archival_gas_costs),
-            self.get_code_gas_cost(archival_gas_costs),
-            self.get_events_gas_cost(archival_gas_costs),
-        ]
-        .into_iter()
-        .fold(GasAmount::ZERO, |accumulator, cost| {
-            accumulator.checked_add(cost).unwrap_or_else(|| {
-                panic!(
-                    "Archival data resources to gas vector overflowed: tried to add \
-                     {accumulator:?} gas to {cost:?} gas.",
-                )
-            })
-        });
-        match mode {
-            GasVectorComputationMode::All => GasVector::from_l2_gas(gas_amount),
-            GasVectorComputationMode::NoL2Gas => GasVector::from_l1_gas(gas_amount),
+        ArchivalDataGasBreakdown {
+            calldata_and_signature: self.get_calldata_and_signature_gas_cost(archival_gas_costs),
+            code: self.get_code_gas_cost(archival_gas_costs),
+            events: self.get_events_gas_cost(archival_gas_costs),
         }
     }
 
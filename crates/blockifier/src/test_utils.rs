@@ -3,6 +3,7 @@ pub mod contracts;
 pub mod declare;
 pub mod deploy_account;
 pub mod dict_state_reader;
+pub mod execution_diff;
 pub mod initial_test_state;
 pub mod invoke;
 pub mod l1_handler;
@@ -363,7 +364,7 @@ macro_rules! check_tx_execution_error_for_invalid_scenario {
 pub fn get_syscall_resources(syscall_selector: SyscallSelector) -> ExecutionResources {
     let versioned_constants = VersionedConstants::create_for_testing();
     let syscall_counter: SyscallCounter = HashMap::from([(syscall_selector, 1)]);
-    versioned_constants.get_additional_os_syscall_resources(&syscall_counter)
+    versioned_constants.get_additional_os_syscall_resources(&syscall_counter).unwrap()
 }
 
 pub fn get_tx_resources(tx_type: TransactionType) -> ExecutionResources {
@@ -377,7 +378,7 @@ pub fn get_tx_resources(tx_type: TransactionType) -> ExecutionResources {
         ExecutionSummary::default(),
     );
 
-    versioned_constants.get_additional_os_tx_resources(tx_type, &starknet_resources, false)
+    versioned_constants.get_additional_os_tx_resources(tx_type, &starknet_resources, false).unwrap()
 }
 
 /// Creates the calldata for the Cairo function "test_deploy" in the featured contract TestContract.
@@ -0,0 +1,73 @@
+use std::collections::HashSet;
+
+use starknet_api::state::StorageKey;
+
+use crate::context::ChainInfo;
+use crate::execution::call_info::{CallInfo, OrderedEvent, OrderedL2ToL1Message, Retdata};
+use crate::execution::entry_point::CallEntryPoint;
+use crate::test_utils::contracts::FeatureContract;
+use crate::test_utils::initial_test_state::test_state;
+use crate::test_utils::BALANCE;
+
+/// The differences between two executions of the same call, e.g., through Cairo native and
+/// through the VM. Each field holds the (first, second) values if they diverge, and `None`
+/// otherwise.
+#[derive(Debug, Default, PartialEq)]
+pub struct ExecutionDiff {
+    pub retdata: Option<(Retdata, Retdata)>,
+    pub gas_consumed: Option<(u64, u64)>,
+    pub events: Option<(Vec<OrderedEvent>, Vec<OrderedEvent>)>,
+    pub l2_to_l1_messages: Option<(Vec<OrderedL2ToL1Message>, Vec<OrderedL2ToL1Message>)>,
+    pub accessed_storage_keys: Option<(HashSet<StorageKey>, HashSet<StorageKey>)>,
+}
+
+impl ExecutionDiff {
+    pub fn new(first: &CallInfo, second: &CallInfo) -> Self {
+        Self {
+            retdata: diff(&first.execution.retdata, &second.execution.retdata),
+            gas_consumed: diff(&first.execution.gas_consumed, &second.execution.gas_consumed),
+            events: diff(&first.execution.events, &second.execution.events),
+            l2_to_l1_messages: diff(
+                &first.execution.l2_to_l1_messages,
+                &second.execution.l2_to_l1_messages,
+            ),
+            accessed_storage_keys: diff(
+                &first.accessed_storage_keys,
+                &second.accessed_storage_keys,
+            ),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self == &Self::default()
+    }
+}
+
+fn diff<T: Clone + PartialEq>(first: &T, second: &T) -> Option<(T, T)> {
+    if first == second { None } else { Some((first.clone(), second.clone())) }
+}
+
+/// Executes the given call on each contract of the pair (typically, the native and the VM
+/// variants of the same contract), each deployed alone in a fresh test state, and returns the
+/// differences between the two executions.
+/// The code and storage addresses of the call are overridden by the address of the executed
+/// contract's instance.
+pub fn run_both_and_compare(
+    entry_point_call: CallEntryPoint,
+    (native_contract, vm_contract): (FeatureContract, FeatureContract),
+    chain_info: &ChainInfo,
+) -> ExecutionDiff {
+    let execute = |contract: FeatureContract| {
+        let mut state = test_state(chain_info, BALANCE, &[(contract, 1)]);
+        let contract_address = contract.get_instance_address(0);
+        CallEntryPoint {
+            code_address: Some(contract_address),
+            storage_address: contract_address,
+            ..entry_point_call.clone()
+        }
+        .execute_directly(&mut state)
+        .unwrap_or_else(|error| panic!("Execution of {contract:?} failed: {error:?}"))
+    };
+
+    ExecutionDiff::new(&execute(native_contract), &execute(vm_contract))
+}
@@ -75,7 +75,8 @@ fn fee_transfer_resources(
                 Arc::new(block_context.to_tx_context(&account_invoke_tx(InvokeTxArgs::default()))),
                 ExecutionMode::Execute,
                 false,
-            ),
+            )
+            .unwrap(),
             &mut remaining_gas,
         )
         .unwrap()
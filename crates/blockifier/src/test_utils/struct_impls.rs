@@ -27,7 +27,7 @@ use crate::execution::common_hints::ExecutionMode;
 use crate::execution::contract_class::ContractClassV1;
 use crate::execution::entry_point::{
     CallEntryPoint,
-    EntryPointExecutionContext,
+    EntryPointExecutionContextBuilder,
     EntryPointExecutionResult,
 };
 #[cfg(feature = "cairo_native")]
@@ -77,11 +77,11 @@ impl CallEntryPoint {
     ) -> EntryPointExecutionResult<CallInfo> {
         let tx_context =
             TransactionContext { block_context: BlockContext::create_for_testing(), tx_info };
-        let mut context = EntryPointExecutionContext::new(
-            Arc::new(tx_context),
-            execution_mode,
-            limit_steps_by_resources,
-        );
+        let mut context = EntryPointExecutionContextBuilder::new(Arc::new(tx_context))
+            .execution_mode(execution_mode)
+            .limit_steps_by_resources(limit_steps_by_resources)
+            .build()
+            .unwrap();
         let mut remaining_gas = self.initial_gas;
         self.execute(state, &mut ExecutionResources::default(), &mut context, &mut remaining_gas)
     }
@@ -1034,7 +1034,8 @@ fn test_max_fee_computation_from_tx_bounds(block_context: BlockContext) {
     macro_rules! assert_max_steps_as_expected {
         ($account_tx:expr, $expected_max_steps:expr $(,)?) => {
             let tx_context = Arc::new(block_context.to_tx_context(&$account_tx));
-            let execution_context = EntryPointExecutionContext::new_invoke(tx_context, true);
+            let execution_context =
+                EntryPointExecutionContext::new_invoke(tx_context, true).unwrap();
             let max_steps = execution_context.vm_run_resources.get_n_steps().unwrap();
             assert_eq!(u64::try_from(max_steps).unwrap(), $expected_max_steps);
         };
@@ -1125,7 +1126,7 @@ fn test_max_fee_to_max_steps_conversion(
         nonce: nonce_manager.next(account_address),
     });
     let tx_context1 = Arc::new(block_context.to_tx_context(&account_tx1));
-    let execution_context1 = EntryPointExecutionContext::new_invoke(tx_context1, true);
+    let execution_context1 = EntryPointExecutionContext::new_invoke(tx_context1, true).unwrap();
     let max_steps_limit1 = execution_context1.vm_run_resources.get_n_steps();
     let tx_execution_info1 = account_tx1.execute(&mut state, &block_context, true, true).unwrap();
     let n_steps1 = tx_execution_info1.receipt.resources.computation.vm_resources.n_steps;
@@ -1146,7 +1147,7 @@ fn test_max_fee_to_max_steps_conversion(
         nonce: nonce_manager.next(account_address),
     });
     let tx_context2 = Arc::new(block_context.to_tx_context(&account_tx2));
-    let execution_context2 = EntryPointExecutionContext::new_invoke(tx_context2, true);
+    let execution_context2 = EntryPointExecutionContext::new_invoke(tx_context2, true).unwrap();
     let max_steps_limit2 = execution_context2.vm_run_resources.get_n_steps();
     let tx_execution_info2 = account_tx2.execute(&mut state, &block_context, true, true).unwrap();
     let n_steps2 = tx_execution_info2.receipt.resources.computation.vm_resources.n_steps;
@@ -4,12 +4,12 @@ use starknet_api::block::GasPrice;
 use starknet_api::core::{ClassHash, ContractAddress, EntryPointSelector, Nonce};
 use starknet_api::execution_resources::GasAmount;
 use starknet_api::transaction::fields::{AllResourceBounds, Fee, Resource};
-use starknet_api::transaction::TransactionVersion;
+use starknet_api::transaction::{TransactionHash, TransactionVersion};
 use starknet_api::StarknetApiError;
 use starknet_types_core::felt::FromStrError;
 use thiserror::Error;
 
-use crate::bouncer::BouncerWeights;
+use crate::bouncer::{BouncerWeights, TransactionTooLarge};
 use crate::execution::call_info::Retdata;
 use crate::execution::errors::{ConstructorEntryPointExecutionError, EntryPointExecutionError};
 use crate::execution::stack_trace::{gen_tx_execution_error_trace, Cairo1RevertSummary};
@@ -25,6 +25,8 @@ pub enum TransactionFeeError {
     ExecuteFeeTransferError(#[from] EntryPointExecutionError),
     #[error("Actual fee ({}) exceeded max fee ({}).", actual_fee.0, max_fee.0)]
     FeeTransferError { max_fee: Fee, actual_fee: Fee },
+    #[error(transparent)]
+    FromStr(#[from] FromStrError),
     #[error("Actual fee ({}) exceeded paid fee on L1 ({}).", actual_fee.0, paid_fee.0)]
     InsufficientFee { paid_fee: Fee, actual_fee: Fee },
     #[error("Resources bounds ({bounds}) exceed balance ({balance}).")]
@@ -94,6 +96,12 @@ pub enum TransactionExecutionError {
          {:?}.", **version, allowed_versions.iter().map(|v| **v).collect::<Vec<_>>()
     )]
     InvalidVersion { version: TransactionVersion, allowed_versions: Vec<TransactionVersion> },
+    #[error("Declare transaction {:#064x} was created without a class info.", **tx_hash)]
+    MissingClassInfo { tx_hash: TransactionHash },
+    #[error("L1 handler transaction {:#064x} was created without the fee paid on L1.", **tx_hash)]
+    MissingPaidFeeOnL1 { tx_hash: TransactionHash },
+    #[error("Non-declare transaction {:#064x} was created with a class info.", **tx_hash)]
+    UnexpectedClassInfo { tx_hash: TransactionHash },
     #[error(transparent)]
     StarknetApiError(#[from] StarknetApiError),
     #[error(transparent)]
@@ -104,6 +112,8 @@ pub enum TransactionExecutionError {
     TransactionPreValidationError(#[from] TransactionPreValidationError),
     #[error(transparent)]
     TryFromIntError(#[from] std::num::TryFromIntError),
+    #[error("Transaction type {tx_type} is not supported.")]
+    UnsupportedTransactionType { tx_type: String },
     #[error(
         "Transaction size exceeds the maximum block capacity. Max block capacity: {}, \
          transaction size: {}.", *max_capacity, *tx_size
@@ -125,6 +135,12 @@ pub enum TransactionExecutionError {
     ProgramError(#[from] ProgramError),
 }
 
+impl From<TransactionTooLarge> for TransactionExecutionError {
+    fn from(TransactionTooLarge { max_capacity, tx_size }: TransactionTooLarge) -> Self {
+        Self::TransactionTooLarge { max_capacity, tx_size }
+    }
+}
+
 #[derive(Debug, Error)]
 pub enum TransactionPreValidationError {
     #[error(
@@ -2,9 +2,10 @@ use std::collections::HashMap;
 
 use cairo_vm::types::builtin_name::BuiltinName;
 use cairo_vm::vm::runners::cairo_runner::ExecutionResources;
+use num_rational::Ratio;
 use starknet_api::core::{ContractAddress, Nonce};
 use starknet_api::data_availability::DataAvailabilityMode;
-use starknet_api::execution_resources::GasVector;
+use starknet_api::execution_resources::{GasAmount, GasVector};
 use starknet_api::transaction::fields::{
     AccountDeploymentData,
     AllResourceBounds,
@@ -228,6 +229,51 @@ impl TransactionExecutionInfo {
     pub fn summarize(&self) -> ExecutionSummary {
         CallInfo::summarize_many(self.non_optional_call_infos())
     }
+
+    /// Returns, per resource, the gas consumed by the transaction against the amount declared in
+    /// its resource bounds.
+    pub fn resource_bounds_utilization(&self, declared: &AllResourceBounds) -> ResourceUtilization {
+        let consumed = &self.receipt.gas;
+        ResourceUtilization {
+            l1_gas: ResourceBoundUtilization {
+                consumed: consumed.l1_gas,
+                declared: declared.l1_gas.max_amount,
+            },
+            l2_gas: ResourceBoundUtilization {
+                consumed: consumed.l2_gas,
+                declared: declared.l2_gas.max_amount,
+            },
+            l1_data_gas: ResourceBoundUtilization {
+                consumed: consumed.l1_data_gas,
+                declared: declared.l1_data_gas.max_amount,
+            },
+        }
+    }
+}
+
+/// Consumed vs. declared amount of a single resource.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct ResourceBoundUtilization {
+    pub consumed: GasAmount,
+    pub declared: GasAmount,
+}
+
+impl ResourceBoundUtilization {
+    /// The consumed fraction of the declared amount; [None] if nothing was declared.
+    pub fn ratio(&self) -> Option<Ratio<u64>> {
+        if self.declared.0 == 0 {
+            return None;
+        }
+        Some(Ratio::new(self.consumed.0, self.declared.0))
+    }
+}
+
+/// Per-resource utilization of a transaction's declared resource bounds.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct ResourceUtilization {
+    pub l1_gas: ResourceBoundUtilization,
+    pub l2_gas: ResourceBoundUtilization,
+    pub l1_data_gas: ResourceBoundUtilization,
 }
 pub trait ExecutionResourcesTraits {
     fn total_n_steps(&self) -> usize;
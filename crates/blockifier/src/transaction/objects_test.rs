@@ -1,7 +1,21 @@
+use num_rational::Ratio;
 use rstest::rstest;
+use starknet_api::block::GasPrice;
 use starknet_api::core::{ClassHash, ContractAddress, EthAddress};
+use starknet_api::execution_resources::{GasAmount, GasVector};
 use starknet_api::state::StorageKey;
-use starknet_api::transaction::L2ToL1Payload;
+use starknet_api::transaction::fields::{
+    AllResourceBounds,
+    Fee,
+    ResourceBounds,
+    ValidResourceBounds,
+};
+use starknet_api::transaction::{
+    signed_tx_version,
+    L2ToL1Payload,
+    TransactionOptions,
+    TransactionVersion,
+};
 use starknet_api::{class_hash, contract_address, storage_key};
 use starknet_types_core::felt::Felt;
 
@@ -15,7 +29,15 @@ use crate::execution::call_info::{
     OrderedL2ToL1Message,
 };
 use crate::execution::entry_point::CallEntryPoint;
-use crate::transaction::objects::TransactionExecutionInfo;
+use crate::fee::receipt::TransactionReceipt;
+use crate::transaction::objects::{
+    CommonAccountFields,
+    CurrentTransactionInfo,
+    DeprecatedTransactionInfo,
+    ResourceBoundUtilization,
+    TransactionExecutionInfo,
+    TransactionInfo,
+};
 
 #[derive(Debug, Default)]
 pub struct TestExecutionSummary {
@@ -228,3 +250,75 @@ fn test_summarize(
     assert_eq!(actual_summary.event_summary, expected_summary.event_summary);
     assert_eq!(actual_summary.l2_to_l1_payload_lengths, expected_summary.l2_to_l1_payload_lengths);
 }
+
+#[test]
+fn test_resource_bounds_utilization() {
+    let resource_bounds = |max_amount| ResourceBounds {
+        max_amount: GasAmount(max_amount),
+        max_price_per_unit: GasPrice(1),
+    };
+    let declared = AllResourceBounds {
+        l1_gas: resource_bounds(100),
+        l2_gas: resource_bounds(0),
+        l1_data_gas: resource_bounds(40),
+    };
+    let tx_execution_info = TransactionExecutionInfo {
+        receipt: TransactionReceipt {
+            gas: GasVector {
+                l1_gas: GasAmount(50),
+                l1_data_gas: GasAmount(40),
+                l2_gas: GasAmount(0),
+            },
+            ..Default::default()
+        },
+        ..Default::default()
+    };
+
+    let utilization = tx_execution_info.resource_bounds_utilization(&declared);
+
+    assert_eq!(
+        utilization.l1_gas,
+        ResourceBoundUtilization { consumed: GasAmount(50), declared: GasAmount(100) }
+    );
+    assert_eq!(utilization.l1_gas.ratio(), Some(Ratio::new(1, 2)));
+    assert_eq!(utilization.l1_data_gas.ratio(), Some(Ratio::from_integer(1)));
+    // Nothing declared, so there is no meaningful ratio.
+    assert_eq!(utilization.l2_gas.ratio(), None);
+}
+
+#[rstest]
+fn test_max_fee_for_execution_info_syscall(#[values(false, true)] only_query: bool) {
+    let max_fee = Fee(42);
+    let deprecated_tx_info = TransactionInfo::Deprecated(DeprecatedTransactionInfo {
+        common_fields: CommonAccountFields {
+            version: TransactionVersion::ONE,
+            only_query,
+            ..Default::default()
+        },
+        max_fee,
+    });
+    let current_tx_info = TransactionInfo::Current(CurrentTransactionInfo {
+        common_fields: CommonAccountFields {
+            version: TransactionVersion::THREE,
+            only_query,
+            ..Default::default()
+        },
+        resource_bounds: ValidResourceBounds::AllResources(AllResourceBounds {
+            l1_gas: ResourceBounds { max_amount: GasAmount(13), max_price_per_unit: GasPrice(61) },
+            ..Default::default()
+        }),
+        ..CurrentTransactionInfo::create_for_testing()
+    });
+
+    // V3 transactions report their resource bounds instead of a max fee, also when simulated.
+    assert_eq!(current_tx_info.max_fee_for_execution_info_syscall(), Fee(0));
+    assert_eq!(deprecated_tx_info.max_fee_for_execution_info_syscall(), max_fee);
+
+    // Only the signed version is affected by the query flag.
+    for tx_info in [deprecated_tx_info, current_tx_info] {
+        let expected_signed_version =
+            signed_tx_version(&tx_info.version(), &TransactionOptions { only_query });
+        assert_eq!(tx_info.signed_version(), expected_signed_version);
+        assert_eq!(tx_info.signed_version() != tx_info.version(), only_query);
+    }
+}
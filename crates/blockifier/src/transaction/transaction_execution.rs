@@ -2,7 +2,7 @@ use std::sync::Arc;
 
 use cairo_vm::vm::runners::cairo_runner::ExecutionResources;
 use starknet_api::contract_class::ClassInfo;
-use starknet_api::core::{calculate_contract_address, ContractAddress, Nonce};
+use starknet_api::core::{calculate_contract_address, ChainId, ContractAddress, Nonce};
 use starknet_api::executable_transaction::{
     AccountTransaction as ApiExecutableTransaction,
     DeclareTransaction,
@@ -10,18 +10,23 @@ use starknet_api::executable_transaction::{
     InvokeTransaction,
     L1HandlerTransaction,
 };
+use starknet_api::rpc_transaction::RpcTransaction;
 use starknet_api::transaction::fields::Fee;
-use starknet_api::transaction::{Transaction as StarknetApiTransaction, TransactionHash};
+use starknet_api::transaction::{
+    Transaction as StarknetApiTransaction,
+    TransactionHash,
+    TransactionHasher,
+};
 
 use crate::bouncer::verify_tx_weights_within_max_capacity;
 use crate::context::BlockContext;
-use crate::execution::call_info::CallInfo;
+use crate::execution::call_info::{CallInfo, ExecutionSummary};
 use crate::execution::entry_point::EntryPointExecutionContext;
 use crate::fee::receipt::TransactionReceipt;
-use crate::state::cached_state::TransactionalState;
-use crate::state::state_api::UpdatableState;
+use crate::state::cached_state::{CachedState, StateChangesKeys, TransactionalState};
+use crate::state::state_api::{StateReader, UpdatableState};
 use crate::transaction::account_transaction::AccountTransaction;
-use crate::transaction::errors::TransactionFeeError;
+use crate::transaction::errors::{TransactionExecutionError, TransactionFeeError};
 use crate::transaction::objects::{
     TransactionExecutionInfo,
     TransactionExecutionResult,
@@ -86,12 +91,12 @@ impl Transaction {
                     tx: l1_handler,
                     tx_hash,
                     paid_fee_on_l1: paid_fee_on_l1
-                        .expect("L1Handler should be created with the fee paid on L1"),
+                        .ok_or(TransactionExecutionError::MissingPaidFeeOnL1 { tx_hash })?,
                 }));
             }
             StarknetApiTransaction::Declare(declare) => {
                 let non_optional_class_info =
-                    class_info.expect("Declare should be created with a ClassInfo.");
+                    class_info.ok_or(TransactionExecutionError::MissingClassInfo { tx_hash })?;
 
                 ApiExecutableTransaction::Declare(DeclareTransaction {
                     tx: declare,
@@ -118,7 +123,12 @@ impl Transaction {
             StarknetApiTransaction::Invoke(invoke) => {
                 ApiExecutableTransaction::Invoke(InvokeTransaction { tx: invoke, tx_hash })
             }
-            _ => unimplemented!(),
+            // Legacy deploy transactions have no account, so they cannot be executed.
+            StarknetApiTransaction::Deploy(_) => {
+                return Err(TransactionExecutionError::UnsupportedTransactionType {
+                    tx_type: "Deploy".to_string(),
+                });
+            }
         };
         let account_tx = match only_query {
             true => AccountTransaction::new_for_query(executable_tx),
@@ -126,6 +136,44 @@ impl Transaction {
         };
         Ok(account_tx.into())
     }
+
+    /// Creates an executable transaction from an RPC transaction, computing its hash on the given
+    /// chain. Declare transactions must be accompanied by the class info of their contract class,
+    /// and other transactions must not.
+    pub fn from_rpc_tx(
+        rpc_tx: RpcTransaction,
+        mut class_info: Option<ClassInfo>,
+        chain_id: &ChainId,
+    ) -> TransactionExecutionResult<Self> {
+        let executable_tx = match rpc_tx {
+            RpcTransaction::Declare(declare) => {
+                let declare_tx: starknet_api::transaction::DeclareTransaction = declare.into();
+                let Some(class_info) = class_info.take() else {
+                    let tx_hash =
+                        declare_tx.calculate_transaction_hash(chain_id, &declare_tx.version())?;
+                    return Err(TransactionExecutionError::MissingClassInfo { tx_hash });
+                };
+                ApiExecutableTransaction::Declare(DeclareTransaction::create(
+                    declare_tx, class_info, chain_id,
+                )?)
+            }
+            RpcTransaction::DeployAccount(deploy_account) => {
+                ApiExecutableTransaction::DeployAccount(DeployAccountTransaction::from_rpc_tx(
+                    deploy_account,
+                    chain_id,
+                )?)
+            }
+            RpcTransaction::Invoke(invoke) => {
+                ApiExecutableTransaction::Invoke(InvokeTransaction::from_rpc_tx(invoke, chain_id)?)
+            }
+        };
+        if class_info.is_some() {
+            return Err(TransactionExecutionError::UnexpectedClassInfo {
+                tx_hash: executable_tx.tx_hash(),
+            });
+        }
+        Ok(AccountTransaction::new(executable_tx).into())
+    }
 }
 
 impl TransactionInfoCreator for Transaction {
@@ -137,6 +185,43 @@ impl TransactionInfoCreator for Transaction {
     }
 }
 
+/// Returns the fee that the L1 handler transaction must pay on L1, i.e., the actual fee of
+/// executing it over the given state. The state is only read; the execution is discarded.
+pub fn l1_handler_required_fee<S: StateReader>(
+    tx: &L1HandlerTransaction,
+    state: &mut S,
+    block_context: &BlockContext,
+) -> TransactionExecutionResult<Fee> {
+    let mut transactional_state = TransactionalState::create_transactional(state);
+    let (_, receipt) = run_l1_handler(tx, &mut transactional_state, block_context)?;
+    transactional_state.abort();
+    Ok(receipt.fee)
+}
+
+fn run_l1_handler<S: StateReader>(
+    tx: &L1HandlerTransaction,
+    state: &mut CachedState<S>,
+    block_context: &BlockContext,
+) -> TransactionExecutionResult<(Option<CallInfo>, TransactionReceipt)> {
+    let tx_context = Arc::new(block_context.to_tx_context(tx));
+    let limit_steps_by_resources = false;
+    let mut execution_resources = ExecutionResources::default();
+    let mut context =
+        EntryPointExecutionContext::new_invoke(tx_context.clone(), limit_steps_by_resources)?;
+    let mut remaining_gas = tx_context.initial_sierra_gas();
+    let execute_call_info =
+        tx.run_execute(state, &mut execution_resources, &mut context, &mut remaining_gas)?;
+
+    let receipt = TransactionReceipt::from_l1_handler(
+        &tx_context,
+        tx.payload_size(),
+        CallInfo::summarize_many(execute_call_info.iter()),
+        &state.get_actual_state_changes()?,
+        &execution_resources,
+    );
+    Ok((execute_call_info, receipt))
+}
+
 impl<U: UpdatableState> ExecutableTransaction<U> for L1HandlerTransaction {
     fn execute_raw(
         &self,
@@ -144,33 +229,24 @@ impl<U: UpdatableState> ExecutableTransaction<U> for L1HandlerTransaction {
         block_context: &BlockContext,
         _execution_flags: ExecutionFlags,
     ) -> TransactionExecutionResult<TransactionExecutionInfo> {
-        let tx_context = Arc::new(block_context.to_tx_context(self));
-        let limit_steps_by_resources = false;
-        let mut execution_resources = ExecutionResources::default();
-        let mut context =
-            EntryPointExecutionContext::new_invoke(tx_context.clone(), limit_steps_by_resources);
-        let mut remaining_gas = tx_context.initial_sierra_gas();
-        let execute_call_info =
-            self.run_execute(state, &mut execution_resources, &mut context, &mut remaining_gas)?;
-        let l1_handler_payload_size = self.payload_size();
-
-        let TransactionReceipt {
-            fee: actual_fee,
-            da_gas,
-            resources: actual_resources,
-            gas: total_gas,
-        } = TransactionReceipt::from_l1_handler(
-            &tx_context,
-            l1_handler_payload_size,
-            CallInfo::summarize_many(execute_call_info.iter()),
-            &state.get_actual_state_changes()?,
-            &execution_resources,
-        );
+        let (
+            execute_call_info,
+            TransactionReceipt {
+                fee: actual_fee,
+                da_gas,
+                resources: actual_resources,
+                gas: total_gas,
+            },
+        ) = run_l1_handler(self, state, block_context)?;
 
         let paid_fee = self.paid_fee_on_l1;
-        // For now, assert only that any amount of fee was paid.
+        // Unless the full fee is enforced, assert only that any amount of fee was paid.
         // The error message still indicates the required fee.
-        if paid_fee == Fee(0) {
+        let insufficient_fee = match block_context.versioned_constants.enforce_l1_handler_fee {
+            true => paid_fee < actual_fee,
+            false => paid_fee == Fee(0),
+        };
+        if insufficient_fee {
             return Err(TransactionFeeError::InsufficientFee { paid_fee, actual_fee })?;
         }
 
@@ -189,13 +265,26 @@ impl<U: UpdatableState> ExecutableTransaction<U> for L1HandlerTransaction {
     }
 }
 
-impl<U: UpdatableState> ExecutableTransaction<U> for Transaction {
-    fn execute_raw(
+/// The summary of a successful transaction execution that the block capacity accounting needs.
+/// Computed once per execution, when checking that the transaction fits an empty block, and reused
+/// by the bouncer.
+#[derive(Debug, Default)]
+pub struct TransactionSummary {
+    pub execution_summary: ExecutionSummary,
+    /// The keys of the state changes of the transaction, including the sequencer balance key if
+    /// the fee transfer to the sequencer is deferred (in concurrency mode).
+    pub state_changes_keys: StateChangesKeys,
+}
+
+impl Transaction {
+    /// Executes the transaction like [`ExecutableTransaction::execute_raw`], and also returns its
+    /// summary, so that callers need not recompute it.
+    pub fn execute_raw_and_summarize<U: UpdatableState>(
         &self,
         state: &mut TransactionalState<'_, U>,
         block_context: &BlockContext,
         execution_flags: ExecutionFlags,
-    ) -> TransactionExecutionResult<TransactionExecutionInfo> {
+    ) -> TransactionExecutionResult<(TransactionExecutionInfo, TransactionSummary)> {
         // TODO(Yoni, 1/8/2024): consider unimplementing the ExecutableTransaction trait for inner
         // types, since now running Transaction::execute_raw is not identical to
         // AccountTransaction::execute_raw.
@@ -208,7 +297,6 @@ impl<U: UpdatableState> ExecutableTransaction<U> for Transaction {
         };
 
         // Check if the transaction is too large to fit any block.
-        // TODO(Yoni, 1/8/2024): consider caching these two.
         let tx_execution_summary = tx_execution_info.summarize();
         let mut tx_state_changes_keys = state.get_actual_state_changes()?.into_keys();
         tx_state_changes_keys.update_sequencer_key_in_storage(
@@ -224,6 +312,25 @@ impl<U: UpdatableState> ExecutableTransaction<U> for Transaction {
             &block_context.bouncer_config,
         )?;
 
+        Ok((
+            tx_execution_info,
+            TransactionSummary {
+                execution_summary: tx_execution_summary,
+                state_changes_keys: tx_state_changes_keys,
+            },
+        ))
+    }
+}
+
+impl<U: UpdatableState> ExecutableTransaction<U> for Transaction {
+    fn execute_raw(
+        &self,
+        state: &mut TransactionalState<'_, U>,
+        block_context: &BlockContext,
+        execution_flags: ExecutionFlags,
+    ) -> TransactionExecutionResult<TransactionExecutionInfo> {
+        let (tx_execution_info, _) =
+            self.execute_raw_and_summarize(state, block_context, execution_flags)?;
         Ok(tx_execution_info)
     }
 }
@@ -10,16 +10,33 @@ use pretty_assertions::assert_eq;
 use rstest::{fixture, rstest};
 use starknet_api::block::GasPriceVector;
 use starknet_api::contract_class::EntryPointType;
-use starknet_api::core::{ChainId, ClassHash, ContractAddress, EthAddress, Nonce};
+use starknet_api::core::{
+    calculate_contract_address,
+    ChainId,
+    ClassHash,
+    ContractAddress,
+    EthAddress,
+    Nonce,
+};
+use starknet_api::data_availability::DataAvailabilityMode;
 use starknet_api::executable_transaction::AccountTransaction as ApiExecutableTransaction;
 use starknet_api::execution_resources::{GasAmount, GasVector};
+use starknet_api::rpc_transaction::{
+    ContractClass as RpcContractClass,
+    RpcDeclareTransaction,
+    RpcDeclareTransactionV3,
+    RpcDeployAccountTransaction,
+    RpcDeployAccountTransactionV3,
+    RpcTransaction,
+};
 use starknet_api::state::StorageKey;
-use starknet_api::test_utils::invoke::InvokeTxArgs;
+use starknet_api::test_utils::invoke::{rpc_invoke_tx, InvokeTxArgs};
 use starknet_api::test_utils::NonceManager;
 use starknet_api::transaction::fields::Resource::{L1DataGas, L1Gas, L2Gas};
 use starknet_api::transaction::fields::{
     AllResourceBounds,
     Calldata,
+    ContractAddressSalt,
     Fee,
     GasVectorComputationMode,
     Resource,
@@ -28,10 +45,17 @@ use starknet_api::transaction::fields::{
     ValidResourceBounds,
 };
 use starknet_api::transaction::{
+    DeclareTransaction,
+    DeclareTransactionV0V1,
+    DeployTransaction,
     EventContent,
     EventData,
     EventKey,
+    L1HandlerTransaction,
     L2ToL1Payload,
+    Transaction as StarknetApiTransaction,
+    TransactionHash,
+    TransactionHasher,
     TransactionVersion,
     QUERY_VERSION_BASE_BIT,
 };
@@ -150,6 +174,7 @@ use crate::transaction::test_utils::{
     INVALID,
     VALID,
 };
+use crate::transaction::transaction_execution::{l1_handler_required_fee, Transaction};
 use crate::transaction::transaction_types::TransactionType;
 use crate::transaction::transactions::ExecutableTransaction;
 use crate::versioned_constants::VersionedConstants;
@@ -336,8 +361,9 @@ fn get_expected_cairo_resources(
     starknet_resources: &StarknetResources,
     call_infos: Vec<&Option<CallInfo>>,
 ) -> ExecutionResources {
-    let mut expected_cairo_resources =
-        versioned_constants.get_additional_os_tx_resources(tx_type, starknet_resources, false);
+    let mut expected_cairo_resources = versioned_constants
+        .get_additional_os_tx_resources(tx_type, starknet_resources, false)
+        .unwrap();
     for call_info in call_infos {
         if let Some(call_info) = &call_info {
             expected_cairo_resources += &call_info.charged_resources.vm_resources
@@ -2241,6 +2267,67 @@ fn test_only_query_flag(
     assert_eq!(tx_execution_info.revert_error, None);
 }
 
+/// A query transaction transfers its fee only if fee charging is requested, although its fee is
+/// computed either way.
+#[rstest]
+fn test_query_tx_fee_transfer(
+    block_context: BlockContext,
+    default_all_resource_bounds: ValidResourceBounds,
+    #[values(false, true)] charge_fee: bool,
+) {
+    let block_context = &block_context;
+    let account_contract = FeatureContract::AccountWithoutValidations(CairoVersion::Cairo1);
+    let test_contract = FeatureContract::TestContract(CairoVersion::Cairo1);
+    let state = &mut test_state(
+        &block_context.chain_info,
+        BALANCE,
+        &[(account_contract, 1), (test_contract, 1)],
+    );
+    let sender_address = account_contract.get_instance_address(0);
+
+    let account_tx = account_invoke_tx(invoke_tx_args! {
+        sender_address,
+        calldata: create_trivial_calldata(test_contract.get_instance_address(0)),
+        resource_bounds: default_all_resource_bounds,
+        only_query: true,
+    });
+    let fee_token_address = block_context.chain_info.fee_token_address(&account_tx.fee_type());
+
+    let tx_execution_info = account_tx.execute(state, block_context, charge_fee, true).unwrap();
+    let actual_fee = tx_execution_info.receipt.fee;
+    assert!(actual_fee > Fee(0));
+    assert_eq!(tx_execution_info.fee_transfer_call_info.is_some(), charge_fee);
+
+    let expected_balance = if charge_fee { BALANCE.0 - actual_fee.0 } else { BALANCE.0 };
+    assert_eq!(
+        state.get_fee_token_balance(sender_address, fee_token_address).unwrap(),
+        (felt!(expected_balance), felt!(0_u8))
+    );
+}
+
+#[rstest]
+fn test_l1_handler_minimal_initial_sierra_gas() {
+    let test_contract = FeatureContract::TestContract(CairoVersion::Cairo1);
+    let chain_info = &ChainInfo::create_for_testing();
+    let state = &mut test_state(chain_info, BALANCE, &[(test_contract, 1)]);
+    let block_context = &BlockContext::create_for_account_testing();
+    let tx = l1handler_tx(Fee(1), test_contract.get_instance_address(0));
+    let tx_context = block_context.to_tx_context(&tx);
+
+    let gas_costs = &block_context.versioned_constants.os_constants.gas_costs;
+    let minimal_initial_gas = tx_context.minimal_initial_sierra_gas();
+    assert_eq!(
+        minimal_initial_gas,
+        GasAmount(gas_costs.transaction_gas_cost + gas_costs.entry_point_initial_budget)
+    );
+
+    // A real execution starts with the initial gas of the transaction, which covers the minimum.
+    let execution_info = tx.execute(state, block_context, false, true).unwrap();
+    let initial_gas = execution_info.execute_call_info.unwrap().call.initial_gas;
+    assert_eq!(initial_gas, tx_context.initial_sierra_gas());
+    assert!(minimal_initial_gas.0 <= initial_gas);
+}
+
 #[rstest]
 fn test_l1_handler(#[values(false, true)] use_kzg_da: bool) {
     let gas_mode = GasVectorComputationMode::NoL2Gas;
@@ -2399,6 +2486,62 @@ fn test_l1_handler(#[values(false, true)] use_kzg_da: bool) {
     );
 }
 
+#[rstest]
+fn test_l1_handler_required_fee(#[values(false, true)] use_kzg_da: bool) {
+    let test_contract = FeatureContract::TestContract(CairoVersion::Cairo1);
+    let chain_info = &ChainInfo::create_for_testing();
+    let state = &mut test_state(chain_info, BALANCE, &[(test_contract, 1)]);
+    let block_context = &BlockContext::create_for_account_testing_with_kzg(use_kzg_da);
+    let contract_address = test_contract.get_instance_address(0);
+    let tx = l1handler_tx(Fee(0), contract_address);
+    let key = StorageKey::try_from(tx.tx.calldata.0[1]).unwrap();
+
+    let required_fee = l1_handler_required_fee(&tx, state, block_context).unwrap();
+    assert!(required_fee > Fee(0));
+    // The state is not modified.
+    assert_eq!(state.get_storage_at(contract_address, key).unwrap(), Felt::ZERO);
+
+    // The required fee is the actual fee of a full execution.
+    let error = tx.execute(state, block_context, false, true).unwrap_err();
+    assert_matches!(
+        error,
+        TransactionExecutionError::TransactionFeeError(
+            TransactionFeeError::InsufficientFee { actual_fee, .. }
+        )
+        if actual_fee == required_fee
+    );
+}
+
+#[rstest]
+fn test_l1_handler_fee_enforcement(#[values(false, true)] enforce_l1_handler_fee: bool) {
+    let test_contract = FeatureContract::TestContract(CairoVersion::Cairo1);
+    let chain_info = &ChainInfo::create_for_testing();
+    let state = &mut test_state(chain_info, BALANCE, &[(test_contract, 1)]);
+    let mut block_context = BlockContext::create_for_account_testing();
+    block_context.versioned_constants.enforce_l1_handler_fee = enforce_l1_handler_fee;
+    let contract_address = test_contract.get_instance_address(0);
+    let dust_fee = Fee(1);
+    let dust_fee_tx = l1handler_tx(dust_fee, contract_address);
+    let required_fee = l1_handler_required_fee(&dust_fee_tx, state, &block_context).unwrap();
+    assert!(required_fee > dust_fee);
+
+    let result = dust_fee_tx.execute(state, &block_context, false, true);
+    if !enforce_l1_handler_fee {
+        assert!(result.is_ok());
+        return;
+    }
+    assert_matches!(
+        result,
+        Err(TransactionExecutionError::TransactionFeeError(
+            TransactionFeeError::InsufficientFee { paid_fee, actual_fee }
+        ))
+        if paid_fee == dust_fee && actual_fee == required_fee
+    );
+    // The full fee is accepted.
+    let tx = l1handler_tx(required_fee, contract_address);
+    assert!(tx.execute(state, &block_context, false, true).is_ok());
+}
+
 #[rstest]
 fn test_execute_tx_with_invalid_tx_version(
     block_context: BlockContext,
@@ -2535,3 +2678,198 @@ fn test_balance_print() {
     let int = balance_to_big_uint(&Felt::from(16_u64), &Felt::from(1_u64));
     assert!(format!("{}", int) == (BigUint::from(u128::MAX) + BigUint::from(17_u128)).to_string());
 }
+
+#[rstest]
+fn test_from_api_unsupported_transaction_type(#[values(false, true)] only_query: bool) {
+    let deploy_tx = StarknetApiTransaction::Deploy(DeployTransaction::default());
+    let result =
+        Transaction::from_api(deploy_tx, TransactionHash::default(), None, None, None, only_query);
+    assert_matches!(
+        result,
+        Err(TransactionExecutionError::UnsupportedTransactionType { tx_type }) if tx_type == "Deploy"
+    );
+}
+
+#[test]
+fn test_from_api_missing_class_info() {
+    let declare_tx =
+        StarknetApiTransaction::Declare(DeclareTransaction::V1(DeclareTransactionV0V1::default()));
+    let tx_hash = TransactionHash(felt!(1_u8));
+    let result = Transaction::from_api(declare_tx, tx_hash, None, None, None, false);
+    assert_matches!(
+        result,
+        Err(TransactionExecutionError::MissingClassInfo { tx_hash: error_tx_hash })
+        if error_tx_hash == tx_hash
+    );
+}
+
+#[test]
+fn test_from_api_missing_paid_fee_on_l1() {
+    let l1_handler_tx = StarknetApiTransaction::L1Handler(L1HandlerTransaction::default());
+    let tx_hash = TransactionHash(felt!(1_u8));
+    let result = Transaction::from_api(l1_handler_tx, tx_hash, None, None, None, false);
+    assert_matches!(
+        result,
+        Err(TransactionExecutionError::MissingPaidFeeOnL1 { tx_hash: error_tx_hash })
+        if error_tx_hash == tx_hash
+    );
+}
+
+fn rpc_declare_tx_for_testing() -> RpcDeclareTransaction {
+    RpcDeclareTransaction::V3(RpcDeclareTransactionV3 {
+        sender_address: contract_address!("0x1"),
+        compiled_class_hash: Default::default(),
+        signature: Default::default(),
+        nonce: nonce!(1_u8),
+        contract_class: RpcContractClass::default(),
+        resource_bounds: AllResourceBounds::default(),
+        tip: Default::default(),
+        paymaster_data: Default::default(),
+        account_deployment_data: Default::default(),
+        nonce_data_availability_mode: DataAvailabilityMode::L1,
+        fee_data_availability_mode: DataAvailabilityMode::L1,
+    })
+}
+
+#[test]
+fn test_from_rpc_tx_declare() {
+    let chain_id = ChainId::create_for_testing();
+    let class_info = calculate_class_info_for_testing(
+        FeatureContract::TestContract(CairoVersion::Cairo1).get_class(),
+    );
+    let rpc_declare_tx = rpc_declare_tx_for_testing();
+    let declare_tx: DeclareTransaction = rpc_declare_tx.clone().into();
+    let expected_tx_hash =
+        declare_tx.calculate_transaction_hash(&chain_id, &declare_tx.version()).unwrap();
+
+    let tx = Transaction::from_rpc_tx(
+        RpcTransaction::Declare(rpc_declare_tx),
+        Some(class_info.clone()),
+        &chain_id,
+    )
+    .unwrap();
+    let Transaction::Account(AccountTransaction {
+        tx: ApiExecutableTransaction::Declare(tx), ..
+    }) = tx
+    else {
+        panic!("Expected a declare transaction.");
+    };
+    assert_eq!(tx.sender_address(), contract_address!("0x1"));
+    assert_eq!(tx.nonce(), nonce!(1_u8));
+    assert_eq!(tx.tx_hash, expected_tx_hash);
+    assert_eq!(tx.class_info, class_info);
+}
+
+#[test]
+fn test_from_rpc_tx_declare_missing_class_info() {
+    let chain_id = ChainId::create_for_testing();
+    let rpc_declare_tx = rpc_declare_tx_for_testing();
+    let declare_tx: DeclareTransaction = rpc_declare_tx.clone().into();
+    let expected_tx_hash =
+        declare_tx.calculate_transaction_hash(&chain_id, &declare_tx.version()).unwrap();
+
+    let result = Transaction::from_rpc_tx(RpcTransaction::Declare(rpc_declare_tx), None, &chain_id);
+    assert_matches!(
+        result,
+        Err(TransactionExecutionError::MissingClassInfo { tx_hash })
+        if tx_hash == expected_tx_hash
+    );
+}
+
+#[test]
+fn test_from_rpc_tx_deploy_account() {
+    let chain_id = ChainId::create_for_testing();
+    let class_hash = class_hash!("0x2");
+    let constructor_calldata = calldata![felt!(3_u8)];
+    let rpc_deploy_account_tx = RpcDeployAccountTransaction::V3(RpcDeployAccountTransactionV3 {
+        signature: Default::default(),
+        nonce: Default::default(),
+        class_hash,
+        contract_address_salt: Default::default(),
+        constructor_calldata: constructor_calldata.clone(),
+        resource_bounds: AllResourceBounds::default(),
+        tip: Default::default(),
+        paymaster_data: Default::default(),
+        nonce_data_availability_mode: DataAvailabilityMode::L1,
+        fee_data_availability_mode: DataAvailabilityMode::L1,
+    });
+    let expected_tx_hash =
+        StarknetApiTransaction::DeployAccount(rpc_deploy_account_tx.clone().into())
+            .calculate_transaction_hash(&chain_id)
+            .unwrap();
+    let expected_contract_address = calculate_contract_address(
+        ContractAddressSalt::default(),
+        class_hash,
+        &constructor_calldata,
+        ContractAddress::default(),
+    )
+    .unwrap();
+
+    let tx = Transaction::from_rpc_tx(
+        RpcTransaction::DeployAccount(rpc_deploy_account_tx),
+        None,
+        &chain_id,
+    )
+    .unwrap();
+    let Transaction::Account(AccountTransaction {
+        tx: ApiExecutableTransaction::DeployAccount(tx),
+        ..
+    }) = tx
+    else {
+        panic!("Expected a deploy account transaction.");
+    };
+    assert_eq!(tx.class_hash(), class_hash);
+    assert_eq!(tx.constructor_calldata(), constructor_calldata);
+    assert_eq!(tx.tx_hash, expected_tx_hash);
+    assert_eq!(tx.contract_address, expected_contract_address);
+}
+
+#[test]
+fn test_from_rpc_tx_invoke() {
+    let chain_id = ChainId::create_for_testing();
+    let rpc_tx = rpc_invoke_tx(invoke_tx_args! {
+        sender_address: contract_address!("0x1"),
+        calldata: calldata![felt!(2_u8)],
+        nonce: nonce!(3_u8),
+    });
+    let RpcTransaction::Invoke(rpc_invoke_tx) = rpc_tx.clone() else {
+        panic!("Expected an RPC invoke transaction.");
+    };
+    let expected_tx_hash = StarknetApiTransaction::Invoke(rpc_invoke_tx.into())
+        .calculate_transaction_hash(&chain_id)
+        .unwrap();
+
+    let tx = Transaction::from_rpc_tx(rpc_tx, None, &chain_id).unwrap();
+    let Transaction::Account(AccountTransaction {
+        tx: ApiExecutableTransaction::Invoke(tx), ..
+    }) = tx
+    else {
+        panic!("Expected an invoke transaction.");
+    };
+    assert_eq!(tx.sender_address(), contract_address!("0x1"));
+    assert_eq!(tx.calldata(), calldata![felt!(2_u8)]);
+    assert_eq!(tx.nonce(), nonce!(3_u8));
+    assert_eq!(tx.tx_hash, expected_tx_hash);
+}
+
+#[test]
+fn test_from_rpc_tx_unexpected_class_info() {
+    let chain_id = ChainId::create_for_testing();
+    let class_info = calculate_class_info_for_testing(
+        FeatureContract::TestContract(CairoVersion::Cairo1).get_class(),
+    );
+    let rpc_tx = rpc_invoke_tx(invoke_tx_args! { sender_address: contract_address!("0x1") });
+    let RpcTransaction::Invoke(rpc_invoke_tx) = rpc_tx.clone() else {
+        panic!("Expected an RPC invoke transaction.");
+    };
+    let expected_tx_hash = StarknetApiTransaction::Invoke(rpc_invoke_tx.into())
+        .calculate_transaction_hash(&chain_id)
+        .unwrap();
+
+    let result = Transaction::from_rpc_tx(rpc_tx, Some(class_info), &chain_id);
+    assert_matches!(
+        result,
+        Err(TransactionExecutionError::UnexpectedClassInfo { tx_hash })
+        if tx_hash == expected_tx_hash
+    );
+}
@@ -1,4 +1,6 @@
 use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::{env, io};
 
 use crate::transaction::errors::NumericConversionError;
 
@@ -6,6 +8,10 @@ use crate::transaction::errors::NumericConversionError;
 #[path = "utils_test.rs"]
 pub mod test;
 
+/// An environment variable naming a directory that overrides the crate directory when resolving
+/// resource files.
+pub const RESOURCE_DIR_ENV_VAR: &str = "SEQUENCER_RESOURCE_DIR";
+
 pub const STRICT_SUBTRACT_MAPPING_ERROR: &str =
     "The source mapping keys are not a subset of the subtract mapping keys";
 /// Returns a `HashMap` containing key-value pairs from the source mapping  that are not included in
@@ -54,6 +60,39 @@ pub fn usize_from_u64(val: u64) -> Result<usize, NumericConversionError> {
     val.try_into().map_err(|_| NumericConversionError::U64ToUsizeError(val))
 }
 
+/// Returns the path of a resource file, given relative to the crate directory. If the
+/// `SEQUENCER_RESOURCE_DIR` environment variable is set and the file exists under it, that file is
+/// used, so binaries deployed without the source tree can relocate their resources.
+pub fn resolve_resource(relative_path: &str) -> io::Result<PathBuf> {
+    let override_dir = env::var_os(RESOURCE_DIR_ENV_VAR).map(PathBuf::from);
+    resolve_resource_in(
+        override_dir.as_deref(),
+        Path::new(env!("CARGO_MANIFEST_DIR")),
+        relative_path,
+    )
+}
+
+fn resolve_resource_in(
+    override_dir: Option<&Path>,
+    default_dir: &Path,
+    relative_path: &str,
+) -> io::Result<PathBuf> {
+    override_dir
+        .into_iter()
+        .chain([default_dir])
+        .map(|dir| dir.join(relative_path))
+        .find(|path| path.is_file())
+        .ok_or_else(|| {
+            io::Error::new(
+                io::ErrorKind::NotFound,
+                format!(
+                    "Resource {relative_path} was not found under {default_dir:?} or \
+                     {RESOURCE_DIR_ENV_VAR}."
+                ),
+            )
+        })
+}
+
 /// Conversion from usize to u64. May fail on architectures with over 64 bits
 /// of address space.
 pub fn u64_from_usize(val: usize) -> u64 {
@@ -2,7 +2,12 @@ use std::collections::HashMap;
 
 use pretty_assertions::assert_eq;
 
-use crate::utils::{strict_subtract_mappings, subtract_mappings, STRICT_SUBTRACT_MAPPING_ERROR};
+use crate::utils::{
+    resolve_resource_in,
+    strict_subtract_mappings,
+    subtract_mappings,
+    STRICT_SUBTRACT_MAPPING_ERROR,
+};
 
 #[test]
 fn test_subtract_mappings() {
@@ -60,3 +65,38 @@ fn test_strict_subtract_mappings_bad() {
         STRICT_SUBTRACT_MAPPING_ERROR
     );
 }
+
+#[test]
+fn test_resolve_resource_override_dir_takes_precedence() {
+    const RESOURCE: &str = "resources/resource.json";
+    let override_dir = tempfile::tempdir().unwrap();
+    let default_dir = tempfile::tempdir().unwrap();
+    for dir in [&override_dir, &default_dir] {
+        std::fs::create_dir(dir.path().join("resources")).unwrap();
+        std::fs::write(dir.path().join(RESOURCE), "{}").unwrap();
+    }
+
+    assert_eq!(
+        resolve_resource_in(Some(override_dir.path()), default_dir.path(), RESOURCE).unwrap(),
+        override_dir.path().join(RESOURCE)
+    );
+    assert_eq!(
+        resolve_resource_in(None, default_dir.path(), RESOURCE).unwrap(),
+        default_dir.path().join(RESOURCE)
+    );
+
+    // Resources missing from the override directory fall back to the default directory.
+    std::fs::remove_file(override_dir.path().join(RESOURCE)).unwrap();
+    assert_eq!(
+        resolve_resource_in(Some(override_dir.path()), default_dir.path(), RESOURCE).unwrap(),
+        default_dir.path().join(RESOURCE)
+    );
+
+    std::fs::remove_file(default_dir.path().join(RESOURCE)).unwrap();
+    assert_eq!(
+        resolve_resource_in(Some(override_dir.path()), default_dir.path(), RESOURCE)
+            .unwrap_err()
+            .kind(),
+        std::io::ErrorKind::NotFound
+    );
+}
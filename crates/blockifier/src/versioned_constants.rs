@@ -1,5 +1,5 @@
-use std::collections::{BTreeMap, HashMap, HashSet};
-use std::path::{Path, PathBuf};
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+use std::path::Path;
 use std::sync::{Arc, LazyLock};
 use std::{fs, io};
 
@@ -13,8 +13,9 @@ use papyrus_config::{ParamPath, ParamPrivacyInput, SerializedParam};
 use paste::paste;
 use semver::Version;
 use serde::de::Error as DeserializationError;
-use serde::{Deserialize, Deserializer, Serialize};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
 use serde_json::{Map, Number, Value};
+use sha2::{Digest, Sha256};
 use starknet_api::block::{GasPrice, StarknetVersion};
 use starknet_api::execution_resources::GasAmount;
 use starknet_api::transaction::fields::GasVectorComputationMode;
@@ -26,6 +27,7 @@ use crate::execution::execution_utils::poseidon_hash_many_cost;
 use crate::execution::syscalls::SyscallSelector;
 use crate::fee::resources::StarknetResources;
 use crate::transaction::transaction_types::TransactionType;
+use crate::utils::resolve_resource;
 
 #[cfg(test)]
 #[path = "versioned_constants_test.rs"]
@@ -93,12 +95,14 @@ macro_rules! define_versioned_constants {
 
         pub static VERSIONED_CONSTANTS_LATEST_JSON: LazyLock<String> = LazyLock::new(|| {
             let latest_variant = StarknetVersion::LATEST;
-            let path_to_json: PathBuf = [
-                env!("CARGO_MANIFEST_DIR"),
-                "src",
-                VersionedConstants::path_to_json(&latest_variant)
-                    .expect("Latest variant should have a path to json.")
-            ].iter().collect();
+            // The paths to the JSON files are relative to `src`; resources are resolved relative to
+            // the crate directory.
+            let relative_path = VersionedConstants::path_to_json(&latest_variant)
+                .expect("Latest variant should have a path to json.")
+                .strip_prefix("../")
+                .expect("Versioned constants should be under the crate directory.");
+            let path_to_json = resolve_resource(relative_path)
+                .unwrap_or_else(|err| panic!("Failed to locate file {relative_path}: {err}"));
             fs::read_to_string(path_to_json.clone())
                 .expect(&format!("Failed to read file {}.", path_to_json.display()))
         });
@@ -115,6 +119,11 @@ define_versioned_constants! {
     (V0_13_4, "../resources/versioned_constants_0_13_4.json"),
 }
 
+/// Versioned constants are validated on deserialization to contain the OS resources of every
+/// transaction type, so looking them up can only fail for constants built in code.
+pub const OS_RESOURCES_OF_ALL_TX_TYPES: &str =
+    "Versioned constants should contain the OS resources of all transaction types.";
+
 pub type ResourceCost = Ratio<u64>;
 
 // TODO: Delete this ratio-converter function once event keys / data length are no longer 128 bits
@@ -123,7 +132,7 @@ pub fn resource_cost_to_u128_ratio(cost: ResourceCost) -> Ratio<u128> {
     Ratio::new((*cost.numer()).into(), (*cost.denom()).into())
 }
 
-#[derive(Clone, Debug, Deserialize, Eq, PartialEq, PartialOrd)]
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, PartialOrd, Serialize)]
 pub struct CompilerVersion(pub Version);
 impl Default for CompilerVersion {
     fn default() -> Self {
@@ -131,10 +140,13 @@ impl Default for CompilerVersion {
     }
 }
 
-#[derive(Clone, Debug, Default, Deserialize)]
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
 pub struct VmResourceCosts {
     pub n_steps: ResourceCost,
-    #[serde(deserialize_with = "builtin_map_from_string_map")]
+    #[serde(
+        deserialize_with = "builtin_map_from_string_map",
+        serialize_with = "builtin_map_to_string_map"
+    )]
     pub builtins: HashMap<BuiltinName, ResourceCost>,
 }
 
@@ -150,11 +162,18 @@ fn builtin_map_from_string_map<'de, D: Deserializer<'de>>(
         .ok_or(D::Error::custom("Invalid builtin name"))
 }
 
+fn builtin_map_to_string_map<S: Serializer>(
+    map: &HashMap<BuiltinName, ResourceCost>,
+    s: S,
+) -> Result<S::Ok, S::Error> {
+    s.collect_map(map.iter().map(|(k, v)| (k.to_str_with_suffix(), v)))
+}
+
 /// Contains constants for the Blockifier that may vary between versions.
 /// Additional constants in the JSON file, not used by Blockifier but included for transparency, are
 /// automatically ignored during deserialization.
 /// Instances of this struct for specific Starknet versions can be selected by using the above enum.
-#[derive(Clone, Debug, Default, Deserialize)]
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
 #[serde(deny_unknown_fields)]
 pub struct VersionedConstants {
     // Limits.
@@ -163,6 +182,19 @@ pub struct VersionedConstants {
     pub deprecated_l2_resource_gas_costs: ArchivalDataGasCosts,
     pub archival_data_gas_costs: ArchivalDataGasCosts,
     pub max_recursion_depth: usize,
+    // The maximal number of inner calls a single execution (validate / execute) may perform.
+    // Unbounded in versions that don't set it.
+    #[serde(default)]
+    pub max_n_inner_calls: Option<usize>,
+    // The maximal length of the return data of a single native call. Guards against malformed
+    // results produced by the native executor. Unbounded in versions that don't set it.
+    #[serde(default)]
+    pub max_native_retdata_length: Option<usize>,
+    // The maximal number of distinct contracts a single execution (validate / execute) may touch,
+    // i.e., call, deploy or run a library call in the context of. Unbounded in versions that don't
+    // set it.
+    #[serde(default)]
+    pub max_n_touched_contracts: Option<usize>,
     pub validate_max_n_steps: u32,
     pub min_compiler_version_for_sierra_gas: CompilerVersion,
     // BACKWARD COMPATIBILITY: If true, the segment_arena builtin instance counter will be
@@ -173,6 +205,14 @@ pub struct VersionedConstants {
     // Transactions settings.
     pub disable_cairo0_redeclaration: bool,
     pub enable_stateful_compression: bool,
+    // If true, L1 handler transactions must pay at least their actual fee on L1. Otherwise, any
+    // non-zero fee is accepted.
+    pub enforce_l1_handler_fee: bool,
+    // If true, a syscall that the syscall handler does not support fails the execution with an
+    // error, instead of panicking. Not part of the versioned constants files; set by the node
+    // through `VersionedConstantsOverrides`.
+    #[serde(skip)]
+    pub unsupported_syscalls_are_recoverable: bool,
 
     // Compiler settings.
     pub enable_reverts: bool,
@@ -190,12 +230,74 @@ pub struct VersionedConstants {
 
     // Just to make sure the value exists, but don't use the actual values.
     #[allow(dead_code)]
+    #[serde(skip_serializing)]
     gateway: serde::de::IgnoredAny,
 }
 
 impl VersionedConstants {
     pub fn from_path(path: &Path) -> VersionedConstantsResult<Self> {
-        Ok(serde_json::from_reader(std::fs::File::open(path)?)?)
+        let versioned_constants: Self = serde_json::from_reader(std::fs::File::open(path)?)?;
+        versioned_constants.validate()?;
+        Ok(versioned_constants)
+    }
+
+    /// Parses the constants from an in-memory JSON string, in the format read by `from_path`.
+    pub fn from_json_str(json: &str) -> VersionedConstantsResult<Self> {
+        let versioned_constants: Self = serde_json::from_str(json)?;
+        versioned_constants.validate()?;
+        Ok(versioned_constants)
+    }
+
+    /// Returns the transaction types that have no OS resources entry. Unlike `validate`, does not
+    /// fail on the first missing entry, so custom constants can be checked up front.
+    pub fn missing_tx_types(&self) -> Vec<TransactionType> {
+        self.os_resources.missing_tx_types()
+    }
+
+    /// Returns the syscalls that have no OS resources entry.
+    pub fn missing_syscalls(&self) -> Vec<SyscallSelector> {
+        self.os_resources.missing_syscalls()
+    }
+
+    /// Validates the constants. Deserialization only validates them outside of tests, so constants
+    /// built in tests should be validated explicitly.
+    pub fn validate(&self) -> VersionedConstantsResult<()> {
+        self.os_resources
+            .validate::<Value>()
+            .map_err(|error| VersionedConstantsError::ValidationError(error.to_string()))
+    }
+
+    /// Returns a SHA-256 hash of the canonical serialized form of the constants (JSON with
+    /// recursively sorted object keys). Equal constants yield equal hashes, regardless of the
+    /// order in which they were loaded or the iteration order of their internal maps.
+    pub fn content_hash(&self) -> [u8; 32] {
+        let value =
+            serde_json::to_value(self).expect("Versioned constants should be serializable.");
+        let canonical_json = canonicalize_json_value(value).to_string();
+        Sha256::digest(canonical_json.as_bytes()).into()
+    }
+
+    /// Returns the gas costs, event limits, step limits and VM resource fee costs that differ
+    /// between `self` (the old constants) and `other` (the new constants).
+    pub fn diff(&self, other: &Self) -> VersionedConstantsDiff {
+        let step_limits = |constants: &Self| {
+            serde_json::json!({
+                "invoke_tx_max_n_steps": constants.invoke_tx_max_n_steps,
+                "validate_max_n_steps": constants.validate_max_n_steps,
+            })
+        };
+        VersionedConstantsDiff {
+            gas_costs: diff_json_fields(
+                &self.os_constants.gas_costs,
+                &other.os_constants.gas_costs,
+            ),
+            event_limits: diff_json_fields(&self.tx_event_limits, &other.tx_event_limits),
+            step_limits: diff_json_fields(&step_limits(self), &step_limits(other)),
+            vm_resource_fee_costs: diff_json_fields(
+                &self.vm_resource_fee_cost,
+                &other.vm_resource_fee_cost,
+            ),
+        }
     }
 
     /// Converts from L1 gas price to L2 gas price with **upward rounding**.
@@ -212,6 +314,40 @@ impl VersionedConstants {
         (*(self.l1_to_l2_gas_price_ratio().inv() * l1_gas_amount.0).ceil().numer()).into()
     }
 
+    /// Converts from L2 gas price to L1 gas price with **downward rounding**.
+    /// Returns zero if the L2/L1 price ratio is zero (or undefined).
+    pub fn convert_l2_to_l1_gas_price_round_down(&self, l2_gas_price: GasPrice) -> GasPrice {
+        let (numer, denom) = self.l1_to_l2_gas_price_ratio_parts();
+        if numer == 0 || denom == 0 {
+            return GasPrice(0);
+        }
+        // The L1/L2 price ratio is the inverse of the L2/L1 price ratio.
+        (*(Ratio::new(denom, numer) * l2_gas_price.0).floor().numer()).into()
+    }
+
+    /// Converts from L2 gas amount to L1 gas amount with **downward rounding**.
+    /// Returns zero if the L2/L1 price ratio is undefined.
+    pub fn convert_l2_to_l1_gas_amount_round_down(&self, l2_gas_amount: GasAmount) -> GasAmount {
+        let (numer, denom) = self.l1_to_l2_gas_price_ratio_parts();
+        if denom == 0 {
+            return GasAmount(0);
+        }
+        // The amount ratio is equal to the price ratio (L2_gas_price/L1_gas_price).
+        let l1_gas_amount =
+            *(Ratio::new(numer, denom) * u128::from(l2_gas_amount.0)).floor().numer();
+        u64::try_from(l1_gas_amount).unwrap_or(u64::MAX).into()
+    }
+
+    /// Returns the numerator and denominator of the L2_gas_price/L1_gas_price ratio, without
+    /// constructing it (the denominator may be zero in malformed constants).
+    fn l1_to_l2_gas_price_ratio_parts(&self) -> (u128, u128) {
+        let n_steps = self.vm_resource_fee_cost().n_steps;
+        (
+            u128::from(*n_steps.numer()),
+            u128::from(*n_steps.denom()) * u128::from(self.os_constants.gas_costs.step_gas_cost),
+        )
+    }
+
     /// Returns the following ratio: L2_gas_price/L1_gas_price.
     fn l1_to_l2_gas_price_ratio(&self) -> ResourceCost {
         Ratio::new(1, self.os_constants.gas_costs.step_gas_cost)
@@ -236,7 +372,7 @@ impl VersionedConstants {
         &self,
         tx_type: &TransactionType,
         calldata_length: usize,
-    ) -> ExecutionResources {
+    ) -> VersionedConstantsResult<ExecutionResources> {
         self.os_resources.resources_for_tx_type(tx_type, calldata_length)
     }
 
@@ -249,7 +385,7 @@ impl VersionedConstants {
         tx_type: TransactionType,
         starknet_resources: &StarknetResources,
         use_kzg_da: bool,
-    ) -> ExecutionResources {
+    ) -> VersionedConstantsResult<ExecutionResources> {
         self.os_resources.get_additional_os_tx_resources(
             tx_type,
             starknet_resources.archival_data.calldata_length,
@@ -261,10 +397,19 @@ impl VersionedConstants {
     pub fn get_additional_os_syscall_resources(
         &self,
         syscall_counter: &SyscallCounter,
-    ) -> ExecutionResources {
+    ) -> VersionedConstantsResult<ExecutionResources> {
         self.os_resources.get_additional_os_syscall_resources(syscall_counter)
     }
 
+    /// Same as [`VersionedConstants::get_additional_os_syscall_resources`], broken down by
+    /// syscall.
+    pub fn get_additional_os_resources_per_syscall(
+        &self,
+        syscall_counter: &SyscallCounter,
+    ) -> VersionedConstantsResult<HashMap<SyscallSelector, ExecutionResources>> {
+        self.os_resources.get_additional_os_resources_per_syscall(syscall_counter)
+    }
+
     pub fn get_validate_block_number_rounding(&self) -> u64 {
         self.os_constants.validate_rounding_consts.validate_block_number_rounding
     }
@@ -311,11 +456,13 @@ impl VersionedConstants {
             validate_max_n_steps,
             max_recursion_depth,
             invoke_tx_max_n_steps,
+            unsupported_syscalls_are_recoverable,
         } = versioned_constants_overrides;
         Self {
             validate_max_n_steps,
             max_recursion_depth,
             invoke_tx_max_n_steps,
+            unsupported_syscalls_are_recoverable,
             ..Self::latest_constants().clone()
         }
     }
@@ -331,7 +478,7 @@ impl VersionedConstants {
     }
 }
 
-#[derive(Clone, Debug, Default, Deserialize, Eq, PartialEq)]
+#[derive(Clone, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
 pub struct ArchivalDataGasCosts {
     // TODO(barak, 18/03/2024): Once we start charging per byte change to milligas_per_data_byte,
     // divide the value by 32 in the JSON file.
@@ -343,14 +490,14 @@ pub struct ArchivalDataGasCosts {
     pub gas_per_code_byte: ResourceCost,
 }
 
-#[derive(Clone, Copy, Debug, Default, Deserialize, Eq, PartialEq)]
+#[derive(Clone, Copy, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
 pub struct EventLimits {
     pub max_data_length: usize,
     pub max_keys_length: usize,
     pub max_n_emitted_events: usize,
 }
 
-#[derive(Clone, Debug, Default, Deserialize)]
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
 // Serde trick for adding validations via a customr deserializer, without forgoing the derive.
 // See: https://github.com/serde-rs/serde/issues/1220.
 #[serde(remote = "Self")]
@@ -372,25 +519,33 @@ pub struct OsResources {
 }
 
 impl OsResources {
+    fn missing_tx_types(&self) -> Vec<TransactionType> {
+        TransactionType::iter()
+            .filter(|tx_type| !self.execute_txs_inner.contains_key(tx_type))
+            .collect()
+    }
+
+    fn missing_syscalls(&self) -> Vec<SyscallSelector> {
+        SyscallSelector::iter()
+            .filter(|syscall_selector| !self.execute_syscalls.contains_key(syscall_selector))
+            .collect()
+    }
+
     pub fn validate<'de, D: Deserializer<'de>>(
         &self,
     ) -> Result<(), <D as Deserializer<'de>>::Error> {
-        for tx_type in TransactionType::iter() {
-            if !self.execute_txs_inner.contains_key(&tx_type) {
-                return Err(DeserializationError::custom(format!(
-                    "ValidationError: os_resources.execute_tx_inner is missing transaction_type: \
-                     {tx_type:?}"
-                )));
-            }
+        if let Some(tx_type) = self.missing_tx_types().first() {
+            return Err(DeserializationError::custom(format!(
+                "ValidationError: os_resources.execute_tx_inner is missing transaction_type: \
+                 {tx_type:?}"
+            )));
         }
 
-        for syscall_handler in SyscallSelector::iter() {
-            if !self.execute_syscalls.contains_key(&syscall_handler) {
-                return Err(DeserializationError::custom(format!(
-                    "ValidationError: os_resources.execute_syscalls are missing syscall handler: \
-                     {syscall_handler:?}"
-                )));
-            }
+        if let Some(syscall_handler) = self.missing_syscalls().first() {
+            return Err(DeserializationError::custom(format!(
+                "ValidationError: os_resources.execute_syscalls are missing syscall handler: \
+                 {syscall_handler:?}"
+            )));
         }
 
         let known_builtin_names: HashSet<&str> = [
@@ -441,14 +596,15 @@ impl OsResources {
         calldata_length: usize,
         data_segment_length: usize,
         use_kzg_da: bool,
-    ) -> ExecutionResources {
-        let mut os_additional_vm_resources = self.resources_for_tx_type(&tx_type, calldata_length);
+    ) -> VersionedConstantsResult<ExecutionResources> {
+        let mut os_additional_vm_resources =
+            self.resources_for_tx_type(&tx_type, calldata_length)?;
 
         if use_kzg_da {
             os_additional_vm_resources += &self.os_kzg_da_resources(data_segment_length);
         }
 
-        os_additional_vm_resources
+        Ok(os_additional_vm_resources)
     }
 
     /// Calculates the additional resources needed for the OS to run the given syscalls;
@@ -456,24 +612,43 @@ impl OsResources {
     fn get_additional_os_syscall_resources(
         &self,
         syscall_counter: &SyscallCounter,
-    ) -> ExecutionResources {
+    ) -> VersionedConstantsResult<ExecutionResources> {
         let mut os_additional_resources = ExecutionResources::default();
-        for (syscall_selector, count) in syscall_counter {
-            let syscall_resources =
-                self.execute_syscalls.get(syscall_selector).unwrap_or_else(|| {
-                    panic!("OS resources of syscall '{syscall_selector:?}' are unknown.")
-                });
-            os_additional_resources += &(syscall_resources * *count);
+        for syscall_resources in
+            self.get_additional_os_resources_per_syscall(syscall_counter)?.values()
+        {
+            os_additional_resources += syscall_resources;
         }
 
-        os_additional_resources
+        Ok(os_additional_resources)
+    }
+
+    /// Calculates the additional resources needed for the OS to run each of the given syscalls,
+    /// multiplied by the number of times it was called.
+    fn get_additional_os_resources_per_syscall(
+        &self,
+        syscall_counter: &SyscallCounter,
+    ) -> VersionedConstantsResult<HashMap<SyscallSelector, ExecutionResources>> {
+        syscall_counter
+            .iter()
+            .map(|(syscall_selector, count)| {
+                let syscall_resources = self
+                    .execute_syscalls
+                    .get(syscall_selector)
+                    .ok_or(VersionedConstantsError::UnknownSyscallResources(*syscall_selector))?;
+                Ok((*syscall_selector, syscall_resources * *count))
+            })
+            .collect()
     }
 
-    fn resources_params_for_tx_type(&self, tx_type: &TransactionType) -> &ResourcesParams {
-        &(self
+    fn resources_params_for_tx_type(
+        &self,
+        tx_type: &TransactionType,
+    ) -> VersionedConstantsResult<&ResourcesParams> {
+        Ok(&self
             .execute_txs_inner
             .get(tx_type)
-            .unwrap_or_else(|| panic!("should contain transaction type '{tx_type:?}'."))
+            .ok_or(VersionedConstantsError::UnknownTransactionTypeResources(*tx_type))?
             .deprecated_resources)
     }
 
@@ -481,9 +656,9 @@ impl OsResources {
         &self,
         tx_type: &TransactionType,
         calldata_length: usize,
-    ) -> ExecutionResources {
-        let resources_vector = self.resources_params_for_tx_type(tx_type);
-        &resources_vector.constant + &(&(resources_vector.calldata_factor) * calldata_length)
+    ) -> VersionedConstantsResult<ExecutionResources> {
+        let resources_vector = self.resources_params_for_tx_type(tx_type)?;
+        Ok(&resources_vector.constant + &(&(resources_vector.calldata_factor) * calldata_length))
     }
 
     fn os_kzg_da_resources(&self, data_segment_length: usize) -> ExecutionResources {
@@ -514,8 +689,17 @@ impl<'de> Deserialize<'de> for OsResources {
     }
 }
 
+impl Serialize for OsResources {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        Self::serialize(self, serializer)
+    }
+}
+
 /// Gas cost constants. For more documentation see in core/os/constants.cairo.
-#[derive(Debug, Default, Deserialize)]
+#[derive(Debug, Default, Deserialize, Serialize)]
 pub struct GasCosts {
     pub step_gas_cost: u64,
     pub memory_hole_gas_cost: u64,
@@ -570,7 +754,7 @@ pub struct GasCosts {
 // conversion into actual values.
 // TODO: consider encoding the * and + operations inside the json file, instead of hardcoded below
 // in the `try_from`.
-#[derive(Debug, Default, Deserialize)]
+#[derive(Debug, Default, Deserialize, Serialize)]
 #[serde(try_from = "OsConstantsRawJson")]
 pub struct OsConstants {
     pub gas_costs: GasCosts,
@@ -735,6 +919,12 @@ pub enum VersionedConstantsError {
     InvalidVersion { version: String },
     #[error("Invalid Starknet version: {0}")]
     InvalidStarknetVersion(StarknetVersion),
+    #[error("OS resources of syscall '{0:?}' are unknown.")]
+    UnknownSyscallResources(SyscallSelector),
+    #[error("OS resources of transaction type '{0:?}' are unknown.")]
+    UnknownTransactionTypeResources(TransactionType),
+    #[error("Invalid versioned constants: {0}")]
+    ValidationError(String),
 }
 
 pub type VersionedConstantsResult<T> = Result<T, VersionedConstantsError>;
@@ -760,7 +950,7 @@ pub enum OsConstantsSerdeError {
     ValidationError(String),
 }
 
-#[derive(Clone, Debug, Deserialize)]
+#[derive(Clone, Debug, Deserialize, Serialize)]
 #[serde(try_from = "ResourceParamsRaw")]
 pub struct ResourcesParams {
     pub constant: ExecutionResources,
@@ -802,7 +992,7 @@ impl TryFrom<ResourceParamsRaw> for ResourcesParams {
     }
 }
 
-#[derive(Clone, Debug, Deserialize)]
+#[derive(Clone, Debug, Deserialize, Serialize)]
 pub struct ValidateRoundingConsts {
     // Flooring factor for block number in validate mode.
     pub validate_block_number_rounding: u64,
@@ -816,17 +1006,94 @@ impl Default for ValidateRoundingConsts {
     }
 }
 
-#[derive(Clone, Debug, Deserialize)]
+#[derive(Clone, Debug, Deserialize, Serialize)]
 pub struct ResourcesByVersion {
     pub resources: ResourcesParams,
     pub deprecated_resources: ResourcesParams,
 }
 
+/// Returns the given JSON value with the keys of all (nested) objects sorted, so that its string
+/// representation does not depend on insertion order.
+fn canonicalize_json_value(value: Value) -> Value {
+    match value {
+        Value::Object(map) => {
+            let sorted: BTreeMap<String, Value> =
+                map.into_iter().map(|(k, v)| (k, canonicalize_json_value(v))).collect();
+            Value::Object(sorted.into_iter().collect())
+        }
+        Value::Array(values) => {
+            Value::Array(values.into_iter().map(canonicalize_json_value).collect())
+        }
+        other => other,
+    }
+}
+
+/// A mapping from the name of each changed constant to its old and new values. A constant missing
+/// from one of the versions is reported as `null` in that version.
+pub type ChangedConstants = BTreeMap<String, (Value, Value)>;
+
+/// The differences between two versioned constants; see [`VersionedConstants::diff`]. Nested
+/// constants are named by their dot-separated path, e.g. `builtins.pedersen_builtin`.
+#[derive(Debug, Default, PartialEq)]
+pub struct VersionedConstantsDiff {
+    pub gas_costs: ChangedConstants,
+    pub event_limits: ChangedConstants,
+    pub step_limits: ChangedConstants,
+    pub vm_resource_fee_costs: ChangedConstants,
+}
+
+impl VersionedConstantsDiff {
+    pub fn is_empty(&self) -> bool {
+        self.gas_costs.is_empty()
+            && self.event_limits.is_empty()
+            && self.step_limits.is_empty()
+            && self.vm_resource_fee_costs.is_empty()
+    }
+}
+
+fn diff_json_fields<T: Serialize>(old: &T, new: &T) -> ChangedConstants {
+    let flatten = |value: &T| {
+        let mut fields = BTreeMap::new();
+        flatten_json_value(
+            String::new(),
+            serde_json::to_value(value).expect("Versioned constants should be serializable."),
+            &mut fields,
+        );
+        fields
+    };
+    let (mut old_fields, mut new_fields) = (flatten(old), flatten(new));
+    let names: BTreeSet<String> = old_fields.keys().chain(new_fields.keys()).cloned().collect();
+
+    names
+        .into_iter()
+        .filter_map(|name| {
+            let old_value = old_fields.remove(&name).unwrap_or(Value::Null);
+            let new_value = new_fields.remove(&name).unwrap_or(Value::Null);
+            (old_value != new_value).then_some((name, (old_value, new_value)))
+        })
+        .collect()
+}
+
+fn flatten_json_value(path: String, value: Value, fields: &mut BTreeMap<String, Value>) {
+    match value {
+        Value::Object(map) => {
+            for (key, value) in map {
+                let nested_path = if path.is_empty() { key } else { format!("{path}.{key}") };
+                flatten_json_value(nested_path, value, fields);
+            }
+        }
+        leaf => {
+            fields.insert(path, leaf);
+        }
+    }
+}
+
 #[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
 pub struct VersionedConstantsOverrides {
     pub validate_max_n_steps: u32,
     pub max_recursion_depth: usize,
     pub invoke_tx_max_n_steps: u32,
+    pub unsupported_syscalls_are_recoverable: bool,
 }
 
 impl Default for VersionedConstantsOverrides {
@@ -836,6 +1103,7 @@ impl Default for VersionedConstantsOverrides {
             validate_max_n_steps: latest_versioned_constants.validate_max_n_steps,
             max_recursion_depth: latest_versioned_constants.max_recursion_depth,
             invoke_tx_max_n_steps: latest_versioned_constants.invoke_tx_max_n_steps,
+            unsupported_syscalls_are_recoverable: false,
         }
     }
 }
@@ -861,6 +1129,13 @@ impl SerializeConfig for VersionedConstantsOverrides {
                 "Maximum number of steps the invoke function is allowed to run.",
                 ParamPrivacyInput::Public,
             ),
+            ser_param(
+                "unsupported_syscalls_are_recoverable",
+                &self.unsupported_syscalls_are_recoverable,
+                "If true, a syscall that is not supported fails the execution with an error, \
+                 instead of panicking.",
+                ParamPrivacyInput::Public,
+            ),
         ])
     }
 }
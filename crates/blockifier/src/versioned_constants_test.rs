@@ -1,3 +1,6 @@
+use std::path::PathBuf;
+
+use assert_matches::assert_matches;
 use glob::{glob, Paths};
 use pretty_assertions::assert_eq;
 
@@ -49,12 +52,14 @@ fn test_versioned_constants_overrides() {
         validate_max_n_steps: updated_validate_max_n_steps,
         max_recursion_depth: updated_max_recursion_depth,
         invoke_tx_max_n_steps: updated_invoke_tx_max_n_steps,
+        unsupported_syscalls_are_recoverable: true,
     });
 
     // Assert the new values are used.
     assert_eq!(result.invoke_tx_max_n_steps, updated_invoke_tx_max_n_steps);
     assert_eq!(result.validate_max_n_steps, updated_validate_max_n_steps);
     assert_eq!(result.max_recursion_depth, updated_max_recursion_depth);
+    assert!(result.unsupported_syscalls_are_recoverable);
 }
 
 #[test]
@@ -167,3 +172,259 @@ fn test_all_jsons_in_enum() {
 fn test_latest_no_panic() {
     VersionedConstants::latest_constants();
 }
+
+#[test]
+fn test_l2_to_l1_gas_conversions_round_trip() {
+    let versioned_constants = VersionedConstants::latest_constants();
+    let ratio = resource_cost_to_u128_ratio(versioned_constants.get_l1_to_l2_gas_price_ratio());
+
+    for l1_gas_price in [1_u128, 7, 1_000, 100_000_000_000, 3 * 10_u128.pow(18)] {
+        let l2_gas_price =
+            versioned_constants.convert_l1_to_l2_gas_price_round_up(l1_gas_price.into());
+        let round_trip = versioned_constants.convert_l2_to_l1_gas_price_round_down(l2_gas_price);
+        // Rounding up and then down may only overshoot by less than a single L2 unit.
+        assert!(round_trip.0 >= l1_gas_price);
+        assert!(Ratio::from_integer(round_trip.0 - l1_gas_price) < ratio.inv());
+    }
+
+    for l1_gas_amount in [1_u64, 7, 1_000, 10_000_000] {
+        let l2_gas_amount =
+            versioned_constants.convert_l1_to_l2_gas_amount_round_up(l1_gas_amount.into());
+        let round_trip = versioned_constants.convert_l2_to_l1_gas_amount_round_down(l2_gas_amount);
+        // The L2/L1 price ratio is below one, so the round trip is exact.
+        assert_eq!(round_trip, l1_gas_amount.into());
+    }
+}
+
+#[test]
+fn test_l2_to_l1_gas_conversions_of_zero() {
+    let versioned_constants = VersionedConstants::latest_constants();
+    assert_eq!(versioned_constants.convert_l2_to_l1_gas_price_round_down(GasPrice(0)), GasPrice(0));
+    assert_eq!(
+        versioned_constants.convert_l2_to_l1_gas_amount_round_down(GasAmount(0)),
+        GasAmount(0)
+    );
+}
+
+#[test]
+fn test_l2_to_l1_gas_conversions_zero_ratio_no_panic() {
+    // Default constants have a zero step gas cost and a zero n_steps fee cost.
+    let versioned_constants = VersionedConstants::default();
+    assert_eq!(
+        versioned_constants.convert_l2_to_l1_gas_price_round_down(GasPrice(100)),
+        GasPrice(0)
+    );
+    assert_eq!(
+        versioned_constants.convert_l2_to_l1_gas_amount_round_down(GasAmount(100)),
+        GasAmount(0)
+    );
+}
+
+#[test]
+fn test_content_hash() {
+    let path = PathBuf::from(format!(
+        "{}/resources/versioned_constants_0_13_4.json",
+        env!("CARGO_MANIFEST_DIR")
+    ));
+    let first = VersionedConstants::from_path(&path).unwrap();
+    let second = VersionedConstants::from_path(&path).unwrap();
+    assert_eq!(first.content_hash(), second.content_hash());
+
+    let mut raw_json: Value = serde_json::from_reader(std::fs::File::open(&path).unwrap()).unwrap();
+    raw_json["invoke_tx_max_n_steps"] = (first.invoke_tx_max_n_steps + 1).into();
+    let tweaked: VersionedConstants = serde_json::from_value(raw_json).unwrap();
+    assert_ne!(first.content_hash(), tweaked.content_hash());
+}
+
+#[test]
+fn test_from_json_str() {
+    let versioned_constants =
+        VersionedConstants::from_json_str(&VERSIONED_CONSTANTS_LATEST_JSON).unwrap();
+    let latest_constants = VersionedConstants::latest_constants();
+    assert_eq!(versioned_constants.content_hash(), latest_constants.content_hash());
+    assert!(versioned_constants.diff(latest_constants).is_empty());
+}
+
+#[test]
+fn test_from_json_str_validates_os_resources() {
+    let mut json_data: Value = serde_json::from_str(&VERSIONED_CONSTANTS_LATEST_JSON).unwrap();
+    json_data["os_resources"]["execute_syscalls"]
+        .as_object_mut()
+        .unwrap()
+        .remove("StorageRead")
+        .unwrap();
+
+    let error = VersionedConstants::from_json_str(&json_data.to_string()).unwrap_err();
+    assert_matches!(error, VersionedConstantsError::ValidationError(_));
+    assert!(error.to_string().contains("missing syscall handler: StorageRead"));
+}
+
+#[test]
+fn test_validate_missing_tx_type_resources() {
+    let versioned_constants = load_constants_without_os_resources("execute_txs_inner", "Declare");
+
+    let error = versioned_constants.validate().unwrap_err();
+    assert_matches!(error, VersionedConstantsError::ValidationError(_));
+    assert!(error.to_string().contains("missing transaction_type: Declare"));
+
+    let mut json_data: Value = serde_json::from_str(&VERSIONED_CONSTANTS_LATEST_JSON).unwrap();
+    json_data["os_resources"]["execute_txs_inner"].as_object_mut().unwrap().remove("Declare");
+    let constants_file = tempfile::NamedTempFile::new().unwrap();
+    serde_json::to_writer(&constants_file, &json_data).unwrap();
+    assert_matches!(
+        VersionedConstants::from_path(constants_file.path()),
+        Err(VersionedConstantsError::ValidationError(_))
+    );
+}
+
+#[test]
+fn test_missing_tx_types_and_syscalls() {
+    let versioned_constants = VersionedConstants::create_for_testing();
+    assert!(versioned_constants.missing_tx_types().is_empty());
+    assert!(versioned_constants.missing_syscalls().is_empty());
+
+    let mut json_data: Value = serde_json::from_str(&VERSIONED_CONSTANTS_LATEST_JSON).unwrap();
+    let execute_txs_inner = json_data["os_resources"]["execute_txs_inner"].as_object_mut().unwrap();
+    execute_txs_inner.remove("Declare").unwrap();
+    execute_txs_inner.remove("L1Handler").unwrap();
+    let execute_syscalls = json_data["os_resources"]["execute_syscalls"].as_object_mut().unwrap();
+    execute_syscalls.remove("Keccak").unwrap();
+    // Deserialization does not validate the OS resources in tests.
+    let incomplete_constants: VersionedConstants = serde_json::from_value(json_data).unwrap();
+
+    assert_eq!(
+        incomplete_constants.missing_tx_types(),
+        vec![TransactionType::Declare, TransactionType::L1Handler]
+    );
+    assert_eq!(incomplete_constants.missing_syscalls(), vec![SyscallSelector::Keccak]);
+}
+
+#[test]
+fn test_additional_os_resources_per_syscall() {
+    let versioned_constants = VersionedConstants::create_for_testing();
+    let syscall_counter: SyscallCounter = HashMap::from([
+        (SyscallSelector::CallContract, 2),
+        (SyscallSelector::StorageRead, 3),
+        (SyscallSelector::EmitEvent, 1),
+    ]);
+
+    let resources_per_syscall =
+        versioned_constants.get_additional_os_resources_per_syscall(&syscall_counter).unwrap();
+    assert_eq!(resources_per_syscall.len(), syscall_counter.len());
+    for (syscall_selector, count) in &syscall_counter {
+        assert_eq!(
+            resources_per_syscall[syscall_selector],
+            &versioned_constants.os_resources.execute_syscalls[syscall_selector] * *count
+        );
+    }
+
+    let mut total_resources = ExecutionResources::default();
+    for syscall_resources in resources_per_syscall.values() {
+        total_resources += syscall_resources;
+    }
+    assert_eq!(
+        total_resources,
+        versioned_constants.get_additional_os_syscall_resources(&syscall_counter).unwrap()
+    );
+}
+
+#[test]
+fn test_additional_os_resources_of_unknown_syscall() {
+    let mut versioned_constants = VersionedConstants::create_for_testing();
+    let mut os_resources = (*versioned_constants.os_resources).clone();
+    os_resources.execute_syscalls.remove(&SyscallSelector::StorageRead);
+    versioned_constants.os_resources = Arc::new(os_resources);
+
+    let syscall_counter: SyscallCounter =
+        HashMap::from([(SyscallSelector::CallContract, 1), (SyscallSelector::StorageRead, 1)]);
+    assert_matches!(
+        versioned_constants.get_additional_os_syscall_resources(&syscall_counter),
+        Err(VersionedConstantsError::UnknownSyscallResources(SyscallSelector::StorageRead))
+    );
+}
+
+/// Loads the latest versioned constants, without the given OS resources entry.
+/// Note that the OS resources are only validated on deserialization outside of tests.
+fn load_constants_without_os_resources(os_resources_field: &str, key: &str) -> VersionedConstants {
+    let mut json_data: Value = serde_json::from_str(&VERSIONED_CONSTANTS_LATEST_JSON).unwrap();
+    json_data["os_resources"][os_resources_field].as_object_mut().unwrap().remove(key).unwrap();
+    serde_json::from_value(json_data).unwrap()
+}
+
+#[test]
+fn test_constants_file_missing_syscall_resources() {
+    let versioned_constants =
+        load_constants_without_os_resources("execute_syscalls", "StorageRead");
+
+    let syscall_counter: SyscallCounter = HashMap::from([(SyscallSelector::StorageRead, 1)]);
+    let error =
+        versioned_constants.get_additional_os_syscall_resources(&syscall_counter).unwrap_err();
+    assert_eq!(error.to_string(), "OS resources of syscall 'StorageRead' are unknown.");
+    assert!(
+        versioned_constants
+            .get_additional_os_syscall_resources(&HashMap::from([(
+                SyscallSelector::StorageWrite,
+                1
+            )]))
+            .is_ok()
+    );
+}
+
+#[test]
+fn test_constants_file_missing_tx_type_resources() {
+    let versioned_constants = load_constants_without_os_resources("execute_txs_inner", "Declare");
+
+    assert_matches!(
+        versioned_constants.os_resources_for_tx_type(&TransactionType::Declare, 0),
+        Err(VersionedConstantsError::UnknownTransactionTypeResources(TransactionType::Declare))
+    );
+    let error = versioned_constants
+        .get_additional_os_tx_resources(
+            TransactionType::Declare,
+            &StarknetResources::default(),
+            true,
+        )
+        .unwrap_err();
+    assert_eq!(error.to_string(), "OS resources of transaction type 'Declare' are unknown.");
+    assert!(
+        versioned_constants.os_resources_for_tx_type(&TransactionType::InvokeFunction, 0).is_ok()
+    );
+}
+
+#[test]
+fn test_diff() {
+    let v0_13_3 = VersionedConstants::get(&StarknetVersion::V0_13_3).unwrap();
+    let v0_13_4 = VersionedConstants::get(&StarknetVersion::V0_13_4).unwrap();
+
+    let diff = v0_13_3.diff(v0_13_4);
+    // Builtin gas costs were introduced in v0.13.4.
+    assert_eq!(diff.gas_costs["pedersen_gas_cost"], (0.into(), 4050.into()));
+    assert_eq!(diff.gas_costs["bitwise_builtin_gas_cost"], (594.into(), 583.into()));
+    assert!(!diff.gas_costs.contains_key("step_gas_cost"));
+    assert!(diff.event_limits.is_empty());
+
+    let reverse_diff = v0_13_4.diff(v0_13_3);
+    assert_eq!(reverse_diff.gas_costs["pedersen_gas_cost"], (4050.into(), 0.into()));
+
+    assert!(v0_13_4.diff(v0_13_4).is_empty());
+}
+
+#[test]
+fn test_diff_nested_and_step_limit_fields() {
+    let versioned_constants = VersionedConstants::create_for_testing();
+    let mut vm_resource_fee_cost = (*versioned_constants.vm_resource_fee_cost).clone();
+    vm_resource_fee_cost.builtins.insert(BuiltinName::pedersen, ResourceCost::new(1, 3));
+    let updated_constants = VersionedConstants {
+        invoke_tx_max_n_steps: versioned_constants.invoke_tx_max_n_steps + 1,
+        vm_resource_fee_cost: Arc::new(vm_resource_fee_cost),
+        ..versioned_constants.clone()
+    };
+
+    let diff = versioned_constants.diff(&updated_constants);
+    assert_eq!(diff.step_limits.keys().collect::<Vec<_>>(), vec!["invoke_tx_max_n_steps"]);
+    assert_eq!(
+        diff.vm_resource_fee_costs.keys().collect::<Vec<_>>(),
+        vec!["builtins.pedersen_builtin"]
+    );
+    assert!(diff.gas_costs.is_empty());
+}
@@ -43,3 +43,28 @@ pub const PAPYRUS_CONSENSUS_HEIGHT: &str = "papyrus_consensus_height";
 
 /// The number of times consensus has progressed due to the sync protocol.
 pub const PAPYRUS_CONSENSUS_SYNC_COUNT: &str = "papyrus_consensus_sync_count";
+
+/// The number of messages consensus received on inbound streams.
+pub const PAPYRUS_CONSENSUS_STREAM_MESSAGES_RECEIVED: &str =
+    "papyrus_consensus_stream_messages_received";
+
+/// The number of inbound streams consensus currently keeps track of.
+pub const PAPYRUS_CONSENSUS_NUM_ACTIVE_INBOUND_STREAMS: &str =
+    "papyrus_consensus_num_active_inbound_streams";
+
+/// The number of out-of-order messages buffered across all of the consensus inbound streams.
+pub const PAPYRUS_CONSENSUS_NUM_BUFFERED_STREAM_MESSAGES: &str =
+    "papyrus_consensus_num_buffered_stream_messages";
+
+/// The largest distance, over the consensus inbound streams, between the highest message id
+/// received and the next message id to deliver.
+pub const PAPYRUS_CONSENSUS_MAX_STREAM_MESSAGE_GAP: &str =
+    "papyrus_consensus_max_stream_message_gap";
+
+/// The number of inbound streams consensus dropped before they were completed.
+pub const PAPYRUS_CONSENSUS_INBOUND_STREAMS_DROPPED: &str =
+    "papyrus_consensus_inbound_streams_dropped";
+
+/// The number of messages consensus received on inbound streams after they were already delivered.
+pub const PAPYRUS_CONSENSUS_STREAM_DELIVERED_MESSAGES_RESENT: &str =
+    "papyrus_consensus_stream_delivered_messages_resent";
@@ -1,6 +1,7 @@
 //! Utils for config validations.
 
 use std::path::Path;
+use std::time::Duration;
 
 use validator::{Validate, ValidationError, ValidationErrors, ValidationErrorsKind};
 
@@ -34,6 +35,14 @@ pub fn validate_vec_u256(vec: &[u8]) -> Result<(), ValidationError> {
     Ok(())
 }
 
+/// Custom validation for a non-zero duration.
+pub fn validate_non_zero_duration(duration: &Duration) -> Result<(), ValidationError> {
+    if duration.is_zero() {
+        return Err(ValidationError::new("The duration is zero"));
+    }
+    Ok(())
+}
+
 /// Struct for parsing a validation error.
 #[derive(Debug)]
 pub struct ParsedValidationError {
@@ -269,7 +269,8 @@ pub fn execute_call(
     let mut context = EntryPointExecutionContext::new_invoke(
         Arc::new(TransactionContext { block_context, tx_info }),
         limit_steps_by_resources,
-    );
+    )
+    .map_err(|error| ExecutionError::ContractError(error.into()))?;
 
     let res = call_entry_point
         .execute(
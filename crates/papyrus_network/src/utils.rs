@@ -38,6 +38,22 @@ impl<K: Unpin + Clone + Eq + Hash, V: Stream + Unpin> StreamHashMap<K, V> {
         self.map.get_mut(key)
     }
 
+    pub fn remove(&mut self, key: &K) -> Option<V> {
+        self.map.remove(key)
+    }
+
+    pub fn contains_key(&self, key: &K) -> bool {
+        self.map.contains_key(key)
+    }
+
+    pub fn len(&self) -> usize {
+        self.map.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.map.is_empty()
+    }
+
     pub fn insert(&mut self, key: K, value: V) -> Option<V> {
         let res = self.map.insert(key, value);
         for waker in self.wakers_waiting_for_new_stream.drain(..) {
@@ -62,6 +62,7 @@ pub struct NodeConfig {
     /// One of p2p_sync or sync must be None.
     /// If P2P sync is active, then network must be active too.
     // TODO(yair): Change NodeConfig to have an option of enum of SyncConfig or P2PSyncConfig.
+    #[validate]
     pub p2p_sync: Option<P2PSyncClientConfig>,
     pub consensus: Option<ConsensusConfig>,
     // TODO(shahak): Make network non-optional once it's developed enough.
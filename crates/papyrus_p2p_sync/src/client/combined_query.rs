@@ -0,0 +1,259 @@
+use std::cmp::min;
+
+use async_stream::stream;
+use futures::stream::BoxStream;
+use futures::StreamExt;
+use papyrus_network::network_manager::ClientResponsesManager;
+use papyrus_protobuf::converters::ProtobufConversionError;
+use papyrus_protobuf::sync::{
+    BlockHashOrNumber,
+    DataOrFin,
+    Direction,
+    HeaderQuery,
+    Query,
+    StateDiffQuery,
+    TransactionQuery,
+};
+use papyrus_storage::body::BodyStorageReader;
+use papyrus_storage::header::HeaderStorageReader;
+use papyrus_storage::state::StateStorageReader;
+use papyrus_storage::{StorageError, StorageReader};
+use starknet_api::block::BlockNumber;
+use starknet_api::core::SequencerPublicKey;
+use tracing::{debug, info, warn};
+
+use super::header::HeaderStreamBuilder;
+use super::progress::WaitingForDataFlags;
+use super::state_diff::StateDiffStreamBuilder;
+use super::stream_builder::{BlockData, DataStreamBuilder, DataStreamResult, ParseDataError};
+use super::transaction::TransactionStreamFactory;
+use super::{
+    create_per_type_streams,
+    HeaderSqmrSender,
+    P2PSyncClientConfig,
+    P2PSyncClientError,
+    StateSqmrDiffSender,
+    TransactionSqmrSender,
+    STEP,
+};
+
+/// Creates a stream that syncs the headers, state diffs and transactions of each block range
+/// together. The queries for all three are sent at once, and the data of each block is parsed in
+/// order (header, state diff, transactions) as it arrives, validating each data type on its own.
+/// Once a query isn't fully answered (e.g. when reaching the tip of the chain, or when a peer
+/// sends bad data), the stream falls back to querying each data type separately for the rest of
+/// the run.
+pub(crate) fn create_combined_query_stream(
+    mut header_sender: HeaderSqmrSender,
+    mut state_diff_sender: StateSqmrDiffSender,
+    mut transaction_sender: TransactionSqmrSender,
+    storage_reader: StorageReader,
+    config: P2PSyncClientConfig,
+    waiting_for_data_flags: WaitingForDataFlags,
+) -> BoxStream<'static, DataStreamResult> {
+    // Each sub-query is bounded by its own configured query length.
+    let num_blocks_per_query = min(
+        config.num_headers_per_query,
+        min(config.num_block_state_diffs_per_query, config.num_block_transactions_per_query),
+    );
+    stream! {
+        'send_combined_queries: loop {
+            let Some(start_block_number) = get_aligned_start_block_number(&storage_reader)? else {
+                info!(
+                    "The stored headers, state diffs and transactions don't end at the same block. \
+                     Falling back to separate queries."
+                );
+                break;
+            };
+            let mut limit = num_blocks_per_query;
+            if let Some(stop_sync_at_block_number) = config.stop_sync_at_block_number {
+                if start_block_number >= stop_sync_at_block_number {
+                    info!("Combined queries hit the stop sync block number.");
+                    return;
+                }
+                limit = min(limit, stop_sync_at_block_number.0 - start_block_number.0);
+            }
+            let end_block_number = start_block_number.0 + limit;
+            debug!(
+                "Downloading headers, state diffs and transactions for blocks [{}, {})",
+                start_block_number.0,
+                end_block_number,
+            );
+            let query = Query {
+                start_block: BlockHashOrNumber::Number(start_block_number),
+                direction: Direction::Forward,
+                limit,
+                step: STEP,
+            };
+            let mut header_responses =
+                header_sender.send_new_query(HeaderQuery(query.clone())).await?;
+            let mut state_diff_responses =
+                state_diff_sender.send_new_query(StateDiffQuery(query.clone())).await?;
+            let mut transaction_responses =
+                transaction_sender.send_new_query(TransactionQuery(query)).await?;
+
+            for block_number in start_block_number.0..end_block_number {
+                let block_number = BlockNumber(block_number);
+                // The stream is polled again only after the yielded data was written, so the
+                // state diff and transactions are parsed after the header is in the storage.
+                let signed_header = match parse_block_data::<_, HeaderStreamBuilder>(
+                    &mut header_responses,
+                    block_number,
+                    &storage_reader,
+                    config.sequencer_pub_key,
+                )
+                .await?
+                {
+                    ParsedBlockData::Data(signed_header) => signed_header,
+                    ParsedBlockData::Missing => break 'send_combined_queries,
+                    ParsedBlockData::BadPeer => {
+                        header_responses.report_peer();
+                        break 'send_combined_queries;
+                    }
+                };
+                let block_hash = signed_header.block_header.block_hash;
+                yield Ok(Box::<dyn BlockData>::from(Box::new(signed_header)));
+
+                let state_diff = match parse_block_data::<_, StateDiffStreamBuilder>(
+                    &mut state_diff_responses,
+                    block_number,
+                    &storage_reader,
+                    config.sequencer_pub_key,
+                )
+                .await?
+                {
+                    ParsedBlockData::Data(state_diff) => state_diff,
+                    ParsedBlockData::Missing => break 'send_combined_queries,
+                    ParsedBlockData::BadPeer => {
+                        state_diff_responses.report_peer();
+                        break 'send_combined_queries;
+                    }
+                };
+                yield Ok(Box::<dyn BlockData>::from(Box::new(state_diff)));
+
+                let block_body = match parse_block_data::<_, TransactionStreamFactory>(
+                    &mut transaction_responses,
+                    block_number,
+                    &storage_reader,
+                    config.sequencer_pub_key,
+                )
+                .await?
+                {
+                    ParsedBlockData::Data(block_body) => block_body,
+                    ParsedBlockData::Missing => break 'send_combined_queries,
+                    ParsedBlockData::BadPeer => {
+                        transaction_responses.report_peer();
+                        break 'send_combined_queries;
+                    }
+                };
+                yield Ok(Box::<dyn BlockData>::from(Box::new(block_body)));
+
+                info!("Added header, state diff and transactions for block {}.", block_number);
+                if config.stop_sync_at_block_hash == Some(block_hash) {
+                    info!("Combined queries hit the stop sync block hash.");
+                    return;
+                }
+            }
+
+            // Consume the None messages signaling the end of the queries.
+            consume_fin(&mut header_responses, HeaderStreamBuilder::TYPE_DESCRIPTION).await?;
+            consume_fin(&mut state_diff_responses, StateDiffStreamBuilder::TYPE_DESCRIPTION)
+                .await?;
+            consume_fin(&mut transaction_responses, TransactionStreamFactory::TYPE_DESCRIPTION)
+                .await?;
+        }
+
+        // Each per-type stream resumes from the marker of its own data type.
+        let mut per_type_streams = create_per_type_streams(
+            header_sender,
+            state_diff_sender,
+            transaction_sender,
+            storage_reader,
+            config,
+            &waiting_for_data_flags,
+        );
+        while let Some(data) = per_type_streams.next().await {
+            yield data;
+        }
+    }
+    .boxed()
+}
+
+/// The outcome of parsing the data of a block from the responses to a combined query.
+enum ParsedBlockData<T> {
+    Data(T),
+    /// The peer didn't send the data of the block.
+    Missing,
+    /// The peer sent bad data and should be reported.
+    BadPeer,
+}
+
+async fn parse_block_data<InputFromNetwork, Builder>(
+    responses_manager: &mut ClientResponsesManager<DataOrFin<InputFromNetwork>>,
+    block_number: BlockNumber,
+    storage_reader: &StorageReader,
+    sequencer_pub_key: Option<SequencerPublicKey>,
+) -> Result<ParsedBlockData<Builder::Output>, P2PSyncClientError>
+where
+    InputFromNetwork: Send + 'static,
+    DataOrFin<InputFromNetwork>: TryFrom<Vec<u8>, Error = ProtobufConversionError>,
+    Builder: DataStreamBuilder<InputFromNetwork>,
+{
+    match Builder::parse_and_verify_data_for_block(
+        responses_manager,
+        block_number,
+        storage_reader,
+        sequencer_pub_key,
+    )
+    .await
+    {
+        Ok(Some(output)) => Ok(ParsedBlockData::Data(output)),
+        Ok(None) => {
+            debug!(
+                "Combined query for {:?} on {:?} returned with partial data. Falling back to \
+                 separate queries.",
+                Builder::TYPE_DESCRIPTION,
+                block_number
+            );
+            Ok(ParsedBlockData::Missing)
+        }
+        Err(ParseDataError::BadPeer(err)) => {
+            warn!(
+                "Combined query for {:?} on {:?} returned with bad peer error: {:?}. Reporting \
+                 peer and falling back to separate queries.",
+                Builder::TYPE_DESCRIPTION,
+                block_number,
+                err
+            );
+            Ok(ParsedBlockData::BadPeer)
+        }
+        Err(ParseDataError::Fatal(err)) => Err(err),
+    }
+}
+
+/// Returns the block to start the next combined query from, or `None` if the stored headers,
+/// state diffs and transactions don't end at the same block.
+fn get_aligned_start_block_number(
+    storage_reader: &StorageReader,
+) -> Result<Option<BlockNumber>, StorageError> {
+    let txn = storage_reader.begin_ro_txn()?;
+    let header_marker = txn.get_header_marker()?;
+    let aligned =
+        txn.get_state_marker()? == header_marker && txn.get_body_marker()? == header_marker;
+    Ok(aligned.then_some(header_marker))
+}
+
+async fn consume_fin<Response>(
+    responses_manager: &mut ClientResponsesManager<DataOrFin<Response>>,
+    type_description: &'static str,
+) -> Result<(), P2PSyncClientError>
+where
+    Response: Send + 'static,
+    DataOrFin<Response>: TryFrom<Vec<u8>, Error = ProtobufConversionError>,
+{
+    match responses_manager.next().await {
+        Some(Ok(DataOrFin(None))) => Ok(()),
+        Some(_) => Err(P2PSyncClientError::TooManyResponses),
+        None => Err(P2PSyncClientError::ReceiverChannelTerminated { type_description }),
+    }
+}
@@ -0,0 +1,276 @@
+use std::fmt::Debug;
+use std::time::Duration;
+
+use futures::StreamExt;
+use indexmap::IndexMap;
+use papyrus_network::network_manager::test_utils::MockClientResponsesManager;
+use papyrus_network::network_manager::GenericReceiver;
+use papyrus_protobuf::sync::{
+    BlockHashOrNumber,
+    ContractDiff,
+    DataOrFin,
+    Direction,
+    HeaderQuery,
+    Query,
+    SignedBlockHeader,
+    StateDiffChunk,
+    StateDiffQuery,
+    TransactionQuery,
+};
+use papyrus_storage::body::BodyStorageReader;
+use papyrus_storage::header::HeaderStorageReader;
+use papyrus_storage::state::StateStorageReader;
+use papyrus_storage::StorageReader;
+use starknet_api::block::{BlockHeader, BlockHeaderWithoutHash, BlockNumber};
+use starknet_api::core::{ContractAddress, Nonce};
+use starknet_api::state::ThinStateDiff;
+use starknet_api::transaction::{
+    FullTransaction,
+    InvokeTransaction,
+    InvokeTransactionOutput,
+    InvokeTransactionV1,
+    Transaction,
+    TransactionHash,
+    TransactionOutput,
+};
+use starknet_types_core::felt::Felt;
+use static_assertions::const_assert;
+use tokio::time::timeout;
+
+use super::test_utils::{
+    create_block_hashes_and_signatures,
+    setup_with_combined_queries,
+    TestArgs,
+    HEADER_QUERY_LENGTH,
+    SLEEP_DURATION_TO_LET_SYNC_ADVANCE,
+    STATE_DIFF_QUERY_LENGTH,
+    TRANSACTION_QUERY_LENGTH,
+};
+
+const N_BLOCKS: u64 = 3;
+const TIMEOUT_FOR_TEST: Duration = Duration::from_secs(60);
+
+// Asserting the constants so the combined query for all blocks fits in a single query.
+const_assert!(N_BLOCKS <= HEADER_QUERY_LENGTH);
+const_assert!(N_BLOCKS <= STATE_DIFF_QUERY_LENGTH);
+const_assert!(N_BLOCKS <= TRANSACTION_QUERY_LENGTH);
+
+struct BlocksData {
+    headers: Vec<SignedBlockHeader>,
+    state_diff_chunks: Vec<StateDiffChunk>,
+    transactions: Vec<FullTransaction>,
+}
+
+type StoredBlock = (BlockHeader, ThinStateDiff, Vec<Transaction>);
+
+fn create_blocks_data() -> BlocksData {
+    let block_hashes_and_signatures =
+        create_block_hashes_and_signatures(N_BLOCKS.try_into().unwrap());
+    let state_diff_chunks = (0..N_BLOCKS)
+        .map(|i| {
+            StateDiffChunk::ContractDiff(ContractDiff {
+                contract_address: ContractAddress::from(i + 1),
+                class_hash: None,
+                nonce: Some(Nonce(Felt::from(i + 1))),
+                storage_diffs: IndexMap::new(),
+            })
+        })
+        .collect::<Vec<_>>();
+    let headers = block_hashes_and_signatures
+        .into_iter()
+        .zip(state_diff_chunks.iter())
+        .enumerate()
+        .map(|(i, ((block_hash, block_signature), state_diff_chunk))| SignedBlockHeader {
+            block_header: BlockHeader {
+                block_hash,
+                block_header_without_hash: BlockHeaderWithoutHash {
+                    block_number: BlockNumber(i.try_into().unwrap()),
+                    ..Default::default()
+                },
+                state_diff_length: Some(state_diff_chunk.len()),
+                n_transactions: 1,
+                ..Default::default()
+            },
+            signatures: vec![block_signature],
+        })
+        .collect();
+    let transactions = (0..N_BLOCKS)
+        .map(|i| FullTransaction {
+            transaction: Transaction::Invoke(InvokeTransaction::V1(InvokeTransactionV1 {
+                nonce: Nonce(Felt::from(i)),
+                ..Default::default()
+            })),
+            transaction_output: TransactionOutput::Invoke(InvokeTransactionOutput::default()),
+            transaction_hash: TransactionHash(Felt::from(i)),
+        })
+        .collect();
+    BlocksData { headers, state_diff_chunks, transactions }
+}
+
+async fn wait_for_blocks_and_read_them(storage_reader: &StorageReader) -> Vec<StoredBlock> {
+    timeout(TIMEOUT_FOR_TEST, async {
+        loop {
+            let txn = storage_reader.begin_ro_txn().unwrap();
+            if txn.get_header_marker().unwrap().0 >= N_BLOCKS
+                && txn.get_state_marker().unwrap().0 >= N_BLOCKS
+                && txn.get_body_marker().unwrap().0 >= N_BLOCKS
+            {
+                break;
+            }
+            drop(txn);
+            tokio::time::sleep(SLEEP_DURATION_TO_LET_SYNC_ADVANCE).await;
+        }
+    })
+    .await
+    .unwrap();
+
+    let txn = storage_reader.begin_ro_txn().unwrap();
+    (0..N_BLOCKS)
+        .map(|i| {
+            let block_number = BlockNumber(i);
+            (
+                txn.get_block_header(block_number).unwrap().unwrap(),
+                txn.get_state_diff(block_number).unwrap().unwrap(),
+                txn.get_block_transactions(block_number).unwrap().unwrap(),
+            )
+        })
+        .collect()
+}
+
+/// Answers every query with the data of the requested blocks that exist, followed by a Fin.
+async fn answer_queries<TQuery, Response>(
+    mut responses_managers: GenericReceiver<
+        MockClientResponsesManager<TQuery, DataOrFin<Response>>,
+    >,
+    data_per_block: Vec<Response>,
+    get_query: fn(TQuery) -> Query,
+) where
+    TQuery: TryFrom<Vec<u8>> + Clone,
+    <TQuery as TryFrom<Vec<u8>>>::Error: Debug + Clone,
+    Response: Clone,
+    DataOrFin<Response>: TryFrom<Vec<u8>>,
+{
+    while let Some(mut responses_manager) = responses_managers.next().await {
+        let query = get_query(responses_manager.query().clone().unwrap());
+        let BlockHashOrNumber::Number(BlockNumber(start_block_number)) = query.start_block else {
+            panic!("Expected a query by block number.");
+        };
+        let end_block_number = (start_block_number + query.limit).min(N_BLOCKS);
+        for block_number in start_block_number..end_block_number {
+            let data = data_per_block[usize::try_from(block_number).unwrap()].clone();
+            // The sync may have already dropped the responses manager.
+            let _ = responses_manager.send_response(DataOrFin(Some(data))).await;
+        }
+        let _ = responses_manager.send_response(DataOrFin(None)).await;
+    }
+}
+
+async fn sync_blocks_with_separate_queries(blocks_data: BlocksData) -> Vec<StoredBlock> {
+    let TestArgs {
+        p2p_sync,
+        storage_reader,
+        mock_header_response_manager,
+        mock_state_diff_response_manager,
+        mock_transaction_response_manager,
+        // The test will fail if we drop this
+        mock_class_response_manager: _class_receiver,
+        ..
+    } = setup_with_combined_queries(false);
+
+    let answer_all_queries = futures::future::join3(
+        answer_queries(mock_header_response_manager, blocks_data.headers, |query| query.0),
+        answer_queries(mock_state_diff_response_manager, blocks_data.state_diff_chunks, |query| {
+            query.0
+        }),
+        answer_queries(mock_transaction_response_manager, blocks_data.transactions, |query| {
+            query.0
+        }),
+    );
+
+    tokio::select! {
+        sync_result = p2p_sync.run() => {
+            sync_result.unwrap();
+            panic!("P2P sync aborted with no failure.");
+        }
+        _ = answer_all_queries => panic!("The queries stopped arriving."),
+        stored_blocks = wait_for_blocks_and_read_them(&storage_reader) => stored_blocks,
+    }
+}
+
+async fn sync_blocks_with_combined_queries(blocks_data: BlocksData) -> Vec<StoredBlock> {
+    let TestArgs {
+        p2p_sync,
+        storage_reader,
+        mut mock_header_response_manager,
+        mut mock_state_diff_response_manager,
+        mut mock_transaction_response_manager,
+        // The test will fail if we drop this
+        mock_class_response_manager: _class_receiver,
+        ..
+    } = setup_with_combined_queries(true);
+
+    let parse_queries_future = async move {
+        // All the queries are sent before any data is received, which wouldn't happen with
+        // separate queries since the state diffs and transactions wait for the headers.
+        let mut mock_header_responses_manager = mock_header_response_manager.next().await.unwrap();
+        let mut mock_state_diff_responses_manager =
+            mock_state_diff_response_manager.next().await.unwrap();
+        let mut mock_transaction_responses_manager =
+            mock_transaction_response_manager.next().await.unwrap();
+        let expected_query = Query {
+            start_block: BlockHashOrNumber::Number(BlockNumber(0)),
+            direction: Direction::Forward,
+            limit: N_BLOCKS,
+            step: 1,
+        };
+        assert_eq!(*mock_header_responses_manager.query(), Ok(HeaderQuery(expected_query.clone())));
+        assert_eq!(
+            *mock_state_diff_responses_manager.query(),
+            Ok(StateDiffQuery(expected_query.clone()))
+        );
+        assert_eq!(
+            *mock_transaction_responses_manager.query(),
+            Ok(TransactionQuery(expected_query))
+        );
+
+        for header in blocks_data.headers {
+            mock_header_responses_manager.send_response(DataOrFin(Some(header))).await.unwrap();
+        }
+        mock_header_responses_manager.send_response(DataOrFin(None)).await.unwrap();
+        for state_diff_chunk in blocks_data.state_diff_chunks {
+            mock_state_diff_responses_manager
+                .send_response(DataOrFin(Some(state_diff_chunk)))
+                .await
+                .unwrap();
+        }
+        mock_state_diff_responses_manager.send_response(DataOrFin(None)).await.unwrap();
+        for transaction in blocks_data.transactions {
+            mock_transaction_responses_manager
+                .send_response(DataOrFin(Some(transaction)))
+                .await
+                .unwrap();
+        }
+        mock_transaction_responses_manager.send_response(DataOrFin(None)).await.unwrap();
+
+        wait_for_blocks_and_read_them(&storage_reader).await
+    };
+
+    tokio::select! {
+        sync_result = p2p_sync.run() => {
+            sync_result.unwrap();
+            panic!("P2P sync aborted with no failure.");
+        }
+        stored_blocks = parse_queries_future => stored_blocks,
+    }
+}
+
+#[tokio::test(start_paused = true)]
+async fn combined_queries_store_same_data_as_separate_queries() {
+    let stored_with_combined_queries =
+        sync_blocks_with_combined_queries(create_blocks_data()).await;
+    let stored_with_separate_queries =
+        sync_blocks_with_separate_queries(create_blocks_data()).await;
+
+    assert_eq!(stored_with_combined_queries.len(), usize::try_from(N_BLOCKS).unwrap());
+    assert_eq!(stored_with_combined_queries, stored_with_separate_queries);
+}
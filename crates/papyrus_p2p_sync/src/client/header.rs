@@ -7,7 +7,8 @@ use papyrus_network::network_manager::ClientResponsesManager;
 use papyrus_protobuf::sync::{DataOrFin, SignedBlockHeader};
 use papyrus_storage::header::{HeaderStorageReader, HeaderStorageWriter};
 use papyrus_storage::{StorageError, StorageReader, StorageWriter};
-use starknet_api::block::BlockNumber;
+use starknet_api::block::{verify_block_signature, BlockNumber};
+use starknet_api::core::{GlobalRoot, SequencerPublicKey};
 use tracing::debug;
 
 use super::stream_builder::{
@@ -109,6 +110,30 @@ impl DataStreamBuilder<SignedBlockHeader> for HeaderStreamBuilder {
         .boxed()
     }
 
+    fn verify_signature(
+        signed_block_header: &SignedBlockHeader,
+        sequencer_pub_key: &SequencerPublicKey,
+    ) -> Result<(), BadPeerError> {
+        let block_header = &signed_block_header.block_header;
+        let block_number = block_header.block_header_without_hash.block_number;
+        let state_diff_commitment = block_header
+            .state_diff_commitment
+            .ok_or(BadPeerError::MissingStateDiffCommitment { block_number })?;
+        let signature = signed_block_header
+            .signatures
+            .first()
+            .expect("Vec::first should return a value on a vector of size 1");
+        match verify_block_signature(
+            sequencer_pub_key,
+            signature,
+            &GlobalRoot(state_diff_commitment.0.0),
+            &block_header.block_hash,
+        ) {
+            Ok(true) => Ok(()),
+            Ok(false) | Err(_) => Err(BadPeerError::InvalidBlockSignature { block_number }),
+        }
+    }
+
     fn get_start_block_number(storage_reader: &StorageReader) -> Result<BlockNumber, StorageError> {
         storage_reader.begin_ro_txn()?.get_header_marker()
     }
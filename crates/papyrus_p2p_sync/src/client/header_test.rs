@@ -1,3 +1,5 @@
+use std::time::Duration;
+
 use futures::StreamExt;
 use papyrus_protobuf::sync::{
     BlockHashOrNumber,
@@ -6,20 +8,42 @@ use papyrus_protobuf::sync::{
     HeaderQuery,
     Query,
     SignedBlockHeader,
+    StateDiffQuery,
+};
+use papyrus_storage::header::{HeaderStorageReader, HeaderStorageWriter};
+use papyrus_storage::state::StateStorageWriter;
+use starknet_api::block::{
+    BlockHash,
+    BlockHeader,
+    BlockHeaderWithoutHash,
+    BlockNumber,
+    BlockSignature,
 };
-use papyrus_storage::header::HeaderStorageReader;
-use starknet_api::block::{BlockHeader, BlockHeaderWithoutHash, BlockNumber};
-use tokio::time::timeout;
+use starknet_api::core::{SequencerPublicKey, StateDiffCommitment};
+use starknet_api::crypto::utils::{PublicKey, Signature};
+use starknet_api::felt;
+use starknet_api::hash::PoseidonHash;
+use starknet_api::state::ThinStateDiff;
+use tokio::time::{timeout, Instant};
+use validator::Validate;
 
 use super::test_utils::{
     create_block_hashes_and_signatures,
     setup,
+    setup_with_max_wait_period_for_new_data,
+    setup_with_populated_storage,
+    setup_with_sequencer_pub_key,
+    setup_with_stop_sync_at_block_hash,
     TestArgs,
     HEADER_QUERY_LENGTH,
     SLEEP_DURATION_TO_LET_SYNC_ADVANCE,
+    STATE_DIFF_QUERY_LENGTH,
     TIMEOUT_FOR_NEW_QUERY_AFTER_PARTIAL_RESPONSE,
     WAIT_PERIOD_FOR_NEW_DATA,
 };
+use super::P2PSyncClientConfig;
+
+const TIMEOUT_FOR_TEST: Duration = Duration::from_secs(5);
 
 #[tokio::test]
 async fn signed_headers_basic_flow() {
@@ -183,4 +207,320 @@ async fn sync_sends_new_header_query_if_it_got_partial_responses() {
     }
 }
 
+#[tokio::test]
+async fn sync_stops_at_block_hash() {
+    const STOP_BLOCK_INDEX: usize = 2;
+    assert!(u64::try_from(STOP_BLOCK_INDEX).unwrap() + 1 < HEADER_QUERY_LENGTH);
+
+    let block_hashes_and_signatures =
+        create_block_hashes_and_signatures(HEADER_QUERY_LENGTH.try_into().unwrap());
+    let (stop_block_hash, _) = block_hashes_and_signatures[STOP_BLOCK_INDEX];
+    let TestArgs {
+        p2p_sync,
+        storage_reader,
+        mut mock_header_response_manager,
+        // The test will fail if we drop these
+        mock_state_diff_response_manager: _state_diff_receiver,
+        mock_transaction_response_manager: _transaction_receiver,
+        mock_class_response_manager: _class_receiver,
+        ..
+    } = setup_with_stop_sync_at_block_hash(stop_block_hash);
+
+    // Create a future that will send the headers of a whole query and check that only the headers
+    // up to the stop block hash were stored.
+    let parse_queries_future = async move {
+        let mut mock_header_responses_manager = mock_header_response_manager.next().await.unwrap();
+
+        for (i, (block_hash, signature)) in block_hashes_and_signatures.into_iter().enumerate() {
+            // The sync may already have dropped the responses manager after stopping.
+            let _ = mock_header_responses_manager
+                .send_response(DataOrFin(Some(SignedBlockHeader {
+                    block_header: BlockHeader {
+                        block_hash,
+                        block_header_without_hash: BlockHeaderWithoutHash {
+                            block_number: BlockNumber(i.try_into().unwrap()),
+                            ..Default::default()
+                        },
+                        state_diff_length: Some(0),
+                        ..Default::default()
+                    },
+                    signatures: vec![signature],
+                })))
+                .await;
+            tokio::time::sleep(SLEEP_DURATION_TO_LET_SYNC_ADVANCE).await;
+        }
+
+        let txn = storage_reader.begin_ro_txn().unwrap();
+        let stop_block_number = BlockNumber(STOP_BLOCK_INDEX.try_into().unwrap());
+        assert_eq!(stop_block_number.unchecked_next(), txn.get_header_marker().unwrap());
+        let block_header = txn.get_block_header(stop_block_number).unwrap().unwrap();
+        assert_eq!(stop_block_hash, block_header.block_hash);
+    };
+
+    tokio::select! {
+        sync_result = p2p_sync.run() => {
+            sync_result.unwrap();
+            panic!("P2P sync aborted with no failure.");
+        }
+        _ = parse_queries_future => {}
+    }
+}
+
+#[tokio::test(start_paused = true)]
+async fn sync_backs_off_exponentially_after_partial_responses() {
+    const MAX_WAIT_PERIOD_FOR_NEW_DATA: Duration = WAIT_PERIOD_FOR_NEW_DATA.saturating_mul(4);
+
+    let TestArgs {
+        p2p_sync,
+        mut mock_header_response_manager,
+        // The test will fail if we drop these
+        mock_state_diff_response_manager: _state_diff_receiver,
+        mock_transaction_response_manager: _transaction_receiver,
+        mock_class_response_manager: _class_receiver,
+        ..
+    } = setup_with_max_wait_period_for_new_data(MAX_WAIT_PERIOD_FOR_NEW_DATA);
+    let block_hashes_and_signatures =
+        create_block_hashes_and_signatures(HEADER_QUERY_LENGTH.try_into().unwrap());
+
+    // Create a future that will answer queries with empty responses and check the time that passed
+    // between consecutive queries.
+    let parse_queries_future = async move {
+        let mut mock_header_responses_manager = mock_header_response_manager.next().await.unwrap();
+        let mut last_query_time = Instant::now();
+        for expected_wait_period in [
+            WAIT_PERIOD_FOR_NEW_DATA,
+            WAIT_PERIOD_FOR_NEW_DATA * 2,
+            MAX_WAIT_PERIOD_FOR_NEW_DATA,
+            MAX_WAIT_PERIOD_FOR_NEW_DATA,
+        ] {
+            mock_header_responses_manager.send_response(DataOrFin(None)).await.unwrap();
+            mock_header_responses_manager = mock_header_response_manager.next().await.unwrap();
+            assert_eq!(last_query_time.elapsed(), expected_wait_period);
+            last_query_time = Instant::now();
+        }
+
+        // A full response resets the wait period.
+        for (i, (block_hash, signature)) in block_hashes_and_signatures.into_iter().enumerate() {
+            mock_header_responses_manager
+                .send_response(DataOrFin(Some(SignedBlockHeader {
+                    block_header: BlockHeader {
+                        block_hash,
+                        block_header_without_hash: BlockHeaderWithoutHash {
+                            block_number: BlockNumber(i.try_into().unwrap()),
+                            ..Default::default()
+                        },
+                        state_diff_length: Some(0),
+                        ..Default::default()
+                    },
+                    signatures: vec![signature],
+                })))
+                .await
+                .unwrap();
+        }
+        mock_header_responses_manager.send_response(DataOrFin(None)).await.unwrap();
+        mock_header_responses_manager = mock_header_response_manager.next().await.unwrap();
+        last_query_time = Instant::now();
+        mock_header_responses_manager.send_response(DataOrFin(None)).await.unwrap();
+        let _mock_header_responses_manager = mock_header_response_manager.next().await.unwrap();
+        assert_eq!(last_query_time.elapsed(), WAIT_PERIOD_FOR_NEW_DATA);
+    };
+
+    tokio::select! {
+        sync_result = p2p_sync.run() => {
+            sync_result.unwrap();
+            panic!("P2P sync aborted with no failure.");
+        }
+        _ = parse_queries_future => {}
+    }
+}
+
+#[test]
+fn config_rejects_max_wait_period_below_wait_period() {
+    let config = P2PSyncClientConfig {
+        wait_period_for_new_data: WAIT_PERIOD_FOR_NEW_DATA,
+        max_wait_period_for_new_data: WAIT_PERIOD_FOR_NEW_DATA,
+        ..Default::default()
+    };
+    assert!(config.validate().is_ok());
+
+    let config = P2PSyncClientConfig {
+        max_wait_period_for_new_data: WAIT_PERIOD_FOR_NEW_DATA / 2,
+        ..config
+    };
+    assert!(config.validate().is_err());
+}
+
+#[tokio::test]
+async fn sync_resumes_from_stored_blocks() {
+    // Headers are stored up to LAST_STORED_HEADER, but state diffs only up to
+    // LAST_STORED_STATE_DIFF, as if the node stopped in the middle of syncing the state diffs.
+    const LAST_STORED_HEADER: BlockNumber = BlockNumber(4);
+    const LAST_STORED_STATE_DIFF: BlockNumber = BlockNumber(1);
+
+    let TestArgs {
+        p2p_sync,
+        mut mock_header_response_manager,
+        mut mock_state_diff_response_manager,
+        // The test will fail if we drop these
+        mock_transaction_response_manager: _transaction_receiver,
+        mock_class_response_manager: _class_receiver,
+        ..
+    } = setup_with_populated_storage(|storage_writer| {
+        let mut txn = storage_writer.begin_rw_txn().unwrap();
+        let block_hashes_and_signatures =
+            create_block_hashes_and_signatures((LAST_STORED_HEADER.0 + 1).try_into().unwrap());
+        for (i, (block_hash, _)) in block_hashes_and_signatures.into_iter().enumerate() {
+            let block_number = BlockNumber(i.try_into().unwrap());
+            let block_header = BlockHeader {
+                block_hash,
+                block_header_without_hash: BlockHeaderWithoutHash {
+                    block_number,
+                    ..Default::default()
+                },
+                state_diff_length: Some(0),
+                ..Default::default()
+            };
+            txn = txn.append_header(block_number, &block_header).unwrap();
+        }
+        for block_number in 0..=LAST_STORED_STATE_DIFF.0 {
+            txn =
+                txn.append_state_diff(BlockNumber(block_number), ThinStateDiff::default()).unwrap();
+        }
+        txn.commit().unwrap();
+    });
+
+    let parse_queries_future = async move {
+        let mock_header_responses_manager = mock_header_response_manager.next().await.unwrap();
+        assert_eq!(
+            *mock_header_responses_manager.query(),
+            Ok(HeaderQuery(Query {
+                start_block: BlockHashOrNumber::Number(LAST_STORED_HEADER.unchecked_next()),
+                direction: Direction::Forward,
+                limit: HEADER_QUERY_LENGTH,
+                step: 1,
+            }))
+        );
+
+        // Each data type resumes from its own marker.
+        let mock_state_diff_responses_manager =
+            mock_state_diff_response_manager.next().await.unwrap();
+        assert_eq!(
+            *mock_state_diff_responses_manager.query(),
+            Ok(StateDiffQuery(Query {
+                start_block: BlockHashOrNumber::Number(LAST_STORED_STATE_DIFF.unchecked_next()),
+                direction: Direction::Forward,
+                limit: STATE_DIFF_QUERY_LENGTH,
+                step: 1,
+            }))
+        );
+    };
+
+    tokio::select! {
+        sync_result = p2p_sync.run() => {
+            sync_result.unwrap();
+            panic!("P2P sync aborted with no failure.");
+        }
+        _ = parse_queries_future => {}
+    }
+}
+
+// Values taken from Mainnet.
+fn mainnet_signed_header() -> (SignedBlockHeader, SequencerPublicKey) {
+    let signed_block_header = SignedBlockHeader {
+        block_header: BlockHeader {
+            block_hash: BlockHash(felt!(
+                "0x7d5db04c5ca2aea828180dc441afb1580e3cee7547a3567ced3aa5bb8b273c0"
+            )),
+            state_diff_commitment: Some(StateDiffCommitment(PoseidonHash(felt!(
+                "0x64689c12248e1110af4b3af0e2b43cd51ad13e8855f10e37669e2a4baf919c6"
+            )))),
+            state_diff_length: Some(0),
+            ..Default::default()
+        },
+        signatures: vec![BlockSignature(Signature {
+            r: felt!("0x1b382bbfd693011c9b7692bc932b23ed9c288deb27c8e75772e172abbe5950c"),
+            s: felt!("0xbe4438085057e1a7c704a0da3b30f7b8340fe3d24c86772abfd24aa597e42"),
+        })],
+    };
+    let sequencer_pub_key = SequencerPublicKey(PublicKey(felt!(
+        "0x48253ff2c3bed7af18bde0b611b083b39445959102d4947c51c4db6aa4f4e58"
+    )));
+    (signed_block_header, sequencer_pub_key)
+}
+
+#[tokio::test]
+async fn sync_accepts_header_with_valid_signature() {
+    let (signed_block_header, sequencer_pub_key) = mainnet_signed_header();
+    let TestArgs {
+        p2p_sync,
+        storage_reader,
+        mut mock_header_response_manager,
+        // The test will fail if we drop these
+        mock_state_diff_response_manager: _state_diff_receiver,
+        mock_transaction_response_manager: _transaction_receiver,
+        mock_class_response_manager: _class_receiver,
+        ..
+    } = setup_with_sequencer_pub_key(sequencer_pub_key);
+
+    let parse_queries_future = async move {
+        let mut mock_header_responses_manager = mock_header_response_manager.next().await.unwrap();
+        mock_header_responses_manager
+            .send_response(DataOrFin(Some(signed_block_header)))
+            .await
+            .unwrap();
+        tokio::time::sleep(SLEEP_DURATION_TO_LET_SYNC_ADVANCE).await;
+
+        assert_eq!(
+            BlockNumber(1),
+            storage_reader.begin_ro_txn().unwrap().get_header_marker().unwrap()
+        );
+    };
+
+    tokio::select! {
+        sync_result = p2p_sync.run() => {
+            sync_result.unwrap();
+            panic!("P2P sync aborted with no failure.");
+        }
+        _ = parse_queries_future => {}
+    }
+}
+
+#[tokio::test]
+async fn sync_rejects_header_with_tampered_signature() {
+    let (mut signed_block_header, sequencer_pub_key) = mainnet_signed_header();
+    signed_block_header.signatures[0].0.s += felt!(1_u8);
+    let TestArgs {
+        p2p_sync,
+        storage_reader,
+        mut mock_header_response_manager,
+        // The test will fail if we drop these
+        mock_state_diff_response_manager: _state_diff_receiver,
+        mock_transaction_response_manager: _transaction_receiver,
+        mock_class_response_manager: _class_receiver,
+        ..
+    } = setup_with_sequencer_pub_key(sequencer_pub_key);
+
+    let parse_queries_future = async move {
+        let mut mock_header_responses_manager = mock_header_response_manager.next().await.unwrap();
+        mock_header_responses_manager
+            .send_response(DataOrFin(Some(signed_block_header)))
+            .await
+            .unwrap();
+
+        mock_header_responses_manager.assert_reported(TIMEOUT_FOR_TEST).await;
+        assert_eq!(
+            BlockNumber(0),
+            storage_reader.begin_ro_txn().unwrap().get_header_marker().unwrap()
+        );
+    };
+
+    tokio::select! {
+        sync_result = p2p_sync.run() => {
+            sync_result.unwrap();
+            panic!("P2P sync aborted with no failure.");
+        }
+        _ = parse_queries_future => {}
+    }
+}
+
 // TODO(shahak): Add negative tests.
@@ -1,7 +1,13 @@
 mod class;
+mod combined_query;
+#[cfg(test)]
+mod combined_query_test;
 mod header;
 #[cfg(test)]
 mod header_test;
+mod progress;
+#[cfg(test)]
+mod progress_test;
 mod state_diff;
 #[cfg(test)]
 mod state_diff_test;
@@ -14,7 +20,9 @@ use std::collections::BTreeMap;
 use std::time::Duration;
 
 use class::ClassStreamBuilder;
+use combined_query::create_combined_query_stream;
 use futures::channel::mpsc::SendError;
+use futures::stream::BoxStream;
 use futures::Stream;
 use header::HeaderStreamBuilder;
 use papyrus_common::pending_classes::ApiContractClass;
@@ -31,22 +39,26 @@ use papyrus_protobuf::sync::{
     StateDiffQuery,
     TransactionQuery,
 };
-use papyrus_storage::{StorageError, StorageReader, StorageWriter};
+use papyrus_storage::{StorageError, StorageReader, StorageResult, StorageWriter};
+use progress::WaitingForDataFlags;
+pub use progress::{DataSyncProgress, SyncProgress, SyncProgressReader};
 use serde::{Deserialize, Serialize};
-use starknet_api::block::BlockNumber;
-use starknet_api::core::ClassHash;
+use starknet_api::block::{BlockHash, BlockNumber};
+use starknet_api::core::{ClassHash, SequencerPublicKey};
 use starknet_api::transaction::FullTransaction;
 use state_diff::StateDiffStreamBuilder;
 use stream_builder::{DataStreamBuilder, DataStreamResult};
 use tokio_stream::StreamExt;
 use tracing::instrument;
 use transaction::TransactionStreamFactory;
+use validator::{Validate, ValidationError};
 const STEP: u64 = 1;
 const ALLOWED_SIGNATURES_LENGTH: usize = 1;
 
 const NETWORK_DATA_TIMEOUT: Duration = Duration::from_secs(300);
 
-#[derive(Clone, Copy, Debug, Serialize, Deserialize, PartialEq)]
+#[derive(Clone, Copy, Debug, Serialize, Deserialize, PartialEq, Validate)]
+#[validate(schema(function = "validate_max_wait_period_for_new_data"))]
 pub struct P2PSyncClientConfig {
     pub num_headers_per_query: u64,
     pub num_block_state_diffs_per_query: u64,
@@ -54,8 +66,21 @@ pub struct P2PSyncClientConfig {
     pub num_block_classes_per_query: u64,
     #[serde(deserialize_with = "deserialize_seconds_to_duration")]
     pub wait_period_for_new_data: Duration,
+    /// The wait period after a partial response doubles with each consecutive partial response,
+    /// starting from `wait_period_for_new_data`, up to this cap.
+    #[serde(deserialize_with = "deserialize_seconds_to_duration")]
+    pub max_wait_period_for_new_data: Duration,
     pub buffer_size: usize,
     pub stop_sync_at_block_number: Option<BlockNumber>,
+    /// Stops the sync once the header with this hash is stored, regardless of its number. If
+    /// `stop_sync_at_block_number` is set as well, the sync stops at whichever is reached first.
+    pub stop_sync_at_block_hash: Option<BlockHash>,
+    /// If set, headers whose signature doesn't verify against this key are rejected and their
+    /// peer is reported.
+    pub sequencer_pub_key: Option<SequencerPublicKey>,
+    /// Whether to query the headers, state diffs and transactions of each block range together.
+    /// Meant for syncing from trusted peers.
+    pub use_combined_queries: bool,
 }
 
 impl SerializeConfig for P2PSyncClientConfig {
@@ -93,6 +118,22 @@ impl SerializeConfig for P2PSyncClientConfig {
                  new query",
                 ParamPrivacyInput::Public,
             ),
+            ser_param(
+                "max_wait_period_for_new_data",
+                &self.max_wait_period_for_new_data.as_secs(),
+                "Maximum time in seconds to wait before sending a new query. The wait period \
+                 doubles after each consecutive query that returned with partial data, and resets \
+                 once a query returns with full data.",
+                ParamPrivacyInput::Public,
+            ),
+            ser_param(
+                "use_combined_queries",
+                &self.use_combined_queries,
+                "Whether to query the headers, state diffs and transactions of each block range \
+                 together instead of in separate streams. Meant for syncing from trusted peers. \
+                 Falls back to separate queries once a combined query isn't fully answered.",
+                ParamPrivacyInput::Public,
+            ),
             ser_param(
                 "buffer_size",
                 &self.buffer_size,
@@ -108,10 +149,41 @@ impl SerializeConfig for P2PSyncClientConfig {
              profiling on the node.",
             ParamPrivacyInput::Public,
         ));
+        config.extend(ser_optional_param(
+            &self.stop_sync_at_block_hash,
+            BlockHash::default(),
+            "stop_sync_at_block_hash",
+            "Stops the sync once the header with the given block hash is stored and closes the \
+             node cleanly. If stop_sync_at_block_number is also set, the sync stops at whichever \
+             is reached first.",
+            ParamPrivacyInput::Public,
+        ));
+        config.extend(ser_optional_param(
+            &self.sequencer_pub_key,
+            SequencerPublicKey::default(),
+            "sequencer_pub_key",
+            "The public key of the sequencer. If set, the signatures of synced headers are \
+             verified against it, and headers with an invalid signature are rejected.",
+            ParamPrivacyInput::Public,
+        ));
         config
     }
 }
 
+// The wait period can only grow from `wait_period_for_new_data`, so a lower cap is a misconfiguration.
+fn validate_max_wait_period_for_new_data(
+    config: &P2PSyncClientConfig,
+) -> Result<(), ValidationError> {
+    if config.max_wait_period_for_new_data < config.wait_period_for_new_data {
+        let mut error = ValidationError::new("Invalid max wait period for new data.");
+        error.message = Some(
+            "max_wait_period_for_new_data must not be lower than wait_period_for_new_data.".into(),
+        );
+        return Err(error);
+    }
+    Ok(())
+}
+
 impl Default for P2PSyncClientConfig {
     fn default() -> Self {
         P2PSyncClientConfig {
@@ -122,9 +194,13 @@ impl Default for P2PSyncClientConfig {
             num_block_transactions_per_query: 100,
             num_block_classes_per_query: 100,
             wait_period_for_new_data: Duration::from_secs(5),
+            max_wait_period_for_new_data: Duration::from_secs(60),
             // TODO(eitan): split this by protocol
             buffer_size: 100000,
             stop_sync_at_block_number: None,
+            stop_sync_at_block_hash: None,
+            sequencer_pub_key: None,
+            use_combined_queries: false,
         }
     }
 }
@@ -176,48 +252,83 @@ impl P2PSyncClientChannels {
         self,
         storage_reader: StorageReader,
         config: P2PSyncClientConfig,
+        waiting_for_data_flags: WaitingForDataFlags,
     ) -> impl Stream<Item = DataStreamResult> + Send + 'static {
-        let header_stream = HeaderStreamBuilder::create_stream(
-            self.header_sender,
-            storage_reader.clone(),
-            config.wait_period_for_new_data,
-            config.num_headers_per_query,
-            config.stop_sync_at_block_number,
-        );
-
-        let state_diff_stream = StateDiffStreamBuilder::create_stream(
-            self.state_diff_sender,
-            storage_reader.clone(),
-            config.wait_period_for_new_data,
-            config.num_block_state_diffs_per_query,
-            config.stop_sync_at_block_number,
-        );
-
-        let transaction_stream = TransactionStreamFactory::create_stream(
-            self.transaction_sender,
-            storage_reader.clone(),
-            config.wait_period_for_new_data,
-            config.num_block_transactions_per_query,
-            config.stop_sync_at_block_number,
-        );
-
         let class_stream = ClassStreamBuilder::create_stream(
             self.class_sender,
             storage_reader.clone(),
-            config.wait_period_for_new_data,
+            config,
             config.num_block_classes_per_query,
-            config.stop_sync_at_block_number,
+            waiting_for_data_flags.classes.clone(),
         );
 
-        header_stream.merge(state_diff_stream).merge(transaction_stream).merge(class_stream)
+        let data_streams = if config.use_combined_queries {
+            create_combined_query_stream(
+                self.header_sender,
+                self.state_diff_sender,
+                self.transaction_sender,
+                storage_reader,
+                config,
+                waiting_for_data_flags,
+            )
+        } else {
+            create_per_type_streams(
+                self.header_sender,
+                self.state_diff_sender,
+                self.transaction_sender,
+                storage_reader,
+                config,
+                &waiting_for_data_flags,
+            )
+        };
+
+        data_streams.merge(class_stream)
     }
 }
 
+/// Creates a stream for each of the headers, state diffs and transactions, each querying its own
+/// data type from its own storage marker.
+fn create_per_type_streams(
+    header_sender: HeaderSqmrSender,
+    state_diff_sender: StateSqmrDiffSender,
+    transaction_sender: TransactionSqmrSender,
+    storage_reader: StorageReader,
+    config: P2PSyncClientConfig,
+    waiting_for_data_flags: &WaitingForDataFlags,
+) -> BoxStream<'static, DataStreamResult> {
+    let header_stream = HeaderStreamBuilder::create_stream(
+        header_sender,
+        storage_reader.clone(),
+        config,
+        config.num_headers_per_query,
+        waiting_for_data_flags.headers.clone(),
+    );
+
+    let state_diff_stream = StateDiffStreamBuilder::create_stream(
+        state_diff_sender,
+        storage_reader.clone(),
+        config,
+        config.num_block_state_diffs_per_query,
+        waiting_for_data_flags.state_diffs.clone(),
+    );
+
+    let transaction_stream = TransactionStreamFactory::create_stream(
+        transaction_sender,
+        storage_reader.clone(),
+        config,
+        config.num_block_transactions_per_query,
+        waiting_for_data_flags.transactions.clone(),
+    );
+
+    futures::StreamExt::boxed(header_stream.merge(state_diff_stream).merge(transaction_stream))
+}
+
 pub struct P2PSyncClient {
     config: P2PSyncClientConfig,
     storage_reader: StorageReader,
     storage_writer: StorageWriter,
     p2p_sync_channels: P2PSyncClientChannels,
+    waiting_for_data_flags: WaitingForDataFlags,
 }
 
 impl P2PSyncClient {
@@ -227,13 +338,32 @@ impl P2PSyncClient {
         storage_writer: StorageWriter,
         p2p_sync_channels: P2PSyncClientChannels,
     ) -> Self {
-        Self { config, storage_reader, storage_writer, p2p_sync_channels }
+        Self {
+            config,
+            storage_reader,
+            storage_writer,
+            p2p_sync_channels,
+            waiting_for_data_flags: WaitingForDataFlags::default(),
+        }
+    }
+
+    /// Returns a snapshot of how far the sync has advanced.
+    pub fn progress(&self) -> StorageResult<SyncProgress> {
+        self.progress_reader().progress()
+    }
+
+    /// Returns a reader of the sync progress that remains usable after the sync starts running.
+    pub fn progress_reader(&self) -> SyncProgressReader {
+        SyncProgressReader::new(self.storage_reader.clone(), self.waiting_for_data_flags.clone())
     }
 
     #[instrument(skip(self), level = "debug", err)]
     pub async fn run(mut self) -> Result<(), P2PSyncClientError> {
-        let mut data_stream =
-            self.p2p_sync_channels.create_stream(self.storage_reader.clone(), self.config);
+        let mut data_stream = self.p2p_sync_channels.create_stream(
+            self.storage_reader.clone(),
+            self.config,
+            self.waiting_for_data_flags.clone(),
+        );
 
         loop {
             let data = data_stream.next().await.expect("Sync data stream should never end")?;
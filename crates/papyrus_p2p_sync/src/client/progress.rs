@@ -0,0 +1,68 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+
+use papyrus_storage::body::BodyStorageReader;
+use papyrus_storage::class::ClassStorageReader;
+use papyrus_storage::header::HeaderStorageReader;
+use papyrus_storage::state::StateStorageReader;
+use papyrus_storage::{StorageReader, StorageResult};
+use starknet_api::block::BlockNumber;
+
+/// The progress of the sync of a single data type.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct DataSyncProgress {
+    /// The highest block whose data is stored, or `None` if no block data is stored yet.
+    pub highest_stored_block: Option<BlockNumber>,
+    /// Whether the sync is waiting before querying for new data, either because the data it
+    /// depends on is not synced yet or because peers returned partial data.
+    pub waiting_for_data: bool,
+}
+
+/// A snapshot of how far the P2P sync has advanced, per data type.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct SyncProgress {
+    pub headers: DataSyncProgress,
+    pub state_diffs: DataSyncProgress,
+    pub transactions: DataSyncProgress,
+    pub classes: DataSyncProgress,
+}
+
+/// Flags that the data streams raise while waiting for new data.
+#[derive(Clone, Debug, Default)]
+pub(crate) struct WaitingForDataFlags {
+    pub headers: Arc<AtomicBool>,
+    pub state_diffs: Arc<AtomicBool>,
+    pub transactions: Arc<AtomicBool>,
+    pub classes: Arc<AtomicBool>,
+}
+
+/// Reports the progress of a [`P2PSyncClient`](super::P2PSyncClient), including while it runs.
+#[derive(Clone)]
+pub struct SyncProgressReader {
+    storage_reader: StorageReader,
+    waiting_for_data_flags: WaitingForDataFlags,
+}
+
+impl SyncProgressReader {
+    pub(crate) fn new(
+        storage_reader: StorageReader,
+        waiting_for_data_flags: WaitingForDataFlags,
+    ) -> Self {
+        Self { storage_reader, waiting_for_data_flags }
+    }
+
+    pub fn progress(&self) -> StorageResult<SyncProgress> {
+        let txn = self.storage_reader.begin_ro_txn()?;
+        let progress = |marker: BlockNumber, waiting_for_data: &AtomicBool| DataSyncProgress {
+            highest_stored_block: marker.prev(),
+            waiting_for_data: waiting_for_data.load(Ordering::Relaxed),
+        };
+        let flags = &self.waiting_for_data_flags;
+        Ok(SyncProgress {
+            headers: progress(txn.get_header_marker()?, &flags.headers),
+            state_diffs: progress(txn.get_state_marker()?, &flags.state_diffs),
+            transactions: progress(txn.get_body_marker()?, &flags.transactions),
+            classes: progress(txn.get_class_marker()?, &flags.classes),
+        })
+    }
+}
@@ -0,0 +1,85 @@
+use futures::StreamExt;
+use papyrus_protobuf::sync::{DataOrFin, SignedBlockHeader};
+use papyrus_storage::header::HeaderStorageReader;
+use starknet_api::block::{BlockHeader, BlockHeaderWithoutHash, BlockNumber};
+
+use super::test_utils::{
+    create_block_hashes_and_signatures,
+    setup,
+    TestArgs,
+    HEADER_QUERY_LENGTH,
+    SLEEP_DURATION_TO_LET_SYNC_ADVANCE,
+};
+use super::DataSyncProgress;
+
+#[tokio::test]
+async fn progress_matches_storage() {
+    const NUM_HEADERS: u8 = 3;
+    assert!(u64::from(NUM_HEADERS) < HEADER_QUERY_LENGTH);
+
+    let TestArgs {
+        p2p_sync,
+        storage_reader,
+        mut mock_header_response_manager,
+        // The test will fail if we drop these
+        mock_state_diff_response_manager: _state_diff_receiver,
+        mock_transaction_response_manager: _transaction_receiver,
+        mock_class_response_manager: _class_receiver,
+        ..
+    } = setup();
+    let progress_reader = p2p_sync.progress_reader();
+    assert_eq!(p2p_sync.progress().unwrap().headers, DataSyncProgress::default());
+    let block_hashes_and_signatures = create_block_hashes_and_signatures(NUM_HEADERS);
+
+    // Create a future that will send a few headers and check the reported progress.
+    let parse_queries_future = async move {
+        let mut mock_header_responses_manager = mock_header_response_manager.next().await.unwrap();
+        for (i, (block_hash, signature)) in block_hashes_and_signatures.into_iter().enumerate() {
+            mock_header_responses_manager
+                .send_response(DataOrFin(Some(SignedBlockHeader {
+                    block_header: BlockHeader {
+                        block_hash,
+                        block_header_without_hash: BlockHeaderWithoutHash {
+                            block_number: BlockNumber(i.try_into().unwrap()),
+                            ..Default::default()
+                        },
+                        state_diff_length: Some(0),
+                        ..Default::default()
+                    },
+                    signatures: vec![signature],
+                })))
+                .await
+                .unwrap();
+        }
+        tokio::time::sleep(SLEEP_DURATION_TO_LET_SYNC_ADVANCE).await;
+
+        let progress = progress_reader.progress().unwrap();
+        let header_marker = storage_reader.begin_ro_txn().unwrap().get_header_marker().unwrap();
+        assert_eq!(header_marker, BlockNumber(NUM_HEADERS.into()));
+        assert_eq!(
+            progress.headers,
+            DataSyncProgress {
+                highest_stored_block: header_marker.prev(),
+                waiting_for_data: false
+            }
+        );
+        // Classes are synced only after state diffs, which were not sent.
+        assert_eq!(
+            progress.classes,
+            DataSyncProgress { highest_stored_block: None, waiting_for_data: true }
+        );
+
+        // End the query with partial data, so the header sync waits before sending a new query.
+        mock_header_responses_manager.send_response(DataOrFin(None)).await.unwrap();
+        tokio::time::sleep(SLEEP_DURATION_TO_LET_SYNC_ADVANCE).await;
+        assert!(progress_reader.progress().unwrap().headers.waiting_for_data);
+    };
+
+    tokio::select! {
+        sync_result = p2p_sync.run() => {
+            sync_result.unwrap();
+            panic!("P2P sync aborted with no failure.");
+        }
+        _ = parse_queries_future => {}
+    }
+}
@@ -1,10 +1,12 @@
 use std::cmp::min;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
 use std::time::Duration;
 
 use async_stream::stream;
 use futures::future::BoxFuture;
 use futures::stream::BoxStream;
-use futures::StreamExt;
+use futures::{FutureExt, StreamExt};
 use papyrus_network::network_manager::{ClientResponsesManager, SqmrClientSender};
 use papyrus_protobuf::converters::ProtobufConversionError;
 use papyrus_protobuf::sync::{BlockHashOrNumber, DataOrFin, Direction, Query};
@@ -12,10 +14,10 @@ use papyrus_storage::header::HeaderStorageReader;
 use papyrus_storage::state::StateStorageReader;
 use papyrus_storage::{StorageError, StorageReader, StorageWriter};
 use starknet_api::block::{BlockNumber, BlockSignature};
-use starknet_api::core::ClassHash;
+use starknet_api::core::{ClassHash, SequencerPublicKey};
 use tracing::{debug, info, warn};
 
-use super::{P2PSyncClientError, STEP};
+use super::{P2PSyncClientConfig, P2PSyncClientError, STEP};
 
 pub type DataStreamResult = Result<Box<dyn BlockData>, P2PSyncClientError>;
 
@@ -52,19 +54,56 @@ where
 
     fn get_start_block_number(storage_reader: &StorageReader) -> Result<BlockNumber, StorageError>;
 
+    /// Parses the data of the given block and, if a sequencer public key is given, verifies its
+    /// signature.
+    fn parse_and_verify_data_for_block<'a>(
+        client_response_manager: &'a mut ClientResponsesManager<DataOrFin<InputFromNetwork>>,
+        block_number: BlockNumber,
+        storage_reader: &'a StorageReader,
+        sequencer_pub_key: Option<SequencerPublicKey>,
+    ) -> BoxFuture<'a, Result<Option<Self::Output>, ParseDataError>> {
+        async move {
+            let maybe_output =
+                Self::parse_data_for_block(client_response_manager, block_number, storage_reader)
+                    .await?;
+            if let (Some(output), Some(sequencer_pub_key)) = (&maybe_output, &sequencer_pub_key) {
+                Self::verify_signature(output, sequencer_pub_key)?;
+            }
+            Ok(maybe_output)
+        }
+        .boxed()
+    }
+
+    /// Verifies that the data was signed by the sequencer. Data that isn't signed is accepted.
+    fn verify_signature(
+        _output: &Self::Output,
+        _sequencer_pub_key: &SequencerPublicKey,
+    ) -> Result<(), BadPeerError> {
+        Ok(())
+    }
+
     fn create_stream<TQuery>(
         mut sqmr_sender: SqmrClientSender<TQuery, DataOrFin<InputFromNetwork>>,
         storage_reader: StorageReader,
-        wait_period_for_new_data: Duration,
+        config: P2PSyncClientConfig,
         num_blocks_per_query: u64,
-        stop_sync_at_block_number: Option<BlockNumber>,
+        waiting_for_data: Arc<AtomicBool>,
     ) -> BoxStream<'static, DataStreamResult>
     where
         TQuery: From<Query> + Send + 'static,
         Vec<u8>: From<TQuery>,
     {
+        let P2PSyncClientConfig {
+            wait_period_for_new_data,
+            max_wait_period_for_new_data,
+            stop_sync_at_block_number,
+            stop_sync_at_block_hash,
+            sequencer_pub_key,
+            ..
+        } = config;
         stream! {
             let mut current_block_number = Self::get_start_block_number(&storage_reader)?;
+            let mut wait_period_after_partial_response = wait_period_for_new_data;
             'send_query_and_parse_responses: loop {
                 let limit = match Self::BLOCK_NUMBER_LIMIT {
                     BlockNumberLimit::Unlimited => num_blocks_per_query,
@@ -77,7 +116,7 @@ where
                         let limit = min(last_block_number.0 - current_block_number.0, num_blocks_per_query);
                         if limit == 0 {
                             debug!("{:?} sync is waiting for a new {}", Self::TYPE_DESCRIPTION, description);
-                            tokio::time::sleep(wait_period_for_new_data).await;
+                            wait_for_new_data(&waiting_for_data, wait_period_for_new_data).await;
                             continue;
                         }
                         limit
@@ -103,17 +142,26 @@ where
                     .await?;
 
                 while current_block_number.0 < end_block_number {
-                    match Self::parse_data_for_block(
-                        &mut client_response_manager, current_block_number, &storage_reader
+                    match Self::parse_and_verify_data_for_block(
+                        &mut client_response_manager,
+                        current_block_number,
+                        &storage_reader,
+                        sequencer_pub_key,
                     ).await {
                         Ok(Some(output)) => yield Ok(Box::<dyn BlockData>::from(Box::new(output))),
                         Ok(None) => {
                             debug!(
                                 "Query for {:?} on {:?} returned with partial data. Waiting {:?} before \
                                  sending another query.",
-                                Self::TYPE_DESCRIPTION, current_block_number, wait_period_for_new_data
+                                Self::TYPE_DESCRIPTION, current_block_number,
+                                wait_period_after_partial_response
+                            );
+                            wait_for_new_data(&waiting_for_data, wait_period_after_partial_response)
+                                .await;
+                            wait_period_after_partial_response = min(
+                                wait_period_after_partial_response.saturating_mul(2),
+                                max_wait_period_for_new_data,
                             );
-                            tokio::time::sleep(wait_period_for_new_data).await;
                             continue 'send_query_and_parse_responses;
                         },
                         Err(ParseDataError::BadPeer(err)) => {
@@ -131,6 +179,15 @@ where
                         },
                     }
                     info!("Added {:?} for block {}.", Self::TYPE_DESCRIPTION, current_block_number);
+                    // The stream is polled again only after the yielded data was written, so the
+                    // header of the current block is already in the storage.
+                    let hit_stop_sync_block_hash = match stop_sync_at_block_hash {
+                        Some(stop_sync_at_block_hash) => storage_reader
+                            .begin_ro_txn()?
+                            .get_block_header(current_block_number)?
+                            .is_some_and(|header| header.block_hash == stop_sync_at_block_hash),
+                        None => false,
+                    };
                     current_block_number = current_block_number.unchecked_next();
                     if stop_sync_at_block_number.is_some_and(|stop_sync_at_block_number| {
                         current_block_number >= stop_sync_at_block_number
@@ -138,12 +195,17 @@ where
                         info!("{:?} hit the stop sync block number.", Self::TYPE_DESCRIPTION);
                         return;
                     }
+                    if hit_stop_sync_block_hash {
+                        info!("{:?} hit the stop sync block hash.", Self::TYPE_DESCRIPTION);
+                        return;
+                    }
                 }
 
                 // Consume the None message signaling the end of the query.
                 match client_response_manager.next().await {
                     Some(Ok(DataOrFin(None))) => {
                         debug!("Query sent to network for {:?} finished", Self::TYPE_DESCRIPTION);
+                        wait_period_after_partial_response = wait_period_for_new_data;
                     },
                     Some(_) => Err(P2PSyncClientError::TooManyResponses)?,
                     None => Err(P2PSyncClientError::ReceiverChannelTerminated {
@@ -156,6 +218,13 @@ where
     }
 }
 
+/// Sleeps for the given period while flagging that the stream is waiting for new data.
+async fn wait_for_new_data(waiting_for_data: &AtomicBool, wait_period: Duration) {
+    waiting_for_data.store(true, Ordering::Relaxed);
+    tokio::time::sleep(wait_period).await;
+    waiting_for_data.store(false, Ordering::Relaxed);
+}
+
 #[derive(thiserror::Error, Debug)]
 pub(crate) enum BadPeerError {
     #[error(
@@ -192,6 +261,13 @@ pub(crate) enum BadPeerError {
     ClassNotInStateDiff { class_hash: ClassHash },
     #[error("Received two classes with the same hash: {class_hash}.")]
     DuplicateClass { class_hash: ClassHash },
+    #[error("The signature of block {block_number} doesn't match the sequencer's public key.")]
+    InvalidBlockSignature { block_number: BlockNumber },
+    #[error(
+        "Can't verify the signature of block {block_number} since its header has no state diff \
+         commitment."
+    )]
+    MissingStateDiffCommitment { block_number: BlockNumber },
 }
 
 #[derive(thiserror::Error, Debug)]
@@ -17,9 +17,9 @@ use papyrus_protobuf::sync::{
     TransactionQuery,
 };
 use papyrus_storage::test_utils::get_test_storage;
-use papyrus_storage::StorageReader;
+use papyrus_storage::{StorageReader, StorageWriter};
 use starknet_api::block::{BlockHash, BlockSignature};
-use starknet_api::core::ClassHash;
+use starknet_api::core::{ClassHash, SequencerPublicKey};
 use starknet_api::crypto::utils::Signature;
 use starknet_api::hash::StarkHash;
 use starknet_api::transaction::FullTransaction;
@@ -44,8 +44,12 @@ lazy_static! {
         num_block_transactions_per_query: TRANSACTION_QUERY_LENGTH,
         num_block_classes_per_query: CLASS_DIFF_QUERY_LENGTH,
         wait_period_for_new_data: WAIT_PERIOD_FOR_NEW_DATA,
+        max_wait_period_for_new_data: WAIT_PERIOD_FOR_NEW_DATA,
         buffer_size: BUFFER_SIZE,
         stop_sync_at_block_number: None,
+        stop_sync_at_block_hash: None,
+        sequencer_pub_key: None,
+        use_combined_queries: false,
     };
 }
 type HeaderTestPayload = MockClientResponsesManager<HeaderQuery, DataOrFin<SignedBlockHeader>>;
@@ -69,9 +73,48 @@ pub struct TestArgs {
 }
 
 pub fn setup() -> TestArgs {
-    let p2p_sync_config = *TEST_CONFIG;
+    setup_with_config(*TEST_CONFIG)
+}
+
+pub fn setup_with_stop_sync_at_block_hash(stop_sync_at_block_hash: BlockHash) -> TestArgs {
+    setup_with_config(P2PSyncClientConfig {
+        stop_sync_at_block_hash: Some(stop_sync_at_block_hash),
+        ..*TEST_CONFIG
+    })
+}
+
+pub fn setup_with_sequencer_pub_key(sequencer_pub_key: SequencerPublicKey) -> TestArgs {
+    setup_with_config(P2PSyncClientConfig {
+        sequencer_pub_key: Some(sequencer_pub_key),
+        ..*TEST_CONFIG
+    })
+}
+
+pub fn setup_with_combined_queries(use_combined_queries: bool) -> TestArgs {
+    setup_with_config(P2PSyncClientConfig { use_combined_queries, ..*TEST_CONFIG })
+}
+
+pub fn setup_with_max_wait_period_for_new_data(max_wait_period_for_new_data: Duration) -> TestArgs {
+    setup_with_config(P2PSyncClientConfig { max_wait_period_for_new_data, ..*TEST_CONFIG })
+}
+
+/// Sets up the sync over a storage that was populated by `populate_storage`, as if the node
+/// restarted after storing that data.
+pub fn setup_with_populated_storage(populate_storage: impl FnOnce(&mut StorageWriter)) -> TestArgs {
+    setup_with_config_and_populated_storage(*TEST_CONFIG, populate_storage)
+}
+
+fn setup_with_config(p2p_sync_config: P2PSyncClientConfig) -> TestArgs {
+    setup_with_config_and_populated_storage(p2p_sync_config, |_| {})
+}
+
+fn setup_with_config_and_populated_storage(
+    p2p_sync_config: P2PSyncClientConfig,
+    populate_storage: impl FnOnce(&mut StorageWriter),
+) -> TestArgs {
     let buffer_size = p2p_sync_config.buffer_size;
-    let ((storage_reader, storage_writer), _temp_dir) = get_test_storage();
+    let ((storage_reader, mut storage_writer), _temp_dir) = get_test_storage();
+    populate_storage(&mut storage_writer);
     let (header_sender, mock_header_response_manager) =
         mock_register_sqmr_protocol_client(buffer_size);
     let (state_diff_sender, mock_state_diff_response_manager) =
@@ -1,3 +1,7 @@
+#[cfg(test)]
+#[path = "consensus_test.rs"]
+mod consensus_test;
+
 use futures::channel::{mpsc, oneshot};
 use starknet_api::block::{BlockHash, BlockNumber};
 use starknet_api::core::ContractAddress;
@@ -6,6 +10,13 @@ use starknet_api::transaction::Transaction;
 
 use crate::converters::ProtobufConversionError;
 
+/// Orders consensus messages by height and then by round, since tuples compare lexicographically.
+pub type HeightAndRound = (u64, u32);
+
+/// Orders proposals like [`HeightAndRound`], and then by valid round. A proposal without a valid
+/// round, i.e., with no prior round, is ordered before the proposals with one.
+pub type HeightRoundAndValidRound = (u64, u32, Option<u32>);
+
 #[derive(Debug, Default, Hash, Clone, Eq, PartialEq)]
 pub struct Proposal {
     pub height: u64,
@@ -16,6 +27,26 @@ pub struct Proposal {
     pub valid_round: Option<u32>,
 }
 
+impl Proposal {
+    pub fn height_and_round(&self) -> HeightAndRound {
+        (self.height, self.round)
+    }
+
+    pub fn height_round_and_valid_round(&self) -> HeightRoundAndValidRound {
+        (self.height, self.round, self.valid_round)
+    }
+
+    /// Returns whether both proposals have the same content: height, round, transactions and block
+    /// hash. Unlike `==`, ignores who proposed the block and its valid round, so a proposer can
+    /// check that rebuilding a proposal from the same inputs yields the same block.
+    pub fn equivalent_to(&self, other: &Proposal) -> bool {
+        self.height == other.height
+            && self.round == other.round
+            && self.transactions == other.transactions
+            && self.block_hash == other.block_hash
+    }
+}
+
 #[derive(Debug, Default, Hash, Clone, Eq, PartialEq)]
 pub enum VoteType {
     Prevote,
@@ -32,6 +63,12 @@ pub struct Vote {
     pub voter: ContractAddress,
 }
 
+impl Vote {
+    pub fn height_and_round(&self) -> HeightAndRound {
+        (self.height, self.round)
+    }
+}
+
 #[derive(Debug, Clone, Hash, Eq, PartialEq)]
 pub enum ConsensusMessage {
     Proposal(Proposal),
@@ -73,6 +110,16 @@ pub struct ProposalInit {
     pub proposer: ContractAddress,
 }
 
+impl ProposalInit {
+    pub fn height_and_round(&self) -> HeightAndRound {
+        (self.height.0, self.round)
+    }
+
+    pub fn height_round_and_valid_round(&self) -> HeightRoundAndValidRound {
+        (self.height.0, self.round, self.valid_round)
+    }
+}
+
 /// There is one or more batches of transactions in a proposed block.
 #[derive(Debug, Clone, PartialEq)]
 pub struct TransactionBatch {
@@ -99,6 +146,67 @@ pub enum ProposalPart {
     Fin(ProposalFin),
 }
 
+/// An error in the order of the parts of a streamed proposal.
+#[derive(thiserror::Error, Debug, Clone, PartialEq)]
+pub enum ProposalAssemblyError {
+    #[error("Received a proposal part before the proposal init.")]
+    MissingInit,
+    #[error("Received the proposal init more than once.")]
+    DuplicateInit,
+    #[error("Received the proposal fin more than once.")]
+    DuplicateFin,
+    #[error("Received a proposal part after the proposal fin.")]
+    PartAfterFin,
+}
+
+/// Assembles a stream of proposal parts back into a complete [`Proposal`]. The parts must start
+/// with a single `Init`, followed by any number of transaction batches and end with a single `Fin`.
+#[derive(Debug, Default)]
+pub struct ProposalPartsAssembler {
+    init: Option<ProposalInit>,
+    transactions: Vec<Transaction>,
+    is_finished: bool,
+}
+
+impl ProposalPartsAssembler {
+    /// Consumes the next part of the proposal. Returns the assembled proposal once its `Fin` is
+    /// received, and `None` while more parts are expected.
+    pub fn add_part(
+        &mut self,
+        part: ProposalPart,
+    ) -> Result<Option<Proposal>, ProposalAssemblyError> {
+        if self.is_finished {
+            return Err(match part {
+                ProposalPart::Fin(_) => ProposalAssemblyError::DuplicateFin,
+                _ => ProposalAssemblyError::PartAfterFin,
+            });
+        }
+        match (part, &self.init) {
+            (ProposalPart::Init(init), None) => {
+                self.init = Some(init);
+                Ok(None)
+            }
+            (ProposalPart::Init(_), Some(_)) => Err(ProposalAssemblyError::DuplicateInit),
+            (_, None) => Err(ProposalAssemblyError::MissingInit),
+            (ProposalPart::Transactions(batch), Some(_)) => {
+                self.transactions.extend(batch.transactions);
+                Ok(None)
+            }
+            (ProposalPart::Fin(fin), Some(init)) => {
+                self.is_finished = true;
+                Ok(Some(Proposal {
+                    height: init.height.0,
+                    round: init.round,
+                    proposer: init.proposer,
+                    transactions: std::mem::take(&mut self.transactions),
+                    block_hash: fin.proposal_content_id,
+                    valid_round: init.valid_round,
+                }))
+            }
+        }
+    }
+}
+
 impl<T> std::fmt::Display for StreamMessage<T>
 where
     T: Clone + Into<Vec<u8>> + TryFrom<Vec<u8>, Error = ProtobufConversionError>,
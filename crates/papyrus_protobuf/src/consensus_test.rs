@@ -0,0 +1,211 @@
+use papyrus_test_utils::{get_rng, GetTestInstance};
+use starknet_api::block::{BlockHash, BlockNumber};
+use starknet_api::core::ContractAddress;
+use starknet_api::transaction::Transaction;
+use starknet_types_core::felt::Felt;
+
+use crate::consensus::{
+    Proposal,
+    ProposalAssemblyError,
+    ProposalFin,
+    ProposalInit,
+    ProposalPart,
+    ProposalPartsAssembler,
+    TransactionBatch,
+    Vote,
+};
+
+fn build_proposal(transactions: &[Transaction], block_hash: BlockHash) -> Proposal {
+    Proposal {
+        height: 1,
+        round: 2,
+        proposer: ContractAddress::from(3_u8),
+        transactions: transactions.to_vec(),
+        block_hash,
+        valid_round: None,
+    }
+}
+
+#[test]
+fn proposal_equivalence() {
+    let mut rng = get_rng();
+    let transactions = vec![Transaction::get_test_instance(&mut rng); 3];
+    let block_hash = BlockHash(Felt::from(4_u8));
+
+    let proposal = build_proposal(&transactions, block_hash);
+    assert!(proposal.equivalent_to(&build_proposal(&transactions, block_hash)));
+
+    // The proposer and valid round do not affect the proposed block.
+    let other_proposer = Proposal {
+        proposer: ContractAddress::from(5_u8),
+        valid_round: Some(1),
+        ..proposal.clone()
+    };
+    assert!(proposal.equivalent_to(&other_proposer));
+
+    let other_transactions = build_proposal(&transactions[1..], block_hash);
+    assert!(!proposal.equivalent_to(&other_transactions));
+    let other_block_hash = build_proposal(&transactions, BlockHash(Felt::from(6_u8)));
+    assert!(!proposal.equivalent_to(&other_block_hash));
+    let other_round = Proposal { round: 3, ..proposal.clone() };
+    assert!(!proposal.equivalent_to(&other_round));
+}
+
+#[test]
+fn height_and_round_ordering() {
+    let mut rng = get_rng();
+    let heights_and_rounds = [(2, 0), (1, 5), (1, 0), (2, 3)];
+    let sorted_heights_and_rounds = vec![(1, 0), (1, 5), (2, 0), (2, 3)];
+
+    let mut votes: Vec<_> = heights_and_rounds
+        .iter()
+        .map(|&(height, round)| Vote { height, round, ..Vote::get_test_instance(&mut rng) })
+        .collect();
+    votes.sort_by_key(Vote::height_and_round);
+    assert_eq!(
+        votes.iter().map(Vote::height_and_round).collect::<Vec<_>>(),
+        sorted_heights_and_rounds
+    );
+
+    let mut proposals: Vec<_> = heights_and_rounds
+        .iter()
+        .map(|&(height, round)| Proposal { height, round, ..Proposal::get_test_instance(&mut rng) })
+        .collect();
+    proposals.sort_by_key(Proposal::height_and_round);
+    assert_eq!(
+        proposals.iter().map(Proposal::height_and_round).collect::<Vec<_>>(),
+        sorted_heights_and_rounds
+    );
+
+    let mut proposal_inits: Vec<_> = heights_and_rounds
+        .iter()
+        .map(|&(height, round)| ProposalInit {
+            height: BlockNumber(height),
+            round,
+            ..ProposalInit::get_test_instance(&mut rng)
+        })
+        .collect();
+    proposal_inits.sort_by_key(ProposalInit::height_and_round);
+    assert_eq!(
+        proposal_inits.iter().map(ProposalInit::height_and_round).collect::<Vec<_>>(),
+        sorted_heights_and_rounds
+    );
+}
+
+#[test]
+fn valid_round_ordering() {
+    let mut rng = get_rng();
+    // No valid round means there is no prior round, which is ordered first, and the round takes
+    // precedence over the valid round.
+    let rounds_and_valid_rounds = [(1, Some(1)), (2, None), (1, None), (1, Some(0))];
+    let sorted_keys = vec![(1, 1, None), (1, 1, Some(0)), (1, 1, Some(1)), (1, 2, None)];
+
+    let mut proposals: Vec<_> = rounds_and_valid_rounds
+        .iter()
+        .map(|&(round, valid_round)| Proposal {
+            height: 1,
+            round,
+            valid_round,
+            ..Proposal::get_test_instance(&mut rng)
+        })
+        .collect();
+    proposals.sort_by_key(Proposal::height_round_and_valid_round);
+    assert_eq!(
+        proposals.iter().map(Proposal::height_round_and_valid_round).collect::<Vec<_>>(),
+        sorted_keys
+    );
+
+    let mut proposal_inits: Vec<_> = rounds_and_valid_rounds
+        .iter()
+        .map(|&(round, valid_round)| ProposalInit {
+            height: BlockNumber(1),
+            round,
+            valid_round,
+            ..ProposalInit::get_test_instance(&mut rng)
+        })
+        .collect();
+    proposal_inits.sort_by_key(ProposalInit::height_round_and_valid_round);
+    assert_eq!(
+        proposal_inits.iter().map(ProposalInit::height_round_and_valid_round).collect::<Vec<_>>(),
+        sorted_keys
+    );
+}
+
+fn proposal_init_part() -> ProposalPart {
+    ProposalPart::Init(ProposalInit {
+        height: BlockNumber(1),
+        round: 2,
+        valid_round: Some(1),
+        proposer: ContractAddress::from(3_u8),
+    })
+}
+
+fn transactions_part(transactions: &[Transaction]) -> ProposalPart {
+    ProposalPart::Transactions(TransactionBatch { transactions: transactions.to_vec() })
+}
+
+fn proposal_fin_part() -> ProposalPart {
+    ProposalPart::Fin(ProposalFin { proposal_content_id: BlockHash(Felt::from(4_u8)) })
+}
+
+#[test]
+fn assemble_proposal_parts() {
+    let mut rng = get_rng();
+    let transactions = vec![Transaction::get_test_instance(&mut rng); 3];
+    let mut assembler = ProposalPartsAssembler::default();
+
+    assert_eq!(assembler.add_part(proposal_init_part()), Ok(None));
+    assert_eq!(assembler.add_part(transactions_part(&transactions[..1])), Ok(None));
+    assert_eq!(assembler.add_part(transactions_part(&transactions[1..])), Ok(None));
+    let proposal = assembler.add_part(proposal_fin_part()).unwrap().unwrap();
+
+    assert_eq!(
+        proposal,
+        Proposal {
+            height: 1,
+            round: 2,
+            proposer: ContractAddress::from(3_u8),
+            transactions,
+            block_hash: BlockHash(Felt::from(4_u8)),
+            valid_round: Some(1),
+        }
+    );
+}
+
+#[test]
+fn assemble_proposal_without_transactions() {
+    let mut assembler = ProposalPartsAssembler::default();
+    assert_eq!(assembler.add_part(proposal_init_part()), Ok(None));
+    let proposal = assembler.add_part(proposal_fin_part()).unwrap().unwrap();
+    assert!(proposal.transactions.is_empty());
+}
+
+#[test]
+fn assemble_malformed_proposal_parts() {
+    let cases = [
+        (vec![transactions_part(&[])], ProposalAssemblyError::MissingInit),
+        (vec![proposal_fin_part()], ProposalAssemblyError::MissingInit),
+        (vec![proposal_init_part(), proposal_init_part()], ProposalAssemblyError::DuplicateInit),
+        (
+            vec![proposal_init_part(), proposal_fin_part(), proposal_fin_part()],
+            ProposalAssemblyError::DuplicateFin,
+        ),
+        (
+            vec![proposal_init_part(), proposal_fin_part(), transactions_part(&[])],
+            ProposalAssemblyError::PartAfterFin,
+        ),
+        (
+            vec![proposal_init_part(), proposal_fin_part(), proposal_init_part()],
+            ProposalAssemblyError::PartAfterFin,
+        ),
+    ];
+
+    for (parts, expected_error) in cases {
+        let mut assembler = ProposalPartsAssembler::default();
+        let (last_part, first_parts) = parts.split_last().unwrap();
+        for part in first_parts {
+            assembler.add_part(part.clone()).unwrap();
+        }
+        assert_eq!(assembler.add_part(last_part.clone()), Err(expected_error));
+    }
+}
@@ -1,3 +1,6 @@
+#[cfg(test)]
+#[path = "common_test.rs"]
+mod common_test;
 use starknet_api::block::{BlockHash, BlockNumber};
 use starknet_api::data_availability::{DataAvailabilityMode, L1DataAvailabilityMode};
 
@@ -12,8 +15,13 @@ pub const PATRICIA_HEIGHT: u32 = 251;
 impl TryFrom<protobuf::Felt252> for starknet_types_core::felt::Felt {
     type Error = ProtobufConversionError;
     fn try_from(value: protobuf::Felt252) -> Result<Self, Self::Error> {
-        let mut felt = [0; 32];
-        felt.copy_from_slice(&value.elements);
+        let felt: [u8; 32] = value.elements.try_into().map_err(|elements| {
+            ProtobufConversionError::BytesDataLengthMismatch {
+                type_description: "Felt252",
+                num_expected: 32,
+                value: elements,
+            }
+        })?;
         // TODO: use from_bytes_checked once it's available.
         Ok(Self::from_bytes_be(&felt))
         // if let Ok(stark_felt) = Self::from_bytes_be(&felt) {
@@ -70,15 +78,13 @@ impl From<protobuf::Uint128> for u128 {
 impl TryFrom<protobuf::Hash> for starknet_api::hash::StarkHash {
     type Error = ProtobufConversionError;
     fn try_from(value: protobuf::Hash) -> Result<Self, Self::Error> {
-        let mut felt = [0; 32];
-        if value.elements.len() != 32 {
-            return Err(ProtobufConversionError::BytesDataLengthMismatch {
+        let felt: [u8; 32] = value.elements.try_into().map_err(|elements| {
+            ProtobufConversionError::BytesDataLengthMismatch {
                 type_description: "Hash",
                 num_expected: 32,
-                value: value.elements,
-            });
-        }
-        felt.copy_from_slice(&value.elements);
+                value: elements,
+            }
+        })?;
         // TODO: use from_bytes_checked once it's available.
         Ok(Self::from_bytes_be(&felt))
         // if let Ok(stark_hash) = Self::new(felt) {
@@ -95,15 +101,13 @@ impl TryFrom<protobuf::Hash> for starknet_api::hash::StarkHash {
 impl TryFrom<protobuf::Address> for starknet_api::core::ContractAddress {
     type Error = ProtobufConversionError;
     fn try_from(value: protobuf::Address) -> Result<Self, Self::Error> {
-        let mut felt = [0; 32];
-        if value.elements.len() != 32 {
-            return Err(ProtobufConversionError::BytesDataLengthMismatch {
+        let felt: [u8; 32] = value.elements.try_into().map_err(|elements| {
+            ProtobufConversionError::BytesDataLengthMismatch {
                 type_description: "Address",
                 num_expected: 32,
-                value: value.elements,
-            });
-        }
-        felt.copy_from_slice(&value.elements);
+                value: elements,
+            }
+        })?;
         // TODO: use from_bytes_checked once it's available.
         let hash = starknet_types_core::felt::Felt::from_bytes_be(&felt);
         // if let Ok(hash) = starknet_api::hash::StarkHash::new(felt) {
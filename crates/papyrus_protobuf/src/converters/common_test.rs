@@ -0,0 +1,59 @@
+use starknet_api::core::{ContractAddress, EthAddress};
+use starknet_api::hash::StarkHash;
+use starknet_types_core::felt::Felt;
+
+use crate::converters::ProtobufConversionError;
+use crate::protobuf;
+
+#[test]
+fn felt_with_wrong_length_is_rejected() {
+    for elements in [vec![1; 31], vec![1; 33]] {
+        assert_eq!(
+            Felt::try_from(protobuf::Felt252 { elements: elements.clone() }),
+            Err(ProtobufConversionError::BytesDataLengthMismatch {
+                type_description: "Felt252",
+                num_expected: 32,
+                value: elements,
+            })
+        );
+    }
+}
+
+#[test]
+fn hash_with_wrong_length_is_rejected() {
+    let elements = vec![1; 31];
+    assert_eq!(
+        StarkHash::try_from(protobuf::Hash { elements: elements.clone() }),
+        Err(ProtobufConversionError::BytesDataLengthMismatch {
+            type_description: "Hash",
+            num_expected: 32,
+            value: elements,
+        })
+    );
+}
+
+#[test]
+fn address_with_wrong_length_is_rejected() {
+    let elements = vec![1; 33];
+    assert_eq!(
+        ContractAddress::try_from(protobuf::Address { elements: elements.clone() }),
+        Err(ProtobufConversionError::BytesDataLengthMismatch {
+            type_description: "Address",
+            num_expected: 32,
+            value: elements,
+        })
+    );
+}
+
+#[test]
+fn ethereum_address_with_wrong_length_is_rejected() {
+    let elements = vec![1; 21];
+    assert_eq!(
+        EthAddress::try_from(protobuf::EthereumAddress { elements: elements.clone() }),
+        Err(ProtobufConversionError::BytesDataLengthMismatch {
+            type_description: "EthereumAddress",
+            num_expected: 20,
+            value: elements,
+        })
+    );
+}
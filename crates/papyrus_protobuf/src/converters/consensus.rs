@@ -23,15 +23,27 @@ use crate::consensus::{
 use crate::converters::ProtobufConversionError;
 use crate::{auto_impl_into_and_try_from_vec_u8, protobuf};
 
+// Converts the transactions of a proposal, reporting the index of the first one that fails.
+fn transactions_try_from_protobuf(
+    transactions: Vec<protobuf::Transaction>,
+) -> Result<Vec<Transaction>, ProtobufConversionError> {
+    transactions
+        .into_iter()
+        .enumerate()
+        .map(|(index, tx)| {
+            tx.try_into().map_err(|error| ProtobufConversionError::TransactionAtIndex {
+                index,
+                error: Box::new(error),
+            })
+        })
+        .collect()
+}
+
 impl TryFrom<protobuf::Proposal> for Proposal {
     type Error = ProtobufConversionError;
 
     fn try_from(value: protobuf::Proposal) -> Result<Self, Self::Error> {
-        let transactions = value
-            .transactions
-            .into_iter()
-            .map(|tx| tx.try_into())
-            .collect::<Result<Vec<Transaction>, ProtobufConversionError>>()?;
+        let transactions = transactions_try_from_protobuf(value.transactions)?;
 
         let height = value.height;
         let round = value.round;
@@ -229,11 +241,7 @@ auto_impl_into_and_try_from_vec_u8!(ProposalInit, protobuf::ProposalInit);
 impl TryFrom<protobuf::TransactionBatch> for TransactionBatch {
     type Error = ProtobufConversionError;
     fn try_from(value: protobuf::TransactionBatch) -> Result<Self, Self::Error> {
-        let transactions = value
-            .transactions
-            .into_iter()
-            .map(|tx| tx.try_into())
-            .collect::<Result<Vec<Transaction>, ProtobufConversionError>>()?;
+        let transactions = transactions_try_from_protobuf(value.transactions)?;
         Ok(TransactionBatch { transactions })
     }
 }
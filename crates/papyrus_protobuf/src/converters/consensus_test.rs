@@ -7,6 +7,7 @@ use starknet_api::transaction::{
     DeployAccountTransaction,
     DeployAccountTransactionV3,
     InvokeTransaction,
+    InvokeTransactionV1,
     InvokeTransactionV3,
     Transaction,
 };
@@ -22,6 +23,8 @@ use crate::consensus::{
     TransactionBatch,
     Vote,
 };
+use crate::converters::ProtobufConversionError;
+use crate::protobuf;
 
 // If all the fields of `AllResources` are 0 upon serialization,
 // then the deserialized value will be interpreted as the `L1Gas` variant.
@@ -66,6 +69,18 @@ fn convert_stream_message_to_vec_u8_and_back() {
     assert_eq!(stream_message, res_data);
 }
 
+#[test]
+fn convert_stream_message_with_boundary_stream_ids_to_vec_u8_and_back() {
+    for stream_id in [0, u64::MAX] {
+        let stream_message: StreamMessage<ConsensusMessage> =
+            StreamMessage { message: StreamMessageBody::Fin, stream_id, message_id: u64::MAX };
+
+        let bytes_data: Vec<u8> = stream_message.clone().into();
+        let res_data = StreamMessage::try_from(bytes_data).unwrap();
+        assert_eq!(stream_message, res_data);
+    }
+}
+
 #[test]
 fn convert_consensus_message_to_vec_u8_and_back() {
     let mut rng = get_rng();
@@ -187,3 +202,33 @@ fn stream_message_display() {
         )
     );
 }
+
+#[test]
+fn proposal_with_corrupted_transaction_reports_its_index() {
+    const N_TRANSACTIONS: usize = 4;
+    let transactions =
+        vec![
+            Transaction::Invoke(InvokeTransaction::V1(InvokeTransactionV1::default()));
+            N_TRANSACTIONS
+        ];
+    let proposal = Proposal { transactions: transactions.clone(), ..Default::default() };
+    let batch = TransactionBatch { transactions };
+
+    for corrupted_index in 0..N_TRANSACTIONS {
+        let mut protobuf_proposal = protobuf::Proposal::from(proposal.clone());
+        protobuf_proposal.transactions[corrupted_index] = protobuf::Transaction::default();
+        assert!(matches!(
+            Proposal::try_from(protobuf_proposal),
+            Err(ProtobufConversionError::TransactionAtIndex { index, .. })
+            if index == corrupted_index
+        ));
+
+        let mut protobuf_batch = protobuf::TransactionBatch::from(batch.clone());
+        protobuf_batch.transactions[corrupted_index] = protobuf::Transaction::default();
+        assert!(matches!(
+            TransactionBatch::try_from(protobuf_batch),
+            Err(ProtobufConversionError::TransactionAtIndex { index, .. })
+            if index == corrupted_index
+        ));
+    }
+}
@@ -373,15 +373,13 @@ impl From<GasVector> for protobuf::receipt::execution_resources::GasVector {
 impl TryFrom<protobuf::EthereumAddress> for EthAddress {
     type Error = ProtobufConversionError;
     fn try_from(value: protobuf::EthereumAddress) -> Result<Self, Self::Error> {
-        let mut felt = [0; 20];
-        if value.elements.len() != 20 {
-            return Err(ProtobufConversionError::BytesDataLengthMismatch {
+        let felt: [u8; 20] = value.elements.try_into().map_err(|elements| {
+            ProtobufConversionError::BytesDataLengthMismatch {
                 type_description: "EthereumAddress",
                 num_expected: 20,
-                value: value.elements,
-            });
-        }
-        felt.copy_from_slice(&value.elements);
+                value: elements,
+            }
+        })?;
         Ok(EthAddress(primitive_types::H160(felt)))
     }
 }
@@ -3,6 +3,7 @@ use std::fmt::Debug;
 use indexmap::IndexMap;
 #[cfg(any(feature = "testing", test))]
 use papyrus_test_utils::{auto_impl_get_test_instance, get_number_of_variants, GetTestInstance};
+use serde::{Deserialize, Serialize};
 use starknet_api::block::{BlockHash, BlockHeader, BlockNumber, BlockSignature};
 use starknet_api::core::{ClassHash, CompiledClassHash, ContractAddress, Nonce};
 use starknet_api::state::StorageKey;
@@ -115,7 +116,7 @@ pub struct SignedBlockHeader {
     pub signatures: Vec<BlockSignature>,
 }
 
-#[derive(Debug, Default, Clone, PartialEq, Eq)]
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct ContractDiff {
     pub contract_address: ContractAddress,
     // Has value only if the contract was deployed or replaced in this block.
@@ -125,18 +126,18 @@ pub struct ContractDiff {
     pub storage_diffs: IndexMap<StorageKey, Felt>,
 }
 
-#[derive(Debug, Default, Clone, PartialEq, Eq)]
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct DeclaredClass {
     pub class_hash: ClassHash,
     pub compiled_class_hash: CompiledClassHash,
 }
 
-#[derive(Debug, Default, Clone, PartialEq, Eq)]
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct DeprecatedDeclaredClass {
     pub class_hash: ClassHash,
 }
 
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub enum StateDiffChunk {
     ContractDiff(ContractDiff),
     DeclaredClass(DeclaredClass),
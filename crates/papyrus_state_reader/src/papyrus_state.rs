@@ -3,15 +3,35 @@ use blockifier::execution::contract_class::{
     ContractClassV1,
     RunnableContractClass,
 };
+#[cfg(feature = "cairo_native")]
+use blockifier::execution::native::contract_class::NativeContractClassV1;
 use blockifier::state::errors::StateError;
 use blockifier::state::global_cache::GlobalContractCache;
 use blockifier::state::state_api::{StateReader, StateResult};
+#[cfg(feature = "cairo_native")]
+use cairo_lang_starknet_classes::contract_class::{
+    ContractClass as CairoLangContractClass,
+    ContractEntryPoint as CairoLangContractEntryPoint,
+    ContractEntryPoints as CairoLangContractEntryPoints,
+};
+#[cfg(feature = "cairo_native")]
+use cairo_lang_utils::bigint::BigUintAsHex;
+#[cfg(feature = "cairo_native")]
+use cairo_native::executor::AotContractExecutor;
+#[cfg(feature = "cairo_native")]
+use cairo_native::OptLevel;
+#[cfg(feature = "cairo_native")]
+use papyrus_storage::class::ClassStorageReader;
 use papyrus_storage::compiled_class::CasmStorageReader;
 use papyrus_storage::db::RO;
 use papyrus_storage::state::StateStorageReader;
 use papyrus_storage::StorageReader;
 use starknet_api::block::BlockNumber;
+#[cfg(feature = "cairo_native")]
+use starknet_api::contract_class::EntryPointType;
 use starknet_api::core::{ClassHash, CompiledClassHash, ContractAddress, Nonce};
+#[cfg(feature = "cairo_native")]
+use starknet_api::state::ContractClass as SierraContractClass;
 use starknet_api::state::{StateNumber, StorageKey};
 use starknet_types_core::felt::Felt;
 
@@ -66,7 +86,15 @@ impl PapyrusReader {
                      inconsistent.",
                 );
 
-            return Ok(RunnableContractClass::V1(ContractClassV1::try_from(casm_contract_class)?));
+            let casm = ContractClassV1::try_from(casm_contract_class)?;
+            // A natively compiled class is cached by the caller in the global cache, so it is
+            // compiled once and shared by all the calls to it, across transactions and blocks.
+            #[cfg(feature = "cairo_native")]
+            let contract_class =
+                RunnableContractClass::V1Native(self.compile_to_native(class_hash, casm)?);
+            #[cfg(not(feature = "cairo_native"))]
+            let contract_class = RunnableContractClass::V1(casm);
+            return Ok(contract_class);
         }
 
         let v0_contract_class = self
@@ -82,6 +110,70 @@ impl PapyrusReader {
             None => Err(StateError::UndeclaredClassHash(class_hash)),
         }
     }
+
+    /// Compiles the Sierra class with the given hash into a native contract class.
+    #[cfg(feature = "cairo_native")]
+    fn compile_to_native(
+        &self,
+        class_hash: ClassHash,
+        casm: ContractClassV1,
+    ) -> StateResult<NativeContractClassV1> {
+        let sierra_contract_class = self
+            .reader()?
+            .get_class(&class_hash)
+            .map_err(|err| StateError::StateReadError(err.to_string()))?
+            .expect(
+                "Should be able to fetch a Sierra class if its definition exists, database is \
+                 inconsistent.",
+            );
+        let sierra_contract_class = into_cairo_lang_contract_class(sierra_contract_class);
+        let compilation_error = |err: String| {
+            StateError::StateReadError(format!(
+                "Failed to compile class {class_hash} to native: {err}"
+            ))
+        };
+        let sierra_program = sierra_contract_class
+            .extract_sierra_program()
+            .map_err(|err| compilation_error(err.to_string()))?;
+        let executor = AotContractExecutor::new(&sierra_program, OptLevel::Default)
+            .map_err(|err| compilation_error(err.to_string()))?;
+        Ok(NativeContractClassV1::new(executor, sierra_contract_class, casm))
+    }
+}
+
+/// Converts a stored Sierra class into the format compiled by Cairo native. The ABI, the debug info
+/// and the contract class version aren't stored, and are not needed for the compilation.
+#[cfg(feature = "cairo_native")]
+fn into_cairo_lang_contract_class(
+    sierra_contract_class: SierraContractClass,
+) -> CairoLangContractClass {
+    let SierraContractClass { sierra_program, entry_points_by_type, .. } = sierra_contract_class;
+    let entry_points = |entry_point_type| -> Vec<CairoLangContractEntryPoint> {
+        entry_points_by_type
+            .get(&entry_point_type)
+            .into_iter()
+            .flatten()
+            .map(|entry_point| CairoLangContractEntryPoint {
+                selector: entry_point.selector.0.to_biguint(),
+                function_idx: entry_point.function_idx.0,
+            })
+            .collect()
+    };
+
+    CairoLangContractClass {
+        sierra_program: sierra_program
+            .iter()
+            .map(|felt| BigUintAsHex { value: felt.to_biguint() })
+            .collect(),
+        sierra_program_debug_info: None,
+        contract_class_version: String::new(),
+        entry_points_by_type: CairoLangContractEntryPoints {
+            external: entry_points(EntryPointType::External),
+            l1_handler: entry_points(EntryPointType::L1Handler),
+            constructor: entry_points(EntryPointType::Constructor),
+        },
+        abi: None,
+    }
 }
 
 // Currently unused - will soon replace the same `impl` for `PapyrusStateReader`.
@@ -1,6 +1,9 @@
+use std::sync::Arc;
+
 use assert_matches::assert_matches;
 use blockifier::abi::abi_utils::selector_from_name;
 use blockifier::execution::call_info::CallExecution;
+use blockifier::execution::contract_class::RunnableContractClass;
 use blockifier::execution::entry_point::CallEntryPoint;
 use blockifier::retdata;
 use blockifier::state::cached_state::CachedState;
@@ -8,21 +11,31 @@ use blockifier::state::global_cache::{GlobalContractCache, GLOBAL_CONTRACT_CACHE
 use blockifier::state::state_api::StateReader;
 use blockifier::test_utils::contracts::FeatureContract;
 use blockifier::test_utils::{trivial_external_entry_point_new, CairoVersion};
+#[cfg(feature = "cairo_native")]
+use cairo_lang_starknet_classes::casm_contract_class::CasmContractClass;
+#[cfg(feature = "cairo_native")]
+use cairo_lang_starknet_classes::contract_class::ContractClass as CairoLangContractClass;
 use indexmap::IndexMap;
 use papyrus_storage::class::ClassStorageWriter;
+#[cfg(feature = "cairo_native")]
+use papyrus_storage::compiled_class::CasmStorageWriter;
 use papyrus_storage::state::StateStorageWriter;
+use papyrus_storage::StorageReader;
 use starknet_api::block::BlockNumber;
 use starknet_api::contract_class::ContractClass;
+#[cfg(feature = "cairo_native")]
+use starknet_api::state::ContractClass as SierraContractClass;
 use starknet_api::state::{StateDiff, StorageKey};
 use starknet_api::{calldata, felt};
 
 use crate::papyrus_state::PapyrusReader;
 
-#[test]
-fn test_entry_point_with_papyrus_state() -> papyrus_storage::StorageResult<()> {
-    let ((storage_reader, mut storage_writer), _) = papyrus_storage::test_utils::get_test_storage();
-
-    let test_contract = FeatureContract::TestContract(CairoVersion::Cairo0);
+/// Writes a block to the given storage, in which the given (Cairo0) contract is declared and
+/// deployed.
+fn declare_and_deploy_in_storage(
+    storage_writer: &mut papyrus_storage::StorageWriter,
+    test_contract: FeatureContract,
+) -> papyrus_storage::StorageResult<()> {
     let test_class_hash = test_contract.get_class_hash();
     let test_class = assert_matches!(
         test_contract.get_class(), ContractClass::V0(contract_class) => contract_class
@@ -44,6 +57,15 @@ fn test_entry_point_with_papyrus_state() -> papyrus_storage::StorageResult<()> {
         .append_classes(block_number, Default::default(), &[(test_class_hash, &test_class)])?
         .commit()?;
 
+    Ok(())
+}
+
+#[test]
+fn test_entry_point_with_papyrus_state() -> papyrus_storage::StorageResult<()> {
+    let ((storage_reader, mut storage_writer), _) = papyrus_storage::test_utils::get_test_storage();
+    let test_contract = FeatureContract::TestContract(CairoVersion::Cairo0);
+    declare_and_deploy_in_storage(&mut storage_writer, test_contract)?;
+
     // BlockNumber is 1 due to the initialization step above.
     let block_number = BlockNumber(1);
     let papyrus_reader = PapyrusReader::new(
@@ -75,3 +97,133 @@ fn test_entry_point_with_papyrus_state() -> papyrus_storage::StorageResult<()> {
 
     Ok(())
 }
+
+#[test]
+fn test_class_is_loaded_once_across_calls_and_readers() -> papyrus_storage::StorageResult<()> {
+    const N_CALLS: usize = 5;
+
+    let ((storage_reader, mut storage_writer), _) = papyrus_storage::test_utils::get_test_storage();
+    let test_contract = FeatureContract::TestContract(CairoVersion::Cairo0);
+    declare_and_deploy_in_storage(&mut storage_writer, test_contract)?;
+    let test_class_hash = test_contract.get_class_hash();
+    let entry_point_call = CallEntryPoint {
+        calldata: calldata![felt!(1234_u16), felt!(18_u8)],
+        entry_point_selector: selector_from_name("test_storage_read_write"),
+        ..trivial_external_entry_point_new(test_contract)
+    };
+
+    // Each block is executed over a fresh reader, while the global cache is shared by all of them
+    // (as done by the block builder).
+    let global_contract_cache = GlobalContractCache::new(GLOBAL_CONTRACT_CACHE_SIZE_FOR_TEST);
+    let new_reader = |storage_reader: StorageReader| {
+        PapyrusReader::new(storage_reader, BlockNumber(1), global_contract_cache.clone())
+    };
+
+    let mut loaded_classes = vec![];
+    for _ in 0..2 {
+        let mut state = CachedState::from(new_reader(storage_reader.clone()));
+        for _ in 0..N_CALLS {
+            entry_point_call.clone().execute_directly(&mut state).unwrap();
+        }
+        loaded_classes.push(state.get_compiled_contract_class(test_class_hash).unwrap());
+    }
+
+    // All the calls, in all the blocks, use the same loaded class.
+    let [first_block_class, second_block_class] = loaded_classes.try_into().unwrap();
+    let (
+        RunnableContractClass::V0(first_block_class),
+        RunnableContractClass::V0(second_block_class),
+    ) = (first_block_class, second_block_class)
+    else {
+        panic!("Expected a Cairo0 contract class.");
+    };
+    assert!(Arc::ptr_eq(&first_block_class.0, &second_block_class.0));
+
+    Ok(())
+}
+
+/// Writes a block to the given storage, in which the given (Sierra) contract is declared and
+/// deployed.
+#[cfg(feature = "cairo_native")]
+fn declare_and_deploy_sierra_in_storage(
+    storage_writer: &mut papyrus_storage::StorageWriter,
+    test_contract: FeatureContract,
+) -> papyrus_storage::StorageResult<()> {
+    let test_class_hash = test_contract.get_class_hash();
+    let raw_class = test_contract.get_raw_class();
+    let cairo_lang_class: CairoLangContractClass = serde_json::from_str(&raw_class).unwrap();
+    let casm = CasmContractClass::from_contract_class(cairo_lang_class, false, usize::MAX).unwrap();
+    // The stored class keeps its ABI as a string.
+    let mut raw_class: serde_json::Value = serde_json::from_str(&raw_class).unwrap();
+    raw_class["abi"] = serde_json::Value::String(raw_class["abi"].to_string());
+    let test_class: SierraContractClass = serde_json::from_value(raw_class).unwrap();
+
+    let state_diff = StateDiff {
+        deployed_contracts: IndexMap::from([(
+            test_contract.get_instance_address(0),
+            test_class_hash,
+        )]),
+        declared_classes: IndexMap::from([(
+            test_class_hash,
+            (test_contract.get_compiled_class_hash(), test_class.clone()),
+        )]),
+        ..Default::default()
+    };
+
+    let block_number = BlockNumber::default();
+    storage_writer
+        .begin_rw_txn()?
+        .append_state_diff(block_number, state_diff.into())?
+        .append_classes(block_number, &[(test_class_hash, &test_class)], Default::default())?
+        .append_casm(&test_class_hash, &casm)?
+        .commit()?;
+
+    Ok(())
+}
+
+#[cfg(feature = "cairo_native")]
+#[test]
+fn test_native_class_is_compiled_once_across_calls_and_readers()
+-> papyrus_storage::StorageResult<()> {
+    const N_CALLS: usize = 5;
+
+    let ((storage_reader, mut storage_writer), _) = papyrus_storage::test_utils::get_test_storage();
+    let test_contract = FeatureContract::TestContract(CairoVersion::Native);
+    declare_and_deploy_sierra_in_storage(&mut storage_writer, test_contract)?;
+    let test_class_hash = test_contract.get_class_hash();
+    let entry_point_call = CallEntryPoint {
+        calldata: calldata![felt!(1234_u16), felt!(18_u8)],
+        entry_point_selector: selector_from_name("test_storage_read_write"),
+        ..trivial_external_entry_point_new(test_contract)
+    };
+
+    // Each block is executed over a fresh reader, while the global cache is shared by all of them
+    // (as done by the block builder).
+    let global_contract_cache = GlobalContractCache::new(GLOBAL_CONTRACT_CACHE_SIZE_FOR_TEST);
+    let new_reader = |storage_reader: StorageReader| {
+        PapyrusReader::new(storage_reader, BlockNumber(1), global_contract_cache.clone())
+    };
+
+    let mut loaded_classes = vec![];
+    for _ in 0..2 {
+        let mut state = CachedState::from(new_reader(storage_reader.clone()));
+        for _ in 0..N_CALLS {
+            let call_info = entry_point_call.clone().execute_directly(&mut state).unwrap();
+            assert_eq!(call_info.execution.retdata, retdata![felt!(18_u8)]);
+        }
+        loaded_classes.push(state.get_compiled_contract_class(test_class_hash).unwrap());
+    }
+
+    // All the calls, in all the blocks, run the same natively compiled class.
+    let [first_block_class, second_block_class] = loaded_classes.try_into().unwrap();
+    let (
+        RunnableContractClass::V1Native(first_block_class),
+        RunnableContractClass::V1Native(second_block_class),
+    ) = (first_block_class, second_block_class)
+    else {
+        panic!("Expected a natively compiled contract class.");
+    };
+    assert!(Arc::ptr_eq(&first_block_class.0, &second_block_class.0));
+
+    Ok(())
+}
@@ -10,10 +10,12 @@ use papyrus_config::converters::{
     deserialize_seconds_to_duration,
 };
 use papyrus_config::dumping::{append_sub_config_name, ser_param, SerializeConfig};
+use papyrus_config::validators::validate_non_zero_duration;
 use papyrus_config::{ParamPath, ParamPrivacyInput, SerializedParam};
 use papyrus_network::NetworkConfig;
 use serde::{Deserialize, Serialize};
 use starknet_api::block::BlockNumber;
+use validator::Validate;
 
 use super::types::ValidatorId;
 
@@ -149,3 +151,55 @@ impl Default for TimeoutsConfig {
         }
     }
 }
+
+/// Configuration for the stream handler.
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq, Validate)]
+pub struct StreamHandlerConfig {
+    /// An inbound stream that does not receive any message for this long is dropped.
+    #[serde(deserialize_with = "deserialize_float_seconds_to_duration")]
+    #[validate(custom = "validate_non_zero_duration")]
+    pub inbound_stream_timeout: Duration,
+    /// The maximal number of out-of-order messages buffered for a single inbound stream.
+    #[validate(range(min = 1))]
+    pub max_buffered_messages_per_stream: usize,
+    /// The maximal total size, in bytes, of the out-of-order messages buffered for a single
+    /// inbound stream.
+    #[validate(range(min = 1))]
+    pub max_buffered_bytes_per_stream: usize,
+}
+
+impl SerializeConfig for StreamHandlerConfig {
+    fn dump(&self) -> BTreeMap<ParamPath, SerializedParam> {
+        BTreeMap::from_iter([
+            ser_param(
+                "inbound_stream_timeout",
+                &self.inbound_stream_timeout.as_secs_f64(),
+                "The time (seconds) after which an inactive inbound stream is dropped.",
+                ParamPrivacyInput::Public,
+            ),
+            ser_param(
+                "max_buffered_messages_per_stream",
+                &self.max_buffered_messages_per_stream,
+                "The maximal number of out-of-order messages buffered for an inbound stream.",
+                ParamPrivacyInput::Public,
+            ),
+            ser_param(
+                "max_buffered_bytes_per_stream",
+                &self.max_buffered_bytes_per_stream,
+                "The maximal total size (bytes) of the out-of-order messages buffered for an \
+                 inbound stream.",
+                ParamPrivacyInput::Public,
+            ),
+        ])
+    }
+}
+
+impl Default for StreamHandlerConfig {
+    fn default() -> Self {
+        Self {
+            inbound_stream_timeout: Duration::from_secs_f64(60.0),
+            max_buffered_messages_per_stream: 1000,
+            max_buffered_bytes_per_stream: 1 << 24,
+        }
+    }
+}
@@ -63,13 +63,15 @@ pub enum ShcTask {
     /// Validating a proposal is handled in 3 stages:
     /// 1. The SHC validates `ProposalInit`, then starts block validation within the context.
     /// 2. SHC returns, allowing the context to validate the content while the Manager await the
-    ///    result without blocking consensus.
+    ///    result without blocking consensus. The fin sent by the proposer is passed on to the
+    ///    context, which validates the content against it.
     /// 3. Once validation is complete, the manager returns the built proposal to the SHC as an
     ///    event, which can be sent to the SM.
     ValidateProposal(
         ProposalInit,
         oneshot::Receiver<ProposalContentId>, // Block built from the content.
         oneshot::Receiver<ProposalContentId>, // Fin sent by the proposer.
+        oneshot::Sender<ProposalContentId>,   // Fin passed on to the context.
     ),
 }
 
@@ -82,7 +84,7 @@ impl PartialEq for ShcTask {
             | (ShcTask::Prevote(d1, e1), ShcTask::Prevote(d2, e2))
             | (ShcTask::Precommit(d1, e1), ShcTask::Precommit(d2, e2)) => d1 == d2 && e1 == e2,
             (ShcTask::BuildProposal(r1, _), ShcTask::BuildProposal(r2, _)) => r1 == r2,
-            (ShcTask::ValidateProposal(pi1, _, _), ShcTask::ValidateProposal(pi2, _, _)) => {
+            (ShcTask::ValidateProposal(pi1, _, _, _), ShcTask::ValidateProposal(pi2, _, _, _)) => {
                 pi1 == pi2
             }
             _ => false,
@@ -122,17 +124,23 @@ impl ShcTask {
                 init,
                 id_built_from_content_receiver,
                 fin_from_proposer_receiver,
+                fin_to_context_sender,
             ) => {
+                let fin = match fin_from_proposer_receiver.await {
+                    Ok(fin) => {
+                        // The context may have already stopped validating the proposal.
+                        let _ = fin_to_context_sender.send(fin);
+                        Some(fin)
+                    }
+                    // ProposalFin never received from peer. Dropping the sender lets the context
+                    // know.
+                    Err(_) => None,
+                };
                 let proposal_id = match id_built_from_content_receiver.await {
                     Ok(proposal_id) => Some(proposal_id),
                     // Proposal never received from peer.
                     Err(_) => None,
                 };
-                let fin = match fin_from_proposer_receiver.await {
-                    Ok(fin) => Some(fin),
-                    // ProposalFin never received from peer.
-                    Err(_) => None,
-                };
                 ShcEvent::ValidateProposal(
                     StateMachineEvent::Proposal(proposal_id, init.round, init.valid_round),
                     fin,
@@ -230,10 +238,21 @@ impl SingleHeightConsensus {
         // Since validating the proposal is non-blocking, we want to avoid validating the same round
         // twice in parallel. This could be caused by a network repeat or a malicious spam attack.
         proposal_entry.insert(None);
+        let (fin_to_context_sender, fin_to_context_receiver) = oneshot::channel();
         let block_receiver = context
-            .validate_proposal(self.height, self.timeouts.proposal_timeout, p2p_messages_receiver)
+            .validate_proposal(
+                self.height,
+                self.timeouts.proposal_timeout,
+                p2p_messages_receiver,
+                fin_to_context_receiver,
+            )
             .await;
-        Ok(ShcReturn::Tasks(vec![ShcTask::ValidateProposal(init, block_receiver, fin_receiver)]))
+        Ok(ShcReturn::Tasks(vec![ShcTask::ValidateProposal(
+            init,
+            block_receiver,
+            fin_receiver,
+            fin_to_context_sender,
+        )]))
     }
 
     async fn process_inbound_proposal<ContextT: ConsensusContext>(
@@ -1,12 +1,18 @@
 //! Stream handler, see StreamManager struct.
 
-use std::cmp::Ordering;
 use std::collections::btree_map::Entry as BTreeEntry;
-use std::collections::hash_map::Entry as HashMapEntry;
 use std::collections::{BTreeMap, HashMap};
 
 use futures::channel::mpsc;
 use futures::StreamExt;
+use papyrus_common::metrics::{
+    PAPYRUS_CONSENSUS_INBOUND_STREAMS_DROPPED,
+    PAPYRUS_CONSENSUS_MAX_STREAM_MESSAGE_GAP,
+    PAPYRUS_CONSENSUS_NUM_ACTIVE_INBOUND_STREAMS,
+    PAPYRUS_CONSENSUS_NUM_BUFFERED_STREAM_MESSAGES,
+    PAPYRUS_CONSENSUS_STREAM_DELIVERED_MESSAGES_RESENT,
+    PAPYRUS_CONSENSUS_STREAM_MESSAGES_RECEIVED,
+};
 use papyrus_network::network_manager::{
     BroadcastTopicClient,
     BroadcastTopicClientTrait,
@@ -16,8 +22,11 @@ use papyrus_network::utils::StreamHashMap;
 use papyrus_network_types::network_types::{BroadcastedMessageMetadata, OpaquePeerId};
 use papyrus_protobuf::consensus::{StreamMessage, StreamMessageBody};
 use papyrus_protobuf::converters::ProtobufConversionError;
+use tokio::time::Instant;
 use tracing::{instrument, warn};
 
+use crate::config::StreamHandlerConfig;
+
 #[cfg(test)]
 #[path = "stream_handler_test.rs"]
 mod stream_handler_test;
@@ -31,6 +40,8 @@ const CHANNEL_BUFFER_LENGTH: usize = 100;
 
 #[derive(Debug, Clone)]
 struct StreamData<T: Clone + Into<Vec<u8>> + TryFrom<Vec<u8>, Error = ProtobufConversionError>> {
+    // Messages are delivered in order, so this is also the delivered-id high-water mark: every
+    // message id below it was already delivered.
     next_message_id: MessageId,
     // Last message ID. If None, it means we have not yet gotten to it.
     fin_message_id: Option<MessageId>,
@@ -38,6 +49,10 @@ struct StreamData<T: Clone + Into<Vec<u8>> + TryFrom<Vec<u8>, Error = ProtobufCo
     sender: mpsc::Sender<T>,
     // A buffer for messages that were received out of order.
     message_buffer: BTreeMap<MessageId, StreamMessage<T>>,
+    // The total encoded size, in bytes, of the messages in `message_buffer`.
+    buffered_bytes: usize,
+    // The time the last message of this stream was received, used to evict inactive streams.
+    last_activity: Instant,
 }
 
 impl<T: Clone + Into<Vec<u8>> + TryFrom<Vec<u8>, Error = ProtobufConversionError>> StreamData<T> {
@@ -48,10 +63,69 @@ impl<T: Clone + Into<Vec<u8>> + TryFrom<Vec<u8>, Error = ProtobufConversionError
             max_message_id_received: 0,
             sender,
             message_buffer: BTreeMap::new(),
+            buffered_bytes: 0,
+            last_activity: Instant::now(),
+        }
+    }
+
+    // Whether a message with this id was already received on the stream.
+    fn duplicate_message_id(&self, message_id: MessageId) -> Option<DuplicateMessageId> {
+        if message_id < self.next_message_id {
+            Some(DuplicateMessageId::AlreadyDelivered)
+        } else if self.message_buffer.contains_key(&message_id) {
+            Some(DuplicateMessageId::Buffered)
+        } else {
+            None
         }
     }
 }
 
+// The kinds of an inbound message whose id was already received on its stream.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum DuplicateMessageId {
+    // The message is still buffered, waiting for its predecessors. Messages may be received more
+    // than once, so this is a benign late duplicate.
+    Buffered,
+    // The message was already delivered to the application, so the peer resent it.
+    AlreadyDelivered,
+}
+
+// An inbound stream that ended cleanly, kept for a while after it is closed so that messages
+// resent on it are recognized instead of opening a new stream.
+#[derive(Debug, Clone)]
+struct CompletedStream {
+    // The delivered-id high-water mark of the stream, i.e. the id of its fin.
+    next_message_id: MessageId,
+    completed_at: Instant,
+}
+
+/// A snapshot of the state of the inbound streams of a StreamHandler.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct StreamHandlerStats {
+    /// The number of inbound streams that are not yet completed.
+    pub num_active_streams: usize,
+    /// The number of out-of-order messages buffered across all inbound streams.
+    pub num_buffered_messages: usize,
+    /// The largest distance, over all inbound streams, between the highest message id received
+    /// and the next message id to deliver.
+    pub max_gap: u64,
+}
+
+/// Reported to the application when an inbound stream ended cleanly, i.e. after all its messages up
+/// to the fin were delivered, just before the stream's channel is closed. A stream that is dropped
+/// or evicted closes its channel without reporting this.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InboundStreamFin {
+    /// The peer that sent the stream.
+    pub peer_id: PeerId,
+    /// The id of the stream, unique per peer.
+    pub stream_id: StreamId,
+    /// The number of messages delivered on the stream, not counting the fin.
+    pub total_messages: u64,
+    /// The message id of the fin.
+    pub fin_message_id: MessageId,
+}
+
 /// A StreamHandler is responsible for:
 /// - Buffering inbound messages and reporting them to the application in order.
 /// - Sending outbound messages to the network, wrapped in StreamMessage.
@@ -61,12 +135,19 @@ pub struct StreamHandler<
     // For each stream ID from the network, send the application a Receiver
     // that will receive the messages in order. This allows sending such Receivers.
     inbound_channel_sender: mpsc::Sender<mpsc::Receiver<T>>,
+    // If given, the application is notified through it of every inbound stream that ended cleanly.
+    inbound_fin_sender: Option<mpsc::Sender<InboundStreamFin>>,
     // This receives messages from the network.
     inbound_receiver: BroadcastTopicServer<StreamMessage<T>>,
     // A map from (peer_id, stream_id) to a struct that contains all the information
     // about the stream. This includes both the message buffer and some metadata
     // (like the latest message ID).
     inbound_stream_data: HashMap<StreamKey, StreamData<T>>,
+    // The inbound streams that ended cleanly within the last `inbound_stream_timeout`.
+    completed_inbound_streams: HashMap<StreamKey, CompletedStream>,
+    // The timeout and buffer limits of the inbound streams. A stream that exceeds a limit is
+    // dropped.
+    config: StreamHandlerConfig,
     // Whenever application wants to start a new stream, it must send out a
     // (stream_id, Receiver) pair. Each receiver gets messages that should
     // be sent out to the network.
@@ -82,17 +163,22 @@ pub struct StreamHandler<
 impl<T: Clone + Send + Into<Vec<u8>> + TryFrom<Vec<u8>, Error = ProtobufConversionError>>
     StreamHandler<T>
 {
-    /// Create a new StreamHandler.
+    /// Create a new StreamHandler. The config is expected to be validated.
     pub fn new(
         inbound_channel_sender: mpsc::Sender<mpsc::Receiver<T>>,
         inbound_receiver: BroadcastTopicServer<StreamMessage<T>>,
         outbound_channel_receiver: mpsc::Receiver<(StreamId, mpsc::Receiver<T>)>,
         outbound_sender: BroadcastTopicClient<StreamMessage<T>>,
+        config: StreamHandlerConfig,
+        inbound_fin_sender: Option<mpsc::Sender<InboundStreamFin>>,
     ) -> Self {
         Self {
             inbound_channel_sender,
+            inbound_fin_sender,
             inbound_receiver,
             inbound_stream_data: HashMap::new(),
+            completed_inbound_streams: HashMap::new(),
+            config,
             outbound_channel_receiver,
             outbound_sender,
             outbound_stream_receivers: StreamHashMap::new(HashMap::new()),
@@ -100,12 +186,47 @@ impl<T: Clone + Send + Into<Vec<u8>> + TryFrom<Vec<u8>, Error = ProtobufConversi
         }
     }
 
+    /// Returns the sorted ids of the inbound streams that are still open, i.e. not yet completed,
+    /// dropped or evicted. Streams of different peers may share an id, which is returned once.
+    ///
+    /// The receiver of each inbound stream is handed to the application exactly once, through
+    /// `inbound_channel_sender`, and the StreamHandler keeps no handle to it. So this cannot be
+    /// used to fetch a receiver again; it only tells which streams are still being delivered.
+    /// The StreamHandler is owned by a single task, so this can only be called while it is not
+    /// running.
+    pub fn open_stream_ids(&self) -> Vec<StreamId> {
+        let mut stream_ids: Vec<StreamId> =
+            self.inbound_stream_data.keys().map(|(_, stream_id)| *stream_id).collect();
+        stream_ids.sort_unstable();
+        stream_ids.dedup();
+        stream_ids
+    }
+
+    /// Returns a snapshot of the state of the inbound streams.
+    pub fn stats(&self) -> StreamHandlerStats {
+        self.inbound_stream_data.values().fold(
+            StreamHandlerStats {
+                num_active_streams: self.inbound_stream_data.len(),
+                ..Default::default()
+            },
+            |mut stats, data| {
+                stats.num_buffered_messages += data.message_buffer.len();
+                stats.max_gap = stats
+                    .max_gap
+                    .max(data.max_message_id_received.saturating_sub(data.next_message_id));
+                stats
+            },
+        )
+    }
+
     /// Listen for messages coming from the network and from the application.
     /// - Outbound messages are wrapped as StreamMessage and sent to the network directly.
     /// - Inbound messages are stripped of StreamMessage and buffered until they can be sent in the
     ///   correct order to the application.
+    /// - Inbound streams that are inactive for longer than the timeout are dropped.
     #[instrument(skip_all)]
     pub async fn run(&mut self) {
+        let mut eviction_interval = tokio::time::interval(self.config.inbound_stream_timeout);
         loop {
             tokio::select!(
                 // Go over the channel receiver to see if there is a new channel.
@@ -131,19 +252,30 @@ impl<T: Clone + Send + Into<Vec<u8>> + TryFrom<Vec<u8>, Error = ProtobufConversi
                 }
                 // Check if there is an inbound message from the network.
                 Some(message) = self.inbound_receiver.next() => {
+                    metrics::increment_counter!(PAPYRUS_CONSENSUS_STREAM_MESSAGES_RECEIVED);
                     self.handle_message(message);
+                    self.report_stats();
+                }
+                // Periodically drop inbound streams that stalled.
+                _ = eviction_interval.tick() => {
+                    self.evict_inactive_streams();
+                    self.report_stats();
                 }
             );
         }
     }
 
-    fn inbound_send(data: &mut StreamData<T>, message: StreamMessage<T>) {
-        // TODO(guyn): reconsider the "expect" here.
-        let sender = &mut data.sender;
+    // Never waits for the application, so a slow consumer of one stream cannot stall the other
+    // streams. Fails if the channel of the stream is full or the application dropped its receiver.
+    fn inbound_send(
+        data: &mut StreamData<T>,
+        message: StreamMessage<T>,
+    ) -> Result<(), mpsc::TrySendError<T>> {
         if let StreamMessageBody::Content(content) = message.message {
-            sender.try_send(content).expect("Send should succeed");
+            data.sender.try_send(content)?;
             data.next_message_id += 1;
         }
+        Ok(())
     }
 
     // Send the message to the network.
@@ -189,38 +321,70 @@ impl<T: Clone + Send + Into<Vec<u8>> + TryFrom<Vec<u8>, Error = ProtobufConversi
         let key = (peer_id, stream_id);
         let message_id = message.message_id;
 
-        let data = match self.inbound_stream_data.entry(key.clone()) {
-            HashMapEntry::Occupied(entry) => entry.into_mut(),
-            HashMapEntry::Vacant(e) => {
-                // If we received a message for a stream that we have not seen before,
-                // we need to create a new receiver for it.
-                let (sender, receiver) = mpsc::channel(CHANNEL_BUFFER_LENGTH);
-                // TODO(guyn): reconsider the "expect" here.
-                self.inbound_channel_sender.try_send(receiver).expect("Send should succeed");
-
-                let data = StreamData::new(sender);
-                e.insert(data)
+        if let Some(completed_stream) = self.completed_inbound_streams.get(&key) {
+            if message_id < completed_stream.next_message_id {
+                Self::report_delivered_message_resent(&key, message_id);
+            } else {
+                warn!(
+                    "Received a message on a completed stream! key: {:?}, message_id: {}",
+                    key, message_id
+                );
             }
-        };
+            return;
+        }
 
-        if data.max_message_id_received < message_id {
-            data.max_message_id_received = message_id;
+        if !self.inbound_stream_data.contains_key(&key) {
+            // If we received a message for a stream that we have not seen before,
+            // we need to create a new receiver for it.
+            let (sender, receiver) = mpsc::channel(CHANNEL_BUFFER_LENGTH);
+            if let Err(e) = self.inbound_channel_sender.try_send(receiver) {
+                warn!(
+                    "Failed to open a new inbound stream, dropping message! key: {:?}, error: {:?}",
+                    key, e
+                );
+                return;
+            }
+            self.inbound_stream_data.insert(key.clone(), StreamData::new(sender));
         }
+        let data = self
+            .inbound_stream_data
+            .get_mut(&key)
+            .expect("Inbound stream data should exist after being inserted.");
+
+        data.last_activity = Instant::now();
 
         // Check for Fin type message.
-        match message.message {
-            StreamMessageBody::Content(_) => {}
-            StreamMessageBody::Fin => {
-                data.fin_message_id = Some(message_id);
-                if data.max_message_id_received > message_id {
-                    // TODO(guyn): replace warnings with more graceful error handling
+        if let StreamMessageBody::Fin = message.message {
+            let previous_fin_message_id = data.fin_message_id;
+            match previous_fin_message_id {
+                Some(previous_fin_message_id) if previous_fin_message_id == message_id => {
+                    warn!(
+                        "Received a duplicate fin message! key: {:?}, fin_message_id: {}",
+                        key, message_id
+                    );
+                    return;
+                }
+                Some(previous_fin_message_id) => {
+                    warn!(
+                        "Received a fin message that conflicts with a previous fin message! \
+                         Dropping the stream. key: {:?}, fin_message_id: {}, \
+                         previous_fin_message_id: {}",
+                        key, message_id, previous_fin_message_id
+                    );
+                    self.drop_inbound_stream(&key);
+                    return;
+                }
+                None if data.max_message_id_received > message_id => {
                     warn!(
                         "Received fin message with id that is smaller than a previous message! \
-                         key: {:?}, fin_message_id: {}, max_message_id_received: {}",
+                         Dropping the stream. key: {:?}, fin_message_id: {}, \
+                         max_message_id_received: {}",
                         key, message_id, data.max_message_id_received
                     );
+                    self.drop_inbound_stream(&key);
                     return;
                 }
+                None => data.fin_message_id = Some(message_id),
             }
         }
 
@@ -236,39 +400,168 @@ impl<T: Clone + Send + Into<Vec<u8>> + TryFrom<Vec<u8>, Error = ProtobufConversi
             return;
         }
 
+        if data.max_message_id_received < message_id {
+            data.max_message_id_received = message_id;
+        }
+
+        match data.duplicate_message_id(message_id) {
+            Some(DuplicateMessageId::AlreadyDelivered) => {
+                Self::report_delivered_message_resent(&key, message_id);
+                return;
+            }
+            Some(DuplicateMessageId::Buffered) => {
+                warn!(
+                    "Received a duplicate of a buffered message, ignoring it. key: {:?}, \
+                     message_id: {}",
+                    key, message_id
+                );
+                return;
+            }
+            None => {}
+        }
+
         // This means we can just send the message without buffering it.
-        match message_id.cmp(&data.next_message_id) {
-            Ordering::Equal => {
-                Self::inbound_send(data, message);
-                Self::process_buffer(data);
-
-                if data.message_buffer.is_empty() && data.fin_message_id.is_some() {
-                    data.sender.close_channel();
-                    self.inbound_stream_data.remove(&key);
+        if message_id == data.next_message_id {
+            let send_result = match Self::inbound_send(data, message) {
+                Ok(()) => Self::process_buffer(data),
+                Err(e) => Err(e),
+            };
+            if let Err(e) = send_result {
+                if e.is_full() {
+                    // TODO(guyn): replace warnings with more graceful error handling
+                    warn!(
+                        "Inbound stream receiver is not keeping up, dropping the stream! key: \
+                         {:?}, next_message_id: {}",
+                        key, data.next_message_id
+                    );
+                } else {
+                    warn!(
+                        "Inbound stream receiver was dropped, closing the stream! key: {:?}, \
+                         error: {:?}",
+                        key, e
+                    );
                 }
+                self.drop_inbound_stream(&key);
+                return;
             }
-            Ordering::Greater => {
-                Self::store(data, key, message);
+
+            if let (true, Some(fin_message_id)) =
+                (data.message_buffer.is_empty(), data.fin_message_id)
+            {
+                if let Some(inbound_fin_sender) = &mut self.inbound_fin_sender {
+                    let (peer_id, stream_id) = key.clone();
+                    let fin = InboundStreamFin {
+                        peer_id,
+                        stream_id,
+                        total_messages: data.next_message_id,
+                        fin_message_id,
+                    };
+                    if let Err(e) = inbound_fin_sender.try_send(fin) {
+                        warn!("Failed to report the fin of an inbound stream: {:?}", e);
+                    }
+                }
+                data.sender.close_channel();
+                let completed_stream = CompletedStream {
+                    next_message_id: data.next_message_id,
+                    completed_at: Instant::now(),
+                };
+                self.inbound_stream_data.remove(&key);
+                self.completed_inbound_streams.insert(key, completed_stream);
             }
-            Ordering::Less => {
+        } else {
+            let message_size = Self::message_size(&message);
+            if data.buffered_bytes + message_size > self.config.max_buffered_bytes_per_stream {
                 // TODO(guyn): replace warnings with more graceful error handling
                 warn!(
-                    "Received message with id that is smaller than the next message expected! \
-                     key: {:?}, message_id: {}, next_message_id: {}",
+                    "Message buffer exceeds its size limit, dropping the stream! key: {:?}, \
+                     message_id: {}, buffered_bytes: {}, message_size: {}",
+                    key, message_id, data.buffered_bytes, message_size
+                );
+                self.drop_inbound_stream(&key);
+                return;
+            }
+            if data.message_buffer.len() >= self.config.max_buffered_messages_per_stream {
+                // TODO(guyn): replace warnings with more graceful error handling
+                warn!(
+                    "Message buffer is full, dropping the stream! key: {:?}, message_id: {}, \
+                     next_message_id: {}",
                     key, message_id, data.next_message_id
                 );
+                self.drop_inbound_stream(&key);
                 return;
             }
+            Self::store(data, key, message, message_size);
         }
     }
 
+    // A peer resending a message that was already delivered violates the protocol, unlike a late
+    // duplicate of a message that is still buffered.
+    fn report_delivered_message_resent(key: &StreamKey, message_id: MessageId) {
+        // TODO(guyn): replace warnings with more graceful error handling
+        warn!(
+            "Received a message that was already delivered! key: {:?}, message_id: {}",
+            key, message_id
+        );
+        metrics::increment_counter!(PAPYRUS_CONSENSUS_STREAM_DELIVERED_MESSAGES_RESENT);
+    }
+
+    // Drop an inbound stream that misbehaved, closing its channel.
+    fn drop_inbound_stream(&mut self, key: &StreamKey) {
+        if let Some(mut data) = self.inbound_stream_data.remove(key) {
+            data.sender.close_channel();
+            metrics::increment_counter!(PAPYRUS_CONSENSUS_INBOUND_STREAMS_DROPPED);
+        }
+    }
+
+    #[allow(clippy::as_conversions)] // FIXME: use int metrics so `as f64` may be removed.
+    fn report_stats(&self) {
+        let stats = self.stats();
+        metrics::gauge!(
+            PAPYRUS_CONSENSUS_NUM_ACTIVE_INBOUND_STREAMS,
+            stats.num_active_streams as f64
+        );
+        metrics::gauge!(
+            PAPYRUS_CONSENSUS_NUM_BUFFERED_STREAM_MESSAGES,
+            stats.num_buffered_messages as f64
+        );
+        metrics::gauge!(PAPYRUS_CONSENSUS_MAX_STREAM_MESSAGE_GAP, stats.max_gap as f64);
+    }
+
+    // Drop the inbound streams that did not receive a message within the timeout, closing their
+    // channels. A message arriving later on an evicted stream opens it as a new stream.
+    fn evict_inactive_streams(&mut self) {
+        let inbound_stream_timeout = self.config.inbound_stream_timeout;
+        self.completed_inbound_streams.retain(|_, completed_stream| {
+            completed_stream.completed_at.elapsed() < inbound_stream_timeout
+        });
+        self.inbound_stream_data.retain(|key, data| {
+            if data.last_activity.elapsed() < inbound_stream_timeout {
+                return true;
+            }
+            // TODO(guyn): replace warnings with more graceful error handling
+            warn!(
+                "Inbound stream was inactive for longer than {:?}. Dropping it. key: {:?}",
+                inbound_stream_timeout, key
+            );
+            data.sender.close_channel();
+            metrics::increment_counter!(PAPYRUS_CONSENSUS_INBOUND_STREAMS_DROPPED);
+            false
+        });
+    }
+
     // Store an inbound message in the buffer.
-    fn store(data: &mut StreamData<T>, key: StreamKey, message: StreamMessage<T>) {
+    fn store(
+        data: &mut StreamData<T>,
+        key: StreamKey,
+        message: StreamMessage<T>,
+        message_size: usize,
+    ) {
         let message_id = message.message_id;
 
         match data.message_buffer.entry(message_id) {
             BTreeEntry::Vacant(e) => {
                 e.insert(message);
+                data.buffered_bytes += message_size;
             }
             BTreeEntry::Occupied(_) => {
                 // TODO(guyn): replace warnings with more graceful error handling
@@ -280,11 +573,21 @@ impl<T: Clone + Send + Into<Vec<u8>> + TryFrom<Vec<u8>, Error = ProtobufConversi
         }
     }
 
+    // The encoded size of the content of the message. A fin has no content.
+    fn message_size(message: &StreamMessage<T>) -> usize {
+        match &message.message {
+            StreamMessageBody::Content(content) => Into::<Vec<u8>>::into(content.clone()).len(),
+            StreamMessageBody::Fin => 0,
+        }
+    }
+
     // Tries to drain as many messages as possible from the buffer (in order),
     // DOES NOT guarantee that the buffer will be empty after calling this function.
-    fn process_buffer(data: &mut StreamData<T>) {
+    fn process_buffer(data: &mut StreamData<T>) -> Result<(), mpsc::TrySendError<T>> {
         while let Some(message) = data.message_buffer.remove(&data.next_message_id) {
-            Self::inbound_send(data, message);
+            data.buffered_bytes -= Self::message_size(&message);
+            Self::inbound_send(data, message)?;
         }
+        Ok(())
     }
 }
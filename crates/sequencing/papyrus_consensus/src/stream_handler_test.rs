@@ -1,8 +1,15 @@
+use std::sync::Arc;
 use std::time::Duration;
 
 use futures::channel::mpsc;
 use futures::stream::StreamExt;
 use futures::SinkExt;
+use metrics_exporter_prometheus::PrometheusBuilder;
+use papyrus_common::metrics::{
+    PAPYRUS_CONSENSUS_INBOUND_STREAMS_DROPPED,
+    PAPYRUS_CONSENSUS_NUM_ACTIVE_INBOUND_STREAMS,
+    PAPYRUS_CONSENSUS_STREAM_MESSAGES_RECEIVED,
+};
 use papyrus_network::network_manager::test_utils::{
     mock_register_broadcast_topic,
     MockBroadcastedMessagesSender,
@@ -11,12 +18,29 @@ use papyrus_network::network_manager::test_utils::{
 use papyrus_network::network_manager::BroadcastTopicChannels;
 use papyrus_network_types::network_types::BroadcastedMessageMetadata;
 use papyrus_protobuf::consensus::{ConsensusMessage, Proposal, StreamMessage, StreamMessageBody};
-use papyrus_test_utils::{get_rng, GetTestInstance};
-
-use super::{MessageId, StreamHandler, StreamId};
+use papyrus_test_utils::{get_rng, prometheus_is_contained, GetTestInstance};
+use prometheus_parse::Value::{Counter, Gauge};
+use starknet_api::transaction::fields::Calldata;
+use starknet_api::transaction::{InvokeTransaction, InvokeTransactionV3, Transaction};
+use starknet_types_core::felt::Felt;
+use validator::Validate;
+
+use super::{
+    DuplicateMessageId,
+    InboundStreamFin,
+    MessageId,
+    StreamHandler,
+    StreamHandlerStats,
+    StreamId,
+    CHANNEL_BUFFER_LENGTH,
+};
+use crate::config::StreamHandlerConfig;
 
 const TIMEOUT: Duration = Duration::from_millis(100);
 const CHANNEL_SIZE: usize = 100;
+const INBOUND_STREAM_TIMEOUT: Duration = Duration::from_secs(10);
+const MAX_BUFFERED_MESSAGES_PER_STREAM: usize = 10;
+const MAX_BUFFERED_BYTES_PER_STREAM: usize = 1 << 12;
 
 #[cfg(test)]
 mod tests {
@@ -34,6 +58,30 @@ mod tests {
         StreamMessage { message: content, stream_id, message_id }
     }
 
+    // A content message such that two of them fit the buffer size limit, but three do not.
+    fn make_large_test_message(
+        stream_id: StreamId,
+        message_id: MessageId,
+    ) -> StreamMessage<ConsensusMessage> {
+        let invoke_tx = InvokeTransactionV3 {
+            calldata: Calldata(Arc::new(vec![Felt::MAX; 36])),
+            ..InvokeTransactionV3::get_test_instance(&mut get_rng())
+        };
+        let proposal = Proposal {
+            transactions: vec![Transaction::Invoke(InvokeTransaction::V3(invoke_tx))],
+            ..Default::default()
+        };
+        let message = StreamMessage {
+            message: StreamMessageBody::Content(ConsensusMessage::Proposal(proposal)),
+            stream_id,
+            message_id,
+        };
+        let message_size = StreamHandler::message_size(&message);
+        assert!(message_size > MAX_BUFFERED_BYTES_PER_STREAM / 3);
+        assert!(message_size <= MAX_BUFFERED_BYTES_PER_STREAM / 2);
+        message
+    }
+
     // Check if two vectors are the same:
     fn do_vecs_match<T: PartialEq>(a: &[T], b: &[T]) -> bool {
         let matching = a.iter().zip(b.iter()).filter(|&(a, b)| a == b).count();
@@ -102,6 +150,12 @@ mod tests {
             inbound_receiver,
             outbound_channel_receiver,
             outbound_sender,
+            StreamHandlerConfig {
+                inbound_stream_timeout: INBOUND_STREAM_TIMEOUT,
+                max_buffered_messages_per_stream: MAX_BUFFERED_MESSAGES_PER_STREAM,
+                max_buffered_bytes_per_stream: MAX_BUFFERED_BYTES_PER_STREAM,
+            },
+            None,
         );
 
         let inbound_metadata = BroadcastedMessageMetadata::get_test_instance(&mut get_rng());
@@ -116,6 +170,27 @@ mod tests {
         )
     }
 
+    #[test]
+    fn config_rejects_zero_limits() {
+        assert!(StreamHandlerConfig::default().validate().is_ok());
+        for config in [
+            StreamHandlerConfig {
+                inbound_stream_timeout: Duration::ZERO,
+                ..StreamHandlerConfig::default()
+            },
+            StreamHandlerConfig {
+                max_buffered_messages_per_stream: 0,
+                ..StreamHandlerConfig::default()
+            },
+            StreamHandlerConfig {
+                max_buffered_bytes_per_stream: 0,
+                ..StreamHandlerConfig::default()
+            },
+        ] {
+            assert!(config.validate().is_err(), "{config:?} should be invalid.");
+        }
+    }
+
     #[tokio::test]
     async fn inbound_in_order() {
         let (mut stream_handler, mut network_sender, mut inbound_channel_receiver, metadata, _, _) =
@@ -205,6 +280,452 @@ mod tests {
         assert!(matches!(receiver.try_next(), Ok(None)));
     }
 
+    #[tokio::test(start_paused = true)]
+    async fn inbound_stream_evicted_after_timeout() {
+        let (
+            mut stream_handler,
+            mut network_sender,
+            mut inbound_channel_receiver,
+            inbound_metadata,
+            _,
+            _,
+        ) = setup_test();
+        let peer_id = inbound_metadata.originator_id.clone();
+        let stream_id = 127;
+
+        // Open a stream that stalls: message 0 never arrives, so message 1 stays buffered.
+        send(&mut network_sender, &inbound_metadata, make_test_message(stream_id, 1, false)).await;
+
+        let join_handle = tokio::spawn(async move {
+            let _ = tokio::time::timeout(TIMEOUT, stream_handler.run()).await;
+            stream_handler
+        });
+        let mut stream_handler = join_handle.await.expect("Task should succeed");
+        assert!(stream_handler.inbound_stream_data.contains_key(&(peer_id.clone(), stream_id)));
+        let mut receiver = inbound_channel_receiver.next().await.unwrap();
+        assert!(receiver.try_next().is_err());
+
+        tokio::time::advance(INBOUND_STREAM_TIMEOUT).await;
+
+        let join_handle = tokio::spawn(async move {
+            let _ = tokio::time::timeout(TIMEOUT, stream_handler.run()).await;
+            stream_handler
+        });
+        let mut stream_handler = join_handle.await.expect("Task should succeed");
+
+        // The stream was dropped and its receiver closed.
+        assert!(stream_handler.inbound_stream_data.is_empty());
+        assert!(matches!(receiver.try_next(), Ok(None)));
+
+        // A message on the evicted stream opens it as a new stream.
+        send(&mut network_sender, &inbound_metadata, make_test_message(stream_id, 0, false)).await;
+
+        let join_handle = tokio::spawn(async move {
+            let _ = tokio::time::timeout(TIMEOUT, stream_handler.run()).await;
+            stream_handler
+        });
+        let stream_handler = join_handle.await.expect("Task should succeed");
+
+        let mut receiver = inbound_channel_receiver.next().await.unwrap();
+        let _ = receiver.next().await.unwrap();
+        let stream_data = &stream_handler.inbound_stream_data[&(peer_id, stream_id)];
+        assert_eq!(stream_data.next_message_id, 1);
+        assert!(stream_data.message_buffer.is_empty());
+    }
+
+    #[tokio::test]
+    async fn inbound_buffer_is_bounded() {
+        let (mut stream_handler, mut network_sender, mut inbound_channel_receiver, metadata, _, _) =
+            setup_test();
+
+        let stream_id = 127;
+        let max_buffered_id = u64::try_from(MAX_BUFFERED_MESSAGES_PER_STREAM).unwrap();
+        // Skip message 0, so all other messages are buffered. The last one is the fin, so the
+        // stream would end once message 0 arrives, if the buffer kept it.
+        for message_id in 1..=max_buffered_id {
+            send(&mut network_sender, &metadata, make_test_message(stream_id, message_id, false))
+                .await;
+        }
+        send(
+            &mut network_sender,
+            &metadata,
+            make_test_message(stream_id, max_buffered_id + 1, true),
+        )
+        .await;
+
+        let join_handle = tokio::spawn(async move {
+            let _ = tokio::time::timeout(TIMEOUT, stream_handler.run()).await;
+            stream_handler
+        });
+        let stream_handler = join_handle.await.expect("Task should succeed");
+
+        // The stream is dropped when the message beyond the buffer capacity arrives, without
+        // delivering any message.
+        let mut receiver = inbound_channel_receiver.next().await.unwrap();
+        assert!(matches!(receiver.try_next(), Ok(None)));
+        assert!(stream_handler.inbound_stream_data.is_empty());
+    }
+
+    #[tokio::test]
+    async fn inbound_buffer_is_bounded_by_bytes() {
+        let (mut stream_handler, mut network_sender, mut inbound_channel_receiver, metadata, _, _) =
+            setup_test();
+
+        let stream_id = 127;
+        // Below the count limit, but the total size is above the byte limit.
+        for message_id in 1..4 {
+            send(&mut network_sender, &metadata, make_large_test_message(stream_id, message_id))
+                .await;
+        }
+
+        let join_handle = tokio::spawn(async move {
+            let _ = tokio::time::timeout(TIMEOUT, stream_handler.run()).await;
+            stream_handler
+        });
+        let stream_handler = join_handle.await.expect("Task should succeed");
+
+        // The stream is dropped when the third message arrives, without delivering any message.
+        let mut receiver = inbound_channel_receiver.next().await.unwrap();
+        assert!(matches!(receiver.try_next(), Ok(None)));
+        assert!(stream_handler.inbound_stream_data.is_empty());
+    }
+
+    #[tokio::test]
+    async fn inbound_buffered_bytes_are_released_on_delivery() {
+        let (mut stream_handler, mut network_sender, mut inbound_channel_receiver, metadata, _, _) =
+            setup_test();
+
+        let stream_id = 127;
+        // Each batch of buffered messages fits the byte limit, and is delivered before the next.
+        for batch_start in [0, 3, 6] {
+            for message_id in (batch_start + 1..batch_start + 3).rev() {
+                send(
+                    &mut network_sender,
+                    &metadata,
+                    make_large_test_message(stream_id, message_id),
+                )
+                .await;
+            }
+            send(&mut network_sender, &metadata, make_large_test_message(stream_id, batch_start))
+                .await;
+        }
+
+        let join_handle = tokio::spawn(async move {
+            let _ = tokio::time::timeout(TIMEOUT, stream_handler.run()).await;
+            stream_handler
+        });
+        let stream_handler = join_handle.await.expect("Task should succeed");
+
+        let mut receiver = inbound_channel_receiver.next().await.unwrap();
+        for _ in 0..9 {
+            let _ = receiver.next().await.unwrap();
+        }
+        let stream_data = &stream_handler.inbound_stream_data[&(metadata.originator_id, stream_id)];
+        assert_eq!(stream_data.buffered_bytes, 0);
+        assert!(stream_data.message_buffer.is_empty());
+    }
+
+    #[tokio::test]
+    async fn inbound_slow_consumer_drops_stream() {
+        let (mut stream_handler, mut network_sender, mut inbound_channel_receiver, metadata, _, _) =
+            setup_test();
+
+        // Send more messages than the application channel can hold, on two streams.
+        let (slow_stream_id, other_stream_id) = (127, 128);
+        let n_messages = u64::try_from(CHANNEL_BUFFER_LENGTH).unwrap() + 10;
+        for i in 0..n_messages {
+            send(&mut network_sender, &metadata, make_test_message(slow_stream_id, i, false)).await;
+        }
+        send(&mut network_sender, &metadata, make_test_message(other_stream_id, 0, false)).await;
+
+        let join_handle = tokio::spawn(async move {
+            let _ = tokio::time::timeout(TIMEOUT, stream_handler.run()).await;
+            stream_handler
+        });
+        // The handler does not wait for the slow consumer, so the other stream is still served.
+        let stream_handler = join_handle.await.expect("Task should succeed");
+        assert!(do_vecs_match(&stream_handler.open_stream_ids(), &[other_stream_id]));
+
+        // The slow stream delivered what its channel could hold, and was closed.
+        let mut slow_receiver = inbound_channel_receiver.next().await.unwrap();
+        let mut n_delivered = 0;
+        while slow_receiver.next().await.is_some() {
+            n_delivered += 1;
+        }
+        assert!(n_delivered < n_messages);
+        let mut other_receiver = inbound_channel_receiver.next().await.unwrap();
+        assert!(other_receiver.try_next().unwrap().is_some());
+    }
+
+    #[tokio::test]
+    async fn inbound_closed_receiver_closes_stream() {
+        let (mut stream_handler, mut network_sender, mut inbound_channel_receiver, metadata, _, _) =
+            setup_test();
+
+        let stream_id = 127;
+        send(&mut network_sender, &metadata, make_test_message(stream_id, 0, false)).await;
+
+        let join_handle = tokio::spawn(async move {
+            let _ = tokio::time::timeout(TIMEOUT, stream_handler.run()).await;
+            stream_handler
+        });
+        let mut stream_handler = join_handle.await.expect("Task should succeed");
+        assert_eq!(stream_handler.inbound_stream_data.len(), 1);
+
+        // The application drops the receiver of the stream.
+        drop(inbound_channel_receiver.next().await.unwrap());
+        send(&mut network_sender, &metadata, make_test_message(stream_id, 1, false)).await;
+
+        let join_handle = tokio::spawn(async move {
+            let _ = tokio::time::timeout(TIMEOUT, stream_handler.run()).await;
+            stream_handler
+        });
+        let mut stream_handler = join_handle.await.expect("Task should succeed");
+        assert!(stream_handler.inbound_stream_data.is_empty());
+
+        // The application stops accepting new streams altogether.
+        drop(inbound_channel_receiver);
+        send(&mut network_sender, &metadata, make_test_message(stream_id + 1, 0, false)).await;
+
+        let join_handle = tokio::spawn(async move {
+            let _ = tokio::time::timeout(TIMEOUT, stream_handler.run()).await;
+            stream_handler
+        });
+        let stream_handler = join_handle.await.expect("Task should succeed");
+        assert!(stream_handler.inbound_stream_data.is_empty());
+    }
+
+    #[tokio::test]
+    async fn inbound_conflicting_fins_drop_stream() {
+        let (mut stream_handler, mut network_sender, mut inbound_channel_receiver, metadata, _, _) =
+            setup_test();
+
+        let stream_id = 127;
+        send(&mut network_sender, &metadata, make_test_message(stream_id, 0, false)).await;
+        send(&mut network_sender, &metadata, make_test_message(stream_id, 3, true)).await;
+        send(&mut network_sender, &metadata, make_test_message(stream_id, 5, true)).await;
+
+        let join_handle = tokio::spawn(async move {
+            let _ = tokio::time::timeout(TIMEOUT, stream_handler.run()).await;
+            stream_handler
+        });
+        let stream_handler = join_handle.await.expect("Task should succeed");
+
+        // Only the message sent before the conflict is delivered, then the stream is closed.
+        let mut receiver = inbound_channel_receiver.next().await.unwrap();
+        let _ = receiver.next().await.unwrap();
+        assert!(matches!(receiver.try_next(), Ok(None)));
+        assert!(stream_handler.inbound_stream_data.is_empty());
+    }
+
+    #[tokio::test]
+    async fn inbound_content_after_fin_is_rejected() {
+        let (mut stream_handler, mut network_sender, mut inbound_channel_receiver, metadata, _, _) =
+            setup_test();
+
+        let stream_id = 127;
+        send(&mut network_sender, &metadata, make_test_message(stream_id, 2, true)).await;
+        send(&mut network_sender, &metadata, make_test_message(stream_id, 3, false)).await;
+        send(&mut network_sender, &metadata, make_test_message(stream_id, 0, false)).await;
+        send(&mut network_sender, &metadata, make_test_message(stream_id, 1, false)).await;
+
+        let join_handle = tokio::spawn(async move {
+            let _ = tokio::time::timeout(TIMEOUT, stream_handler.run()).await;
+            stream_handler
+        });
+        let stream_handler = join_handle.await.expect("Task should succeed");
+
+        // The messages up to the fin are delivered and the stream completes normally.
+        let mut receiver = inbound_channel_receiver.next().await.unwrap();
+        for _ in 0..2 {
+            let _ = receiver.next().await.unwrap();
+        }
+        assert!(matches!(receiver.try_next(), Ok(None)));
+        assert!(stream_handler.inbound_stream_data.is_empty());
+    }
+
+    #[tokio::test]
+    async fn inbound_delivered_message_resent() {
+        let (mut stream_handler, mut network_sender, mut inbound_channel_receiver, metadata, _, _) =
+            setup_test();
+
+        let stream_id = 127;
+        send(&mut network_sender, &metadata, make_test_message(stream_id, 0, false)).await;
+        send(&mut network_sender, &metadata, make_test_message(stream_id, 1, false)).await;
+        send(&mut network_sender, &metadata, make_test_message(stream_id, 3, false)).await;
+        send(&mut network_sender, &metadata, make_test_message(stream_id, 0, false)).await;
+        send(&mut network_sender, &metadata, make_test_message(stream_id, 3, false)).await;
+
+        let join_handle = tokio::spawn(async move {
+            let _ = tokio::time::timeout(TIMEOUT, stream_handler.run()).await;
+            stream_handler
+        });
+        let stream_handler = join_handle.await.expect("Task should succeed");
+
+        // The resent messages are not delivered again.
+        let mut receiver = inbound_channel_receiver.next().await.unwrap();
+        for _ in 0..2 {
+            let _ = receiver.next().await.unwrap();
+        }
+        assert!(receiver.try_next().is_err());
+
+        let stream_data = &stream_handler.inbound_stream_data[&(metadata.originator_id, stream_id)];
+        assert_eq!(stream_data.message_buffer.len(), 1);
+        assert_eq!(stream_data.duplicate_message_id(0), Some(DuplicateMessageId::AlreadyDelivered));
+        assert_eq!(stream_data.duplicate_message_id(1), Some(DuplicateMessageId::AlreadyDelivered));
+        assert_eq!(stream_data.duplicate_message_id(2), None);
+        assert_eq!(stream_data.duplicate_message_id(3), Some(DuplicateMessageId::Buffered));
+    }
+
+    #[tokio::test]
+    async fn inbound_message_resent_after_fin_does_not_reopen_stream() {
+        let (mut stream_handler, mut network_sender, mut inbound_channel_receiver, metadata, _, _) =
+            setup_test();
+
+        let stream_id = 127;
+        send(&mut network_sender, &metadata, make_test_message(stream_id, 0, false)).await;
+        send(&mut network_sender, &metadata, make_test_message(stream_id, 1, true)).await;
+        send(&mut network_sender, &metadata, make_test_message(stream_id, 0, false)).await;
+
+        let join_handle = tokio::spawn(async move {
+            let _ = tokio::time::timeout(TIMEOUT, stream_handler.run()).await;
+            stream_handler
+        });
+        let stream_handler = join_handle.await.expect("Task should succeed");
+
+        let mut receiver = inbound_channel_receiver.next().await.unwrap();
+        let _ = receiver.next().await.unwrap();
+        assert!(matches!(receiver.try_next(), Ok(None)));
+        // No new stream was opened for the resent message.
+        assert!(inbound_channel_receiver.try_next().is_err());
+        assert!(stream_handler.inbound_stream_data.is_empty());
+        assert!(
+            stream_handler
+                .completed_inbound_streams
+                .contains_key(&(metadata.originator_id, stream_id))
+        );
+    }
+
+    #[tokio::test]
+    async fn inbound_stream_metrics() {
+        let handle = PrometheusBuilder::new().install_recorder().unwrap();
+        let (mut stream_handler, mut network_sender, _inbound_channel_receiver, metadata, _, _) =
+            setup_test();
+
+        // Two streams: the first stays open, the second is dropped due to conflicting fins.
+        send(&mut network_sender, &metadata, make_test_message(1, 1, false)).await;
+        send(&mut network_sender, &metadata, make_test_message(2, 3, true)).await;
+        send(&mut network_sender, &metadata, make_test_message(2, 5, true)).await;
+
+        let join_handle = tokio::spawn(async move {
+            let _ = tokio::time::timeout(TIMEOUT, stream_handler.run()).await;
+        });
+        join_handle.await.expect("Task should succeed");
+
+        // Other tests may report to the same recorder concurrently, so only lower bounds hold.
+        let metrics = handle.render();
+        let Some(Counter(messages_received)) = prometheus_is_contained(
+            metrics.clone(),
+            PAPYRUS_CONSENSUS_STREAM_MESSAGES_RECEIVED,
+            &[],
+        ) else {
+            panic!("{PAPYRUS_CONSENSUS_STREAM_MESSAGES_RECEIVED} should be a counter.");
+        };
+        assert!(messages_received >= 3.0);
+        let Some(Counter(streams_dropped)) = prometheus_is_contained(
+            metrics.clone(),
+            PAPYRUS_CONSENSUS_INBOUND_STREAMS_DROPPED,
+            &[],
+        ) else {
+            panic!("{PAPYRUS_CONSENSUS_INBOUND_STREAMS_DROPPED} should be a counter.");
+        };
+        assert!(streams_dropped >= 1.0);
+        assert!(matches!(
+            prometheus_is_contained(metrics, PAPYRUS_CONSENSUS_NUM_ACTIVE_INBOUND_STREAMS, &[]),
+            Some(Gauge(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn inbound_stats() {
+        let (mut stream_handler, mut network_sender, _inbound_channel_receiver, metadata, _, _) =
+            setup_test();
+        assert_eq!(stream_handler.stats(), StreamHandlerStats::default());
+
+        // The first stream delivers message 0 and buffers messages 2 and 3.
+        for message_id in [0, 2, 3] {
+            send(&mut network_sender, &metadata, make_test_message(1, message_id, false)).await;
+        }
+        // The second stream buffers messages 4 and 7.
+        for message_id in [4, 7] {
+            send(&mut network_sender, &metadata, make_test_message(2, message_id, false)).await;
+        }
+
+        let join_handle = tokio::spawn(async move {
+            let _ = tokio::time::timeout(TIMEOUT, stream_handler.run()).await;
+            stream_handler
+        });
+        let stream_handler = join_handle.await.expect("Task should succeed");
+
+        assert_eq!(
+            stream_handler.stats(),
+            StreamHandlerStats { num_active_streams: 2, num_buffered_messages: 4, max_gap: 7 }
+        );
+    }
+
+    #[tokio::test]
+    async fn inbound_open_stream_ids() {
+        let (mut stream_handler, mut network_sender, _inbound_channel_receiver, metadata, _, _) =
+            setup_test();
+        assert!(stream_handler.open_stream_ids().is_empty());
+
+        for stream_id in [5, 1, 3] {
+            send(&mut network_sender, &metadata, make_test_message(stream_id, 0, false)).await;
+        }
+        // A completed stream is no longer open.
+        send(&mut network_sender, &metadata, make_test_message(9, 0, true)).await;
+
+        let join_handle = tokio::spawn(async move {
+            let _ = tokio::time::timeout(TIMEOUT, stream_handler.run()).await;
+            stream_handler
+        });
+        let stream_handler = join_handle.await.expect("Task should succeed");
+
+        assert_eq!(stream_handler.open_stream_ids(), vec![1, 3, 5]);
+    }
+
+    #[tokio::test]
+    async fn inbound_fin_is_reported() {
+        let (mut stream_handler, mut network_sender, mut inbound_channel_receiver, metadata, _, _) =
+            setup_test();
+        let (inbound_fin_sender, mut inbound_fin_receiver) = mpsc::channel(CHANNEL_SIZE);
+        stream_handler.inbound_fin_sender = Some(inbound_fin_sender);
+        let peer_id = metadata.originator_id.clone();
+        let stream_id = 4;
+
+        // The fin arrives before the messages preceding it.
+        send(&mut network_sender, &metadata, make_test_message(stream_id, 5, true)).await;
+        for message_id in (0..5).rev() {
+            send(&mut network_sender, &metadata, make_test_message(stream_id, message_id, false))
+                .await;
+        }
+        // A stream that does not end is not reported.
+        send(&mut network_sender, &metadata, make_test_message(stream_id + 1, 0, false)).await;
+
+        let join_handle = tokio::spawn(async move {
+            let _ = tokio::time::timeout(TIMEOUT, stream_handler.run()).await;
+        });
+        join_handle.await.expect("Task should succeed");
+
+        let mut receiver = inbound_channel_receiver.next().await.unwrap();
+        assert_eq!(receiver.by_ref().collect::<Vec<_>>().await.len(), 5);
+        assert_eq!(
+            inbound_fin_receiver.next().await,
+            Some(InboundStreamFin { peer_id, stream_id, total_messages: 5, fin_message_id: 5 })
+        );
+        assert!(inbound_fin_receiver.next().await.is_none());
+    }
+
     #[tokio::test]
     async fn inbound_multiple_streams() {
         let (
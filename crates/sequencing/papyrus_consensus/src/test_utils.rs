@@ -34,7 +34,8 @@ mock! {
             &mut self,
             height: BlockNumber,
             timeout: Duration,
-            content: mpsc::Receiver<u32>
+            content: mpsc::Receiver<u32>,
+            fin_receiver: oneshot::Receiver<ProposalContentId>,
         ) -> oneshot::Receiver<ProposalContentId>;
 
         async fn repropose(
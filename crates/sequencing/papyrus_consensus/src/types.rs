@@ -61,6 +61,8 @@ pub trait ConsensusContext {
     ///   state of the block.
     /// - `timeout`: The maximum time to wait for the block to be built.
     /// - `content`: A receiver for the stream of the block's content.
+    /// - `fin_receiver`: A receiver for the block id sent by the proposer. The Sender is dropped if
+    ///   the proposer's fin is never received.
     ///
     /// Returns:
     /// - A receiver for the block id. If a valid block cannot be built the Sender will be dropped
@@ -70,6 +72,7 @@ pub trait ConsensusContext {
         height: BlockNumber,
         timeout: Duration,
         content: mpsc::Receiver<Self::ProposalChunk>,
+        fin_receiver: oneshot::Receiver<ProposalContentId>,
     ) -> oneshot::Receiver<ProposalContentId>;
 
     /// This function is called by consensus to retrieve the content of a previously built or
@@ -142,6 +142,7 @@ impl ConsensusContext for PapyrusConsensusContext {
         height: BlockNumber,
         _timeout: Duration,
         mut content: mpsc::Receiver<Transaction>,
+        _fin_receiver: oneshot::Receiver<ProposalContentId>,
     ) -> oneshot::Receiver<ProposalContentId> {
         let (fin_sender, fin_receiver) = oneshot::channel();
 
@@ -29,12 +29,15 @@ use papyrus_protobuf::consensus::{
     Vote,
 };
 use starknet_api::block::{BlockHash, BlockHashAndNumber, BlockNumber};
+use starknet_api::core::StateDiffCommitment;
 use starknet_api::executable_transaction::Transaction;
+use starknet_api::hash::PoseidonHash;
 use starknet_batcher_types::batcher_types::{
     BuildProposalInput,
     DecisionReachedInput,
     GetProposalContent,
     GetProposalContentInput,
+    ProposalCommitment,
     ProposalId,
     ProposalStatus,
     SendProposalContent,
@@ -64,6 +67,8 @@ pub struct SequencerConsensusContext {
     proposal_id: u64,
     current_height: Option<BlockNumber>,
     network_broadcast_client: BroadcastTopicClient<ProposalPart>,
+    // The maximal number of transactions in a single batch of a proposal received from a peer.
+    max_transaction_batch_size: usize,
 }
 
 impl SequencerConsensusContext {
@@ -71,6 +76,7 @@ impl SequencerConsensusContext {
         batcher: Arc<dyn BatcherClient>,
         network_broadcast_client: BroadcastTopicClient<ProposalPart>,
         num_validators: u64,
+        max_transaction_batch_size: usize,
     ) -> Self {
         Self {
             batcher,
@@ -79,6 +85,7 @@ impl SequencerConsensusContext {
             valid_proposals: Arc::new(Mutex::new(HeightToIdToContent::new())),
             proposal_id: 0,
             current_height: None,
+            max_transaction_batch_size,
         }
     }
 }
@@ -154,6 +161,7 @@ impl ConsensusContext for SequencerConsensusContext {
         height: BlockNumber,
         timeout: Duration,
         content: mpsc::Receiver<Self::ProposalChunk>,
+        proposer_fin_receiver: oneshot::Receiver<ProposalContentId>,
     ) -> oneshot::Receiver<ProposalContentId> {
         debug!("Validating proposal for height: {height} with timeout: {timeout:?}");
         let (fin_sender, fin_receiver) = oneshot::channel();
@@ -161,6 +169,7 @@ impl ConsensusContext for SequencerConsensusContext {
         let valid_proposals = Arc::clone(&self.valid_proposals);
         let proposal_id = ProposalId(self.proposal_id);
         self.proposal_id += 1;
+        let max_transaction_batch_size = self.max_transaction_batch_size;
 
         let chrono_timeout =
             chrono::Duration::from_std(timeout).expect("Can't convert timeout to chrono::Duration");
@@ -183,6 +192,8 @@ impl ConsensusContext for SequencerConsensusContext {
                     batcher,
                     valid_proposals,
                     content,
+                    max_transaction_batch_size,
+                    proposer_fin_receiver,
                     fin_sender,
                 );
                 if let Err(e) = tokio::time::timeout(timeout, validate_fut).await {
@@ -280,14 +291,16 @@ async fn stream_build_proposal(
 ) {
     let mut content = Vec::new();
     loop {
-        let response =
-            match batcher.get_proposal_content(GetProposalContentInput { proposal_id }).await {
-                Ok(response) => response,
-                Err(e) => {
-                    warn!("Failed to get proposal content: {e:?}");
-                    return;
-                }
-            };
+        let response = match batcher
+            .get_proposal_content(GetProposalContentInput { proposal_id, max_txs: None })
+            .await
+        {
+            Ok(response) => response,
+            Err(e) => {
+                warn!("Failed to get proposal content: {e:?}");
+                return;
+            }
+        };
         match response.content {
             GetProposalContent::Txs(txs) => {
                 content.extend_from_slice(&txs[..]);
@@ -342,9 +355,10 @@ async fn stream_build_proposal(
 }
 
 // Handles receiving a proposal from another node without blocking consensus:
-// 1. Receives the proposal content from the network.
+// 1. Receives the proposal content from the network, aborting it on an oversized batch.
 // 2. Pass this to the batcher.
-// 3. Once finished, receive the commitment from the batcher.
+// 3. Once finished, pass the proposer's commitment to the batcher, which validates the content
+//    against it, and receive the commitment from the batcher.
 // 4. Store the proposal for re-proposal.
 // 5. Send the commitment to consensus.
 async fn stream_validate_proposal(
@@ -353,10 +367,25 @@ async fn stream_validate_proposal(
     batcher: Arc<dyn BatcherClient>,
     valid_proposals: Arc<Mutex<HeightToIdToContent>>,
     mut content_receiver: mpsc::Receiver<Vec<Transaction>>,
+    max_transaction_batch_size: usize,
+    proposer_fin_receiver: oneshot::Receiver<ProposalContentId>,
     fin_sender: oneshot::Sender<ProposalContentId>,
 ) {
     let mut content = Vec::new();
     while let Some(txs) = content_receiver.next().await {
+        if txs.len() > max_transaction_batch_size {
+            warn!(
+                "Proposal {proposal_id:?} has a batch of {} transactions, exceeding the maximum of \
+                 {max_transaction_batch_size}.",
+                txs.len()
+            );
+            let input =
+                SendProposalContentInput { proposal_id, content: SendProposalContent::Abort };
+            batcher.send_proposal_content(input).await.unwrap_or_else(|e| {
+                panic!("Failed to abort proposal in the batcher: {proposal_id:?}. {e:?}")
+            });
+            return;
+        }
         content.extend_from_slice(&txs[..]);
         let input =
             SendProposalContentInput { proposal_id, content: SendProposalContent::Txs(txs) };
@@ -374,9 +403,17 @@ async fn stream_validate_proposal(
             }
         }
     }
-    // TODO: In the future we will receive a Fin from the network instead of the channel closing.
-    // We will just send the network Fin out along with what the batcher calculates.
-    let input = SendProposalContentInput { proposal_id, content: SendProposalContent::Finish };
+    let Ok(proposer_fin) = proposer_fin_receiver.await else {
+        warn!("Proposal fin was not received: {:?}", proposal_id);
+        return;
+    };
+    let proposer_commitment = ProposalCommitment {
+        state_diff_commitment: StateDiffCommitment(PoseidonHash(proposer_fin.0)),
+    };
+    let input = SendProposalContentInput {
+        proposal_id,
+        content: SendProposalContent::Finish(Some(proposer_commitment)),
+    };
     let response = batcher
         .send_proposal_content(input)
         .await
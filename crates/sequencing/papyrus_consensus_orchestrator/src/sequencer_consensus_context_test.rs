@@ -2,10 +2,10 @@ use std::sync::{Arc, OnceLock};
 use std::time::Duration;
 use std::vec;
 
-use futures::channel::mpsc;
+use futures::channel::{mpsc, oneshot};
 use futures::SinkExt;
 use lazy_static::lazy_static;
-use papyrus_consensus::types::ConsensusContext;
+use papyrus_consensus::types::{ConsensusContext, ProposalContentId};
 use papyrus_network::network_manager::test_utils::{
     mock_register_broadcast_topic,
     TestSubscriberChannels,
@@ -39,6 +39,7 @@ use crate::sequencer_consensus_context::SequencerConsensusContext;
 const TIMEOUT: Duration = Duration::from_millis(100);
 const CHANNEL_SIZE: usize = 5000;
 const NUM_VALIDATORS: u64 = 4;
+const MAX_TRANSACTION_BATCH_SIZE: usize = 10;
 const STATE_DIFF_COMMITMENT: StateDiffCommitment = StateDiffCommitment(PoseidonHash(Felt::ZERO));
 
 lazy_static! {
@@ -52,6 +53,13 @@ fn generate_invoke_tx(tx_hash: Felt) -> Transaction {
     })))
 }
 
+// The fin sent by the proposer, matching the commitment of the content.
+fn proposer_fin_receiver() -> oneshot::Receiver<ProposalContentId> {
+    let (proposer_fin_sender, proposer_fin_receiver) = oneshot::channel();
+    proposer_fin_sender.send(BlockHash(STATE_DIFF_COMMITMENT.0.0)).unwrap();
+    proposer_fin_receiver
+}
+
 #[tokio::test]
 async fn build_proposal() {
     let mut batcher = MockBatcherClient::new();
@@ -68,7 +76,10 @@ async fn build_proposal() {
     let proposal_id_clone = Arc::clone(&proposal_id);
     batcher.expect_get_proposal_content().times(1).returning(move |input| {
         assert_eq!(input.proposal_id, *proposal_id_clone.get().unwrap());
-        Ok(GetProposalContentResponse { content: GetProposalContent::Txs(TX_BATCH.clone()) })
+        Ok(GetProposalContentResponse {
+            content: GetProposalContent::Txs(TX_BATCH.clone()),
+            execution_outcomes: None,
+        })
     });
     let proposal_id_clone = Arc::clone(&proposal_id);
     batcher.expect_get_proposal_content().times(1).returning(move |input| {
@@ -77,14 +88,19 @@ async fn build_proposal() {
             content: GetProposalContent::Finished(ProposalCommitment {
                 state_diff_commitment: STATE_DIFF_COMMITMENT,
             }),
+            execution_outcomes: None,
         })
     });
     let TestSubscriberChannels { mock_network: _mock_network, subscriber_channels } =
         mock_register_broadcast_topic().expect("Failed to create mock network");
     let BroadcastTopicChannels { broadcasted_messages_receiver: _, broadcast_topic_client } =
         subscriber_channels;
-    let mut context =
-        SequencerConsensusContext::new(Arc::new(batcher), broadcast_topic_client, NUM_VALIDATORS);
+    let mut context = SequencerConsensusContext::new(
+        Arc::new(batcher),
+        broadcast_topic_client,
+        NUM_VALIDATORS,
+        MAX_TRANSACTION_BATCH_SIZE,
+    );
     let init = ProposalInit {
         height: BlockNumber(0),
         round: 0,
@@ -124,7 +140,12 @@ async fn validate_proposal_success() {
     batcher.expect_send_proposal_content().times(1).returning(
         move |input: SendProposalContentInput| {
             assert_eq!(input.proposal_id, *proposal_id_clone.get().unwrap());
-            assert!(matches!(input.content, SendProposalContent::Finish));
+            assert_eq!(
+                input.content,
+                SendProposalContent::Finish(Some(ProposalCommitment {
+                    state_diff_commitment: STATE_DIFF_COMMITMENT,
+                }))
+            );
             Ok(SendProposalContentResponse {
                 response: ProposalStatus::Finished(ProposalCommitment {
                     state_diff_commitment: STATE_DIFF_COMMITMENT,
@@ -136,15 +157,88 @@ async fn validate_proposal_success() {
         mock_register_broadcast_topic().expect("Failed to create mock network");
     let BroadcastTopicChannels { broadcasted_messages_receiver: _, broadcast_topic_client } =
         subscriber_channels;
-    let mut context =
-        SequencerConsensusContext::new(Arc::new(batcher), broadcast_topic_client, NUM_VALIDATORS);
+    let mut context = SequencerConsensusContext::new(
+        Arc::new(batcher),
+        broadcast_topic_client,
+        NUM_VALIDATORS,
+        MAX_TRANSACTION_BATCH_SIZE,
+    );
     let (mut content_sender, content_receiver) = mpsc::channel(CHANNEL_SIZE);
     content_sender.send(TX_BATCH.clone()).await.unwrap();
-    let fin_receiver = context.validate_proposal(BlockNumber(0), TIMEOUT, content_receiver).await;
+    let fin_receiver = context
+        .validate_proposal(BlockNumber(0), TIMEOUT, content_receiver, proposer_fin_receiver())
+        .await;
     content_sender.close_channel();
     assert_eq!(fin_receiver.await.unwrap().0, STATE_DIFF_COMMITMENT.0.0);
 }
 
+#[tokio::test]
+async fn validate_proposal_without_proposer_fin() {
+    let mut batcher = MockBatcherClient::new();
+    batcher.expect_validate_proposal().returning(move |_| Ok(()));
+    batcher.expect_start_height().return_once(|_| Ok(()));
+    // Only the content is sent to the batcher, it is never told the proposal is finished.
+    batcher.expect_send_proposal_content().times(1).returning(
+        move |input: SendProposalContentInput| {
+            assert!(matches!(input.content, SendProposalContent::Txs(_)));
+            Ok(SendProposalContentResponse { response: ProposalStatus::Processing })
+        },
+    );
+    let TestSubscriberChannels { mock_network: _, subscriber_channels } =
+        mock_register_broadcast_topic().expect("Failed to create mock network");
+    let BroadcastTopicChannels { broadcasted_messages_receiver: _, broadcast_topic_client } =
+        subscriber_channels;
+    let mut context = SequencerConsensusContext::new(
+        Arc::new(batcher),
+        broadcast_topic_client,
+        NUM_VALIDATORS,
+        MAX_TRANSACTION_BATCH_SIZE,
+    );
+    let (mut content_sender, content_receiver) = mpsc::channel(CHANNEL_SIZE);
+    content_sender.send(TX_BATCH.clone()).await.unwrap();
+    // The proposer's fin is never received.
+    let (_, proposer_fin_receiver) = oneshot::channel();
+    let fin_receiver = context
+        .validate_proposal(BlockNumber(0), TIMEOUT, content_receiver, proposer_fin_receiver)
+        .await;
+    content_sender.close_channel();
+    assert_eq!(fin_receiver.await, Err(oneshot::Canceled));
+}
+
+#[tokio::test]
+async fn validate_proposal_with_oversized_batch() {
+    let mut batcher = MockBatcherClient::new();
+    batcher.expect_validate_proposal().returning(move |_| Ok(()));
+    batcher.expect_start_height().return_once(|_| Ok(()));
+    // The oversized batch is not passed to the batcher, which is told to abort the proposal.
+    batcher.expect_send_proposal_content().times(1).returning(
+        move |input: SendProposalContentInput| {
+            assert_eq!(input.content, SendProposalContent::Abort);
+            Ok(SendProposalContentResponse { response: ProposalStatus::InvalidProposal })
+        },
+    );
+    let TestSubscriberChannels { mock_network: _, subscriber_channels } =
+        mock_register_broadcast_topic().expect("Failed to create mock network");
+    let BroadcastTopicChannels { broadcasted_messages_receiver: _, broadcast_topic_client } =
+        subscriber_channels;
+    let mut context = SequencerConsensusContext::new(
+        Arc::new(batcher),
+        broadcast_topic_client,
+        NUM_VALIDATORS,
+        MAX_TRANSACTION_BATCH_SIZE,
+    );
+    let (mut content_sender, content_receiver) = mpsc::channel(CHANNEL_SIZE);
+    content_sender
+        .send(vec![generate_invoke_tx(Felt::TWO); MAX_TRANSACTION_BATCH_SIZE + 1])
+        .await
+        .unwrap();
+    let fin_receiver = context
+        .validate_proposal(BlockNumber(0), TIMEOUT, content_receiver, proposer_fin_receiver())
+        .await;
+    content_sender.close_channel();
+    assert_eq!(fin_receiver.await, Err(oneshot::Canceled));
+}
+
 #[tokio::test]
 async fn repropose() {
     // Receive a proposal. Then re-retrieve it.
@@ -162,7 +256,12 @@ async fn repropose() {
     );
     batcher.expect_send_proposal_content().times(1).returning(
         move |input: SendProposalContentInput| {
-            assert!(matches!(input.content, SendProposalContent::Finish));
+            assert_eq!(
+                input.content,
+                SendProposalContent::Finish(Some(ProposalCommitment {
+                    state_diff_commitment: STATE_DIFF_COMMITMENT,
+                }))
+            );
             Ok(SendProposalContentResponse {
                 response: ProposalStatus::Finished(ProposalCommitment {
                     state_diff_commitment: STATE_DIFF_COMMITMENT,
@@ -174,14 +273,20 @@ async fn repropose() {
         mock_register_broadcast_topic().expect("Failed to create mock network");
     let BroadcastTopicChannels { broadcasted_messages_receiver: _, broadcast_topic_client } =
         subscriber_channels;
-    let mut context =
-        SequencerConsensusContext::new(Arc::new(batcher), broadcast_topic_client, NUM_VALIDATORS);
+    let mut context = SequencerConsensusContext::new(
+        Arc::new(batcher),
+        broadcast_topic_client,
+        NUM_VALIDATORS,
+        MAX_TRANSACTION_BATCH_SIZE,
+    );
 
     // Receive a valid proposal.
     let (mut content_sender, content_receiver) = mpsc::channel(CHANNEL_SIZE);
     let txs = vec![generate_invoke_tx(Felt::TWO)];
     content_sender.send(txs.clone()).await.unwrap();
-    let fin_receiver = context.validate_proposal(BlockNumber(0), TIMEOUT, content_receiver).await;
+    let fin_receiver = context
+        .validate_proposal(BlockNumber(0), TIMEOUT, content_receiver, proposer_fin_receiver())
+        .await;
     content_sender.close_channel();
     assert_eq!(fin_receiver.await.unwrap().0, STATE_DIFF_COMMITMENT.0.0);
 
@@ -351,4 +351,26 @@ impl Transaction {
             Transaction::L1Handler(tx) => tx.tx_hash,
         }
     }
+
+    /// Calculates the hash of the transaction on the given chain. Differs from `tx_hash` if the
+    /// transaction was signed for another chain.
+    pub fn calculate_transaction_hash(
+        &self,
+        chain_id: &ChainId,
+    ) -> Result<TransactionHash, StarknetApiError> {
+        match self {
+            Transaction::Account(AccountTransaction::Declare(tx_data)) => {
+                tx_data.tx.calculate_transaction_hash(chain_id, &tx_data.tx.version())
+            }
+            Transaction::Account(AccountTransaction::DeployAccount(tx_data)) => {
+                tx_data.tx.calculate_transaction_hash(chain_id, &tx_data.tx.version())
+            }
+            Transaction::Account(AccountTransaction::Invoke(tx_data)) => {
+                tx_data.tx.calculate_transaction_hash(chain_id, &tx_data.tx.version())
+            }
+            Transaction::L1Handler(tx_data) => {
+                tx_data.tx.calculate_transaction_hash(chain_id, &tx_data.tx.version)
+            }
+        }
+    }
 }
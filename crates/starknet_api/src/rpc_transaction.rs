@@ -91,6 +91,28 @@ impl RpcTransaction {
             RpcTransaction::Invoke(RpcInvokeTransaction::V3(tx)) => Ok(tx.sender_address),
         }
     }
+
+    /// Returns the total number of felts in the calldata, constructor calldata, signature,
+    /// paymaster data and account deployment data of the transaction. The contract class of a
+    /// declare transaction is not counted.
+    pub fn total_felt_length(&self) -> usize {
+        match self {
+            RpcTransaction::Declare(RpcDeclareTransaction::V3(tx)) => {
+                tx.signature.0.len()
+                    + tx.paymaster_data.0.len()
+                    + tx.account_deployment_data.0.len()
+            }
+            RpcTransaction::DeployAccount(RpcDeployAccountTransaction::V3(tx)) => {
+                tx.constructor_calldata.0.len() + tx.signature.0.len() + tx.paymaster_data.0.len()
+            }
+            RpcTransaction::Invoke(RpcInvokeTransaction::V3(tx)) => {
+                tx.calldata.0.len()
+                    + tx.signature.0.len()
+                    + tx.paymaster_data.0.len()
+                    + tx.account_deployment_data.0.len()
+            }
+        }
+    }
 }
 
 impl From<RpcTransaction> for Transaction {
@@ -170,6 +192,7 @@ impl From<RpcInvokeTransaction> for InvokeTransaction {
 /// A declare transaction of a Cairo-v1 contract class that can be added to Starknet through the
 /// RPC.
 #[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize, Hash)]
+#[serde(deny_unknown_fields)]
 pub struct RpcDeclareTransactionV3 {
     // TODO: Check with Shahak why we need to keep the DeclareType.
     // pub r#type: DeclareType,
@@ -207,6 +230,7 @@ impl From<RpcDeclareTransactionV3> for DeclareTransactionV3 {
 
 /// A deploy account transaction that can be added to Starknet through the RPC.
 #[derive(Clone, Debug, Deserialize, Eq, Hash, Ord, PartialEq, PartialOrd, Serialize)]
+#[serde(deny_unknown_fields)]
 pub struct RpcDeployAccountTransactionV3 {
     pub signature: TransactionSignature,
     pub nonce: Nonce,
@@ -239,6 +263,7 @@ impl From<RpcDeployAccountTransactionV3> for DeployAccountTransactionV3 {
 
 /// An invoke account transaction that can be added to Starknet through the RPC.
 #[derive(Clone, Debug, Deserialize, Eq, Hash, Ord, PartialEq, PartialOrd, Serialize)]
+#[serde(deny_unknown_fields)]
 pub struct RpcInvokeTransactionV3 {
     pub sender_address: ContractAddress,
     pub calldata: Calldata,
@@ -24,6 +24,7 @@ use crate::transaction::fields::{
     ContractAddressSalt,
     PaymasterData,
     ResourceBounds,
+    ResourceBoundsError,
     Tip,
     TransactionSignature,
 };
@@ -93,3 +94,77 @@ fn test_rpc_transactions(#[case] tx: RpcTransaction) {
     let deserialized: RpcTransaction = serde_json::from_str(&serialized).unwrap();
     assert_eq!(tx, deserialized);
 }
+
+#[test]
+fn test_validated_resource_bounds() {
+    let AllResourceBounds { l1_gas, l2_gas, l1_data_gas } = create_resource_bounds_for_testing();
+    assert_eq!(
+        AllResourceBounds::validated(l1_gas, l2_gas, l1_data_gas),
+        Ok(create_resource_bounds_for_testing())
+    );
+}
+
+#[rstest]
+#[case::all_zero(AllResourceBounds::default(), |_| ResourceBoundsError::AllZero)]
+#[case::zero_l2_gas(
+    AllResourceBounds {
+        l2_gas: ResourceBounds { max_amount: GasAmount(0), max_price_per_unit: GasPrice(31) },
+        ..create_resource_bounds_for_testing()
+    },
+    ResourceBoundsError::ZeroL2GasMaxAmount
+)]
+#[case::max_fee_overflow(
+    AllResourceBounds {
+        l2_gas: ResourceBounds { max_amount: GasAmount(2), max_price_per_unit: GasPrice(u128::MAX) },
+        ..create_resource_bounds_for_testing()
+    },
+    ResourceBoundsError::MaxFeeOverflow
+)]
+fn test_validated_resource_bounds_rejects_malformed_bounds(
+    #[case] resource_bounds: AllResourceBounds,
+    #[case] expected_error: fn(AllResourceBounds) -> ResourceBoundsError,
+) {
+    let AllResourceBounds { l1_gas, l2_gas, l1_data_gas } = resource_bounds;
+    assert_eq!(
+        AllResourceBounds::validated(l1_gas, l2_gas, l1_data_gas),
+        Err(expected_error(resource_bounds))
+    );
+}
+
+#[rstest]
+#[case(RpcTransaction::Declare(create_declare_v3()))]
+#[case(RpcTransaction::DeployAccount(create_deploy_account_v3()))]
+#[case(RpcTransaction::Invoke(create_invoke_v3()))]
+fn test_rpc_transaction_with_unknown_field_is_rejected(#[case] tx: RpcTransaction) {
+    let mut json = serde_json::to_value(&tx).unwrap();
+    json.as_object_mut().unwrap().insert("unknown_field".to_string(), serde_json::json!("0x1"));
+    let error = serde_json::from_value::<RpcTransaction>(json).unwrap_err();
+    assert!(
+        error.to_string().contains("unknown field `unknown_field`"),
+        "Unexpected error: {error}"
+    );
+}
+
+#[test]
+fn test_rpc_invoke_transaction_with_empty_account_deployment_data() {
+    let RpcInvokeTransaction::V3(invoke_tx) = create_invoke_v3();
+    let tx = RpcTransaction::Invoke(RpcInvokeTransaction::V3(RpcInvokeTransactionV3 {
+        account_deployment_data: AccountDeploymentData::default(),
+        paymaster_data: PaymasterData::default(),
+        ..invoke_tx
+    }));
+    let serialized = serde_json::to_string(&tx).unwrap();
+    let deserialized: RpcTransaction = serde_json::from_str(&serialized).unwrap();
+    assert_eq!(tx, deserialized);
+}
+
+#[rstest]
+// Signature (2), paymaster data (1) and account deployment data (1).
+#[case(RpcTransaction::Declare(create_declare_v3()), 4)]
+// Constructor calldata (1), signature (1) and paymaster data (2).
+#[case(RpcTransaction::DeployAccount(create_deploy_account_v3()), 4)]
+// Calldata (2), paymaster data (2) and account deployment data (1).
+#[case(RpcTransaction::Invoke(create_invoke_v3()), 5)]
+fn test_total_felt_length(#[case] tx: RpcTransaction, #[case] expected_length: usize) {
+    assert_eq!(tx.total_felt_length(), expected_length);
+}
@@ -361,7 +361,41 @@ impl std::fmt::Display for AllResourceBounds {
     }
 }
 
+/// An error in the resource bounds of a transaction.
+#[derive(Clone, Debug, Eq, PartialEq, thiserror::Error)]
+pub enum ResourceBoundsError {
+    #[error("All resource bounds are zero.")]
+    AllZero,
+    #[error("The max amount of L2 gas must be non-zero; got resource bounds {0}.")]
+    ZeroL2GasMaxAmount(AllResourceBounds),
+    #[error("The max possible fee of resource bounds {0} overflows.")]
+    MaxFeeOverflow(AllResourceBounds),
+}
+
 impl AllResourceBounds {
+    /// Creates resource bounds, rejecting bounds that no transaction can execute with: all-zero
+    /// bounds, a zero max amount of L2 gas, or bounds whose max possible fee overflows.
+    pub fn validated(
+        l1_gas: ResourceBounds,
+        l2_gas: ResourceBounds,
+        l1_data_gas: ResourceBounds,
+    ) -> Result<Self, ResourceBoundsError> {
+        let resource_bounds = Self { l1_gas, l2_gas, l1_data_gas };
+        if l1_gas.is_zero() && l2_gas.is_zero() && l1_data_gas.is_zero() {
+            return Err(ResourceBoundsError::AllZero);
+        }
+        if l2_gas.max_amount == GasAmount(0) {
+            return Err(ResourceBoundsError::ZeroL2GasMaxAmount(resource_bounds));
+        }
+        [l1_gas, l2_gas, l1_data_gas]
+            .into_iter()
+            .try_fold(Fee(0), |max_fee, bounds| {
+                bounds.max_amount.checked_mul(bounds.max_price_per_unit)?.checked_add(max_fee)
+            })
+            .ok_or(ResourceBoundsError::MaxFeeOverflow(resource_bounds))?;
+        Ok(resource_bounds)
+    }
+
     pub fn get_bound(&self, resource: Resource) -> ResourceBounds {
         match resource {
             Resource::L1Gas => self.l1_gas,
@@ -1,7 +1,8 @@
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 use std::sync::Arc;
 
 use blockifier::state::global_cache::GlobalContractCache;
+use blockifier::versioned_constants::VersionedConstants;
 #[cfg(test)]
 use mockall::automock;
 use papyrus_storage::state::{StateStorageReader, StateStorageWriter};
@@ -10,16 +11,23 @@ use starknet_api::executable_transaction::Transaction;
 use starknet_api::state::ThinStateDiff;
 use starknet_batcher_types::batcher_types::{
     BatcherResult,
+    BlockResourceSummary,
     BuildProposalInput,
     DecisionReachedInput,
+    GetBlockResourceSummaryInput,
     GetProposalContent,
     GetProposalContentInput,
     GetProposalContentResponse,
+    GetStateDiffChunksInput,
+    GetStateDiffChunksResponse,
+    ProposalCommitment,
     ProposalId,
     ProposalStatus as ResponseProposalStatus,
     SendProposalContent,
     SendProposalContentInput,
     SendProposalContentResponse,
+    SimulateTransactionInput,
+    SimulateTransactionResponse,
     StartHeightInput,
     ValidateProposalInput,
 };
@@ -27,9 +35,9 @@ use starknet_batcher_types::errors::BatcherError;
 use starknet_mempool_types::communication::SharedMempoolClient;
 use starknet_mempool_types::mempool_types::CommitBlockArgs;
 use starknet_sequencer_infra::component_definitions::ComponentStarter;
-use tracing::{debug, error, info, instrument, trace};
+use tracing::{debug, error, info, instrument, trace, warn};
 
-use crate::block_builder::BlockBuilderFactory;
+use crate::block_builder::{BlockBuilderFactory, BlockBuilderFactoryTrait, ExecutedTransaction};
 use crate::config::BatcherConfig;
 use crate::proposal_manager::{
     GenerateProposalError,
@@ -40,19 +48,34 @@ use crate::proposal_manager::{
     ProposalStatus,
     StartHeightError,
 };
-use crate::transaction_provider::{DummyL1ProviderClient, ProposeTransactionProvider};
+use crate::state_diff_chunks::split_state_diff;
+use crate::transaction_provider::{
+    DummyL1ProviderClient,
+    ProposeTransactionProvider,
+    ValidateTransactionProvider,
+};
+use crate::transaction_simulator::TransactionSimulatorTrait;
 
-type OutputStreamReceiver = tokio::sync::mpsc::UnboundedReceiver<Transaction>;
+type OutputStreamReceiver = tokio::sync::mpsc::Receiver<ExecutedTransaction>;
 type InputStreamSender = tokio::sync::mpsc::Sender<Transaction>;
 
+/// The number of most recently committed blocks whose resource summaries are kept.
+const MAX_CACHED_BLOCK_RESOURCE_SUMMARIES: usize = 100;
+
 pub struct Batcher {
     pub config: BatcherConfig,
     pub storage_reader: Arc<dyn BatcherStorageReaderTrait>,
     pub storage_writer: Box<dyn BatcherStorageWriterTrait>,
     pub mempool_client: SharedMempoolClient,
     proposal_manager: Box<dyn ProposalManagerTrait>,
+    transaction_simulator: Arc<dyn TransactionSimulatorTrait>,
     build_proposals: HashMap<ProposalId, OutputStreamReceiver>,
     validate_proposals: HashMap<ProposalId, InputStreamSender>,
+    // The state diff of the last decided proposal, kept for DA publishing.
+    decided_proposal: Option<(ProposalId, ThinStateDiff)>,
+    // The resource summaries of the most recently committed blocks. The storage keeps only the
+    // state diffs, so older summaries are not available.
+    block_resource_summaries: BTreeMap<BlockNumber, BlockResourceSummary>,
 }
 
 impl Batcher {
@@ -62,6 +85,7 @@ impl Batcher {
         storage_writer: Box<dyn BatcherStorageWriterTrait>,
         mempool_client: SharedMempoolClient,
         proposal_manager: Box<dyn ProposalManagerTrait>,
+        transaction_simulator: Arc<dyn TransactionSimulatorTrait>,
     ) -> Self {
         Self {
             config: config.clone(),
@@ -69,11 +93,41 @@ impl Batcher {
             storage_writer,
             mempool_client,
             proposal_manager,
+            transaction_simulator,
             build_proposals: HashMap::new(),
             validate_proposals: HashMap::new(),
+            decided_proposal: None,
+            block_resource_summaries: BTreeMap::new(),
         }
     }
 
+    /// Creates a batcher over the given storage handles, so the storage backing it can be chosen by
+    /// the caller (e.g. an in-memory storage in tests).
+    pub fn with_storage(
+        config: BatcherConfig,
+        mempool_client: SharedMempoolClient,
+        storage_reader: Arc<dyn BatcherStorageReaderTrait>,
+        storage_writer: Box<dyn BatcherStorageWriterTrait>,
+        block_builder_factory: Arc<dyn BlockBuilderFactoryTrait + Send + Sync>,
+        transaction_simulator: Arc<dyn TransactionSimulatorTrait>,
+    ) -> Self {
+        let proposal_manager = Box::new(ProposalManager::new(
+            block_builder_factory,
+            storage_reader.clone(),
+            config.max_concurrent_proposals,
+            config.outstream_content_buffer_full_policy,
+            config.proposal_expiry_grace_period,
+        ));
+        Self::new(
+            config,
+            storage_reader,
+            storage_writer,
+            mempool_client,
+            proposal_manager,
+            transaction_simulator,
+        )
+    }
+
     pub async fn start_height(&mut self, input: StartHeightInput) -> BatcherResult<()> {
         self.build_proposals.clear();
         self.validate_proposals.clear();
@@ -86,12 +140,11 @@ impl Batcher {
         build_proposal_input: BuildProposalInput,
     ) -> BatcherResult<()> {
         let proposal_id = build_proposal_input.proposal_id;
-        let deadline =
-            tokio::time::Instant::from_std(build_proposal_input.deadline_as_instant().map_err(
-                |_| BatcherError::TimeToDeadlineError { deadline: build_proposal_input.deadline },
-            )?);
+        let deadline = tokio::time::Instant::from_std(build_proposal_input.deadline_as_instant()?);
+        self.remove_expired_proposal_streams().await;
 
-        let (tx_sender, tx_receiver) = tokio::sync::mpsc::unbounded_channel();
+        let (tx_sender, tx_receiver) =
+            tokio::sync::mpsc::channel(self.config.outstream_content_buffer_size);
         let tx_provider = ProposeTransactionProvider::new(
             self.mempool_client.clone(),
             // TODO: use a real L1 provider client.
@@ -105,6 +158,8 @@ impl Batcher {
                 build_proposal_input.retrospective_block_hash,
                 deadline,
                 tx_sender,
+                // TODO: expose the proposal progress to consensus.
+                None,
                 tx_provider,
             )
             .await
@@ -120,7 +175,30 @@ impl Batcher {
         &mut self,
         validate_proposal_input: ValidateProposalInput,
     ) -> BatcherResult<()> {
-        todo!();
+        let proposal_id = validate_proposal_input.proposal_id;
+        let deadline =
+            tokio::time::Instant::from_std(validate_proposal_input.deadline_as_instant()?);
+
+        let (input_tx_sender, input_tx_receiver) =
+            tokio::sync::mpsc::channel(self.config.input_stream_content_buffer_size);
+        let tx_provider = ValidateTransactionProvider {
+            tx_receiver: input_tx_receiver,
+            // TODO: use a real L1 provider client.
+            l1_provider_client: Arc::new(DummyL1ProviderClient),
+        };
+
+        self.proposal_manager
+            .validate_block_proposal(
+                proposal_id,
+                validate_proposal_input.retrospective_block_hash,
+                deadline,
+                tx_provider,
+            )
+            .await
+            .map_err(BatcherError::from)?;
+
+        self.validate_proposals.insert(proposal_id, input_tx_sender);
+        Ok(())
     }
 
     // This function assumes that requests are received in order, otherwise the content could
@@ -134,12 +212,10 @@ impl Batcher {
 
         match send_proposal_content_input.content {
             SendProposalContent::Txs(txs) => self.send_txs_and_get_status(proposal_id, txs).await,
-            SendProposalContent::Finish => {
-                self.close_tx_channel_and_get_commitement(proposal_id).await
-            }
-            SendProposalContent::Abort => {
-                unimplemented!("Abort not implemented yet.");
+            SendProposalContent::Finish(expected_commitment) => {
+                self.close_tx_channel_and_get_commitement(proposal_id, expected_commitment).await
             }
+            SendProposalContent::Abort => self.abort_proposal(proposal_id).await,
         }
     }
 
@@ -150,6 +226,14 @@ impl Batcher {
     ) -> BatcherResult<SendProposalContentResponse> {
         match self.proposal_manager.get_proposal_status(proposal_id).await {
             ProposalStatus::Processing => {
+                if let Some(tx) = txs.iter().find(|tx| !self.targets_chain(tx)) {
+                    warn!(
+                        "Proposal {} contains transaction {} of another chain. Aborting it.",
+                        proposal_id,
+                        tx.tx_hash()
+                    );
+                    return self.abort_proposal(proposal_id).await;
+                }
                 // TODO: validate L1 transactions.
                 let tx_provider_sender = &self
                     .validate_proposals
@@ -172,12 +256,49 @@ impl Batcher {
         }
     }
 
+    // Cancels the proposal, releasing the proposal manager for a new one.
+    async fn abort_proposal(
+        &mut self,
+        proposal_id: ProposalId,
+    ) -> BatcherResult<SendProposalContentResponse> {
+        self.proposal_manager.abort_proposal(proposal_id).await;
+        self.validate_proposals.remove(&proposal_id);
+        Ok(SendProposalContentResponse { response: ResponseProposalStatus::InvalidProposal })
+    }
+
+    // Whether the hash of the transaction was calculated for the chain of this batcher, i.e. it
+    // was not signed for another chain.
+    fn targets_chain(&self, tx: &Transaction) -> bool {
+        let chain_id = &self.config.block_builder_config.chain_info.chain_id;
+        tx.calculate_transaction_hash(chain_id).is_ok_and(|tx_hash| tx_hash == tx.tx_hash())
+    }
+
+    // Drops the content streams of the expired proposals, so their content is released although
+    // their consumer did not reach its end. Returns the expired proposals.
+    async fn remove_expired_proposal_streams(&mut self) -> Vec<ProposalId> {
+        let expired_proposals = self.proposal_manager.expired_proposals().await;
+        for proposal_id in &expired_proposals {
+            self.build_proposals.remove(proposal_id);
+        }
+        expired_proposals
+    }
+
     async fn close_tx_channel_and_get_commitement(
         &mut self,
         proposal_id: ProposalId,
+        expected_commitment: Option<ProposalCommitment>,
     ) -> BatcherResult<SendProposalContentResponse> {
         debug!("Send proposal content done for {}", proposal_id);
 
+        // The proposal was already found invalid, e.g. by a previous content item.
+        if let ProposalStatus::Failed = self.proposal_manager.get_proposal_status(proposal_id).await
+        {
+            self.validate_proposals.remove(&proposal_id);
+            return Ok(SendProposalContentResponse {
+                response: ResponseProposalStatus::InvalidProposal,
+            });
+        }
+
         let tx_provider_sender = self
             .validate_proposals
             .remove(&proposal_id)
@@ -185,7 +306,25 @@ impl Batcher {
         drop(tx_provider_sender);
 
         let proposal_commitment =
-            self.proposal_manager.await_proposal_commitment(proposal_id).await?;
+            match self.proposal_manager.await_proposal_commitment(proposal_id).await {
+                Ok(proposal_commitment) => proposal_commitment,
+                Err(GetProposalResultError::BlockBuilderError(err)) => {
+                    warn!("Proposal {} failed re-execution: {}", proposal_id, err);
+                    return Ok(SendProposalContentResponse {
+                        response: ResponseProposalStatus::InvalidProposal,
+                    });
+                }
+                Err(err) => return Err(err.into()),
+            };
+        if let Some(expected_commitment) = expected_commitment {
+            if proposal_commitment != expected_commitment {
+                warn!(
+                    "Proposal {} commitment {:?} does not match the expected {:?}.",
+                    proposal_id, proposal_commitment, expected_commitment
+                );
+                return self.abort_proposal(proposal_id).await;
+            }
+        }
         Ok(SendProposalContentResponse {
             response: ResponseProposalStatus::Finished(proposal_commitment),
         })
@@ -197,6 +336,14 @@ impl Batcher {
         get_proposal_content_input: GetProposalContentInput,
     ) -> BatcherResult<GetProposalContentResponse> {
         let proposal_id = get_proposal_content_input.proposal_id;
+        let max_txs = get_proposal_content_input.max_txs.map_or(
+            self.config.outstream_content_buffer_size,
+            // A zero limit would be mistaken for the end of the stream.
+            |max_txs| max_txs.clamp(1, self.config.outstream_content_buffer_size),
+        );
+        if self.remove_expired_proposal_streams().await.contains(&proposal_id) {
+            return Err(BatcherError::ProposalExpired);
+        }
 
         let tx_stream = &mut self
             .build_proposals
@@ -204,13 +351,16 @@ impl Batcher {
             .ok_or(BatcherError::ProposalNotFound { proposal_id })?;
 
         // Blocking until we have some txs to stream or the proposal is done.
-        let mut txs = Vec::new();
-        let n_executed_txs =
-            tx_stream.recv_many(&mut txs, self.config.outstream_content_buffer_size).await;
+        let mut executed_txs = Vec::new();
+        let n_executed_txs = tx_stream.recv_many(&mut executed_txs, max_txs).await;
 
         if n_executed_txs != 0 {
             debug!("Streaming {} txs", n_executed_txs);
-            return Ok(GetProposalContentResponse { content: GetProposalContent::Txs(txs) });
+            let (txs, execution_outcomes) = executed_txs.into_iter().unzip();
+            return Ok(GetProposalContentResponse {
+                content: GetProposalContent::Txs(txs),
+                execution_outcomes: Some(execution_outcomes),
+            });
         }
 
         // Finished streaming all the transactions.
@@ -221,6 +371,7 @@ impl Batcher {
             self.proposal_manager.await_proposal_commitment(proposal_id).await?;
         Ok(GetProposalContentResponse {
             content: GetProposalContent::Finished(proposal_commitment),
+            execution_outcomes: None,
         })
     }
 
@@ -228,8 +379,13 @@ impl Batcher {
     pub async fn decision_reached(&mut self, input: DecisionReachedInput) -> BatcherResult<()> {
         let proposal_id = input.proposal_id;
         let proposal_output = self.proposal_manager.take_proposal_result(proposal_id).await?;
-        let ProposalOutput { state_diff, nonces: address_to_nonce, tx_hashes, .. } =
-            proposal_output;
+        let ProposalOutput {
+            state_diff,
+            nonces: address_to_nonce,
+            tx_hashes,
+            resource_summary,
+            ..
+        } = proposal_output;
         // TODO: Keep the height from start_height or get it from the input.
         let height = self.storage_reader.height().map_err(|err| {
             error!("Failed to get height from storage: {}", err);
@@ -240,10 +396,15 @@ impl Batcher {
             proposal_id, height
         );
         trace!("Transactions: {:#?}, State diff: {:#?}.", tx_hashes, state_diff);
-        self.storage_writer.commit_proposal(height, state_diff).map_err(|err| {
+        self.storage_writer.commit_proposal(height, state_diff.clone()).map_err(|err| {
             error!("Failed to commit proposal to storage: {}", err);
             BatcherError::InternalError
         })?;
+        self.decided_proposal = Some((proposal_id, state_diff));
+        self.block_resource_summaries.insert(height, resource_summary);
+        if self.block_resource_summaries.len() > MAX_CACHED_BLOCK_RESOURCE_SUMMARIES {
+            self.block_resource_summaries.pop_first();
+        }
         if let Err(mempool_err) =
             self.mempool_client.commit_block(CommitBlockArgs { address_to_nonce, tx_hashes }).await
         {
@@ -252,22 +413,96 @@ impl Batcher {
         }
         Ok(())
     }
+
+    #[instrument(skip(self), err)]
+    pub async fn get_state_diff_chunks(
+        &self,
+        input: GetStateDiffChunksInput,
+    ) -> BatcherResult<GetStateDiffChunksResponse> {
+        let proposal_id = input.proposal_id;
+        match &self.decided_proposal {
+            Some((decided_proposal_id, state_diff)) if *decided_proposal_id == proposal_id => {
+                Ok(GetStateDiffChunksResponse {
+                    chunks: split_state_diff(
+                        state_diff.clone(),
+                        self.config.max_state_diff_chunk_size,
+                    ),
+                })
+            }
+            _ => match self.proposal_manager.get_proposal_status(proposal_id).await {
+                ProposalStatus::NotFound => Err(BatcherError::ProposalNotFound { proposal_id }),
+                _ => Err(BatcherError::ProposalNotDecided { proposal_id }),
+            },
+        }
+    }
+
+    /// Returns the resources used by a block committed by this batcher. Only the most recently
+    /// committed blocks are kept.
+    #[instrument(skip(self), err)]
+    pub fn get_block_resource_summary(
+        &self,
+        input: GetBlockResourceSummaryInput,
+    ) -> BatcherResult<BlockResourceSummary> {
+        let block_number = input.block_number;
+        self.block_resource_summaries
+            .get(&block_number)
+            .cloned()
+            .ok_or(BatcherError::BlockResourceSummaryNotFound { block_number })
+    }
+
+    /// The versioned constants under which the batcher executes transactions.
+    pub fn versioned_constants(&self) -> Arc<VersionedConstants> {
+        self.transaction_simulator.versioned_constants()
+    }
+
+    /// Executes the transaction on top of the latest committed state, regardless of the active
+    /// height, and discards the state changes it made. The execution runs on a blocking thread, so
+    /// it doesn't stall the other batcher requests.
+    #[instrument(skip(self), err)]
+    pub async fn simulate_transaction(
+        &self,
+        input: SimulateTransactionInput,
+    ) -> BatcherResult<SimulateTransactionResponse> {
+        let height = self.storage_reader.height().map_err(|err| {
+            error!("Failed to get height from storage: {}", err);
+            BatcherError::InternalError
+        })?;
+        let tx_hash = input.tx.tx_hash();
+        let transaction_simulator = self.transaction_simulator.clone();
+        tokio::task::spawn_blocking(move || {
+            transaction_simulator.simulate_transaction(height, input.tx, input.flags)
+        })
+        .await
+        .map_err(|err| {
+            error!("Transaction simulation task failed: {}", err);
+            BatcherError::InternalError
+        })?
+        .map_err(|err| BatcherError::SimulationFailed { tx_hash, error: err.to_string() })
+    }
 }
 
-pub fn create_batcher(config: BatcherConfig, mempool_client: SharedMempoolClient) -> Batcher {
+pub fn create_batcher(
+    config: BatcherConfig,
+    mempool_client: SharedMempoolClient,
+    versioned_constants: Arc<VersionedConstants>,
+) -> Batcher {
     let (storage_reader, storage_writer) = papyrus_storage::open_storage(config.storage.clone())
         .expect("Failed to open batcher's storage");
 
     let block_builder_factory = Arc::new(BlockBuilderFactory {
         block_builder_config: config.block_builder_config.clone(),
+        versioned_constants,
         storage_reader: storage_reader.clone(),
         global_class_hash_to_class: GlobalContractCache::new(config.global_contract_cache_size),
     });
-    let storage_reader = Arc::new(storage_reader);
-    let storage_writer = Box::new(storage_writer);
-    let proposal_manager =
-        Box::new(ProposalManager::new(block_builder_factory, storage_reader.clone()));
-    Batcher::new(config, storage_reader, storage_writer, mempool_client, proposal_manager)
+    Batcher::with_storage(
+        config,
+        mempool_client,
+        Arc::new(storage_reader),
+        Box::new(storage_writer),
+        block_builder_factory.clone(),
+        block_builder_factory,
+    )
 }
 
 #[cfg_attr(test, automock)]
@@ -346,6 +581,7 @@ impl From<GetProposalResultError> for BatcherError {
                 BatcherError::ExecutedProposalNotFound { proposal_id }
             }
             GetProposalResultError::Aborted => BatcherError::ProposalAborted,
+            GetProposalResultError::Expired => BatcherError::ProposalExpired,
         }
     }
 }
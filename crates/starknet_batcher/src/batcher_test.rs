@@ -1,36 +1,81 @@
 use std::collections::{HashMap, HashSet};
-use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
 
 use assert_matches::assert_matches;
 use async_trait::async_trait;
+use blockifier::bouncer::BouncerWeights;
+use blockifier::fee::receipt::TransactionReceipt;
+use blockifier::transaction::objects::TransactionExecutionInfo;
 use futures::future::BoxFuture;
 use futures::FutureExt;
-use mockall::automock;
+use indexmap::IndexMap;
+use metrics_exporter_prometheus::PrometheusBuilder;
 use mockall::predicate::eq;
+use mockall::{automock, Sequence};
+use papyrus_test_utils::prometheus_is_contained;
+use prometheus_parse::Value;
 use rstest::{fixture, rstest};
 use starknet_api::block::{BlockHashAndNumber, BlockNumber};
-use starknet_api::core::{ContractAddress, Nonce, StateDiffCommitment};
-use starknet_api::executable_transaction::Transaction;
+use starknet_api::core::{ChainId, ContractAddress, Nonce, StateDiffCommitment};
+use starknet_api::executable_transaction::{AccountTransaction, Transaction};
+use starknet_api::execution_resources::{GasAmount, GasVector};
 use starknet_api::hash::PoseidonHash;
 use starknet_api::state::ThinStateDiff;
-use starknet_api::transaction::TransactionHash;
+use starknet_api::test_utils::invoke::{executable_invoke_tx, InvokeTxArgs};
+use starknet_api::transaction::fields::Fee;
+use starknet_api::transaction::{TransactionHash, TransactionHasher};
 use starknet_api::{contract_address, felt, nonce};
 use starknet_batcher_types::batcher_types::{
+    BlockResourceSummary,
+    BlockWeights,
     BuildProposalInput,
     DecisionReachedInput,
+    GetBlockResourceSummaryInput,
     GetProposalContent,
     GetProposalContentInput,
     GetProposalContentResponse,
+    GetStateDiffChunksInput,
     ProposalCommitment,
     ProposalId,
+    ProposalStatus as ResponseProposalStatus,
+    SendProposalContent,
+    SendProposalContentInput,
+    SimulateTransactionInput,
+    SimulateTransactionResponse,
+    SimulationFlags,
     StartHeightInput,
+    TransactionExecutionOutcome,
+    ValidateProposalInput,
 };
+use starknet_batcher_types::communication::{BatcherRequest, BatcherResponse};
 use starknet_batcher_types::errors::BatcherError;
 use starknet_mempool_types::communication::MockMempoolClient;
 use starknet_mempool_types::mempool_types::CommitBlockArgs;
+use starknet_sequencer_infra::component_definitions::ComponentRequestHandler;
 
-use crate::batcher::{Batcher, MockBatcherStorageReaderTrait, MockBatcherStorageWriterTrait};
+use crate::batcher::{
+    Batcher,
+    BatcherStorageReaderTrait,
+    BatcherStorageWriterTrait,
+    MockBatcherStorageReaderTrait,
+    MockBatcherStorageWriterTrait,
+};
+use crate::block_builder::{
+    BlockBuilderError,
+    BlockExecutionArtifacts,
+    MockBlockBuilderFactoryTrait,
+    MockBlockBuilderTrait,
+    OutputContentSender,
+    ProposalProgressSender,
+};
 use crate::config::BatcherConfig;
+use crate::metrics::{
+    BATCHER_REQUEST_ERRORS_TOTAL,
+    BATCHER_REQUEST_LATENCY_SECONDS,
+    REQUEST_LABEL,
+};
 use crate::proposal_manager::{
     GenerateProposalError,
     GetProposalResultError,
@@ -40,8 +85,14 @@ use crate::proposal_manager::{
     ProposalStatus,
     StartHeightError,
 };
-use crate::test_utils::test_txs;
+use crate::test_utils::{
+    test_state_diff,
+    test_txs,
+    unite_state_diff_chunks,
+    PendingBlockBuilder,
+};
 use crate::transaction_provider::{ProposeTransactionProvider, ValidateTransactionProvider};
+use crate::transaction_simulator::MockTransactionSimulatorTrait;
 
 const INITIAL_HEIGHT: BlockNumber = BlockNumber(3);
 const STREAMING_CHUNK_SIZE: usize = 3;
@@ -75,6 +126,7 @@ fn batcher(proposal_manager: MockProposalManagerTraitWrapper) -> Batcher {
         Box::new(storage_writer()),
         Arc::new(mempool_client()),
         Box::new(proposal_manager),
+        Arc::new(MockTransactionSimulatorTrait::new()),
     )
 }
 
@@ -91,8 +143,9 @@ async fn get_stream_content() {
 
     let mut proposal_manager = MockProposalManagerTraitWrapper::new();
     proposal_manager.expect_wrap_start_height().times(1).return_once(|_| async { Ok(()) }.boxed());
+    proposal_manager.expect_wrap_expired_proposals().returning(|| async { Vec::new() }.boxed());
     proposal_manager.expect_wrap_build_block_proposal().times(1).return_once(
-        move |_proposal_id, _block_hash, _deadline, tx_sender, _tx_provider| {
+        move |_proposal_id, _block_hash, _deadline, tx_sender, _progress_sender, _tx_provider| {
             simulate_build_block_proposal(tx_sender, txs_to_stream).boxed()
         },
     );
@@ -116,33 +169,112 @@ async fn get_stream_content() {
     let expected_n_chunks = expected_streamed_txs.len().div_ceil(STREAMING_CHUNK_SIZE);
     let mut aggregated_streamed_txs = Vec::new();
     for _ in 0..expected_n_chunks {
-        let content = batcher
-            .get_proposal_content(GetProposalContentInput { proposal_id: PROPOSAL_ID })
+        let response = batcher
+            .get_proposal_content(GetProposalContentInput {
+                proposal_id: PROPOSAL_ID,
+                max_txs: None,
+            })
             .await
-            .unwrap()
-            .content;
-        let mut txs = assert_matches!(content, GetProposalContent::Txs(txs) => txs);
+            .unwrap();
+        let mut txs = assert_matches!(response.content, GetProposalContent::Txs(txs) => txs);
         assert!(txs.len() <= STREAMING_CHUNK_SIZE, "{} < {}", txs.len(), STREAMING_CHUNK_SIZE);
+        assert_eq!(response.execution_outcomes.map(|outcomes| outcomes.len()), Some(txs.len()));
         aggregated_streamed_txs.append(&mut txs);
     }
     assert_eq!(aggregated_streamed_txs, expected_streamed_txs);
 
     let commitment = batcher
-        .get_proposal_content(GetProposalContentInput { proposal_id: PROPOSAL_ID })
+        .get_proposal_content(GetProposalContentInput { proposal_id: PROPOSAL_ID, max_txs: None })
         .await
         .unwrap();
     assert_matches!(
         commitment,
         GetProposalContentResponse {
-            content: GetProposalContent::Finished(proposal_commitment)
+            content: GetProposalContent::Finished(proposal_commitment),
+            execution_outcomes: None,
         } if proposal_commitment == expected_proposal_commitment
     );
 
-    let exhausted =
-        batcher.get_proposal_content(GetProposalContentInput { proposal_id: PROPOSAL_ID }).await;
+    let exhausted = batcher
+        .get_proposal_content(GetProposalContentInput { proposal_id: PROPOSAL_ID, max_txs: None })
+        .await;
     assert_matches!(exhausted, Err(BatcherError::ProposalNotFound { .. }));
 }
 
+#[rstest]
+#[case::bounded_by_request(2, 2)]
+#[case::clamped_to_chunk_size(STREAMING_CHUNK_SIZE + 1, STREAMING_CHUNK_SIZE)]
+#[tokio::test]
+async fn get_stream_content_with_max_txs(
+    #[case] max_txs: usize,
+    #[case] expected_max_chunk_size: usize,
+) {
+    const PROPOSAL_ID: ProposalId = ProposalId(0);
+    let expected_streamed_txs = test_txs(0..STREAMING_CHUNK_SIZE * 2 + 1);
+    let txs_to_stream = expected_streamed_txs.clone();
+
+    let mut proposal_manager = MockProposalManagerTraitWrapper::new();
+    proposal_manager.expect_wrap_expired_proposals().returning(|| async { Vec::new() }.boxed());
+    proposal_manager.expect_wrap_build_block_proposal().times(1).return_once(
+        move |_proposal_id, _block_hash, _deadline, tx_sender, _progress_sender, _tx_provider| {
+            simulate_build_block_proposal(tx_sender, txs_to_stream).boxed()
+        },
+    );
+    proposal_manager
+        .expect_wrap_executed_proposal_commitment()
+        .times(1)
+        .return_once(|_| async { Ok(proposal_commitment(7)) }.boxed());
+
+    let mut batcher = batcher(proposal_manager);
+    batcher
+        .build_proposal(BuildProposalInput {
+            proposal_id: PROPOSAL_ID,
+            retrospective_block_hash: None,
+            deadline: chrono::Utc::now() + chrono::Duration::seconds(1),
+        })
+        .await
+        .unwrap();
+    // Let the block builder fill the output buffer, so the responses are bounded by the limit
+    // rather than by the transactions streamed so far.
+    tokio::task::yield_now().await;
+
+    let mut aggregated_streamed_txs = Vec::new();
+    let mut chunk_sizes = Vec::new();
+    loop {
+        let response = batcher
+            .get_proposal_content(GetProposalContentInput {
+                proposal_id: PROPOSAL_ID,
+                max_txs: Some(max_txs),
+            })
+            .await
+            .unwrap();
+        let GetProposalContent::Txs(mut txs) = response.content else {
+            break;
+        };
+        chunk_sizes.push(txs.len());
+        aggregated_streamed_txs.append(&mut txs);
+    }
+    assert_eq!(chunk_sizes.iter().max(), Some(&expected_max_chunk_size));
+    assert_eq!(aggregated_streamed_txs, expected_streamed_txs);
+}
+
+#[rstest]
+#[tokio::test]
+async fn get_proposal_content_unknown_proposal() {
+    const PROPOSAL_ID: ProposalId = ProposalId(0);
+    let mut proposal_manager = MockProposalManagerTraitWrapper::new();
+    proposal_manager.expect_wrap_expired_proposals().returning(|| async { Vec::new() }.boxed());
+    let mut batcher = batcher(proposal_manager);
+
+    let result = batcher
+        .get_proposal_content(GetProposalContentInput { proposal_id: PROPOSAL_ID, max_txs: None })
+        .await;
+    assert_matches!(
+        result,
+        Err(BatcherError::ProposalNotFound { proposal_id }) if proposal_id == PROPOSAL_ID
+    );
+}
+
 #[rstest]
 #[tokio::test]
 async fn decision_reached(
@@ -169,6 +301,7 @@ async fn decision_reached(
                     commitment: expected_proposal_commitment,
                     tx_hashes: tx_hashes_clone,
                     nonces: nonces_clone,
+                    resource_summary: BlockResourceSummary::default(),
                 })
             }
             .boxed()
@@ -176,11 +309,13 @@ async fn decision_reached(
     );
     mempool_client
         .expect_commit_block()
+        .times(1)
         .with(eq(CommitBlockArgs { address_to_nonce, tx_hashes }))
         .returning(|_| Ok(()));
 
     storage_writer
         .expect_commit_proposal()
+        .times(1)
         .with(eq(INITIAL_HEIGHT), eq(expected_state_diff))
         .returning(|_, _| Ok(()));
 
@@ -190,10 +325,538 @@ async fn decision_reached(
         Box::new(storage_writer),
         Arc::new(mempool_client),
         Box::new(proposal_manager),
+        Arc::new(MockTransactionSimulatorTrait::new()),
     );
     batcher.decision_reached(DecisionReachedInput { proposal_id: ProposalId(0) }).await.unwrap();
 }
 
+// A storage that keeps the committed state diffs in memory. Clones share the same state diffs.
+#[derive(Clone, Default)]
+struct InMemoryBatcherStorage {
+    state_diffs: Arc<Mutex<Vec<ThinStateDiff>>>,
+}
+
+impl BatcherStorageReaderTrait for InMemoryBatcherStorage {
+    fn height(&self) -> papyrus_storage::StorageResult<BlockNumber> {
+        Ok(BlockNumber(self.state_diffs.lock().unwrap().len().try_into().unwrap()))
+    }
+}
+
+impl BatcherStorageWriterTrait for InMemoryBatcherStorage {
+    fn commit_proposal(
+        &mut self,
+        height: BlockNumber,
+        state_diff: ThinStateDiff,
+    ) -> papyrus_storage::StorageResult<()> {
+        assert_eq!(height, self.height()?);
+        self.state_diffs.lock().unwrap().push(state_diff);
+        Ok(())
+    }
+}
+
+#[rstest]
+#[tokio::test]
+async fn batcher_with_injected_storage(
+    batcher_config: BatcherConfig,
+    mempool_client: MockMempoolClient,
+) {
+    let storage = InMemoryBatcherStorage::default();
+    let mut batcher = Batcher::with_storage(
+        batcher_config,
+        Arc::new(mempool_client),
+        Arc::new(storage.clone()),
+        Box::new(storage.clone()),
+        Arc::new(MockBlockBuilderFactoryTrait::new()),
+        Arc::new(MockTransactionSimulatorTrait::new()),
+    );
+
+    // The height is read from the injected storage.
+    batcher.start_height(StartHeightInput { height: BlockNumber(0) }).await.unwrap();
+    assert_eq!(
+        batcher.start_height(StartHeightInput { height: BlockNumber(1) }).await,
+        Err(BatcherError::StorageNotSynced {
+            storage_height: BlockNumber(0),
+            requested_height: BlockNumber(1)
+        })
+    );
+
+    // Commits are written to the injected storage.
+    batcher.storage_writer.commit_proposal(BlockNumber(0), ThinStateDiff::default()).unwrap();
+    assert_eq!(*storage.state_diffs.lock().unwrap(), vec![ThinStateDiff::default()]);
+    batcher.start_height(StartHeightInput { height: BlockNumber(1) }).await.unwrap();
+}
+
+#[rstest]
+#[tokio::test(start_paused = true)]
+async fn expired_proposal_releases_proposal_slot(
+    batcher_config: BatcherConfig,
+    mempool_client: MockMempoolClient,
+) {
+    const PROPOSAL_DEADLINE: Duration = Duration::from_secs(1);
+    const GRACE_PERIOD: Duration = Duration::from_secs(1);
+    let build_proposal_input = |proposal_id| BuildProposalInput {
+        proposal_id,
+        deadline: chrono::Utc::now() + PROPOSAL_DEADLINE,
+        retrospective_block_hash: None,
+    };
+
+    // The consumer never completes the first proposal, and its building never finishes after
+    // streaming a transaction.
+    let dropped = Arc::new(AtomicBool::new(false));
+    let dropped_clone = dropped.clone();
+    let mut block_builder_factory = MockBlockBuilderFactoryTrait::new();
+    let mut seq = Sequence::new();
+    block_builder_factory.expect_create_block_builder().times(1).in_sequence(&mut seq).return_once(
+        move |_, _, _, output_content_sender, _, _| {
+            let executed_tx = (test_txs(0..1).remove(0), TransactionExecutionOutcome::default());
+            output_content_sender.unwrap().try_send(executed_tx).unwrap();
+            Ok(Box::new(PendingBlockBuilder { dropped: dropped_clone }))
+        },
+    );
+    block_builder_factory.expect_create_block_builder().times(1).in_sequence(&mut seq).return_once(
+        |_, _, _, _, _, _| {
+            Ok(Box::new(PendingBlockBuilder { dropped: Arc::new(AtomicBool::new(false)) }))
+        },
+    );
+    let storage = InMemoryBatcherStorage::default();
+    let mut batcher = Batcher::with_storage(
+        BatcherConfig { proposal_expiry_grace_period: GRACE_PERIOD, ..batcher_config },
+        Arc::new(mempool_client),
+        Arc::new(storage.clone()),
+        Box::new(storage),
+        Arc::new(block_builder_factory),
+        Arc::new(MockTransactionSimulatorTrait::new()),
+    );
+
+    batcher.start_height(StartHeightInput { height: BlockNumber(0) }).await.unwrap();
+    batcher.build_proposal(build_proposal_input(ProposalId(0))).await.unwrap();
+    assert_matches!(
+        batcher.build_proposal(build_proposal_input(ProposalId(1))).await,
+        Err(BatcherError::ServerBusy { .. })
+    );
+
+    tokio::time::advance(PROPOSAL_DEADLINE + GRACE_PERIOD).await;
+    // The block building task is cancelled, dropping the block builder.
+    tokio::time::timeout(GRACE_PERIOD, async {
+        while !dropped.load(Ordering::SeqCst) {
+            tokio::time::sleep(Duration::from_millis(1)).await;
+        }
+    })
+    .await
+    .expect("The block builder should be dropped.");
+
+    batcher.build_proposal(build_proposal_input(ProposalId(1))).await.unwrap();
+    // The content of the expired proposal is released, although its consumer did not read it.
+    assert_matches!(
+        batcher
+            .get_proposal_content(GetProposalContentInput {
+                proposal_id: ProposalId(0),
+                max_txs: None,
+            })
+            .await,
+        Err(BatcherError::ProposalExpired)
+    );
+}
+
+#[rstest]
+#[tokio::test]
+async fn send_proposal_content_abort() {
+    const PROPOSAL_ID: ProposalId = ProposalId(0);
+    let mut proposal_manager = MockProposalManagerTraitWrapper::new();
+    proposal_manager
+        .expect_wrap_abort_proposal()
+        .times(1)
+        .with(eq(PROPOSAL_ID))
+        .return_once(|_| async {}.boxed());
+
+    let mut batcher = batcher(proposal_manager);
+    let response = batcher
+        .send_proposal_content(SendProposalContentInput {
+            proposal_id: PROPOSAL_ID,
+            content: SendProposalContent::Abort,
+        })
+        .await
+        .unwrap();
+    assert_matches!(response.response, ResponseProposalStatus::InvalidProposal);
+}
+
+#[rstest]
+#[tokio::test]
+async fn send_proposal_content_rejects_foreign_chain_tx(batcher_config: BatcherConfig) {
+    const PROPOSAL_ID: ProposalId = ProposalId(0);
+    let own_chain_id = batcher_config.block_builder_config.chain_info.chain_id.clone();
+    let foreign_chain_id = ChainId::Other("FOREIGN_CHAIN".to_string());
+    let txs =
+        vec![invoke_tx_for_chain(&own_chain_id, 0), invoke_tx_for_chain(&foreign_chain_id, 1)];
+
+    let mut proposal_manager = MockProposalManagerTraitWrapper::new();
+    proposal_manager
+        .expect_wrap_get_proposal_status()
+        .with(eq(PROPOSAL_ID))
+        .return_once(|_| async { ProposalStatus::Processing }.boxed());
+    proposal_manager
+        .expect_wrap_abort_proposal()
+        .times(1)
+        .with(eq(PROPOSAL_ID))
+        .return_once(|_| async {}.boxed());
+
+    let mut batcher = Batcher::new(
+        batcher_config,
+        Arc::new(storage_reader()),
+        Box::new(storage_writer()),
+        Arc::new(mempool_client()),
+        Box::new(proposal_manager),
+        Arc::new(MockTransactionSimulatorTrait::new()),
+    );
+    let response = batcher
+        .send_proposal_content(SendProposalContentInput {
+            proposal_id: PROPOSAL_ID,
+            content: SendProposalContent::Txs(txs),
+        })
+        .await
+        .unwrap();
+    assert_matches!(response.response, ResponseProposalStatus::InvalidProposal);
+}
+
+fn validate_proposal_input(proposal_id: ProposalId) -> ValidateProposalInput {
+    ValidateProposalInput {
+        proposal_id,
+        deadline: chrono::Utc::now() + chrono::Duration::seconds(1),
+        retrospective_block_hash: None,
+    }
+}
+
+fn proposal_commitment(commitment: u128) -> ProposalCommitment {
+    ProposalCommitment {
+        state_diff_commitment: StateDiffCommitment(PoseidonHash(felt!(commitment))),
+    }
+}
+
+#[rstest]
+#[tokio::test]
+async fn validate_proposal_with_tampered_tx_is_invalid(batcher_config: BatcherConfig) {
+    const PROPOSAL_ID: ProposalId = ProposalId(0);
+    let chain_id = batcher_config.block_builder_config.chain_info.chain_id.clone();
+    // A transaction whose content was changed after its hash was computed.
+    let mut tampered_tx =
+        executable_invoke_tx(InvokeTxArgs { nonce: nonce!(1_u8), ..Default::default() });
+    tampered_tx.tx_hash = invoke_tx_for_chain(&chain_id, 0).tx_hash();
+    let tampered_tx = Transaction::Account(AccountTransaction::Invoke(tampered_tx));
+
+    let mut proposal_manager = MockProposalManagerTraitWrapper::new();
+    proposal_manager
+        .expect_wrap_validate_block_proposal()
+        .times(1)
+        .return_once(|_, _, _, _| async { Ok(()) }.boxed());
+    let mut seq = Sequence::new();
+    proposal_manager
+        .expect_wrap_get_proposal_status()
+        .times(1)
+        .in_sequence(&mut seq)
+        .return_once(|_| async { ProposalStatus::Processing }.boxed());
+    proposal_manager
+        .expect_wrap_get_proposal_status()
+        .times(2)
+        .in_sequence(&mut seq)
+        .returning(|_| async { ProposalStatus::Failed }.boxed());
+    proposal_manager
+        .expect_wrap_abort_proposal()
+        .times(1)
+        .with(eq(PROPOSAL_ID))
+        .return_once(|_| async {}.boxed());
+    // The proposal is rejected without waiting for its re-execution.
+    proposal_manager.expect_wrap_executed_proposal_commitment().times(0);
+
+    let mut batcher = Batcher::new(
+        batcher_config,
+        Arc::new(storage_reader()),
+        Box::new(storage_writer()),
+        Arc::new(mempool_client()),
+        Box::new(proposal_manager),
+        Arc::new(MockTransactionSimulatorTrait::new()),
+    );
+    batcher.validate_proposal(validate_proposal_input(PROPOSAL_ID)).await.unwrap();
+
+    // Once found invalid, the remaining content of the proposal is ignored.
+    for content in [
+        SendProposalContent::Txs(vec![tampered_tx]),
+        SendProposalContent::Txs(vec![invoke_tx_for_chain(&chain_id, 2)]),
+        SendProposalContent::Finish(Some(proposal_commitment(7))),
+    ] {
+        let response = batcher
+            .send_proposal_content(SendProposalContentInput { proposal_id: PROPOSAL_ID, content })
+            .await
+            .unwrap();
+        assert_eq!(response.response, ResponseProposalStatus::InvalidProposal);
+    }
+}
+
+#[rstest]
+#[case::no_expected_commitment(None, ResponseProposalStatus::Finished(proposal_commitment(7)))]
+#[case::matching_commitment(
+    Some(proposal_commitment(7)),
+    ResponseProposalStatus::Finished(proposal_commitment(7))
+)]
+#[case::mismatching_commitment(
+    Some(proposal_commitment(8)),
+    ResponseProposalStatus::InvalidProposal
+)]
+#[tokio::test]
+async fn validate_proposal_compares_commitment_on_finish(
+    #[case] expected_commitment: Option<ProposalCommitment>,
+    #[case] expected_response: ResponseProposalStatus,
+) {
+    const PROPOSAL_ID: ProposalId = ProposalId(0);
+    let is_invalid = expected_response == ResponseProposalStatus::InvalidProposal;
+
+    let mut proposal_manager = MockProposalManagerTraitWrapper::new();
+    proposal_manager
+        .expect_wrap_validate_block_proposal()
+        .times(1)
+        .return_once(|_, _, _, _| async { Ok(()) }.boxed());
+    proposal_manager
+        .expect_wrap_get_proposal_status()
+        .with(eq(PROPOSAL_ID))
+        .returning(|_| async { ProposalStatus::Processing }.boxed());
+    proposal_manager
+        .expect_wrap_executed_proposal_commitment()
+        .times(1)
+        .return_once(|_| async { Ok(proposal_commitment(7)) }.boxed());
+    // A mismatching proposal is discarded, so it cannot be committed.
+    proposal_manager
+        .expect_wrap_abort_proposal()
+        .times(usize::from(is_invalid))
+        .with(eq(PROPOSAL_ID))
+        .returning(|_| async {}.boxed());
+
+    let mut batcher = batcher(proposal_manager);
+    batcher.validate_proposal(validate_proposal_input(PROPOSAL_ID)).await.unwrap();
+    let response = batcher
+        .send_proposal_content(SendProposalContentInput {
+            proposal_id: PROPOSAL_ID,
+            content: SendProposalContent::Finish(expected_commitment),
+        })
+        .await
+        .unwrap();
+    assert_eq!(response.response, expected_response);
+}
+
+#[rstest]
+#[tokio::test]
+async fn decision_reached_commits_built_proposal_to_storage(
+    batcher_config: BatcherConfig,
+    mut mempool_client: MockMempoolClient,
+) {
+    const PROPOSAL_ID: ProposalId = ProposalId(0);
+    let expected_output =
+        ProposalOutput::try_from(BlockExecutionArtifacts::create_for_testing()).unwrap();
+    let mut block_builder_factory = MockBlockBuilderFactoryTrait::new();
+    block_builder_factory.expect_create_block_builder().times(1).return_once(|_, _, _, _, _, _| {
+        let mut block_builder = MockBlockBuilderTrait::new();
+        block_builder
+            .expect_build_block()
+            .return_once(|| Ok(BlockExecutionArtifacts::create_for_testing()));
+        Ok(Box::new(block_builder))
+    });
+    mempool_client.expect_commit_block().times(1).returning(|_| Ok(()));
+    let storage = InMemoryBatcherStorage::default();
+    let mut batcher = Batcher::with_storage(
+        batcher_config,
+        Arc::new(mempool_client),
+        Arc::new(storage.clone()),
+        Box::new(storage.clone()),
+        Arc::new(block_builder_factory),
+        Arc::new(MockTransactionSimulatorTrait::new()),
+    );
+
+    batcher.start_height(StartHeightInput { height: BlockNumber(0) }).await.unwrap();
+    batcher
+        .build_proposal(BuildProposalInput {
+            proposal_id: PROPOSAL_ID,
+            retrospective_block_hash: None,
+            deadline: chrono::Utc::now() + chrono::Duration::seconds(1),
+        })
+        .await
+        .unwrap();
+    let content = batcher
+        .get_proposal_content(GetProposalContentInput { proposal_id: PROPOSAL_ID, max_txs: None })
+        .await
+        .unwrap()
+        .content;
+    assert_matches!(
+        content,
+        GetProposalContent::Finished(commitment) if commitment == expected_output.commitment
+    );
+
+    batcher.decision_reached(DecisionReachedInput { proposal_id: PROPOSAL_ID }).await.unwrap();
+
+    // The block is stored at the proposal's height, and the storage moved to the next height.
+    assert_eq!(*storage.state_diffs.lock().unwrap(), vec![expected_output.state_diff]);
+    assert_eq!(storage.height().unwrap(), BlockNumber(1));
+    // The decided proposal can not be committed again.
+    assert_eq!(
+        batcher.decision_reached(DecisionReachedInput { proposal_id: PROPOSAL_ID }).await,
+        Err(BatcherError::ExecutedProposalNotFound { proposal_id: PROPOSAL_ID })
+    );
+}
+
+#[rstest]
+#[tokio::test]
+async fn get_block_resource_summary_of_committed_block(
+    batcher_config: BatcherConfig,
+    mut mempool_client: MockMempoolClient,
+) {
+    const PROPOSAL_ID: ProposalId = ProposalId(0);
+    const N_TXS: usize = 3;
+    let tx_da_gas = GasVector { l1_data_gas: GasAmount(10), ..GasVector::ZERO };
+    let bouncer_weights = BouncerWeights { n_steps: 100, ..BouncerWeights::empty() };
+    let execution_infos: IndexMap<_, _> = (0..N_TXS)
+        .map(|i| {
+            let execution_info = TransactionExecutionInfo {
+                receipt: TransactionReceipt { da_gas: tx_da_gas, ..Default::default() },
+                ..Default::default()
+            };
+            (TransactionHash(felt!(u8::try_from(i).unwrap())), execution_info)
+        })
+        .collect();
+    let mut block_builder_factory = MockBlockBuilderFactoryTrait::new();
+    block_builder_factory.expect_create_block_builder().times(1).return_once(
+        move |_, _, _, _, _, _| {
+            let mut block_builder = MockBlockBuilderTrait::new();
+            block_builder.expect_build_block().return_once(move || {
+                Ok(BlockExecutionArtifacts {
+                    execution_infos,
+                    bouncer_weights,
+                    ..BlockExecutionArtifacts::create_for_testing()
+                })
+            });
+            Ok(Box::new(block_builder))
+        },
+    );
+    mempool_client.expect_commit_block().times(1).returning(|_| Ok(()));
+    let storage = InMemoryBatcherStorage::default();
+    let mut batcher = Batcher::with_storage(
+        batcher_config,
+        Arc::new(mempool_client),
+        Arc::new(storage.clone()),
+        Box::new(storage),
+        Arc::new(block_builder_factory),
+        Arc::new(MockTransactionSimulatorTrait::new()),
+    );
+
+    batcher.start_height(StartHeightInput { height: BlockNumber(0) }).await.unwrap();
+    batcher
+        .build_proposal(BuildProposalInput {
+            proposal_id: PROPOSAL_ID,
+            retrospective_block_hash: None,
+            deadline: chrono::Utc::now() + chrono::Duration::seconds(1),
+        })
+        .await
+        .unwrap();
+    batcher
+        .get_proposal_content(GetProposalContentInput { proposal_id: PROPOSAL_ID, max_txs: None })
+        .await
+        .unwrap();
+    batcher.decision_reached(DecisionReachedInput { proposal_id: PROPOSAL_ID }).await.unwrap();
+
+    let summary = batcher
+        .get_block_resource_summary(GetBlockResourceSummaryInput { block_number: BlockNumber(0) })
+        .unwrap();
+    assert_eq!(
+        summary,
+        BlockResourceSummary {
+            n_txs: N_TXS,
+            weights: BlockWeights { n_steps: 100, ..Default::default() },
+            da_gas: GasVector { l1_data_gas: GasAmount(30), ..GasVector::ZERO },
+        }
+    );
+    assert_eq!(
+        batcher.get_block_resource_summary(GetBlockResourceSummaryInput {
+            block_number: BlockNumber(1)
+        }),
+        Err(BatcherError::BlockResourceSummaryNotFound { block_number: BlockNumber(1) })
+    );
+}
+
+#[rstest]
+#[tokio::test]
+async fn get_state_diff_chunks_of_decided_proposal(
+    mut storage_writer: MockBatcherStorageWriterTrait,
+    mut mempool_client: MockMempoolClient,
+) {
+    const PROPOSAL_ID: ProposalId = ProposalId(0);
+    const MAX_STATE_DIFF_CHUNK_SIZE: usize = 2;
+
+    let mut proposal_manager = MockProposalManagerTraitWrapper::new();
+    proposal_manager.expect_wrap_take_proposal_result().times(1).with(eq(PROPOSAL_ID)).return_once(
+        |_| {
+            async move {
+                Ok(ProposalOutput {
+                    state_diff: test_state_diff(),
+                    commitment: ProposalCommitment::default(),
+                    tx_hashes: HashSet::new(),
+                    nonces: HashMap::new(),
+                    resource_summary: BlockResourceSummary::default(),
+                })
+            }
+            .boxed()
+        },
+    );
+    mempool_client.expect_commit_block().times(1).returning(|_| Ok(()));
+    storage_writer.expect_commit_proposal().times(1).returning(|_, _| Ok(()));
+
+    let mut batcher = Batcher::new(
+        BatcherConfig { max_state_diff_chunk_size: MAX_STATE_DIFF_CHUNK_SIZE, ..batcher_config() },
+        Arc::new(storage_reader()),
+        Box::new(storage_writer),
+        Arc::new(mempool_client),
+        Box::new(proposal_manager),
+        Arc::new(MockTransactionSimulatorTrait::new()),
+    );
+    batcher.decision_reached(DecisionReachedInput { proposal_id: PROPOSAL_ID }).await.unwrap();
+
+    let chunks = batcher
+        .get_state_diff_chunks(GetStateDiffChunksInput { proposal_id: PROPOSAL_ID })
+        .await
+        .unwrap()
+        .chunks;
+    assert!(chunks.iter().all(|chunk| chunk.len() <= MAX_STATE_DIFF_CHUNK_SIZE));
+    assert_eq!(unite_state_diff_chunks(chunks), test_state_diff());
+}
+
+#[rstest]
+#[case::unknown(
+    ProposalStatus::NotFound,
+    BatcherError::ProposalNotFound { proposal_id: ProposalId(0) }
+)]
+#[case::processing(
+    ProposalStatus::Processing,
+    BatcherError::ProposalNotDecided { proposal_id: ProposalId(0) }
+)]
+#[case::executed(
+    ProposalStatus::Finished,
+    BatcherError::ProposalNotDecided { proposal_id: ProposalId(0) }
+)]
+#[tokio::test]
+async fn get_state_diff_chunks_of_undecided_proposal(
+    #[case] proposal_status: ProposalStatus,
+    #[case] expected_error: BatcherError,
+) {
+    const PROPOSAL_ID: ProposalId = ProposalId(0);
+    let mut proposal_manager = MockProposalManagerTraitWrapper::new();
+    proposal_manager
+        .expect_wrap_get_proposal_status()
+        .times(1)
+        .with(eq(PROPOSAL_ID))
+        .return_once(move |_| async move { proposal_status }.boxed());
+
+    let batcher = batcher(proposal_manager);
+    assert_eq!(
+        batcher.get_state_diff_chunks(GetStateDiffChunksInput { proposal_id: PROPOSAL_ID }).await,
+        Err(expected_error)
+    );
+}
+
 #[rstest]
 #[tokio::test]
 async fn decision_reached_no_executed_proposal() {
@@ -213,13 +876,181 @@ async fn decision_reached_no_executed_proposal() {
     assert_eq!(decision_reached_result, Err(expected_error));
 }
 
+fn simulation_response() -> SimulateTransactionResponse {
+    SimulateTransactionResponse { actual_fee: Fee(100), ..Default::default() }
+}
+
+#[rstest]
+#[tokio::test]
+async fn simulate_transaction_on_committed_state() {
+    let tx = test_txs(0..1).remove(0);
+    let flags = SimulationFlags { charge_fee: true, ..Default::default() };
+    let mut transaction_simulator = MockTransactionSimulatorTrait::new();
+    transaction_simulator
+        .expect_simulate_transaction()
+        .times(1)
+        .with(eq(INITIAL_HEIGHT), eq(tx.clone()), eq(flags))
+        .return_once(|_, _, _| Ok(simulation_response()));
+
+    let batcher = Batcher::new(
+        batcher_config(),
+        Arc::new(storage_reader()),
+        Box::new(storage_writer()),
+        Arc::new(mempool_client()),
+        Box::new(MockProposalManagerTraitWrapper::new()),
+        Arc::new(transaction_simulator),
+    );
+    let response = batcher.simulate_transaction(SimulateTransactionInput { tx, flags }).await;
+    assert_eq!(response, Ok(simulation_response()));
+}
+
+#[rstest]
+#[tokio::test]
+async fn simulate_transaction_failure() {
+    let tx = test_txs(0..1).remove(0);
+    let tx_hash = tx.tx_hash();
+    let mut transaction_simulator = MockTransactionSimulatorTrait::new();
+    transaction_simulator
+        .expect_simulate_transaction()
+        .return_once(|_, _, _| Err(BlockBuilderError::Aborted));
+
+    let batcher = Batcher::new(
+        batcher_config(),
+        Arc::new(storage_reader()),
+        Box::new(storage_writer()),
+        Arc::new(mempool_client()),
+        Box::new(MockProposalManagerTraitWrapper::new()),
+        Arc::new(transaction_simulator),
+    );
+    let response = batcher
+        .simulate_transaction(SimulateTransactionInput { tx, flags: SimulationFlags::default() })
+        .await;
+    assert_eq!(
+        response,
+        Err(BatcherError::SimulationFailed {
+            tx_hash,
+            error: BlockBuilderError::Aborted.to_string()
+        })
+    );
+}
+
+#[tokio::test]
+async fn handle_request_records_metrics() {
+    const PROPOSAL_ID: ProposalId = ProposalId(0);
+    let handle = PrometheusBuilder::new().install_recorder().unwrap();
+    let past_deadline = chrono::Utc::now() - chrono::Duration::seconds(1);
+
+    // A stub batcher, under which every request but starting a height and aborting a proposal
+    // fails.
+    let mut proposal_manager = MockProposalManagerTraitWrapper::new();
+    proposal_manager.expect_wrap_start_height().return_once(|_| async { Ok(()) }.boxed());
+    proposal_manager.expect_wrap_abort_proposal().return_once(|_| async {}.boxed());
+    proposal_manager.expect_wrap_take_proposal_result().return_once(|proposal_id| {
+        async move { Err(GetProposalResultError::ProposalDoesNotExist { proposal_id }) }.boxed()
+    });
+    proposal_manager
+        .expect_wrap_get_proposal_status()
+        .returning(|_| async { ProposalStatus::NotFound }.boxed());
+    let mut transaction_simulator = MockTransactionSimulatorTrait::new();
+    transaction_simulator
+        .expect_simulate_transaction()
+        .return_once(|_, _, _| Err(BlockBuilderError::Aborted));
+    let mut batcher = Batcher::new(
+        batcher_config(),
+        Arc::new(storage_reader()),
+        Box::new(storage_writer()),
+        Arc::new(mempool_client()),
+        Box::new(proposal_manager),
+        Arc::new(transaction_simulator),
+    );
+
+    let requests = [
+        (
+            "BuildProposal",
+            BatcherRequest::BuildProposal(BuildProposalInput {
+                proposal_id: PROPOSAL_ID,
+                deadline: past_deadline,
+                retrospective_block_hash: None,
+            }),
+        ),
+        (
+            "GetProposalContent",
+            BatcherRequest::GetProposalContent(GetProposalContentInput {
+                proposal_id: PROPOSAL_ID,
+                max_txs: None,
+            }),
+        ),
+        (
+            "ValidateProposal",
+            BatcherRequest::ValidateProposal(ValidateProposalInput {
+                deadline: past_deadline,
+                ..validate_proposal_input(PROPOSAL_ID)
+            }),
+        ),
+        (
+            "SendProposalContent",
+            BatcherRequest::SendProposalContent(SendProposalContentInput {
+                proposal_id: PROPOSAL_ID,
+                content: SendProposalContent::Abort,
+            }),
+        ),
+        ("StartHeight", BatcherRequest::StartHeight(StartHeightInput { height: INITIAL_HEIGHT })),
+        (
+            "DecisionReached",
+            BatcherRequest::DecisionReached(DecisionReachedInput { proposal_id: PROPOSAL_ID }),
+        ),
+        (
+            "GetStateDiffChunks",
+            BatcherRequest::GetStateDiffChunks(GetStateDiffChunksInput {
+                proposal_id: PROPOSAL_ID,
+            }),
+        ),
+        (
+            "SimulateTransaction",
+            BatcherRequest::SimulateTransaction(SimulateTransactionInput {
+                tx: test_txs(0..1).remove(0),
+                flags: SimulationFlags::default(),
+            }),
+        ),
+        (
+            "GetBlockResourceSummary",
+            BatcherRequest::GetBlockResourceSummary(GetBlockResourceSummaryInput {
+                block_number: INITIAL_HEIGHT,
+            }),
+        ),
+    ];
+
+    for (request_type, request) in requests {
+        let response = batcher.handle_request(request).await;
+        let is_success = matches!(
+            response,
+            BatcherResponse::StartHeight(Ok(())) | BatcherResponse::SendProposalContent(Ok(_))
+        );
+
+        let labels = [(REQUEST_LABEL, request_type)];
+        // The latency histogram is rendered as a summary, whose count is an untyped sample.
+        let latency_count_metric = format!("{BATCHER_REQUEST_LATENCY_SECONDS}_count");
+        assert_eq!(
+            prometheus_is_contained(handle.render(), &latency_count_metric, &labels),
+            Some(Value::Untyped(1.0)),
+            "Latency of {request_type} was not recorded."
+        );
+        let expected_errors = if is_success { None } else { Some(Value::Counter(1.0)) };
+        assert_eq!(
+            prometheus_is_contained(handle.render(), BATCHER_REQUEST_ERRORS_TOTAL, &labels),
+            expected_errors,
+            "Unexpected errors count of {request_type}."
+        );
+    }
+}
+
 async fn simulate_build_block_proposal(
-    tx_sender: tokio::sync::mpsc::UnboundedSender<Transaction>,
+    tx_sender: OutputContentSender,
     txs: Vec<Transaction>,
 ) -> Result<(), GenerateProposalError> {
     tokio::spawn(async move {
         for tx in txs {
-            tx_sender.send(tx).unwrap();
+            tx_sender.send((tx, TransactionExecutionOutcome::default())).await.unwrap();
         }
     });
     Ok(())
@@ -238,7 +1069,8 @@ trait ProposalManagerTraitWrapper: Send + Sync {
         proposal_id: ProposalId,
         retrospective_block_hash: Option<BlockHashAndNumber>,
         deadline: tokio::time::Instant,
-        output_content_sender: tokio::sync::mpsc::UnboundedSender<Transaction>,
+        output_content_sender: OutputContentSender,
+        proposal_progress_sender: Option<ProposalProgressSender>,
         tx_provider: ProposeTransactionProvider,
     ) -> BoxFuture<'_, Result<(), GenerateProposalError>>;
 
@@ -257,6 +1089,8 @@ trait ProposalManagerTraitWrapper: Send + Sync {
 
     fn wrap_get_proposal_status(&self, proposal_id: ProposalId) -> BoxFuture<'_, ProposalStatus>;
 
+    fn wrap_expired_proposals(&self) -> BoxFuture<'_, Vec<ProposalId>>;
+
     fn wrap_executed_proposal_commitment(
         &self,
         proposal_id: ProposalId,
@@ -276,7 +1110,8 @@ impl<T: ProposalManagerTraitWrapper> ProposalManagerTrait for T {
         proposal_id: ProposalId,
         retrospective_block_hash: Option<BlockHashAndNumber>,
         deadline: tokio::time::Instant,
-        output_content_sender: tokio::sync::mpsc::UnboundedSender<Transaction>,
+        output_content_sender: OutputContentSender,
+        proposal_progress_sender: Option<ProposalProgressSender>,
         tx_provider: ProposeTransactionProvider,
     ) -> Result<(), GenerateProposalError> {
         self.wrap_build_block_proposal(
@@ -284,6 +1119,7 @@ impl<T: ProposalManagerTraitWrapper> ProposalManagerTrait for T {
             retrospective_block_hash,
             deadline,
             output_content_sender,
+            proposal_progress_sender,
             tx_provider,
         )
         .await
@@ -316,6 +1152,10 @@ impl<T: ProposalManagerTraitWrapper> ProposalManagerTrait for T {
         self.wrap_get_proposal_status(proposal_id).await
     }
 
+    async fn expired_proposals(&self) -> Vec<ProposalId> {
+        self.wrap_expired_proposals().await
+    }
+
     async fn await_proposal_commitment(
         &mut self,
         proposal_id: ProposalId,
@@ -328,6 +1168,13 @@ impl<T: ProposalManagerTraitWrapper> ProposalManagerTrait for T {
     }
 }
 
+// An invoke transaction whose hash is calculated for the given chain.
+fn invoke_tx_for_chain(chain_id: &ChainId, nonce: u128) -> Transaction {
+    let mut tx = executable_invoke_tx(InvokeTxArgs { nonce: nonce!(nonce), ..Default::default() });
+    tx.tx_hash = tx.tx.calculate_transaction_hash(chain_id, &tx.tx.version()).unwrap();
+    Transaction::Account(AccountTransaction::Invoke(tx))
+}
+
 fn test_tx_hashes(range: std::ops::Range<u128>) -> HashSet<TransactionHash> {
     range.map(|i| TransactionHash(felt!(i))).collect()
 }
@@ -1,4 +1,5 @@
 use std::collections::BTreeMap;
+use std::sync::Arc;
 
 use async_trait::async_trait;
 use blockifier::blockifier::block::{BlockInfo, GasPrices};
@@ -11,7 +12,7 @@ use blockifier::blockifier::transaction_executor::{
 };
 use blockifier::bouncer::{BouncerConfig, BouncerWeights};
 use blockifier::context::{BlockContext, ChainInfo};
-use blockifier::state::cached_state::CommitmentStateDiff;
+use blockifier::state::cached_state::{CachedState, CommitmentStateDiff};
 use blockifier::state::errors::StateError;
 use blockifier::state::global_cache::GlobalContractCache;
 use blockifier::transaction::errors::TransactionExecutionError as BlockifierTransactionExecutionError;
@@ -29,13 +30,24 @@ use serde::{Deserialize, Serialize};
 use starknet_api::block::{BlockHashAndNumber, BlockNumber, BlockTimestamp, NonzeroGasPrice};
 use starknet_api::core::ContractAddress;
 use starknet_api::executable_transaction::Transaction;
-use starknet_api::transaction::TransactionHash;
+use starknet_api::transaction::{
+    RevertedTransactionExecutionStatus,
+    TransactionExecutionStatus,
+    TransactionHash,
+};
+use starknet_batcher_types::batcher_types::{
+    SimulateTransactionResponse,
+    SimulationFlags,
+    TransactionExecutionOutcome,
+};
 use thiserror::Error;
+use tokio::sync::mpsc::error::{SendError, TrySendError};
 use tokio::sync::Mutex;
 use tracing::{debug, error, info, trace};
 
 use crate::transaction_executor::TransactionExecutorTrait;
 use crate::transaction_provider::{NextTxs, TransactionProvider, TransactionProviderError};
+use crate::transaction_simulator::{simulate_transaction, TransactionSimulatorTrait};
 
 #[derive(Debug, Error)]
 pub enum BlockBuilderError {
@@ -50,11 +62,15 @@ pub enum BlockBuilderError {
     #[error(transparent)]
     TransactionExecutionError(#[from] BlockifierTransactionExecutionError),
     #[error(transparent)]
-    StreamTransactionsError(#[from] tokio::sync::mpsc::error::SendError<Transaction>),
+    StreamTransactionsError(#[from] SendError<ExecutedTransaction>),
     #[error("Build block with fail_on_err mode, failed on error {}.", _0)]
     FailOnError(BlockifierTransactionExecutorError),
     #[error("The block builder was aborted.")]
     Aborted,
+    #[error("The output content buffer is full.")]
+    OutputContentBufferFull,
+    #[error("The data availability gas of the block overflowed.")]
+    DaGasOverflow,
 }
 
 pub type BlockBuilderResult<T> = Result<T, BlockBuilderError>;
@@ -68,6 +84,23 @@ pub struct BlockExecutionArtifacts {
     pub bouncer_weights: BouncerWeights,
 }
 
+/// A snapshot of the progress of a block being built, emitted after every processed transaction
+/// chunk.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ProposalProgress {
+    pub n_txs_included: usize,
+    pub n_steps: usize,
+    pub elapsed: tokio::time::Duration,
+    pub time_to_deadline: tokio::time::Duration,
+}
+
+pub type ProposalProgressSender = tokio::sync::mpsc::UnboundedSender<ProposalProgress>;
+
+/// A transaction added to the block, along with the outcome of its execution.
+pub type ExecutedTransaction = (Transaction, TransactionExecutionOutcome);
+
+pub type OutputContentSender = tokio::sync::mpsc::Sender<ExecutedTransaction>;
+
 /// The BlockBuilderTrait is responsible for building a new block from transactions provided by the
 /// tx_provider. The block building will stop at time deadline.
 /// The transactions that were added to the block will be streamed to the output_content_sender.
@@ -77,16 +110,30 @@ pub trait BlockBuilderTrait: Send {
     async fn build_block(&mut self) -> BlockBuilderResult<BlockExecutionArtifacts>;
 }
 
+/// What the block builder does when the consumer of the streamed transactions falls behind and the
+/// output content buffer is full.
+#[derive(Clone, Copy, Debug, Default, Deserialize, PartialEq, Serialize)]
+pub enum OutputContentBufferFullPolicy {
+    /// Wait for the consumer to make room, pausing block building meanwhile. The block building
+    /// fails if the buffer is still full at the deadline, and stops if it is aborted while waiting.
+    #[default]
+    Block,
+    /// Fail the block building, so the proposal ends and frees its slot.
+    Drop,
+}
+
 pub struct BlockBuilderExecutionParams {
     pub deadline: tokio::time::Instant,
     pub fail_on_err: bool,
+    pub output_content_buffer_full_policy: OutputContentBufferFullPolicy,
 }
 
 pub struct BlockBuilder {
     // TODO(Yael 14/10/2024): make the executor thread safe and delete this mutex.
     executor: Mutex<Box<dyn TransactionExecutorTrait>>,
     tx_provider: Box<dyn TransactionProvider>,
-    output_content_sender: Option<tokio::sync::mpsc::UnboundedSender<Transaction>>,
+    output_content_sender: Option<OutputContentSender>,
+    proposal_progress_sender: Option<ProposalProgressSender>,
     abort_signal_receiver: tokio::sync::oneshot::Receiver<()>,
 
     // Parameters to configure the block builder behavior.
@@ -98,7 +145,8 @@ impl BlockBuilder {
     pub fn new(
         executor: Box<dyn TransactionExecutorTrait>,
         tx_provider: Box<dyn TransactionProvider>,
-        output_content_sender: Option<tokio::sync::mpsc::UnboundedSender<Transaction>>,
+        output_content_sender: Option<OutputContentSender>,
+        proposal_progress_sender: Option<ProposalProgressSender>,
         abort_signal_receiver: tokio::sync::oneshot::Receiver<()>,
         tx_chunk_size: usize,
         execution_params: BlockBuilderExecutionParams,
@@ -107,6 +155,7 @@ impl BlockBuilder {
             executor: Mutex::new(executor),
             tx_provider,
             output_content_sender,
+            proposal_progress_sender,
             abort_signal_receiver,
             tx_chunk_size,
             execution_params,
@@ -119,6 +168,8 @@ impl BlockBuilderTrait for BlockBuilder {
     async fn build_block(&mut self) -> BlockBuilderResult<BlockExecutionArtifacts> {
         let mut block_is_full = false;
         let mut execution_infos = IndexMap::new();
+        let start_time = tokio::time::Instant::now();
+        let mut n_steps = 0;
         // TODO(yael 6/10/2024): delete the timeout condition once the executor has a timeout
         while !block_is_full && tokio::time::Instant::now() < self.execution_params.deadline {
             if self.abort_signal_receiver.try_recv().is_ok() {
@@ -144,14 +195,39 @@ impl BlockBuilderTrait for BlockBuilder {
             }
             let results = self.executor.lock().await.add_txs_to_block(&executor_input_chunk);
             trace!("Transaction execution results: {:?}", results);
+            let n_txs_before_chunk = execution_infos.len();
             block_is_full = collect_execution_results_and_stream_txs(
                 next_tx_chunk,
                 results,
                 &mut execution_infos,
                 &self.output_content_sender,
-                self.execution_params.fail_on_err,
+                &mut self.abort_signal_receiver,
+                &self.execution_params,
             )
             .await?;
+
+            if let Some(proposal_progress_sender) = &self.proposal_progress_sender {
+                n_steps += execution_infos
+                    .values()
+                    .skip(n_txs_before_chunk)
+                    .map(|execution_info| {
+                        execution_info.receipt.resources.computation.vm_resources.n_steps
+                    })
+                    .sum::<usize>();
+                let progress = ProposalProgress {
+                    n_txs_included: execution_infos.len(),
+                    n_steps,
+                    elapsed: start_time.elapsed(),
+                    time_to_deadline: self
+                        .execution_params
+                        .deadline
+                        .saturating_duration_since(tokio::time::Instant::now()),
+                };
+                // Progress is best effort; a dropped receiver should not fail the block.
+                if proposal_progress_sender.send(progress).is_err() {
+                    debug!("Proposal progress receiver was dropped.");
+                }
+            }
         }
         let (commitment_state_diff, visited_segments_mapping, bouncer_weights) =
             self.executor.lock().await.close_block()?;
@@ -169,15 +245,37 @@ async fn collect_execution_results_and_stream_txs(
     tx_chunk: Vec<Transaction>,
     results: Vec<TransactionExecutorResult<TransactionExecutionInfo>>,
     execution_infos: &mut IndexMap<TransactionHash, TransactionExecutionInfo>,
-    output_content_sender: &Option<tokio::sync::mpsc::UnboundedSender<Transaction>>,
-    fail_on_err: bool,
+    output_content_sender: &Option<OutputContentSender>,
+    abort_signal_receiver: &mut tokio::sync::oneshot::Receiver<()>,
+    execution_params: &BlockBuilderExecutionParams,
 ) -> BlockBuilderResult<bool> {
+    let fail_on_err = execution_params.fail_on_err;
     for (input_tx, result) in tx_chunk.into_iter().zip(results.into_iter()) {
         match result {
             Ok(tx_execution_info) => {
+                let execution_outcome = execution_outcome(&tx_execution_info);
                 execution_infos.insert(input_tx.tx_hash(), tx_execution_info);
                 if let Some(output_content_sender) = output_content_sender {
-                    output_content_sender.send(input_tx)?;
+                    let executed_tx = (input_tx, execution_outcome);
+                    match execution_params.output_content_buffer_full_policy {
+                        OutputContentBufferFullPolicy::Block => tokio::select! {
+                            result = output_content_sender.send(executed_tx) => result?,
+                            _ = tokio::time::sleep_until(execution_params.deadline) => {
+                                info!("Output content buffer is still full at the deadline.");
+                                return Err(BlockBuilderError::OutputContentBufferFull);
+                            }
+                            Ok(()) = &mut *abort_signal_receiver => {
+                                info!("Received abort signal. Aborting block builder.");
+                                return Err(BlockBuilderError::Aborted);
+                            }
+                        },
+                        OutputContentBufferFullPolicy::Drop => output_content_sender
+                            .try_send(executed_tx)
+                            .map_err(|err| match err {
+                                TrySendError::Full(_) => BlockBuilderError::OutputContentBufferFull,
+                                TrySendError::Closed(tx) => SendError(tx).into(),
+                            })?,
+                    }
                 }
             }
             // TODO(yael 18/9/2024): add timeout error handling here once this
@@ -197,6 +295,20 @@ async fn collect_execution_results_and_stream_txs(
     Ok(false)
 }
 
+pub(crate) fn execution_outcome(
+    tx_execution_info: &TransactionExecutionInfo,
+) -> TransactionExecutionOutcome {
+    let execution_status = match &tx_execution_info.revert_error {
+        Some(revert_error) => {
+            TransactionExecutionStatus::Reverted(RevertedTransactionExecutionStatus {
+                revert_reason: revert_error.to_string(),
+            })
+        }
+        None => TransactionExecutionStatus::Succeeded,
+    };
+    TransactionExecutionOutcome { execution_status, gas_consumed: tx_execution_info.receipt.gas }
+}
+
 pub struct BlockMetadata {
     pub height: BlockNumber,
     pub retrospective_block_hash: Option<BlockHashAndNumber>,
@@ -210,7 +322,8 @@ pub trait BlockBuilderFactoryTrait {
         block_metadata: BlockMetadata,
         execution_params: BlockBuilderExecutionParams,
         tx_provider: Box<dyn TransactionProvider>,
-        output_content_sender: Option<tokio::sync::mpsc::UnboundedSender<Transaction>>,
+        output_content_sender: Option<OutputContentSender>,
+        proposal_progress_sender: Option<ProposalProgressSender>,
         abort_signal_receiver: tokio::sync::oneshot::Receiver<()>,
     ) -> BlockBuilderResult<Box<dyn BlockBuilderTrait>>;
 }
@@ -275,18 +388,18 @@ impl SerializeConfig for BlockBuilderConfig {
 
 pub struct BlockBuilderFactory {
     pub block_builder_config: BlockBuilderConfig,
+    // Shared with the gateway, so that transactions are validated and executed under the same
+    // versioned constants.
+    pub versioned_constants: Arc<VersionedConstants>,
     pub storage_reader: StorageReader,
     pub global_class_hash_to_class: GlobalContractCache,
 }
 
 impl BlockBuilderFactory {
-    fn preprocess_and_create_transaction_executor(
-        &self,
-        block_metadata: &BlockMetadata,
-    ) -> BlockBuilderResult<TransactionExecutor<PapyrusReader>> {
+    fn block_context(&self, height: BlockNumber) -> BlockBuilderResult<BlockContext> {
         let block_builder_config = self.block_builder_config.clone();
         let next_block_info = BlockInfo {
-            block_number: block_metadata.height,
+            block_number: height,
             block_timestamp: BlockTimestamp(chrono::Utc::now().timestamp().try_into()?),
             sequencer_address: block_builder_config.sequencer_address,
             // TODO (yael 7/10/2024): add logic to compute gas prices
@@ -296,33 +409,42 @@ impl BlockBuilderFactory {
             },
             use_kzg_da: block_builder_config.use_kzg_da,
         };
-        let versioned_constants = VersionedConstants::get_versioned_constants(
-            block_builder_config.versioned_constants_overrides,
-        );
         let block_context = BlockContext::new(
             next_block_info,
             block_builder_config.chain_info,
-            versioned_constants,
+            VersionedConstants::clone(&self.versioned_constants),
             block_builder_config.bouncer_config,
         );
+        Ok(block_context)
+    }
 
-        // TODO(Yael: 8/9/2024) Need to reconsider which StateReader to use. the papyrus execution
-        // state reader does not implement the Sync trait since it is using cell so I used
-        // the blockifier state reader instead. Also the blockifier reader is implementing a global
-        // cache.
-        let state_reader = PapyrusReader::new(
+    fn state_reader(&self, height: BlockNumber) -> PapyrusReader {
+        PapyrusReader::new(
             self.storage_reader.clone(),
-            block_metadata.height,
+            height,
             // TODO(Yael 18/9/2024): dont forget to flush the cached_state cache into the global
             // cache on decision_reached.
             self.global_class_hash_to_class.clone(),
-        );
+        )
+    }
+
+    fn preprocess_and_create_transaction_executor(
+        &self,
+        block_metadata: &BlockMetadata,
+    ) -> BlockBuilderResult<TransactionExecutor<PapyrusReader>> {
+        let block_context = self.block_context(block_metadata.height)?;
+
+        // TODO(Yael: 8/9/2024) Need to reconsider which StateReader to use. the papyrus execution
+        // state reader does not implement the Sync trait since it is using cell so I used
+        // the blockifier state reader instead. Also the blockifier reader is implementing a global
+        // cache.
+        let state_reader = self.state_reader(block_metadata.height);
 
         let executor = TransactionExecutor::pre_process_and_create(
             state_reader,
             block_context,
             block_metadata.retrospective_block_hash,
-            block_builder_config.execute_config,
+            self.block_builder_config.execute_config.clone(),
         )?;
 
         Ok(executor)
@@ -335,7 +457,8 @@ impl BlockBuilderFactoryTrait for BlockBuilderFactory {
         block_metadata: BlockMetadata,
         execution_params: BlockBuilderExecutionParams,
         tx_provider: Box<dyn TransactionProvider>,
-        output_content_sender: Option<tokio::sync::mpsc::UnboundedSender<Transaction>>,
+        output_content_sender: Option<OutputContentSender>,
+        proposal_progress_sender: Option<ProposalProgressSender>,
         abort_signal_receiver: tokio::sync::oneshot::Receiver<()>,
     ) -> BlockBuilderResult<Box<dyn BlockBuilderTrait>> {
         let executor = self.preprocess_and_create_transaction_executor(&block_metadata)?;
@@ -343,9 +466,27 @@ impl BlockBuilderFactoryTrait for BlockBuilderFactory {
             Box::new(executor),
             tx_provider,
             output_content_sender,
+            proposal_progress_sender,
             abort_signal_receiver,
             self.block_builder_config.tx_chunk_size,
             execution_params,
         )))
     }
 }
+
+impl TransactionSimulatorTrait for BlockBuilderFactory {
+    fn simulate_transaction(
+        &self,
+        height: BlockNumber,
+        tx: Transaction,
+        flags: SimulationFlags,
+    ) -> BlockBuilderResult<SimulateTransactionResponse> {
+        let block_context = self.block_context(height)?;
+        let mut state = CachedState::new(self.state_reader(height));
+        Ok(simulate_transaction(&mut state, &block_context, tx, flags)?)
+    }
+
+    fn versioned_constants(&self) -> Arc<VersionedConstants> {
+        self.versioned_constants.clone()
+    }
+}
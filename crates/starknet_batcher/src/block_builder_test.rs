@@ -5,6 +5,7 @@ use blockifier::blockifier::transaction_executor::{
 };
 use blockifier::bouncer::BouncerWeights;
 use blockifier::fee::fee_checks::FeeCheckError;
+use blockifier::fee::receipt::TransactionReceipt;
 use blockifier::transaction::objects::{RevertError, TransactionExecutionInfo};
 use blockifier::transaction::transaction_execution::Transaction as BlockifierTransaction;
 use indexmap::IndexMap;
@@ -12,10 +13,16 @@ use mockall::predicate::eq;
 use mockall::Sequence;
 use rstest::rstest;
 use starknet_api::executable_transaction::Transaction;
+use starknet_api::execution_resources::{GasAmount, GasVector};
 use starknet_api::felt;
 use starknet_api::transaction::fields::Fee;
-use starknet_api::transaction::TransactionHash;
-use tokio::sync::mpsc::{UnboundedReceiver, UnboundedSender};
+use starknet_api::transaction::{
+    RevertedTransactionExecutionStatus,
+    TransactionExecutionStatus,
+    TransactionHash,
+};
+use starknet_batcher_types::batcher_types::TransactionExecutionOutcome;
+use tokio::sync::mpsc::{Receiver, UnboundedSender};
 
 use crate::block_builder::{
     BlockBuilder,
@@ -24,6 +31,10 @@ use crate::block_builder::{
     BlockBuilderResult,
     BlockBuilderTrait,
     BlockExecutionArtifacts,
+    ExecutedTransaction,
+    OutputContentBufferFullPolicy,
+    OutputContentSender,
+    ProposalProgress,
 };
 use crate::test_utils::test_txs;
 use crate::transaction_executor::MockTransactionExecutorTrait;
@@ -34,8 +45,8 @@ const BLOCK_GENERATION_LONG_DEADLINE_SECS: u64 = 5;
 const TX_CHANNEL_SIZE: usize = 50;
 const TX_CHUNK_SIZE: usize = 3;
 
-fn output_channel() -> (UnboundedSender<Transaction>, UnboundedReceiver<Transaction>) {
-    tokio::sync::mpsc::unbounded_channel()
+fn output_channel() -> (OutputContentSender, Receiver<ExecutedTransaction>) {
+    tokio::sync::mpsc::channel(TX_CHANNEL_SIZE)
 }
 
 fn block_execution_artifacts(
@@ -286,7 +297,7 @@ async fn verify_build_block_output(
     expected_block_len: usize,
     expected_block_artifacts: BlockExecutionArtifacts,
     result_block_artifacts: BlockExecutionArtifacts,
-    mut output_stream_receiver: UnboundedReceiver<Transaction>,
+    mut output_stream_receiver: Receiver<ExecutedTransaction>,
 ) {
     // Verify the transactions in the output channel.
     let mut output_txs = vec![];
@@ -294,7 +305,7 @@ async fn verify_build_block_output(
 
     assert_eq!(output_txs.len(), expected_block_len);
     for tx in input_txs.iter().take(expected_block_len) {
-        assert!(output_txs.contains(tx));
+        assert!(output_txs.iter().any(|(output_tx, _)| output_tx == tx));
     }
 
     // Verify the block artifacts.
@@ -304,22 +315,70 @@ async fn verify_build_block_output(
 async fn run_build_block(
     mock_transaction_executor: MockTransactionExecutorTrait,
     tx_provider: MockTransactionProvider,
-    output_sender: Option<UnboundedSender<Transaction>>,
+    output_sender: Option<OutputContentSender>,
     fail_on_err: bool,
     abort_receiver: tokio::sync::oneshot::Receiver<()>,
     deadline_secs: u64,
 ) -> BlockBuilderResult<BlockExecutionArtifacts> {
+    run_build_block_with_progress(
+        mock_transaction_executor,
+        tx_provider,
+        output_sender,
+        None,
+        fail_on_err,
+        abort_receiver,
+        deadline_secs,
+    )
+    .await
+}
+
+async fn run_build_block_with_progress(
+    mock_transaction_executor: MockTransactionExecutorTrait,
+    tx_provider: MockTransactionProvider,
+    output_sender: Option<OutputContentSender>,
+    progress_sender: Option<UnboundedSender<ProposalProgress>>,
+    fail_on_err: bool,
+    abort_receiver: tokio::sync::oneshot::Receiver<()>,
+    deadline_secs: u64,
+) -> BlockBuilderResult<BlockExecutionArtifacts> {
+    let mut block_builder = block_builder(
+        mock_transaction_executor,
+        tx_provider,
+        output_sender,
+        progress_sender,
+        abort_receiver,
+        execution_params(deadline_secs, fail_on_err, OutputContentBufferFullPolicy::Block),
+    );
+
+    block_builder.build_block().await
+}
+
+fn execution_params(
+    deadline_secs: u64,
+    fail_on_err: bool,
+    output_content_buffer_full_policy: OutputContentBufferFullPolicy,
+) -> BlockBuilderExecutionParams {
     let deadline = tokio::time::Instant::now() + tokio::time::Duration::from_secs(deadline_secs);
-    let mut block_builder = BlockBuilder::new(
+    BlockBuilderExecutionParams { deadline, fail_on_err, output_content_buffer_full_policy }
+}
+
+fn block_builder(
+    mock_transaction_executor: MockTransactionExecutorTrait,
+    tx_provider: MockTransactionProvider,
+    output_sender: Option<OutputContentSender>,
+    progress_sender: Option<UnboundedSender<ProposalProgress>>,
+    abort_receiver: tokio::sync::oneshot::Receiver<()>,
+    execution_params: BlockBuilderExecutionParams,
+) -> BlockBuilder {
+    BlockBuilder::new(
         Box::new(mock_transaction_executor),
         Box::new(tx_provider),
         output_sender,
+        progress_sender,
         abort_receiver,
         TX_CHUNK_SIZE,
-        BlockBuilderExecutionParams { deadline, fail_on_err },
-    );
-
-    block_builder.build_block().await
+        execution_params,
+    )
 }
 
 // TODO: Add test case for failed transaction.
@@ -366,6 +425,100 @@ async fn test_build_block(
     .await;
 }
 
+#[tokio::test]
+async fn test_build_block_streams_execution_outcomes() {
+    let input_txs = test_txs(0..3);
+    let succeeded_execution_info = |l1_gas| TransactionExecutionInfo {
+        receipt: TransactionReceipt {
+            gas: GasVector::from_l1_gas(GasAmount(l1_gas)),
+            ..Default::default()
+        },
+        ..Default::default()
+    };
+    let reverted_execution_info = execution_info();
+    let expected_revert_reason = reverted_execution_info.revert_error.as_ref().unwrap().to_string();
+
+    let mut mock_transaction_executor = MockTransactionExecutorTrait::new();
+    mock_transaction_executor.expect_add_txs_to_block().times(1).return_once(move |_| {
+        vec![
+            Ok(succeeded_execution_info(10)),
+            Ok(reverted_execution_info),
+            Ok(succeeded_execution_info(30)),
+        ]
+    });
+    set_close_block_expectations(&mut mock_transaction_executor, input_txs.len());
+    let mock_tx_provider = mock_tx_provider_limitless_calls(1, vec![input_txs.clone()]);
+
+    let (output_tx_sender, mut output_tx_receiver) = output_channel();
+    let (_abort_sender, abort_receiver) = tokio::sync::oneshot::channel();
+    run_build_block(
+        mock_transaction_executor,
+        mock_tx_provider,
+        Some(output_tx_sender),
+        false,
+        abort_receiver,
+        BLOCK_GENERATION_DEADLINE_SECS,
+    )
+    .await
+    .unwrap();
+
+    let mut output_txs = vec![];
+    output_tx_receiver.recv_many(&mut output_txs, TX_CHANNEL_SIZE).await;
+    let (output_txs, execution_outcomes): (Vec<_>, Vec<_>) = output_txs.into_iter().unzip();
+    assert_eq!(output_txs, input_txs);
+    assert_eq!(
+        execution_outcomes,
+        vec![
+            TransactionExecutionOutcome {
+                execution_status: TransactionExecutionStatus::Succeeded,
+                gas_consumed: GasVector::from_l1_gas(GasAmount(10)),
+            },
+            TransactionExecutionOutcome {
+                execution_status: TransactionExecutionStatus::Reverted(
+                    RevertedTransactionExecutionStatus { revert_reason: expected_revert_reason },
+                ),
+                gas_consumed: GasVector::default(),
+            },
+            TransactionExecutionOutcome {
+                execution_status: TransactionExecutionStatus::Succeeded,
+                gas_consumed: GasVector::from_l1_gas(GasAmount(30)),
+            },
+        ]
+    );
+}
+
+#[tokio::test]
+async fn test_build_block_emits_progress() {
+    let input_txs = test_txs(0..6);
+    let (mock_transaction_executor, mock_tx_provider, expected_block_artifacts) =
+        two_chunks_test_expectations(&input_txs);
+
+    let (progress_sender, mut progress_receiver) = tokio::sync::mpsc::unbounded_channel();
+    let (_abort_sender, abort_receiver) = tokio::sync::oneshot::channel();
+
+    let result_block_artifacts = run_build_block_with_progress(
+        mock_transaction_executor,
+        mock_tx_provider,
+        None,
+        Some(progress_sender),
+        false,
+        abort_receiver,
+        BLOCK_GENERATION_DEADLINE_SECS,
+    )
+    .await
+    .unwrap();
+    assert_eq!(result_block_artifacts, expected_block_artifacts);
+
+    let mut progress_events = vec![];
+    progress_receiver.recv_many(&mut progress_events, TX_CHANNEL_SIZE).await;
+
+    // An event is emitted for each of the two chunks.
+    let n_txs_included: Vec<usize> =
+        progress_events.iter().map(|progress| progress.n_txs_included).collect();
+    assert_eq!(n_txs_included, vec![TX_CHUNK_SIZE, 2 * TX_CHUNK_SIZE]);
+    assert!(progress_events.windows(2).all(|pair| pair[0].elapsed <= pair[1].elapsed));
+}
+
 #[tokio::test]
 async fn test_validate_block() {
     let input_txs = test_txs(0..3);
@@ -485,3 +638,149 @@ async fn test_build_block_abort_immediately() {
         Err(BlockBuilderError::Aborted)
     );
 }
+
+#[tokio::test]
+async fn test_build_block_blocks_on_full_output_buffer() {
+    let input_txs = test_txs(0..3);
+    let (mock_transaction_executor, mock_tx_provider, expected_block_artifacts) =
+        one_chunk_test_expectations(&input_txs);
+
+    // A consumer that doesn't read yet, with room for a single transaction.
+    let (output_tx_sender, output_tx_receiver) = tokio::sync::mpsc::channel(1);
+    let (_abort_sender, abort_receiver) = tokio::sync::oneshot::channel();
+    let mut block_builder = block_builder(
+        mock_transaction_executor,
+        mock_tx_provider,
+        Some(output_tx_sender),
+        None,
+        abort_receiver,
+        execution_params(
+            BLOCK_GENERATION_DEADLINE_SECS,
+            false,
+            OutputContentBufferFullPolicy::Block,
+        ),
+    );
+    let mut build_block_task = tokio::spawn(async move { block_builder.build_block().await });
+
+    // Block building waits for the stalled consumer.
+    assert!(
+        tokio::time::timeout(tokio::time::Duration::from_millis(100), &mut build_block_task)
+            .await
+            .is_err()
+    );
+
+    // Once the consumer reads, block building completes with all the transactions.
+    let output_txs_task = tokio::spawn(async move {
+        let mut output_tx_receiver = output_tx_receiver;
+        let mut output_txs = vec![];
+        while let Some((tx, _)) = output_tx_receiver.recv().await {
+            output_txs.push(tx);
+        }
+        output_txs
+    });
+    let result_block_artifacts = build_block_task.await.unwrap().unwrap();
+    assert_eq!(result_block_artifacts, expected_block_artifacts);
+    assert_eq!(output_txs_task.await.unwrap(), input_txs);
+}
+
+#[tokio::test]
+async fn test_build_block_fails_on_output_buffer_full_at_deadline() {
+    let mock_tx_provider = mock_tx_provider_limitless_calls(1, vec![test_txs(0..3)]);
+
+    // The block building stops at the deadline while waiting for the consumer, without closing
+    // the block.
+    let mut mock_transaction_executor = MockTransactionExecutorTrait::new();
+    mock_transaction_executor
+        .expect_add_txs_to_block()
+        .return_once(|_| (0..3).map(|_| Ok(execution_info())).collect());
+    mock_transaction_executor.expect_close_block().times(0);
+
+    // A stalled consumer, with room for a single transaction.
+    let (output_tx_sender, _output_tx_receiver) = tokio::sync::mpsc::channel(1);
+    let (_abort_sender, abort_receiver) = tokio::sync::oneshot::channel();
+    let mut block_builder = block_builder(
+        mock_transaction_executor,
+        mock_tx_provider,
+        Some(output_tx_sender),
+        None,
+        abort_receiver,
+        execution_params(
+            BLOCK_GENERATION_DEADLINE_SECS,
+            false,
+            OutputContentBufferFullPolicy::Block,
+        ),
+    );
+
+    assert_matches!(
+        block_builder.build_block().await,
+        Err(BlockBuilderError::OutputContentBufferFull)
+    );
+}
+
+#[tokio::test]
+async fn test_build_block_abort_while_output_buffer_full() {
+    let mock_tx_provider = mock_tx_provider_limitless_calls(1, vec![test_txs(0..3)]);
+
+    let mut mock_transaction_executor = MockTransactionExecutorTrait::new();
+    mock_transaction_executor
+        .expect_add_txs_to_block()
+        .return_once(|_| (0..3).map(|_| Ok(execution_info())).collect());
+    mock_transaction_executor.expect_close_block().times(0);
+
+    // A stalled consumer, with room for a single transaction.
+    let (output_tx_sender, _output_tx_receiver) = tokio::sync::mpsc::channel(1);
+    let (abort_sender, abort_receiver) = tokio::sync::oneshot::channel();
+    let mut block_builder = block_builder(
+        mock_transaction_executor,
+        mock_tx_provider,
+        Some(output_tx_sender),
+        None,
+        abort_receiver,
+        execution_params(
+            BLOCK_GENERATION_LONG_DEADLINE_SECS,
+            false,
+            OutputContentBufferFullPolicy::Block,
+        ),
+    );
+
+    // Send the abort signal while the block builder waits for room in the buffer.
+    tokio::spawn(async move {
+        tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
+        abort_sender.send(()).unwrap();
+    });
+
+    assert_matches!(block_builder.build_block().await, Err(BlockBuilderError::Aborted));
+}
+
+#[tokio::test]
+async fn test_build_block_fails_on_full_output_buffer_with_drop_policy() {
+    let mock_tx_provider = mock_tx_provider_limitless_calls(1, vec![test_txs(0..3)]);
+
+    // The block building stops once the buffer is full, without closing the block.
+    let mut mock_transaction_executor = MockTransactionExecutorTrait::new();
+    mock_transaction_executor
+        .expect_add_txs_to_block()
+        .return_once(|_| (0..3).map(|_| Ok(execution_info())).collect());
+    mock_transaction_executor.expect_close_block().times(0);
+
+    // A stalled consumer, with room for a single transaction.
+    let (output_tx_sender, _output_tx_receiver) = tokio::sync::mpsc::channel(1);
+    let (_abort_sender, abort_receiver) = tokio::sync::oneshot::channel();
+    let mut block_builder = block_builder(
+        mock_transaction_executor,
+        mock_tx_provider,
+        Some(output_tx_sender),
+        None,
+        abort_receiver,
+        execution_params(
+            BLOCK_GENERATION_LONG_DEADLINE_SECS,
+            false,
+            OutputContentBufferFullPolicy::Drop,
+        ),
+    );
+
+    assert_matches!(
+        block_builder.build_block().await,
+        Err(BlockBuilderError::OutputContentBufferFull)
+    );
+}
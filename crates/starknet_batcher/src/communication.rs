@@ -1,9 +1,19 @@
+use std::future::Future;
+use std::time::Instant;
+
 use async_trait::async_trait;
+use metrics::{histogram, increment_counter};
+use starknet_batcher_types::batcher_types::BatcherResult;
 use starknet_batcher_types::communication::{BatcherRequest, BatcherResponse};
 use starknet_sequencer_infra::component_definitions::ComponentRequestHandler;
 use starknet_sequencer_infra::component_server::{LocalComponentServer, RemoteComponentServer};
 
 use crate::batcher::Batcher;
+use crate::metrics::{
+    BATCHER_REQUEST_ERRORS_TOTAL,
+    BATCHER_REQUEST_LATENCY_SECONDS,
+    REQUEST_LABEL,
+};
 
 pub type LocalBatcherServer = LocalComponentServer<Batcher, BatcherRequest, BatcherResponse>;
 pub type RemoteBatcherServer = RemoteComponentServer<BatcherRequest, BatcherResponse>;
@@ -12,24 +22,58 @@ pub type RemoteBatcherServer = RemoteComponentServer<BatcherRequest, BatcherResp
 impl ComponentRequestHandler<BatcherRequest, BatcherResponse> for Batcher {
     async fn handle_request(&mut self, request: BatcherRequest) -> BatcherResponse {
         match request {
-            BatcherRequest::BuildProposal(input) => {
-                BatcherResponse::BuildProposal(self.build_proposal(input).await)
-            }
-            BatcherRequest::GetProposalContent(input) => {
-                BatcherResponse::GetProposalContent(self.get_proposal_content(input).await)
-            }
-            BatcherRequest::StartHeight(input) => {
-                BatcherResponse::StartHeight(self.start_height(input).await)
-            }
-            BatcherRequest::DecisionReached(input) => {
-                BatcherResponse::DecisionReached(self.decision_reached(input).await)
-            }
-            BatcherRequest::ValidateProposal(input) => {
-                BatcherResponse::ValidateProposal(self.validate_proposal(input).await)
-            }
-            BatcherRequest::SendProposalContent(input) => {
-                BatcherResponse::SendProposalContent(self.send_proposal_content(input).await)
+            BatcherRequest::BuildProposal(input) => BatcherResponse::BuildProposal(
+                with_request_metrics("BuildProposal", self.build_proposal(input)).await,
+            ),
+            BatcherRequest::GetProposalContent(input) => BatcherResponse::GetProposalContent(
+                with_request_metrics("GetProposalContent", self.get_proposal_content(input)).await,
+            ),
+            BatcherRequest::StartHeight(input) => BatcherResponse::StartHeight(
+                with_request_metrics("StartHeight", self.start_height(input)).await,
+            ),
+            BatcherRequest::DecisionReached(input) => BatcherResponse::DecisionReached(
+                with_request_metrics("DecisionReached", self.decision_reached(input)).await,
+            ),
+            BatcherRequest::ValidateProposal(input) => BatcherResponse::ValidateProposal(
+                with_request_metrics("ValidateProposal", self.validate_proposal(input)).await,
+            ),
+            BatcherRequest::SendProposalContent(input) => BatcherResponse::SendProposalContent(
+                with_request_metrics("SendProposalContent", self.send_proposal_content(input))
+                    .await,
+            ),
+            BatcherRequest::GetStateDiffChunks(input) => BatcherResponse::GetStateDiffChunks(
+                with_request_metrics("GetStateDiffChunks", self.get_state_diff_chunks(input)).await,
+            ),
+            BatcherRequest::SimulateTransaction(input) => BatcherResponse::SimulateTransaction(
+                with_request_metrics("SimulateTransaction", self.simulate_transaction(input)).await,
+            ),
+            BatcherRequest::GetBlockResourceSummary(input) => {
+                BatcherResponse::GetBlockResourceSummary(
+                    with_request_metrics("GetBlockResourceSummary", async {
+                        self.get_block_resource_summary(input)
+                    })
+                    .await,
+                )
             }
         }
     }
 }
+
+// Handles a request, recording its latency and whether it failed. The request type is one of a
+// fixed set of variant names, keeping the cardinality of the metrics low.
+async fn with_request_metrics<T>(
+    request_type: &'static str,
+    response: impl Future<Output = BatcherResult<T>>,
+) -> BatcherResult<T> {
+    let start = Instant::now();
+    let response = response.await;
+    histogram!(
+        BATCHER_REQUEST_LATENCY_SECONDS,
+        start.elapsed().as_secs_f64(),
+        REQUEST_LABEL => request_type
+    );
+    if response.is_err() {
+        increment_counter!(BATCHER_REQUEST_ERRORS_TOTAL, REQUEST_LABEL => request_type);
+    }
+    response
+}
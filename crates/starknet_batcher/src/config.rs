@@ -1,20 +1,32 @@
 use std::collections::BTreeMap;
+use std::time::Duration;
 
+use papyrus_config::converters::deserialize_milliseconds_to_duration;
 use papyrus_config::dumping::{append_sub_config_name, ser_param, SerializeConfig};
 use papyrus_config::{ParamPath, ParamPrivacyInput, SerializedParam};
 use serde::{Deserialize, Serialize};
 use validator::Validate;
 
-use crate::block_builder::BlockBuilderConfig;
+use crate::block_builder::{BlockBuilderConfig, OutputContentBufferFullPolicy};
 
 /// The batcher related configuration.
 #[derive(Clone, Debug, Serialize, Deserialize, Validate, PartialEq)]
 pub struct BatcherConfig {
     pub storage: papyrus_storage::StorageConfig,
+    #[validate(range(min = 1))]
     pub outstream_content_buffer_size: usize,
+    pub outstream_content_buffer_full_policy: OutputContentBufferFullPolicy,
+    #[validate(range(min = 1))]
+    pub input_stream_content_buffer_size: usize,
     pub block_builder_config: BlockBuilderConfig,
     pub global_contract_cache_size: usize,
     pub max_l1_handler_txs_per_block_proposal: usize,
+    #[validate(range(min = 1))]
+    pub max_concurrent_proposals: usize,
+    #[validate(range(min = 1))]
+    pub max_state_diff_chunk_size: usize,
+    #[serde(deserialize_with = "deserialize_milliseconds_to_duration")]
+    pub proposal_expiry_grace_period: Duration,
 }
 
 impl SerializeConfig for BatcherConfig {
@@ -28,6 +40,20 @@ impl SerializeConfig for BatcherConfig {
             ParamPrivacyInput::Public,
         )]);
         dump.append(&mut BTreeMap::from([
+            ser_param(
+                "outstream_content_buffer_full_policy",
+                &self.outstream_content_buffer_full_policy,
+                "What block building does when the outstream buffer is full: 'Block' waits for \
+                 the consumer, 'Drop' fails the proposal.",
+                ParamPrivacyInput::Public,
+            ),
+            ser_param(
+                "input_stream_content_buffer_size",
+                &self.input_stream_content_buffer_size,
+                "Maximum items to add to the input stream buffer of a validated proposal before \
+                 blocking further filling of the stream.",
+                ParamPrivacyInput::Public,
+            ),
             ser_param(
                 "global_contract_cache_size",
                 &self.global_contract_cache_size,
@@ -41,6 +67,25 @@ impl SerializeConfig for BatcherConfig {
                 "The maximum number of L1 handler transactions to include in a block proposal.",
                 ParamPrivacyInput::Public,
             ),
+            ser_param(
+                "max_concurrent_proposals",
+                &self.max_concurrent_proposals,
+                "The maximum number of proposals that are proposed or validated concurrently.",
+                ParamPrivacyInput::Public,
+            ),
+            ser_param(
+                "max_state_diff_chunk_size",
+                &self.max_state_diff_chunk_size,
+                "The maximum number of entries in a state diff chunk streamed for DA publishing.",
+                ParamPrivacyInput::Public,
+            ),
+            ser_param(
+                "proposal_expiry_grace_period",
+                &self.proposal_expiry_grace_period.as_millis(),
+                "Time (milliseconds) after the deadline of a proposal at which it expires, \
+                 cancelling its building and freeing its slot.",
+                ParamPrivacyInput::Public,
+            ),
         ]));
         dump.append(&mut append_sub_config_name(self.storage.dump(), "storage"));
         dump.append(&mut append_sub_config_name(
@@ -66,9 +111,14 @@ impl Default for BatcherConfig {
             },
             // TODO: set a more reasonable default value.
             outstream_content_buffer_size: 100,
+            outstream_content_buffer_full_policy: OutputContentBufferFullPolicy::Block,
+            input_stream_content_buffer_size: 400,
             block_builder_config: BlockBuilderConfig::default(),
             global_contract_cache_size: 400,
             max_l1_handler_txs_per_block_proposal: 3,
+            max_concurrent_proposals: 1,
+            max_state_diff_chunk_size: 1000,
+            proposal_expiry_grace_period: Duration::from_secs(1),
         }
     }
 }
@@ -7,12 +7,19 @@ mod block_builder_test;
 pub mod communication;
 pub mod config;
 pub mod fee_market;
+pub mod metrics;
 mod proposal_manager;
 #[cfg(test)]
 mod proposal_manager_test;
+mod state_diff_chunks;
+#[cfg(test)]
+mod state_diff_chunks_test;
 #[cfg(test)]
 mod test_utils;
 mod transaction_executor;
 mod transaction_provider;
 #[cfg(test)]
 mod transaction_provider_test;
+pub mod transaction_simulator;
+#[cfg(test)]
+mod transaction_simulator_test;
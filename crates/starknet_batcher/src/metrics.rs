@@ -0,0 +1,8 @@
+/// The time, in seconds, it took the batcher to handle a request, labeled by the request type.
+pub const BATCHER_REQUEST_LATENCY_SECONDS: &str = "batcher_request_latency_seconds";
+
+/// The number of requests the batcher failed to handle, labeled by the request type.
+pub const BATCHER_REQUEST_ERRORS_TOTAL: &str = "batcher_request_errors_total";
+
+/// The label of the request type, i.e., the name of the request variant.
+pub const REQUEST_LABEL: &str = "request";
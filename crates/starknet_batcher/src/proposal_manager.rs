@@ -1,18 +1,26 @@
 use std::collections::{HashMap, HashSet};
 use std::sync::Arc;
+use std::time::Duration;
 
 use async_trait::async_trait;
+use blockifier::bouncer::BouncerWeights;
 use indexmap::IndexMap;
 use starknet_api::block::{BlockHashAndNumber, BlockNumber};
 use starknet_api::block_hash::state_diff_hash::calculate_state_diff_hash;
 use starknet_api::core::{ContractAddress, Nonce};
-use starknet_api::executable_transaction::Transaction;
+use starknet_api::execution_resources::GasVector;
 use starknet_api::state::ThinStateDiff;
 use starknet_api::transaction::TransactionHash;
-use starknet_batcher_types::batcher_types::{ProposalCommitment, ProposalId};
+use starknet_batcher_types::batcher_types::{
+    BlockBuiltinCount,
+    BlockResourceSummary,
+    BlockWeights,
+    ProposalCommitment,
+    ProposalId,
+};
 use thiserror::Error;
 use tokio::sync::Mutex;
-use tracing::{debug, error, info, instrument, Instrument};
+use tracing::{debug, error, info, instrument, warn, Instrument};
 
 use crate::batcher::BatcherStorageReaderTrait;
 use crate::block_builder::{
@@ -22,6 +30,9 @@ use crate::block_builder::{
     BlockBuilderTrait,
     BlockExecutionArtifacts,
     BlockMetadata,
+    OutputContentBufferFullPolicy,
+    OutputContentSender,
+    ProposalProgressSender,
 };
 use crate::transaction_provider::{ProposeTransactionProvider, ValidateTransactionProvider};
 
@@ -67,8 +78,11 @@ pub enum GetProposalResultError {
     ProposalDoesNotExist { proposal_id: ProposalId },
     #[error("Proposal was aborted")]
     Aborted,
+    #[error("Proposal expired")]
+    Expired,
 }
 
+#[derive(Debug)]
 pub enum ProposalStatus {
     Processing,
     Finished,
@@ -85,12 +99,11 @@ pub trait ProposalManagerTrait: Send + Sync {
         proposal_id: ProposalId,
         retrospective_block_hash: Option<BlockHashAndNumber>,
         deadline: tokio::time::Instant,
-        tx_sender: tokio::sync::mpsc::UnboundedSender<Transaction>,
+        tx_sender: OutputContentSender,
+        proposal_progress_sender: Option<ProposalProgressSender>,
         tx_provider: ProposeTransactionProvider,
     ) -> Result<(), GenerateProposalError>;
 
-    // TODO: delete allow dead code once the batcher uses this code.
-    #[allow(dead_code)]
     async fn validate_block_proposal(
         &mut self,
         proposal_id: ProposalId,
@@ -106,12 +119,14 @@ pub trait ProposalManagerTrait: Send + Sync {
 
     async fn get_proposal_status(&self, proposal_id: ProposalId) -> ProposalStatus;
 
+    // Returns the proposals that expired before they were done building.
+    async fn expired_proposals(&self) -> Vec<ProposalId>;
+
     async fn await_proposal_commitment(
         &mut self,
         proposal_id: ProposalId,
     ) -> ProposalResult<ProposalCommitment>;
 
-    #[allow(dead_code)]
     async fn abort_proposal(&mut self, proposal_id: ProposalId);
 }
 
@@ -121,6 +136,14 @@ struct ProposalTask {
     join_handle: tokio::task::JoinHandle<()>,
 }
 
+impl ProposalTask {
+    // Signals the block builder to stop and cancels the task, dropping the in-flight execution.
+    fn abort(self) {
+        self.abort_signal_sender.send(()).ok();
+        self.join_handle.abort();
+    }
+}
+
 /// Main struct for handling block proposals.
 /// Taking care of:
 /// - Proposing new blocks.
@@ -132,11 +155,17 @@ pub(crate) struct ProposalManager {
     storage_reader: Arc<dyn BatcherStorageReaderTrait>,
     active_height: Option<BlockNumber>,
 
-    /// The block proposal that is currently being built, if any.
-    /// At any given time, there can be only one proposal being actively executed (either proposed
-    /// or validated).
-    active_proposal: Arc<Mutex<Option<ProposalId>>>,
-    active_proposal_task: Option<ProposalTask>,
+    /// The block proposals that are currently being built.
+    /// At any given time, there can be at most `max_concurrent_proposals` proposals being actively
+    /// executed (either proposed or validated).
+    active_proposals: Arc<Mutex<HashSet<ProposalId>>>,
+    active_proposal_tasks: HashMap<ProposalId, ProposalTask>,
+    max_concurrent_proposals: usize,
+    // What the block builders do when the consumer of the proposal content falls behind.
+    output_content_buffer_full_policy: OutputContentBufferFullPolicy,
+    // How long after its deadline a proposal that is still being built expires. An expired
+    // proposal is cancelled, so a consumer that never completes it does not hold its slot.
+    proposal_expiry_grace_period: Duration,
 
     // Use a factory object, to be able to mock BlockBuilder in tests.
     block_builder_factory: Arc<dyn BlockBuilderFactoryTrait + Send + Sync>,
@@ -151,6 +180,7 @@ pub struct ProposalOutput {
     pub commitment: ProposalCommitment,
     pub tx_hashes: HashSet<TransactionHash>,
     pub nonces: HashMap<ContractAddress, Nonce>,
+    pub resource_summary: BlockResourceSummary,
 }
 
 #[async_trait]
@@ -185,13 +215,15 @@ impl ProposalManagerTrait for ProposalManager {
     /// Starts a new block proposal generation task for the given proposal_id and height with
     /// transactions from the mempool.
     /// Requires tx_sender for sending the generated transactions to the caller.
-    #[instrument(skip(self, tx_sender, tx_provider), err, fields(self.active_height))]
+    /// If proposal_progress_sender is given, progress snapshots are sent to it while building.
+    #[instrument(skip(self, tx_sender, proposal_progress_sender, tx_provider), err, fields(self.active_height))]
     async fn build_block_proposal(
         &mut self,
         proposal_id: ProposalId,
         retrospective_block_hash: Option<BlockHashAndNumber>,
         deadline: tokio::time::Instant,
-        tx_sender: tokio::sync::mpsc::UnboundedSender<Transaction>,
+        tx_sender: OutputContentSender,
+        proposal_progress_sender: Option<ProposalProgressSender>,
         tx_provider: ProposeTransactionProvider,
     ) -> Result<(), GenerateProposalError> {
         self.set_active_proposal(proposal_id).await?;
@@ -204,14 +236,20 @@ impl ProposalManagerTrait for ProposalManager {
 
         let block_builder = self.block_builder_factory.create_block_builder(
             BlockMetadata { height, retrospective_block_hash },
-            BlockBuilderExecutionParams { deadline, fail_on_err: false },
+            BlockBuilderExecutionParams {
+                deadline,
+                fail_on_err: false,
+                output_content_buffer_full_policy: self.output_content_buffer_full_policy,
+            },
             Box::new(tx_provider),
             Some(tx_sender.clone()),
+            proposal_progress_sender,
             abort_signal_receiver,
         )?;
 
-        let join_handle = self.spawn_build_block_task(proposal_id, block_builder).await;
-        self.active_proposal_task = Some(ProposalTask { abort_signal_sender, join_handle });
+        let join_handle = self.spawn_build_block_task(proposal_id, block_builder, deadline).await;
+        self.active_proposal_tasks
+            .insert(proposal_id, ProposalTask { abort_signal_sender, join_handle });
 
         Ok(())
     }
@@ -236,14 +274,20 @@ impl ProposalManagerTrait for ProposalManager {
 
         let block_builder = self.block_builder_factory.create_block_builder(
             BlockMetadata { height, retrospective_block_hash },
-            BlockBuilderExecutionParams { deadline, fail_on_err: true },
+            BlockBuilderExecutionParams {
+                deadline,
+                fail_on_err: true,
+                output_content_buffer_full_policy: self.output_content_buffer_full_policy,
+            },
             Box::new(tx_provider),
             None,
+            None,
             abort_signal_receiver,
         )?;
 
-        let join_handle = self.spawn_build_block_task(proposal_id, block_builder).await;
-        self.active_proposal_task = Some(ProposalTask { abort_signal_sender, join_handle });
+        let join_handle = self.spawn_build_block_task(proposal_id, block_builder, deadline).await;
+        self.active_proposal_tasks
+            .insert(proposal_id, ProposalTask { abort_signal_sender, join_handle });
 
         Ok(())
     }
@@ -265,7 +309,7 @@ impl ProposalManagerTrait for ProposalManager {
             Some(Ok(_)) => ProposalStatus::Finished,
             Some(Err(_)) => ProposalStatus::Failed,
             None => {
-                if self.active_proposal.lock().await.as_ref() == Some(&proposal_id) {
+                if self.active_proposals.lock().await.contains(&proposal_id) {
                     ProposalStatus::Processing
                 } else {
                     ProposalStatus::NotFound
@@ -274,12 +318,22 @@ impl ProposalManagerTrait for ProposalManager {
         }
     }
 
+    async fn expired_proposals(&self) -> Vec<ProposalId> {
+        self.executed_proposals
+            .lock()
+            .await
+            .iter()
+            .filter(|(_, result)| matches!(result, Err(GetProposalResultError::Expired)))
+            .map(|(proposal_id, _)| *proposal_id)
+            .collect()
+    }
+
     async fn await_proposal_commitment(
         &mut self,
         proposal_id: ProposalId,
     ) -> ProposalResult<ProposalCommitment> {
-        if self.active_proposal.lock().await.is_some_and(|id| id == proposal_id) {
-            self.await_active_proposal().await;
+        if let Some(proposal_task) = self.active_proposal_tasks.remove(&proposal_id) {
+            proposal_task.join_handle.await.ok();
         }
         let proposals = self.executed_proposals.lock().await;
         let output = proposals
@@ -291,15 +345,20 @@ impl ProposalManagerTrait for ProposalManager {
         }
     }
 
-    // Aborts the proposal with the given ID, if active.
-    // Should be used in validate flow, if the consensus decides to abort the proposal.
+    // Aborts the proposal with the given ID, if it exists. An already executed proposal is
+    // discarded, so it cannot be committed.
+    // Should be used in validate flow, if the consensus decides to abort the proposal, or the
+    // proposal turns out to be invalid.
     async fn abort_proposal(&mut self, proposal_id: ProposalId) {
-        if self.active_proposal.lock().await.is_some_and(|id| id == proposal_id) {
-            self.abort_active_proposal().await;
-            self.executed_proposals
-                .lock()
-                .await
-                .insert(proposal_id, Err(GetProposalResultError::Aborted));
+        let was_active = self.active_proposals.lock().await.remove(&proposal_id);
+        if was_active {
+            if let Some(proposal_task) = self.active_proposal_tasks.remove(&proposal_id) {
+                proposal_task.abort();
+            }
+        }
+        let mut executed_proposals = self.executed_proposals.lock().await;
+        if was_active || executed_proposals.contains_key(&proposal_id) {
+            executed_proposals.insert(proposal_id, Err(GetProposalResultError::Aborted));
         }
     }
 }
@@ -308,39 +367,51 @@ impl ProposalManager {
     pub fn new(
         block_builder_factory: Arc<dyn BlockBuilderFactoryTrait + Send + Sync>,
         storage_reader: Arc<dyn BatcherStorageReaderTrait>,
+        max_concurrent_proposals: usize,
+        output_content_buffer_full_policy: OutputContentBufferFullPolicy,
+        proposal_expiry_grace_period: Duration,
     ) -> Self {
         Self {
             storage_reader,
-            active_proposal: Arc::new(Mutex::new(None)),
+            active_proposals: Arc::new(Mutex::new(HashSet::new())),
             block_builder_factory,
-            active_proposal_task: None,
+            active_proposal_tasks: HashMap::new(),
+            max_concurrent_proposals,
+            output_content_buffer_full_policy,
+            proposal_expiry_grace_period,
             active_height: None,
             executed_proposals: Arc::new(Mutex::new(HashMap::new())),
         }
     }
 
+    // Spawns the block building task, which is cancelled if the proposal expires, i.e., if it is
+    // not done by the end of the grace period after its deadline.
     async fn spawn_build_block_task(
         &mut self,
         proposal_id: ProposalId,
         mut block_builder: Box<dyn BlockBuilderTrait>,
+        deadline: tokio::time::Instant,
     ) -> tokio::task::JoinHandle<()> {
-        let active_proposal = self.active_proposal.clone();
+        let active_proposals = self.active_proposals.clone();
         let executed_proposals = self.executed_proposals.clone();
+        let expiry = deadline + self.proposal_expiry_grace_period;
 
         tokio::spawn(
             async move {
-                let result = block_builder
-                    .build_block()
-                    .await
-                    .map(ProposalOutput::from)
-                    .map_err(|e| GetProposalResultError::BlockBuilderError(Arc::new(e)));
-
-                // The proposal is done, clear the active proposal.
-                // Keep the proposal result only if it is the same as the active proposal.
-                // The active proposal might have changed if this proposal was aborted.
-                let mut active_proposal = active_proposal.lock().await;
-                if active_proposal.is_some_and(|id| id == proposal_id) {
-                    active_proposal.take();
+                let result =
+                    match tokio::time::timeout_at(expiry, block_builder.build_block()).await {
+                        Ok(result) => result
+                            .and_then(ProposalOutput::try_from)
+                            .map_err(|e| GetProposalResultError::BlockBuilderError(Arc::new(e))),
+                        Err(_) => {
+                            warn!("Proposal {} expired, cancelling its building.", proposal_id);
+                            Err(GetProposalResultError::Expired)
+                        }
+                    };
+
+                // The proposal is done, remove it from the active proposals.
+                // Keep the proposal result only if it is still active, it might have been aborted.
+                if active_proposals.lock().await.remove(&proposal_id) {
                     executed_proposals.lock().await.insert(proposal_id, result);
                 }
             }
@@ -349,61 +420,67 @@ impl ProposalManager {
     }
 
     async fn reset_active_height(&mut self) {
-        if let Some(_active_proposal) = self.active_proposal.lock().await.take() {
-            // TODO: Abort the block_builder.
-        }
+        self.abort_active_proposals().await;
         self.executed_proposals.lock().await.clear();
         self.active_height = None;
     }
 
     // Sets a new active proposal task.
-    // Fails if either there is no active height, there is another proposal being generated, or a
-    // proposal with the same ID already exists.
+    // Fails if either there is no active height, the maximal number of proposals are being
+    // generated, or a proposal with the same ID already exists.
     async fn set_active_proposal(
         &mut self,
         proposal_id: ProposalId,
     ) -> Result<(), GenerateProposalError> {
         self.active_height.ok_or(GenerateProposalError::NoActiveHeight)?;
 
-        if self.executed_proposals.lock().await.contains_key(&proposal_id) {
+        let mut active_proposals = self.active_proposals.lock().await;
+        if self.executed_proposals.lock().await.contains_key(&proposal_id)
+            || active_proposals.contains(&proposal_id)
+        {
             return Err(GenerateProposalError::ProposalAlreadyExists { proposal_id });
         }
 
-        let mut active_proposal = self.active_proposal.lock().await;
-        if let Some(current_generating_proposal_id) = *active_proposal {
+        if active_proposals.len() >= self.max_concurrent_proposals {
+            let current_generating_proposal_id =
+                *active_proposals.iter().min().expect("There should be an active proposal.");
             return Err(GenerateProposalError::AlreadyGeneratingProposal {
                 current_generating_proposal_id,
                 new_proposal_id: proposal_id,
             });
         }
 
-        debug!("Set proposal {} as the one being generated.", proposal_id);
-        *active_proposal = Some(proposal_id);
+        debug!("Set proposal {} as one of the proposals being generated.", proposal_id);
+        active_proposals.insert(proposal_id);
         Ok(())
     }
 
-    // Awaits the active proposal.
+    // Awaits the active proposals.
     // Returns true if there was an active proposal, and false otherwise.
+    #[cfg(test)]
     pub async fn await_active_proposal(&mut self) -> bool {
-        if let Some(proposal_task) = self.active_proposal_task.take() {
+        let proposal_tasks: Vec<_> = self.active_proposal_tasks.drain().collect();
+        let had_active_proposal = !proposal_tasks.is_empty();
+        for (_, proposal_task) in proposal_tasks {
             proposal_task.join_handle.await.ok();
-            return true;
         }
-        false
+        had_active_proposal
     }
 
-    // Ends the current active proposal.
+    // Ends all the active proposals, cancelling their block building tasks.
     // This call is non-blocking.
-    async fn abort_active_proposal(&mut self) {
-        self.active_proposal.lock().await.take();
-        if let Some(proposal_task) = self.active_proposal_task.take() {
-            proposal_task.abort_signal_sender.send(()).ok();
+    async fn abort_active_proposals(&mut self) {
+        self.active_proposals.lock().await.clear();
+        for (_, proposal_task) in self.active_proposal_tasks.drain() {
+            proposal_task.abort();
         }
     }
 }
 
-impl From<BlockExecutionArtifacts> for ProposalOutput {
-    fn from(artifacts: BlockExecutionArtifacts) -> Self {
+impl TryFrom<BlockExecutionArtifacts> for ProposalOutput {
+    type Error = BlockBuilderError;
+
+    fn try_from(artifacts: BlockExecutionArtifacts) -> Result<Self, Self::Error> {
         let commitment_state_diff = artifacts.commitment_state_diff;
         let nonces = HashMap::from_iter(
             commitment_state_diff
@@ -427,7 +504,39 @@ impl From<BlockExecutionArtifacts> for ProposalOutput {
         let commitment =
             ProposalCommitment { state_diff_commitment: calculate_state_diff_hash(&state_diff) };
         let tx_hashes = HashSet::from_iter(artifacts.execution_infos.keys().copied());
+        let da_gas =
+            artifacts.execution_infos.values().try_fold(GasVector::ZERO, |da_gas, info| {
+                da_gas.checked_add(info.receipt.da_gas).ok_or(BlockBuilderError::DaGasOverflow)
+            })?;
+        let resource_summary = BlockResourceSummary {
+            n_txs: artifacts.execution_infos.len(),
+            weights: block_weights(&artifacts.bouncer_weights),
+            da_gas,
+        };
+
+        Ok(Self { state_diff, commitment, tx_hashes, nonces, resource_summary })
+    }
+}
 
-        Self { state_diff, commitment, tx_hashes, nonces }
+fn block_weights(bouncer_weights: &BouncerWeights) -> BlockWeights {
+    let builtin_count = &bouncer_weights.builtin_count;
+    BlockWeights {
+        builtin_count: BlockBuiltinCount {
+            add_mod: builtin_count.add_mod,
+            bitwise: builtin_count.bitwise,
+            ecdsa: builtin_count.ecdsa,
+            ec_op: builtin_count.ec_op,
+            keccak: builtin_count.keccak,
+            mul_mod: builtin_count.mul_mod,
+            pedersen: builtin_count.pedersen,
+            poseidon: builtin_count.poseidon,
+            range_check: builtin_count.range_check,
+            range_check96: builtin_count.range_check96,
+        },
+        gas: bouncer_weights.gas,
+        message_segment_length: bouncer_weights.message_segment_length,
+        n_events: bouncer_weights.n_events,
+        n_steps: bouncer_weights.n_steps,
+        state_diff_size: bouncer_weights.state_diff_size,
     }
 }
@@ -1,19 +1,29 @@
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
 
 use assert_matches::assert_matches;
+use blockifier::fee::receipt::TransactionReceipt;
+use blockifier::transaction::objects::TransactionExecutionInfo;
+use mockall::Sequence;
 use rstest::{fixture, rstest};
 use starknet_api::block::BlockNumber;
-use starknet_api::executable_transaction::Transaction;
+use starknet_api::execution_resources::{GasAmount, GasVector};
+use starknet_api::felt;
+use starknet_api::transaction::TransactionHash;
 use starknet_batcher_types::batcher_types::ProposalId;
 use starknet_mempool_types::communication::MockMempoolClient;
 
 use crate::batcher::MockBatcherStorageReaderTrait;
 use crate::block_builder::{
+    BlockBuilderError,
     BlockBuilderResult,
     BlockBuilderTrait,
     BlockExecutionArtifacts,
+    ExecutedTransaction,
     MockBlockBuilderFactoryTrait,
     MockBlockBuilderTrait,
+    OutputContentBufferFullPolicy,
+    OutputContentSender,
 };
 use crate::proposal_manager::{
     GenerateProposalError,
@@ -21,8 +31,10 @@ use crate::proposal_manager::{
     ProposalManager,
     ProposalManagerTrait,
     ProposalOutput,
+    ProposalStatus,
     StartHeightError,
 };
+use crate::test_utils::PendingBlockBuilder;
 use crate::transaction_provider::{
     MockL1ProviderClient,
     ProposeTransactionProvider,
@@ -33,13 +45,13 @@ const INITIAL_HEIGHT: BlockNumber = BlockNumber(3);
 const BLOCK_GENERATION_TIMEOUT: tokio::time::Duration = tokio::time::Duration::from_secs(1);
 const MAX_L1_HANDLER_TXS_PER_BLOCK_PROPOSAL: usize = 3;
 const INPUT_CHANNEL_SIZE: usize = 30;
+const OUTPUT_CHANNEL_SIZE: usize = 30;
+const MAX_CONCURRENT_PROPOSALS: usize = 1;
+const PROPOSAL_EXPIRY_GRACE_PERIOD: tokio::time::Duration = tokio::time::Duration::from_secs(1);
 
 #[fixture]
-fn output_streaming() -> (
-    tokio::sync::mpsc::UnboundedSender<Transaction>,
-    tokio::sync::mpsc::UnboundedReceiver<Transaction>,
-) {
-    tokio::sync::mpsc::unbounded_channel()
+fn output_streaming() -> (OutputContentSender, tokio::sync::mpsc::Receiver<ExecutedTransaction>) {
+    tokio::sync::mpsc::channel(OUTPUT_CHANNEL_SIZE)
 }
 
 struct MockDependencies {
@@ -61,7 +73,7 @@ impl MockDependencies {
         self.block_builder_factory
             .expect_create_block_builder()
             .times(times)
-            .returning(move |_, _, _, _, _| simulate_build_block());
+            .returning(move |_, _, _, _, _, _| simulate_build_block());
     }
 
     // This function simulates a long build block operation. This is required for a test that
@@ -79,7 +91,7 @@ impl MockDependencies {
         self.block_builder_factory
             .expect_create_block_builder()
             .times(times)
-            .returning(move |_, _, _, _, _| simulate_long_build_block());
+            .returning(move |_, _, _, _, _, _| simulate_long_build_block());
     }
 }
 
@@ -111,6 +123,9 @@ fn proposal_manager(mock_dependencies: MockDependencies) -> ProposalManager {
     ProposalManager::new(
         Arc::new(mock_dependencies.block_builder_factory),
         Arc::new(mock_dependencies.storage_reader),
+        MAX_CONCURRENT_PROPOSALS,
+        OutputContentBufferFullPolicy::Block,
+        PROPOSAL_EXPIRY_GRACE_PERIOD,
     )
 }
 
@@ -125,7 +140,14 @@ async fn build_proposal_non_blocking(
 ) {
     let (output_sender, _receiver) = output_streaming();
     proposal_manager
-        .build_block_proposal(proposal_id, None, proposal_deadline(), output_sender, tx_provider)
+        .build_block_proposal(
+            proposal_id,
+            None,
+            proposal_deadline(),
+            output_sender,
+            None,
+            tx_provider,
+        )
         .await
         .unwrap();
 }
@@ -188,10 +210,7 @@ async fn start_height(
 async fn build_proposal_fails_without_start_height(
     mock_dependencies: MockDependencies,
     propose_tx_provider: ProposeTransactionProvider,
-    output_streaming: (
-        tokio::sync::mpsc::UnboundedSender<Transaction>,
-        tokio::sync::mpsc::UnboundedReceiver<Transaction>,
-    ),
+    output_streaming: (OutputContentSender, tokio::sync::mpsc::Receiver<ExecutedTransaction>),
 ) {
     let mut proposal_manager = proposal_manager(mock_dependencies);
     let err = proposal_manager
@@ -200,6 +219,7 @@ async fn build_proposal_fails_without_start_height(
             None,
             proposal_deadline(),
             output_streaming.0,
+            None,
             propose_tx_provider,
         )
         .await;
@@ -289,6 +309,7 @@ async fn multiple_proposals_generation_fail(
             None,
             proposal_deadline(),
             output_sender_0,
+            None,
             propose_tx_provider.clone(),
         )
         .await
@@ -302,6 +323,7 @@ async fn multiple_proposals_generation_fail(
             None,
             proposal_deadline(),
             output_sender_1,
+            None,
             propose_tx_provider,
         )
         .await;
@@ -328,7 +350,7 @@ async fn take_proposal_result_no_active_proposal(
     build_proposal(&mut proposal_manager, propose_tx_provider, ProposalId(0)).await;
 
     let expected_proposal_output =
-        ProposalOutput::from(BlockExecutionArtifacts::create_for_testing());
+        ProposalOutput::try_from(BlockExecutionArtifacts::create_for_testing()).unwrap();
     assert_eq!(
         proposal_manager.take_proposal_result(ProposalId(0)).await.unwrap(),
         expected_proposal_output
@@ -362,6 +384,144 @@ async fn abort_active_proposal(
     assert!(!proposal_manager.await_active_proposal().await);
 }
 
+#[rstest]
+#[tokio::test]
+async fn abort_executed_proposal(
+    mut mock_dependencies: MockDependencies,
+    validate_tx_provider: ValidateTransactionProvider,
+) {
+    mock_dependencies.expect_build_block(1);
+    let mut proposal_manager = proposal_manager(mock_dependencies);
+
+    proposal_manager.start_height(INITIAL_HEIGHT).await.unwrap();
+    validate_proposal(&mut proposal_manager, validate_tx_provider, ProposalId(0)).await;
+
+    proposal_manager.abort_proposal(ProposalId(0)).await;
+
+    // The executed proposal is discarded, so it cannot be committed.
+    assert_matches!(
+        proposal_manager.take_proposal_result(ProposalId(0)).await,
+        Err(GetProposalResultError::Aborted)
+    );
+}
+
+#[rstest]
+#[tokio::test]
+async fn abort_cancels_block_building_and_releases_proposal_slot(
+    mut mock_dependencies: MockDependencies,
+    propose_tx_provider: ProposeTransactionProvider,
+) {
+    let dropped = Arc::new(AtomicBool::new(false));
+    let dropped_clone = dropped.clone();
+    let mut seq = Sequence::new();
+    mock_dependencies
+        .block_builder_factory
+        .expect_create_block_builder()
+        .times(1)
+        .in_sequence(&mut seq)
+        .return_once(move |_, _, _, _, _, _| {
+            Ok(Box::new(PendingBlockBuilder { dropped: dropped_clone }))
+        });
+    mock_dependencies
+        .block_builder_factory
+        .expect_create_block_builder()
+        .times(1)
+        .in_sequence(&mut seq)
+        .return_once(|_, _, _, _, _, _| {
+            let mut mock_block_builder = MockBlockBuilderTrait::new();
+            mock_block_builder
+                .expect_build_block()
+                .return_once(|| Ok(BlockExecutionArtifacts::create_for_testing()));
+            Ok(Box::new(mock_block_builder))
+        });
+    let mut proposal_manager = proposal_manager(mock_dependencies);
+
+    proposal_manager.start_height(INITIAL_HEIGHT).await.unwrap();
+    build_proposal_non_blocking(&mut proposal_manager, propose_tx_provider.clone(), ProposalId(0))
+        .await;
+    // Let the block building start.
+    tokio::task::yield_now().await;
+
+    proposal_manager.abort_proposal(ProposalId(0)).await;
+
+    // The block building task is cancelled, dropping the block builder.
+    tokio::time::timeout(BLOCK_GENERATION_TIMEOUT, async {
+        while !dropped.load(Ordering::SeqCst) {
+            tokio::task::yield_now().await;
+        }
+    })
+    .await
+    .expect("The block builder should be dropped.");
+
+    // A new proposal can be built right away.
+    build_proposal(&mut proposal_manager, propose_tx_provider, ProposalId(1)).await;
+    assert_matches!(proposal_manager.take_proposal_result(ProposalId(1)).await, Ok(_));
+}
+
+#[rstest]
+#[tokio::test]
+async fn concurrent_proposals_up_to_limit(
+    mut mock_dependencies: MockDependencies,
+    propose_tx_provider: ProposeTransactionProvider,
+) {
+    const N_CONCURRENT_PROPOSALS: u64 = 3;
+    mock_dependencies.block_builder_factory.expect_create_block_builder().returning(
+        |_, _, _, _, _, _| {
+            Ok(Box::new(PendingBlockBuilder { dropped: Arc::new(AtomicBool::new(false)) }))
+        },
+    );
+    let mut proposal_manager = ProposalManager::new(
+        Arc::new(mock_dependencies.block_builder_factory),
+        Arc::new(mock_dependencies.storage_reader),
+        N_CONCURRENT_PROPOSALS.try_into().unwrap(),
+        OutputContentBufferFullPolicy::Block,
+        PROPOSAL_EXPIRY_GRACE_PERIOD,
+    );
+    proposal_manager.start_height(INITIAL_HEIGHT).await.unwrap();
+
+    // Up to the limit, the proposals are generated concurrently.
+    for id in 0..N_CONCURRENT_PROPOSALS {
+        build_proposal_non_blocking(
+            &mut proposal_manager,
+            propose_tx_provider.clone(),
+            ProposalId(id),
+        )
+        .await;
+    }
+    for id in 0..N_CONCURRENT_PROPOSALS {
+        assert_matches!(
+            proposal_manager.get_proposal_status(ProposalId(id)).await,
+            ProposalStatus::Processing
+        );
+    }
+
+    // Beyond the limit, a new proposal is rejected.
+    let (output_sender, _receiver) = output_streaming();
+    let new_proposal_id = ProposalId(N_CONCURRENT_PROPOSALS);
+    let rejected_request = proposal_manager
+        .build_block_proposal(
+            new_proposal_id,
+            None,
+            proposal_deadline(),
+            output_sender,
+            None,
+            propose_tx_provider.clone(),
+        )
+        .await;
+    assert_matches!(
+        rejected_request,
+        Err(GenerateProposalError::AlreadyGeneratingProposal {
+            current_generating_proposal_id,
+            new_proposal_id: rejected_proposal_id
+        }) if current_generating_proposal_id == ProposalId(0)
+            && rejected_proposal_id == new_proposal_id
+    );
+
+    // Once a proposal is done, there is room for a new one.
+    proposal_manager.abort_proposal(ProposalId(1)).await;
+    build_proposal_non_blocking(&mut proposal_manager, propose_tx_provider, new_proposal_id).await;
+}
+
 #[rstest]
 #[tokio::test]
 async fn abort_and_restart_height(
@@ -390,6 +550,25 @@ async fn abort_and_restart_height(
     );
 
     // Make sure there is no active proposal.
-    // TODO: uncommomment once the abort is implemented. This line will panic now.
-    // assert!(!proposal_manager.await_active_proposal().await);
+    assert!(!proposal_manager.await_active_proposal().await);
+}
+
+#[test]
+fn proposal_output_fails_on_da_gas_overflow() {
+    let tx_da_gas = GasVector { l1_gas: GasAmount(u64::MAX), ..GasVector::ZERO };
+    let execution_infos = (0..2_u8)
+        .map(|i| {
+            let execution_info = TransactionExecutionInfo {
+                receipt: TransactionReceipt { da_gas: tx_da_gas, ..Default::default() },
+                ..Default::default()
+            };
+            (TransactionHash(felt!(i)), execution_info)
+        })
+        .collect();
+    let artifacts = BlockExecutionArtifacts {
+        execution_infos,
+        ..BlockExecutionArtifacts::create_for_testing()
+    };
+
+    assert_matches!(ProposalOutput::try_from(artifacts), Err(BlockBuilderError::DaGasOverflow));
 }
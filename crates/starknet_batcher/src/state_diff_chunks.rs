@@ -0,0 +1,83 @@
+use indexmap::IndexSet;
+use papyrus_protobuf::sync::{
+    ContractDiff,
+    DeclaredClass,
+    DeprecatedDeclaredClass,
+    StateDiffChunk,
+};
+use starknet_api::state::ThinStateDiff;
+
+/// Splits the state diff into chunks of at most `max_chunk_size` entries (see
+/// [`StateDiffChunk::len`]), in a deterministic order: contract diffs, then declared classes, then
+/// deprecated declared classes.
+/// A contract diff larger than the limit is split into several contract diffs of the same contract.
+pub(crate) fn split_state_diff(
+    state_diff: ThinStateDiff,
+    max_chunk_size: usize,
+) -> Vec<StateDiffChunk> {
+    assert!(max_chunk_size > 0, "State diff chunks must hold at least one entry.");
+    let ThinStateDiff {
+        deployed_contracts,
+        mut storage_diffs,
+        declared_classes,
+        deprecated_declared_classes,
+        mut nonces,
+        replaced_classes,
+    } = state_diff;
+
+    let mut contract_addresses = IndexSet::new();
+    contract_addresses.extend(
+        deployed_contracts
+            .keys()
+            .chain(replaced_classes.keys())
+            .chain(nonces.keys())
+            .chain(storage_diffs.keys())
+            .copied(),
+    );
+
+    let mut chunks = Vec::new();
+    for contract_address in contract_addresses {
+        let mut contract_diff = ContractDiff { contract_address, ..Default::default() };
+        let mut push_if_full = |contract_diff: &mut ContractDiff| {
+            if contract_diff_len(contract_diff) == max_chunk_size {
+                let empty_contract_diff = ContractDiff { contract_address, ..Default::default() };
+                chunks.push(StateDiffChunk::ContractDiff(std::mem::replace(
+                    contract_diff,
+                    empty_contract_diff,
+                )));
+            }
+        };
+
+        contract_diff.class_hash = deployed_contracts
+            .get(&contract_address)
+            .or_else(|| replaced_classes.get(&contract_address))
+            .copied();
+        push_if_full(&mut contract_diff);
+        if let Some(nonce) = nonces.swap_remove(&contract_address) {
+            contract_diff.nonce = Some(nonce);
+            push_if_full(&mut contract_diff);
+        }
+        for (key, value) in storage_diffs.swap_remove(&contract_address).unwrap_or_default() {
+            contract_diff.storage_diffs.insert(key, value);
+            push_if_full(&mut contract_diff);
+        }
+
+        if contract_diff_len(&contract_diff) > 0 {
+            chunks.push(StateDiffChunk::ContractDiff(contract_diff));
+        }
+    }
+
+    chunks.extend(declared_classes.into_iter().map(|(class_hash, compiled_class_hash)| {
+        StateDiffChunk::DeclaredClass(DeclaredClass { class_hash, compiled_class_hash })
+    }));
+    chunks.extend(deprecated_declared_classes.into_iter().map(|class_hash| {
+        StateDiffChunk::DeprecatedDeclaredClass(DeprecatedDeclaredClass { class_hash })
+    }));
+    chunks
+}
+
+fn contract_diff_len(contract_diff: &ContractDiff) -> usize {
+    contract_diff.storage_diffs.len()
+        + usize::from(contract_diff.class_hash.is_some())
+        + usize::from(contract_diff.nonce.is_some())
+}
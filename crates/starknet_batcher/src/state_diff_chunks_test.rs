@@ -0,0 +1,22 @@
+use rstest::rstest;
+use starknet_api::state::ThinStateDiff;
+
+use crate::state_diff_chunks::split_state_diff;
+use crate::test_utils::{test_state_diff, unite_state_diff_chunks};
+
+#[rstest]
+#[case::single_entry_chunks(1)]
+#[case::contract_diff_split(2)]
+#[case::contract_diff_fits(5)]
+#[case::unlimited(usize::MAX)]
+fn split_state_diff_reassembles(#[case] max_chunk_size: usize) {
+    let chunks = split_state_diff(test_state_diff(), max_chunk_size);
+
+    assert!(chunks.iter().all(|chunk| !chunk.is_empty() && chunk.len() <= max_chunk_size));
+    assert_eq!(unite_state_diff_chunks(chunks), test_state_diff());
+}
+
+#[test]
+fn split_empty_state_diff() {
+    assert_eq!(split_state_diff(ThinStateDiff::default(), 1), vec![]);
+}
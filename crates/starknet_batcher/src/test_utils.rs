@@ -1,15 +1,38 @@
 use std::ops::Range;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
 
+use async_trait::async_trait;
 use blockifier::blockifier::transaction_executor::VisitedSegmentsMapping;
 use blockifier::bouncer::BouncerWeights;
 use blockifier::state::cached_state::CommitmentStateDiff;
-use indexmap::IndexMap;
+use indexmap::{indexmap, IndexMap};
+use papyrus_protobuf::sync::StateDiffChunk;
 use starknet_api::executable_transaction::{AccountTransaction, Transaction};
-use starknet_api::felt;
+use starknet_api::state::ThinStateDiff;
 use starknet_api::test_utils::invoke::{executable_invoke_tx, InvokeTxArgs};
 use starknet_api::transaction::TransactionHash;
+use starknet_api::{class_hash, compiled_class_hash, contract_address, felt, nonce, storage_key};
 
-use crate::block_builder::BlockExecutionArtifacts;
+use crate::block_builder::{BlockBuilderResult, BlockBuilderTrait, BlockExecutionArtifacts};
+
+// A block builder that never finishes building, and records when it is dropped.
+pub struct PendingBlockBuilder {
+    pub dropped: Arc<AtomicBool>,
+}
+
+#[async_trait]
+impl BlockBuilderTrait for PendingBlockBuilder {
+    async fn build_block(&mut self) -> BlockBuilderResult<BlockExecutionArtifacts> {
+        futures::future::pending().await
+    }
+}
+
+impl Drop for PendingBlockBuilder {
+    fn drop(&mut self) {
+        self.dropped.store(true, Ordering::SeqCst);
+    }
+}
 
 pub fn test_txs(tx_hash_range: Range<usize>) -> Vec<Transaction> {
     tx_hash_range
@@ -32,3 +55,63 @@ impl BlockExecutionArtifacts {
         }
     }
 }
+
+pub fn test_state_diff() -> ThinStateDiff {
+    ThinStateDiff {
+        deployed_contracts: indexmap! { contract_address!(1_u8) => class_hash!(1_u8) },
+        storage_diffs: indexmap! {
+            contract_address!(1_u8) => indexmap! {
+                storage_key!(1_u8) => felt!(1_u8),
+                storage_key!(2_u8) => felt!(2_u8),
+                storage_key!(3_u8) => felt!(3_u8),
+            },
+            contract_address!(2_u8) => indexmap! { storage_key!(1_u8) => felt!(4_u8) },
+        },
+        declared_classes: indexmap! { class_hash!(2_u8) => compiled_class_hash!(2_u8) },
+        deprecated_declared_classes: vec![class_hash!(3_u8)],
+        nonces: indexmap! {
+            contract_address!(1_u8) => nonce!(1_u8),
+            contract_address!(3_u8) => nonce!(1_u8),
+        },
+        replaced_classes: IndexMap::default(),
+    }
+}
+
+/// Reassembles state diff chunks into a state diff. Contract class hashes are treated as deployed.
+pub fn unite_state_diff_chunks(chunks: Vec<StateDiffChunk>) -> ThinStateDiff {
+    let mut state_diff = ThinStateDiff::default();
+    for chunk in chunks {
+        match chunk {
+            StateDiffChunk::ContractDiff(contract_diff) => {
+                let contract_address = contract_diff.contract_address;
+                if let Some(class_hash) = contract_diff.class_hash {
+                    assert!(
+                        state_diff
+                            .deployed_contracts
+                            .insert(contract_address, class_hash)
+                            .is_none()
+                    );
+                }
+                if let Some(nonce) = contract_diff.nonce {
+                    assert!(state_diff.nonces.insert(contract_address, nonce).is_none());
+                }
+                if !contract_diff.storage_diffs.is_empty() {
+                    state_diff
+                        .storage_diffs
+                        .entry(contract_address)
+                        .or_default()
+                        .extend(contract_diff.storage_diffs);
+                }
+            }
+            StateDiffChunk::DeclaredClass(declared_class) => {
+                state_diff
+                    .declared_classes
+                    .insert(declared_class.class_hash, declared_class.compiled_class_hash);
+            }
+            StateDiffChunk::DeprecatedDeclaredClass(deprecated_declared_class) => {
+                state_diff.deprecated_declared_classes.push(deprecated_declared_class.class_hash);
+            }
+        }
+    }
+    state_diff
+}
@@ -0,0 +1,84 @@
+use std::sync::Arc;
+
+use blockifier::context::BlockContext;
+use blockifier::execution::call_info::CallInfo;
+use blockifier::state::cached_state::TransactionalState;
+use blockifier::state::state_api::UpdatableState;
+use blockifier::transaction::errors::TransactionExecutionError as BlockifierTransactionExecutionError;
+use blockifier::transaction::objects::TransactionExecutionInfo;
+use blockifier::transaction::transaction_execution::Transaction as BlockifierTransaction;
+use blockifier::transaction::transactions::{ExecutableTransaction, ExecutionFlags};
+use blockifier::versioned_constants::VersionedConstants;
+#[cfg(test)]
+use mockall::automock;
+use serde::Serialize;
+use starknet_api::block::BlockNumber;
+use starknet_api::executable_transaction::Transaction;
+use starknet_batcher_types::batcher_types::{SimulateTransactionResponse, SimulationFlags};
+
+use crate::block_builder::{execution_outcome, BlockBuilderResult};
+
+/// Executes single transactions on top of the committed state, without committing their changes.
+#[cfg_attr(test, automock)]
+pub trait TransactionSimulatorTrait: Send + Sync {
+    /// Simulates the transaction as the first transaction of the block at the given height, on top
+    /// of the state committed up to that height.
+    fn simulate_transaction(
+        &self,
+        height: BlockNumber,
+        tx: Transaction,
+        flags: SimulationFlags,
+    ) -> BlockBuilderResult<SimulateTransactionResponse>;
+
+    /// The versioned constants under which the transactions are executed.
+    fn versioned_constants(&self) -> Arc<VersionedConstants>;
+}
+
+/// The call infos of a simulated transaction.
+#[derive(Serialize)]
+struct SimulationTrace<'a> {
+    validate_invocation: &'a Option<CallInfo>,
+    execute_invocation: &'a Option<CallInfo>,
+    fee_transfer_invocation: &'a Option<CallInfo>,
+}
+
+/// Executes the transaction on a scratch transactional state on top of `state`, and discards all
+/// the changes it made, whether the execution succeeded or not.
+pub(crate) fn simulate_transaction<U: UpdatableState>(
+    state: &mut U,
+    block_context: &BlockContext,
+    tx: Transaction,
+    flags: SimulationFlags,
+) -> Result<SimulateTransactionResponse, BlockifierTransactionExecutionError> {
+    let tx = BlockifierTransaction::from(tx);
+    let execution_flags = ExecutionFlags {
+        charge_fee: flags.charge_fee,
+        validate: !flags.skip_validate,
+        concurrency_mode: false,
+    };
+    let mut transactional_state = TransactionalState::create_transactional(state);
+    let execution_result = tx.execute_raw(&mut transactional_state, block_context, execution_flags);
+    transactional_state.abort();
+
+    let execution_info = execution_result?;
+    Ok(simulation_response(&execution_info, flags.include_trace))
+}
+
+fn simulation_response(
+    execution_info: &TransactionExecutionInfo,
+    include_trace: bool,
+) -> SimulateTransactionResponse {
+    let trace = include_trace.then(|| {
+        let trace = SimulationTrace {
+            validate_invocation: &execution_info.validate_call_info,
+            execute_invocation: &execution_info.execute_call_info,
+            fee_transfer_invocation: &execution_info.fee_transfer_call_info,
+        };
+        serde_json::to_string(&trace).expect("Call infos should be serializable to JSON.")
+    });
+    SimulateTransactionResponse {
+        execution_outcome: execution_outcome(execution_info),
+        actual_fee: execution_info.receipt.fee,
+        trace,
+    }
+}
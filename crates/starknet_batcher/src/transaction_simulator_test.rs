@@ -0,0 +1,45 @@
+use blockifier::context::BlockContext;
+use blockifier::state::cached_state::StateMaps;
+use blockifier::state::state_api::StateReader;
+use blockifier::test_utils::contracts::FeatureContract;
+use blockifier::test_utils::initial_test_state::test_state;
+use blockifier::test_utils::{create_trivial_calldata, CairoVersion, BALANCE};
+use rstest::rstest;
+use starknet_api::executable_transaction::{AccountTransaction, Transaction};
+use starknet_api::test_utils::invoke::executable_invoke_tx;
+use starknet_api::transaction::TransactionExecutionStatus;
+use starknet_api::{invoke_tx_args, nonce};
+use starknet_batcher_types::batcher_types::SimulationFlags;
+
+use crate::transaction_simulator::simulate_transaction;
+
+#[rstest]
+fn simulate_invoke_discards_state_changes(#[values(true, false)] include_trace: bool) {
+    let block_context = BlockContext::create_for_account_testing();
+    let account = FeatureContract::AccountWithoutValidations(CairoVersion::Cairo1);
+    let test_contract = FeatureContract::TestContract(CairoVersion::Cairo0);
+    let mut state =
+        test_state(block_context.chain_info(), BALANCE, &[(account, 1), (test_contract, 1)]);
+    let sender_address = account.get_instance_address(0);
+    let tx =
+        Transaction::Account(AccountTransaction::Invoke(executable_invoke_tx(invoke_tx_args! {
+            sender_address,
+            calldata: create_trivial_calldata(test_contract.get_instance_address(0)),
+        })));
+    let flags = SimulationFlags { include_trace, ..Default::default() };
+
+    let response = simulate_transaction(&mut state, &block_context, tx, flags).unwrap();
+
+    assert_eq!(response.execution_outcome.execution_status, TransactionExecutionStatus::Succeeded);
+    let gas_consumed = response.execution_outcome.gas_consumed;
+    assert!(gas_consumed.l1_gas.0 > 0 || gas_consumed.l2_gas.0 > 0);
+    assert_eq!(response.trace.is_some(), include_trace);
+    if let Some(trace) = response.trace {
+        let trace: serde_json::Value = serde_json::from_str(&trace).unwrap();
+        assert!(!trace["validate_invocation"].is_null());
+        assert!(!trace["execute_invocation"].is_null());
+    }
+    // The nonce increment and the storage writes of the transaction are discarded.
+    assert_eq!(state.get_nonce_at(sender_address).unwrap(), nonce!(0_u8));
+    assert_eq!(state.to_state_diff().unwrap(), StateMaps::default());
+}
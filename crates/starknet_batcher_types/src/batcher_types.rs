@@ -1,10 +1,14 @@
 use std::fmt::Debug;
 
 use chrono::prelude::*;
+use papyrus_protobuf::sync::StateDiffChunk;
 use serde::{Deserialize, Serialize};
 use starknet_api::block::{BlockHashAndNumber, BlockNumber};
 use starknet_api::core::StateDiffCommitment;
 use starknet_api::executable_transaction::Transaction;
+use starknet_api::execution_resources::GasVector;
+use starknet_api::transaction::fields::Fee;
+use starknet_api::transaction::TransactionExecutionStatus;
 
 use crate::errors::BatcherError;
 
@@ -44,14 +48,103 @@ pub struct BuildProposalInput {
 pub struct GetProposalContentInput {
     // TBD: We don't really need the proposal_id because there is only one proposal at a time.
     pub proposal_id: ProposalId,
+    /// The maximal number of transactions in a single response, clamped to the batcher's
+    /// configured chunk size. `None` means the configured chunk size.
+    #[serde(default)]
+    pub max_txs: Option<usize>,
 }
 
-#[derive(Clone, Debug, Serialize, Deserialize)]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub struct GetProposalContentResponse {
     pub content: GetProposalContent,
+    /// The execution outcomes of the transactions in `GetProposalContent::Txs`, in the same order.
+    /// [None] if the outcomes are unknown to the sender.
+    #[serde(default)]
+    pub execution_outcomes: Option<Vec<TransactionExecutionOutcome>>,
+}
+
+/// The outcome of executing a transaction during block building.
+#[derive(Clone, Debug, Default, Eq, PartialEq, Serialize, Deserialize)]
+pub struct TransactionExecutionOutcome {
+    pub execution_status: TransactionExecutionStatus,
+    pub gas_consumed: GasVector,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct GetStateDiffChunksInput {
+    pub proposal_id: ProposalId,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct GetStateDiffChunksResponse {
+    /// The state diff of the proposal, in order. Each chunk holds at most the configured number of
+    /// entries.
+    pub chunks: Vec<StateDiffChunk>,
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct GetBlockResourceSummaryInput {
+    pub block_number: BlockNumber,
+}
+
+/// The resources used by a committed block, aggregated over its transactions.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct BlockResourceSummary {
+    pub n_txs: usize,
+    /// The weights of the block counted against its capacity.
+    pub weights: BlockWeights,
+    /// The data availability gas of the block's transactions.
+    pub da_gas: GasVector,
+}
+
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, Serialize, Deserialize)]
+pub struct BlockWeights {
+    pub builtin_count: BlockBuiltinCount,
+    pub gas: usize,
+    pub message_segment_length: usize,
+    pub n_events: usize,
+    pub n_steps: usize,
+    pub state_diff_size: usize,
+}
+
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, Serialize, Deserialize)]
+pub struct BlockBuiltinCount {
+    pub add_mod: usize,
+    pub bitwise: usize,
+    pub ecdsa: usize,
+    pub ec_op: usize,
+    pub keccak: usize,
+    pub mul_mod: usize,
+    pub pedersen: usize,
+    pub poseidon: usize,
+    pub range_check: usize,
+    pub range_check96: usize,
+}
+
+/// Flags controlling how a transaction is simulated.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, Serialize, Deserialize)]
+pub struct SimulationFlags {
+    pub skip_validate: bool,
+    pub charge_fee: bool,
+    /// Whether to return the call infos of the execution (i.e. its trace).
+    pub include_trace: bool,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct SimulateTransactionInput {
+    pub tx: Transaction,
+    pub flags: SimulationFlags,
+}
+
+#[derive(Clone, Debug, Default, Eq, PartialEq, Serialize, Deserialize)]
+pub struct SimulateTransactionResponse {
+    pub execution_outcome: TransactionExecutionOutcome,
+    pub actual_fee: Fee,
+    /// The call infos of the execution, serialized as JSON. Omitted unless a trace was requested.
+    pub trace: Option<String>,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub enum GetProposalContent {
     Txs(Vec<Transaction>),
     Finished(ProposalCommitment),
@@ -66,13 +159,31 @@ pub struct ValidateProposalInput {
 }
 
 impl BuildProposalInput {
-    pub fn deadline_as_instant(&self) -> Result<std::time::Instant, chrono::OutOfRangeError> {
-        let time_to_deadline = self.deadline - chrono::Utc::now();
-        let as_duration = time_to_deadline.to_std()?;
-        Ok(std::time::Instant::now() + as_duration)
+    pub fn deadline_as_instant(&self) -> Result<std::time::Instant, BatcherError> {
+        deadline_as_instant(self.deadline)
     }
 }
 
+impl ValidateProposalInput {
+    pub fn deadline_as_instant(&self) -> Result<std::time::Instant, BatcherError> {
+        deadline_as_instant(self.deadline)
+    }
+}
+
+// Converts the deadline to an instant, failing if it already passed or if the instant can't be
+// represented.
+fn deadline_as_instant(deadline: DateTime<Utc>) -> Result<std::time::Instant, BatcherError> {
+    let time_to_deadline = deadline - chrono::Utc::now();
+    if time_to_deadline < chrono::TimeDelta::zero() {
+        return Err(BatcherError::DeadlineInPast { deadline });
+    }
+    time_to_deadline
+        .to_std()
+        .ok()
+        .and_then(|as_duration| std::time::Instant::now().checked_add(as_duration))
+        .ok_or(BatcherError::TimeToDeadlineError { deadline })
+}
+
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct SendProposalContentInput {
     pub proposal_id: ProposalId,
@@ -83,7 +194,9 @@ pub struct SendProposalContentInput {
 #[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub enum SendProposalContent {
     Txs(Vec<Transaction>),
-    Finish,
+    /// Marks the end of the content. If the proposer's commitment is given, the proposal is valid
+    /// only if the re-executed content yields the same commitment.
+    Finish(Option<ProposalCommitment>),
     Abort,
 }
 
@@ -92,7 +205,7 @@ pub struct SendProposalContentResponse {
     pub response: ProposalStatus,
 }
 
-#[derive(Clone, Debug, Serialize, Deserialize)]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub enum ProposalStatus {
     Processing,
     // Only sent in response to `Finish`.
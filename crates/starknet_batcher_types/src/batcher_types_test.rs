@@ -0,0 +1,125 @@
+use std::time::{Duration, Instant};
+
+use chrono::{DateTime, Utc};
+use starknet_api::executable_transaction::{AccountTransaction, Transaction};
+use starknet_api::execution_resources::{GasAmount, GasVector};
+use starknet_api::felt;
+use starknet_api::test_utils::invoke::{executable_invoke_tx, InvokeTxArgs};
+use starknet_api::transaction::{
+    RevertedTransactionExecutionStatus,
+    TransactionExecutionStatus,
+    TransactionHash,
+};
+
+use crate::batcher_types::{
+    BuildProposalInput,
+    GetProposalContent,
+    GetProposalContentInput,
+    GetProposalContentResponse,
+    ProposalId,
+    TransactionExecutionOutcome,
+    ValidateProposalInput,
+};
+use crate::errors::BatcherError;
+
+fn build_proposal_input(deadline: DateTime<Utc>) -> BuildProposalInput {
+    BuildProposalInput { proposal_id: ProposalId(0), deadline, retrospective_block_hash: None }
+}
+
+fn validate_proposal_input(deadline: DateTime<Utc>) -> ValidateProposalInput {
+    ValidateProposalInput { proposal_id: ProposalId(0), deadline, retrospective_block_hash: None }
+}
+
+#[test]
+fn deadline_in_past() {
+    let deadline = Utc::now() - chrono::Duration::seconds(1);
+    assert_eq!(
+        build_proposal_input(deadline).deadline_as_instant(),
+        Err(BatcherError::DeadlineInPast { deadline })
+    );
+    assert_eq!(
+        validate_proposal_input(deadline).deadline_as_instant(),
+        Err(BatcherError::DeadlineInPast { deadline })
+    );
+}
+
+#[test]
+fn deadline_in_near_future() {
+    let deadline = Utc::now() + chrono::Duration::seconds(1);
+    for instant in [
+        build_proposal_input(deadline).deadline_as_instant().unwrap(),
+        validate_proposal_input(deadline).deadline_as_instant().unwrap(),
+    ] {
+        let time_to_deadline = instant.saturating_duration_since(Instant::now());
+        assert!(time_to_deadline > Duration::ZERO);
+        assert!(time_to_deadline <= Duration::from_secs(1));
+    }
+}
+
+#[test]
+fn deadline_in_far_future() {
+    const TIME_TO_DEADLINE: Duration = Duration::from_secs(100 * 365 * 24 * 60 * 60);
+    let deadline = Utc::now() + TIME_TO_DEADLINE;
+    for instant in [
+        build_proposal_input(deadline).deadline_as_instant().unwrap(),
+        validate_proposal_input(deadline).deadline_as_instant().unwrap(),
+    ] {
+        let time_to_deadline = instant.saturating_duration_since(Instant::now());
+        assert!(time_to_deadline > TIME_TO_DEADLINE - Duration::from_secs(1));
+        assert!(time_to_deadline <= TIME_TO_DEADLINE);
+    }
+}
+
+#[test]
+fn get_proposal_content_response_serde_round_trip() {
+    let tx = |tx_hash| {
+        Transaction::Account(AccountTransaction::Invoke(executable_invoke_tx(InvokeTxArgs {
+            tx_hash: TransactionHash(felt!(tx_hash)),
+            ..Default::default()
+        })))
+    };
+    let response = GetProposalContentResponse {
+        content: GetProposalContent::Txs(vec![tx(1_u8), tx(2_u8)]),
+        execution_outcomes: Some(vec![
+            TransactionExecutionOutcome {
+                execution_status: TransactionExecutionStatus::Succeeded,
+                gas_consumed: GasVector::from_l1_gas(GasAmount(100)),
+            },
+            TransactionExecutionOutcome {
+                execution_status: TransactionExecutionStatus::Reverted(
+                    RevertedTransactionExecutionStatus { revert_reason: "reason".to_string() },
+                ),
+                gas_consumed: GasVector::from_l2_gas(GasAmount(200)),
+            },
+        ]),
+    };
+
+    let serialized = serde_json::to_string(&response).unwrap();
+    assert_eq!(serde_json::from_str::<GetProposalContentResponse>(&serialized).unwrap(), response);
+}
+
+#[test]
+fn get_proposal_content_response_without_execution_outcomes() {
+    let response = GetProposalContentResponse {
+        content: GetProposalContent::Txs(vec![]),
+        execution_outcomes: Some(vec![]),
+    };
+    let mut serialized = serde_json::to_value(&response).unwrap();
+    serialized.as_object_mut().unwrap().remove("execution_outcomes");
+
+    assert_eq!(
+        serde_json::from_value::<GetProposalContentResponse>(serialized).unwrap(),
+        GetProposalContentResponse { execution_outcomes: None, ..response }
+    );
+}
+
+#[test]
+fn get_proposal_content_input_without_max_txs() {
+    let input = GetProposalContentInput { proposal_id: ProposalId(0), max_txs: Some(1) };
+    let mut serialized = serde_json::to_value(&input).unwrap();
+    serialized.as_object_mut().unwrap().remove("max_txs");
+
+    let deserialized = serde_json::from_value::<GetProposalContentInput>(serialized).unwrap();
+    assert_eq!(deserialized.proposal_id, input.proposal_id);
+    assert_eq!(deserialized.max_txs, None);
+}
@@ -18,12 +18,18 @@ use thiserror::Error;
 
 use crate::batcher_types::{
     BatcherResult,
+    BlockResourceSummary,
     BuildProposalInput,
     DecisionReachedInput,
+    GetBlockResourceSummaryInput,
     GetProposalContentInput,
     GetProposalContentResponse,
+    GetStateDiffChunksInput,
+    GetStateDiffChunksResponse,
     SendProposalContentInput,
     SendProposalContentResponse,
+    SimulateTransactionInput,
+    SimulateTransactionResponse,
     StartHeightInput,
     ValidateProposalInput,
 };
@@ -58,6 +64,8 @@ pub trait BatcherClient: Send + Sync {
     ///     send.
     ///   * If this marks the end of the content, i.e. `SendProposalContent::Finish` is received,
     ///     the batcher will block until the proposal has finished processing before responding.
+    ///   * Once a proposal is found invalid, any further content for it is ignored and answered
+    ///     with `InvalidProposal`.
     async fn send_proposal_content(
         &self,
         input: SendProposalContentInput,
@@ -69,6 +77,22 @@ pub trait BatcherClient: Send + Sync {
     /// Notifies the batcher that a decision has been reached.
     /// This closes the process of the given height, and the accepted proposal is committed.
     async fn decision_reached(&self, input: DecisionReachedInput) -> BatcherClientResult<()>;
+    /// Gets the state diff of a decided proposal, split into chunks for DA publishing.
+    async fn get_state_diff_chunks(
+        &self,
+        input: GetStateDiffChunksInput,
+    ) -> BatcherClientResult<GetStateDiffChunksResponse>;
+    /// Executes a single transaction on top of the latest committed state and discards the
+    /// resulting state changes.
+    async fn simulate_transaction(
+        &self,
+        input: SimulateTransactionInput,
+    ) -> BatcherClientResult<SimulateTransactionResponse>;
+    /// Gets the resources used by a recently committed block.
+    async fn get_block_resource_summary(
+        &self,
+        input: GetBlockResourceSummaryInput,
+    ) -> BatcherClientResult<BlockResourceSummary>;
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -79,6 +103,9 @@ pub enum BatcherRequest {
     SendProposalContent(SendProposalContentInput),
     StartHeight(StartHeightInput),
     DecisionReached(DecisionReachedInput),
+    GetStateDiffChunks(GetStateDiffChunksInput),
+    SimulateTransaction(SimulateTransactionInput),
+    GetBlockResourceSummary(GetBlockResourceSummaryInput),
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -89,6 +116,9 @@ pub enum BatcherResponse {
     SendProposalContent(BatcherResult<SendProposalContentResponse>),
     StartHeight(BatcherResult<()>),
     DecisionReached(BatcherResult<()>),
+    GetStateDiffChunks(BatcherResult<GetStateDiffChunksResponse>),
+    SimulateTransaction(BatcherResult<SimulateTransactionResponse>),
+    GetBlockResourceSummary(BatcherResult<BlockResourceSummary>),
 }
 
 #[derive(Clone, Debug, Error)]
@@ -165,4 +195,46 @@ where
             BatcherError
         )
     }
+
+    async fn get_state_diff_chunks(
+        &self,
+        input: GetStateDiffChunksInput,
+    ) -> BatcherClientResult<GetStateDiffChunksResponse> {
+        let request = BatcherRequest::GetStateDiffChunks(input);
+        let response = self.send(request).await;
+        handle_response_variants!(
+            BatcherResponse,
+            GetStateDiffChunks,
+            BatcherClientError,
+            BatcherError
+        )
+    }
+
+    async fn simulate_transaction(
+        &self,
+        input: SimulateTransactionInput,
+    ) -> BatcherClientResult<SimulateTransactionResponse> {
+        let request = BatcherRequest::SimulateTransaction(input);
+        let response = self.send(request).await;
+        handle_response_variants!(
+            BatcherResponse,
+            SimulateTransaction,
+            BatcherClientError,
+            BatcherError
+        )
+    }
+
+    async fn get_block_resource_summary(
+        &self,
+        input: GetBlockResourceSummaryInput,
+    ) -> BatcherClientResult<BlockResourceSummary> {
+        let request = BatcherRequest::GetBlockResourceSummary(input);
+        let response = self.send(request).await;
+        handle_response_variants!(
+            BatcherResponse,
+            GetBlockResourceSummary,
+            BatcherClientError,
+            BatcherError
+        )
+    }
 }
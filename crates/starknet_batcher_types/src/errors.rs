@@ -1,12 +1,20 @@
 use chrono::prelude::*;
 use serde::{Deserialize, Serialize};
 use starknet_api::block::BlockNumber;
+use starknet_api::transaction::TransactionHash;
 use thiserror::Error;
 
 use crate::batcher_types::ProposalId;
 
 #[derive(Clone, Debug, Error, PartialEq, Eq, Serialize, Deserialize)]
 pub enum BatcherError {
+    #[error(
+        "Resource summary of block {block_number} not found. Only the most recent blocks \
+         committed by this batcher are kept."
+    )]
+    BlockResourceSummaryNotFound { block_number: BlockNumber },
+    #[error("Deadline {deadline} already passed.")]
+    DeadlineInPast { deadline: chrono::DateTime<Utc> },
     #[error(
         "Decision reached for proposal with ID {proposal_id} that does not exist (might still \
          being executed)."
@@ -37,8 +45,14 @@ pub enum BatcherError {
     ProposalFailed,
     #[error("Proposal aborted.")]
     ProposalAborted,
+    #[error("Proposal expired before it was done.")]
+    ProposalExpired,
+    #[error("Proposal with ID {proposal_id} was not decided.")]
+    ProposalNotDecided { proposal_id: ProposalId },
     #[error("Proposal with ID {proposal_id} not found.")]
     ProposalNotFound { proposal_id: ProposalId },
+    #[error("Simulation of transaction {tx_hash} failed: {error}.")]
+    SimulationFailed { tx_hash: TransactionHash, error: String },
     #[error(
         "Storage is not synced. Storage height: {storage_height}, requested height: \
          {requested_height}."
@@ -1,3 +1,5 @@
 pub mod batcher_types;
+#[cfg(test)]
+mod batcher_types_test;
 pub mod communication;
 pub mod errors;
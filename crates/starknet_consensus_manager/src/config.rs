@@ -1,23 +1,37 @@
 use std::collections::BTreeMap;
 
-use papyrus_config::dumping::{append_sub_config_name, SerializeConfig};
-use papyrus_config::{ParamPath, SerializedParam};
+use papyrus_config::dumping::{append_sub_config_name, ser_param, SerializeConfig};
+use papyrus_config::{ParamPath, ParamPrivacyInput, SerializedParam};
 use papyrus_consensus::config::ConsensusConfig;
 use serde::{Deserialize, Serialize};
 use validator::Validate;
 
 /// The consensus manager related configuration.
 /// TODO(Matan): Remove ConsensusManagerConfig if it's only field remains ConsensusConfig.
-#[derive(Clone, Default, Debug, Serialize, Deserialize, Validate, PartialEq)]
+#[derive(Clone, Debug, Serialize, Deserialize, Validate, PartialEq)]
 pub struct ConsensusManagerConfig {
     pub consensus_config: ConsensusConfig,
+    /// The maximal number of transactions a single batch of a proposal received from a peer may
+    /// hold.
+    #[validate(range(min = 1))]
+    pub max_transaction_batch_size: usize,
 }
 
 impl SerializeConfig for ConsensusManagerConfig {
     fn dump(&self) -> BTreeMap<ParamPath, SerializedParam> {
-        let sub_configs =
-            vec![append_sub_config_name(self.consensus_config.dump(), "consensus_config")];
+        let mut config = BTreeMap::from_iter([ser_param(
+            "max_transaction_batch_size",
+            &self.max_transaction_batch_size,
+            "The maximal number of transactions in a batch of a proposal received from a peer.",
+            ParamPrivacyInput::Public,
+        )]);
+        config.extend(append_sub_config_name(self.consensus_config.dump(), "consensus_config"));
+        config
+    }
+}
 
-        sub_configs.into_iter().flatten().collect()
+impl Default for ConsensusManagerConfig {
+    fn default() -> Self {
+        Self { consensus_config: ConsensusConfig::default(), max_transaction_batch_size: 10_000 }
     }
 }
@@ -47,6 +47,7 @@ impl ConsensusManager {
             Arc::clone(&self.batcher_client),
             proposals_broadcast_channels.broadcast_topic_client.clone(),
             self.config.consensus_config.num_validators,
+            self.config.max_transaction_batch_size,
         );
 
         let mut network_handle = tokio::task::spawn(network_manager.run());
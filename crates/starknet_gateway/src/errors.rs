@@ -2,7 +2,7 @@ use axum::http::StatusCode;
 use blockifier::state::errors::StateError;
 use serde_json::{Error as SerdeError, Value};
 use starknet_api::block::GasPrice;
-use starknet_api::transaction::fields::{Resource, ResourceBounds};
+use starknet_api::transaction::fields::{Resource, ResourceBounds, ResourceBoundsError};
 use starknet_api::StarknetApiError;
 use starknet_gateway_types::errors::GatewaySpecError;
 use thiserror::Error;
@@ -33,6 +33,8 @@ pub enum StatelessTransactionValidatorError {
     #[error("Invalid {field_name} data availability mode.")]
     InvalidDataAvailabilityMode { field_name: String },
     #[error(transparent)]
+    InvalidResourceBounds(#[from] ResourceBoundsError),
+    #[error(transparent)]
     InvalidSierraVersion(#[from] VersionIdError),
     #[error(
         "Signature length exceeded maximum: length {signature_length}
@@ -64,6 +66,7 @@ impl From<StatelessTransactionValidatorError> for GatewaySpecError {
             StatelessTransactionValidatorError::CalldataTooLong { .. }
             | StatelessTransactionValidatorError::EntryPointsNotUniquelySorted
             | StatelessTransactionValidatorError::InvalidDataAvailabilityMode { .. }
+            | StatelessTransactionValidatorError::InvalidResourceBounds(..)
             | StatelessTransactionValidatorError::InvalidSierraVersion(..)
             | StatelessTransactionValidatorError::NonEmptyField { .. }
             | StatelessTransactionValidatorError::SignatureTooLong { .. }
@@ -2,6 +2,7 @@ use std::clone::Clone;
 use std::sync::Arc;
 
 use blockifier::context::ChainInfo;
+use blockifier::versioned_constants::VersionedConstants;
 use papyrus_network_types::network_types::BroadcastedMessageMetadata;
 use starknet_api::executable_transaction::AccountTransaction;
 use starknet_api::rpc_transaction::RpcTransaction;
@@ -44,6 +45,7 @@ impl Gateway {
         state_reader_factory: Arc<dyn StateReaderFactory>,
         gateway_compiler: GatewayCompiler,
         mempool_client: SharedMempoolClient,
+        versioned_constants: Arc<VersionedConstants>,
     ) -> Self {
         Self {
             config: config.clone(),
@@ -52,6 +54,7 @@ impl Gateway {
             }),
             stateful_tx_validator: Arc::new(StatefulTransactionValidator {
                 config: config.stateful_tx_validator_config.clone(),
+                versioned_constants,
             }),
             state_reader_factory,
             gateway_compiler: Arc::new(gateway_compiler),
@@ -152,11 +155,18 @@ pub fn create_gateway(
     rpc_state_reader_config: RpcStateReaderConfig,
     compiler_config: SierraToCasmCompilationConfig,
     mempool_client: SharedMempoolClient,
+    versioned_constants: Arc<VersionedConstants>,
 ) -> Gateway {
     let state_reader_factory = Arc::new(RpcStateReaderFactory { config: rpc_state_reader_config });
     let gateway_compiler = GatewayCompiler::new_command_line_compiler(compiler_config);
 
-    Gateway::new(config, state_reader_factory, gateway_compiler, mempool_client)
+    Gateway::new(
+        config,
+        state_reader_factory,
+        gateway_compiler,
+        mempool_client,
+        versioned_constants,
+    )
 }
 
 impl ComponentStarter for Gateway {}
@@ -3,6 +3,7 @@ use std::sync::Arc;
 use assert_matches::assert_matches;
 use blockifier::context::ChainInfo;
 use blockifier::test_utils::CairoVersion;
+use blockifier::versioned_constants::VersionedConstants;
 use mempool_test_utils::starknet_api_test_utils::{declare_tx, invoke_tx};
 use mockall::predicate::eq;
 use papyrus_network_types::network_types::BroadcastedMessageMetadata;
@@ -63,11 +64,15 @@ struct MockDependencies {
 
 impl MockDependencies {
     fn gateway(self) -> Gateway {
+        let versioned_constants = VersionedConstants::get_versioned_constants(
+            self.config.stateful_tx_validator_config.versioned_constants_overrides.clone(),
+        );
         Gateway::new(
             self.config,
             Arc::new(self.state_reader_factory),
             self.compiler,
             Arc::new(self.mock_mempool_client),
+            Arc::new(versioned_constants),
         )
     }
 
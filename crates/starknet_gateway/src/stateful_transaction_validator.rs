@@ -1,3 +1,5 @@
+use std::sync::Arc;
+
 use blockifier::blockifier::block::BlockInfo;
 use blockifier::blockifier::stateful_validator::{
     StatefulValidator,
@@ -29,6 +31,9 @@ mod stateful_transaction_validator_test;
 
 pub struct StatefulTransactionValidator {
     pub config: StatefulTransactionValidatorConfig,
+    // Shared with the batcher, so that transactions are validated and executed under the same
+    // versioned constants.
+    pub versioned_constants: Arc<VersionedConstants>,
 }
 
 type BlockifierStatefulValidator = StatefulValidator<Box<dyn MempoolStateReader>>;
@@ -92,9 +97,7 @@ impl StatefulTransactionValidator {
         let latest_block_info = get_latest_block_info(state_reader_factory)?;
         let state_reader = state_reader_factory.get_state_reader(latest_block_info.block_number);
         let state = CachedState::new(state_reader);
-        let versioned_constants = VersionedConstants::get_versioned_constants(
-            self.config.versioned_constants_overrides.clone(),
-        );
+        let versioned_constants = VersionedConstants::clone(&self.versioned_constants);
         let mut block_info = latest_block_info;
         block_info.block_number = block_info.block_number.unchecked_next();
         // TODO(yael 21/4/24): create the block context using pre_process_block once we will be
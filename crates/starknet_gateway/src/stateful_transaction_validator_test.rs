@@ -1,3 +1,5 @@
+use std::sync::Arc;
+
 use blockifier::blockifier::stateful_validator::{
     StatefulValidatorError as BlockifierStatefulValidatorError,
     StatefulValidatorResult as BlockifierStatefulValidatorResult,
@@ -5,6 +7,7 @@ use blockifier::blockifier::stateful_validator::{
 use blockifier::context::ChainInfo;
 use blockifier::test_utils::CairoVersion;
 use blockifier::transaction::errors::{TransactionFeeError, TransactionPreValidationError};
+use blockifier::versioned_constants::VersionedConstants;
 use mempool_test_utils::starknet_api_test_utils::{
     executable_invoke_tx as create_executable_invoke_tx,
     TEST_SENDER_ADDRESS,
@@ -48,7 +51,11 @@ pub const STATEFUL_VALIDATOR_FEE_ERROR: BlockifierStatefulValidatorError =
 
 #[fixture]
 fn stateful_validator() -> StatefulTransactionValidator {
-    StatefulTransactionValidator { config: StatefulTransactionValidatorConfig::default() }
+    let config = StatefulTransactionValidatorConfig::default();
+    let versioned_constants = Arc::new(VersionedConstants::get_versioned_constants(
+        config.versioned_constants_overrides.clone(),
+    ));
+    StatefulTransactionValidator { config, versioned_constants }
 }
 
 // TODO(Arni): consider testing declare and deploy account.
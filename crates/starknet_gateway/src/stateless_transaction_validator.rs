@@ -56,6 +56,8 @@ impl StatelessTransactionValidator {
         }
         if self.config.validate_non_zero_l2_gas_fee {
             validate_resource_is_non_zero(resource_bounds_mapping, Resource::L2Gas)?;
+            let AllResourceBounds { l1_gas, l2_gas, l1_data_gas } = *resource_bounds_mapping;
+            AllResourceBounds::validated(l1_gas, l2_gas, l1_data_gas)?;
         }
         if self.config.validate_non_zero_l1_data_gas_fee {
             validate_resource_is_non_zero(resource_bounds_mapping, Resource::L1DataGas)?;
@@ -5,8 +5,10 @@ use assert_matches::assert_matches;
 use mempool_test_utils::declare_tx_args;
 use mempool_test_utils::starknet_api_test_utils::rpc_declare_tx;
 use rstest::rstest;
+use starknet_api::block::GasPrice;
 use starknet_api::core::{EntryPointSelector, L2_ADDRESS_UPPER_BOUND};
 use starknet_api::data_availability::DataAvailabilityMode;
+use starknet_api::execution_resources::GasAmount;
 use starknet_api::rpc_transaction::{ContractClass, EntryPointByType};
 use starknet_api::state::EntryPoint;
 use starknet_api::transaction::fields::{
@@ -15,6 +17,7 @@ use starknet_api::transaction::fields::{
     PaymasterData,
     Resource,
     ResourceBounds,
+    ResourceBoundsError,
     TransactionSignature,
 };
 use starknet_api::{calldata, contract_address, felt, StarknetApiError};
@@ -50,6 +53,12 @@ static DEFAULT_VALIDATOR_CONFIG_FOR_TESTING: LazyLock<StatelessTransactionValida
         max_sierra_version: *MAX_SIERRA_VERSION,
     });
 
+const MAX_FEE_OVERFLOW_RESOURCE_BOUNDS: AllResourceBounds = AllResourceBounds {
+    l1_gas: NON_EMPTY_RESOURCE_BOUNDS,
+    l2_gas: ResourceBounds { max_amount: GasAmount(2), max_price_per_unit: GasPrice(u128::MAX) },
+    l1_data_gas: NON_EMPTY_RESOURCE_BOUNDS,
+};
+
 #[rstest]
 #[case::ignore_resource_bounds(
     StatelessTransactionValidatorConfig {
@@ -174,6 +183,16 @@ fn test_positive_flow(
         resource: Resource::L1DataGas, resource_bounds: ResourceBounds::default()
     }
 )]
+#[case::max_fee_overflow(
+    StatelessTransactionValidatorConfig{
+        validate_non_zero_l2_gas_fee: true,
+        ..*DEFAULT_VALIDATOR_CONFIG_FOR_TESTING
+    },
+    MAX_FEE_OVERFLOW_RESOURCE_BOUNDS,
+    StatelessTransactionValidatorError::InvalidResourceBounds(
+        ResourceBoundsError::MaxFeeOverflow(MAX_FEE_OVERFLOW_RESOURCE_BOUNDS)
+    )
+)]
 fn test_invalid_resource_bounds(
     #[case] config: StatelessTransactionValidatorConfig,
     #[case] resource_bounds: AllResourceBounds,
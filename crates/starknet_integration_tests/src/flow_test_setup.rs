@@ -61,7 +61,8 @@ impl FlowTestSetup {
         let (config, _required_params, consensus_proposals_channels) =
             create_config(rpc_server_addr, storage_for_test.batcher_storage_config).await;
 
-        let (clients, servers) = create_node_modules(&config);
+        let (clients, servers) =
+            create_node_modules(&config).expect("Failed to create the node modules.");
 
         let HttpServerConfig { ip, port } = config.http_server_config;
         let add_tx_http_client = HttpTestClient::new(SocketAddr::from((ip, port)));
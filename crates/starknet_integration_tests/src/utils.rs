@@ -84,6 +84,7 @@ fn create_consensus_manager_config_and_channels()
             network_config,
             ..Default::default()
         },
+        ..Default::default()
     };
     (consensus_manager_config, broadcast_channels)
 }
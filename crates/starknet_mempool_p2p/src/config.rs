@@ -1,6 +1,12 @@
 use std::collections::BTreeMap;
+use std::time::Duration;
 
+use papyrus_config::converters::{
+    deserialize_milliseconds_to_duration,
+    deserialize_seconds_to_duration,
+};
 use papyrus_config::dumping::{append_sub_config_name, ser_param, SerializeConfig};
+use papyrus_config::validators::validate_non_zero_duration;
 use papyrus_config::{ParamPath, ParamPrivacyInput, SerializedParam};
 use papyrus_network::NetworkConfig;
 use serde::{Deserialize, Serialize};
@@ -13,6 +19,13 @@ pub struct MempoolP2pConfig {
     // TODO: Enter this inside NetworkConfig
     pub executable_version: Option<String>,
     pub network_buffer_size: usize,
+    #[validate(range(min = 1))]
+    pub max_concurrent_gateway_requests: usize,
+    pub gateway_requests_soft_limit: usize,
+    #[validate(range(min = 1))]
+    pub seen_transactions_cache_size: usize,
+    #[validate]
+    pub gateway_retry_config: GatewayRetryConfig,
 }
 
 impl Default for MempoolP2pConfig {
@@ -22,6 +35,10 @@ impl Default for MempoolP2pConfig {
             // TODO: Consider filling this once the sequencer node has a name.
             executable_version: None,
             network_buffer_size: 10000,
+            max_concurrent_gateway_requests: 1000,
+            gateway_requests_soft_limit: 500,
+            seen_transactions_cache_size: 10000,
+            gateway_retry_config: GatewayRetryConfig::default(),
         }
     }
 }
@@ -29,16 +46,90 @@ impl Default for MempoolP2pConfig {
 impl SerializeConfig for MempoolP2pConfig {
     fn dump(&self) -> BTreeMap<ParamPath, SerializedParam> {
         vec![
-            BTreeMap::from_iter([ser_param(
-                "network_buffer_size",
-                &self.network_buffer_size,
-                "Network buffer size.",
-                ParamPrivacyInput::Public,
-            )]),
+            BTreeMap::from_iter([
+                ser_param(
+                    "network_buffer_size",
+                    &self.network_buffer_size,
+                    "Network buffer size.",
+                    ParamPrivacyInput::Public,
+                ),
+                ser_param(
+                    "max_concurrent_gateway_requests",
+                    &self.max_concurrent_gateway_requests,
+                    "Maximum number of transactions received from the network that may be \
+                     forwarded to the gateway concurrently.",
+                    ParamPrivacyInput::Public,
+                ),
+                ser_param(
+                    "gateway_requests_soft_limit",
+                    &self.gateway_requests_soft_limit,
+                    "Number of in-flight gateway requests above which finished requests are \
+                     handled before accepting new transactions from the network.",
+                    ParamPrivacyInput::Public,
+                ),
+                ser_param(
+                    "seen_transactions_cache_size",
+                    &self.seen_transactions_cache_size,
+                    "Number of recently received transactions remembered in order to avoid \
+                     forwarding duplicates to the gateway.",
+                    ParamPrivacyInput::Public,
+                ),
+            ]),
             append_sub_config_name(self.network_config.dump(), "network_config"),
+            append_sub_config_name(self.gateway_retry_config.dump(), "gateway_retry_config"),
         ]
         .into_iter()
         .flatten()
         .collect()
     }
 }
+
+/// Configuration for resubmitting transactions that the gateway failed to add due to a transient
+/// error.
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq, Validate)]
+pub struct GatewayRetryConfig {
+    #[validate(range(min = 1))]
+    pub queue_capacity: usize,
+    #[serde(deserialize_with = "deserialize_seconds_to_duration")]
+    pub max_age: Duration,
+    #[serde(deserialize_with = "deserialize_milliseconds_to_duration")]
+    #[validate(custom = "validate_non_zero_duration")]
+    pub interval: Duration,
+}
+
+impl Default for GatewayRetryConfig {
+    fn default() -> Self {
+        Self {
+            queue_capacity: 1000,
+            max_age: Duration::from_secs(60),
+            interval: Duration::from_millis(1000),
+        }
+    }
+}
+
+impl SerializeConfig for GatewayRetryConfig {
+    fn dump(&self) -> BTreeMap<ParamPath, SerializedParam> {
+        BTreeMap::from_iter([
+            ser_param(
+                "queue_capacity",
+                &self.queue_capacity,
+                "Maximum number of transactions waiting to be resubmitted to the gateway.",
+                ParamPrivacyInput::Public,
+            ),
+            ser_param(
+                "max_age",
+                &self.max_age.as_secs(),
+                "Time (seconds) since the first failure after which a transaction is no longer \
+                 resubmitted to the gateway.",
+                ParamPrivacyInput::Public,
+            ),
+            ser_param(
+                "interval",
+                &self.interval.as_millis(),
+                "Time (milliseconds) between consecutive resubmissions of the waiting \
+                 transactions to the gateway.",
+                ParamPrivacyInput::Public,
+            ),
+        ])
+    }
+}
@@ -1,21 +1,27 @@
 pub mod config;
+pub mod metrics;
 pub mod propagator;
 pub mod runner;
 
+use futures::channel::mpsc::Receiver;
+use futures::FutureExt;
 use papyrus_network::gossipsub_impl::Topic;
 use papyrus_network::network_manager::{BroadcastTopicChannels, NetworkManager};
 use starknet_gateway_types::communication::SharedGatewayClient;
 
 use crate::config::MempoolP2pConfig;
 use crate::propagator::MempoolP2pPropagator;
-use crate::runner::MempoolP2pRunner;
+use crate::runner::{MempoolP2pRunner, PeerReportEvent};
 
 pub const MEMPOOL_TOPIC: &str = "starknet_mempool_transaction_propagation/0.1.0";
 
+/// Creates the propagator and the runner, along with the receiver of the events the runner emits
+/// whenever it reports a peer.
 pub fn create_p2p_propagator_and_runner(
     mempool_p2p_config: MempoolP2pConfig,
     gateway_client: SharedGatewayClient,
-) -> (MempoolP2pPropagator, MempoolP2pRunner) {
+) -> (MempoolP2pPropagator, MempoolP2pRunner, Receiver<PeerReportEvent>) {
+    let chain_id = mempool_p2p_config.network_config.chain_id.clone();
     let mut network_manager = NetworkManager::new(
         mempool_p2p_config.network_config,
         mempool_p2p_config.executable_version,
@@ -28,11 +34,19 @@ pub fn create_p2p_propagator_and_runner(
             )
             .expect("Failed to register broadcast topic");
     let mempool_p2p_propagator = MempoolP2pPropagator::new(broadcast_topic_client.clone());
+    let (peer_report_sender, peer_report_receiver) =
+        futures::channel::mpsc::channel(mempool_p2p_config.network_buffer_size);
     let mempool_p2p_runner = MempoolP2pRunner::new(
-        Some(network_manager),
+        network_manager.run().boxed(),
         broadcasted_messages_receiver,
         broadcast_topic_client,
         gateway_client,
+        mempool_p2p_config.max_concurrent_gateway_requests,
+        mempool_p2p_config.gateway_requests_soft_limit,
+        mempool_p2p_config.seen_transactions_cache_size,
+        mempool_p2p_config.gateway_retry_config,
+        chain_id,
+        Some(peer_report_sender),
     );
-    (mempool_p2p_propagator, mempool_p2p_runner)
+    (mempool_p2p_propagator, mempool_p2p_runner, peer_report_receiver)
 }
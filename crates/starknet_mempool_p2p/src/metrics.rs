@@ -0,0 +1,21 @@
+/// The number of peers reported for sending faulty transactions or transactions rejected by the
+/// gateway.
+pub const MEMPOOL_P2P_PEER_REPORTS_TOTAL: &str = "mempool_p2p_peer_reports_total";
+
+/// The number of transactions received from the network that could not be parsed.
+pub const MEMPOOL_P2P_FAULTY_TXS_TOTAL: &str = "mempool_p2p_faulty_txs_total";
+
+/// The number of transactions received from the network that the gateway failed to add.
+pub const MEMPOOL_P2P_GATEWAY_ADD_TX_ERRORS_TOTAL: &str = "mempool_p2p_gateway_add_tx_errors_total";
+
+/// The number of transactions waiting to be resubmitted to the gateway after a transient error.
+pub const MEMPOOL_P2P_RETRY_QUEUE_DEPTH: &str = "mempool_p2p_retry_queue_depth";
+
+/// The time, in seconds, since the first failure of the oldest transaction waiting to be
+/// resubmitted to the gateway.
+pub const MEMPOOL_P2P_RETRY_QUEUE_OLDEST_AGE_SECONDS: &str =
+    "mempool_p2p_retry_queue_oldest_age_seconds";
+
+/// The number of transactions that were given up on after transient gateway errors, either because
+/// the retry queue was full or because they were failing for too long.
+pub const MEMPOOL_P2P_RETRY_QUEUE_DROPPED_TOTAL: &str = "mempool_p2p_retry_queue_dropped_total";
@@ -1,86 +1,446 @@
+mod retry_queue;
 #[cfg(test)]
 mod test;
 
+use std::collections::HashMap;
+use std::num::NonZeroUsize;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
 use async_trait::async_trait;
+use futures::channel::mpsc::Sender;
+use futures::future::{AbortHandle, Abortable, Aborted, BoxFuture};
 use futures::stream::FuturesUnordered;
-use futures::{pin_mut, FutureExt, StreamExt, TryFutureExt};
+use futures::{FutureExt, StreamExt};
+use lru::LruCache;
+use metrics::{counter, gauge, increment_counter};
 use papyrus_network::network_manager::{
     BroadcastTopicClient,
     BroadcastTopicClientTrait,
     BroadcastTopicServer,
-    NetworkManager,
+    NetworkError,
 };
+use papyrus_network_types::network_types::{BroadcastedMessageMetadata, OpaquePeerId};
 use papyrus_protobuf::mempool::RpcTransactionWrapper;
-use starknet_gateway_types::communication::{GatewayClientError, SharedGatewayClient};
-use starknet_gateway_types::errors::GatewayError;
+use starknet_api::core::ChainId;
+use starknet_api::rpc_transaction::RpcTransaction;
+use starknet_api::transaction::{Transaction, TransactionHash};
+use starknet_gateway_types::communication::{
+    GatewayClientError,
+    GatewayClientResult,
+    SharedGatewayClient,
+};
+use starknet_gateway_types::errors::{GatewayError, GatewaySpecError};
 use starknet_gateway_types::gateway_types::GatewayInput;
+use starknet_sequencer_infra::component_client::ClientError;
 use starknet_sequencer_infra::component_definitions::ComponentStarter;
 use starknet_sequencer_infra::component_server::WrapperServer;
 use starknet_sequencer_infra::errors::ComponentError;
-use tracing::warn;
+use starknet_sequencer_infra::shutdown::ShutdownSignal;
+use tokio::time::Instant;
+use tracing::{debug, error, warn};
+
+use crate::config::GatewayRetryConfig;
+use crate::metrics::{
+    MEMPOOL_P2P_FAULTY_TXS_TOTAL,
+    MEMPOOL_P2P_GATEWAY_ADD_TX_ERRORS_TOTAL,
+    MEMPOOL_P2P_PEER_REPORTS_TOTAL,
+    MEMPOOL_P2P_RETRY_QUEUE_DEPTH,
+    MEMPOOL_P2P_RETRY_QUEUE_DROPPED_TOTAL,
+    MEMPOOL_P2P_RETRY_QUEUE_OLDEST_AGE_SECONDS,
+};
+use crate::runner::retry_queue::{RetryEntry, RetryQueue};
+
+/// Why a peer was reported.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum GatewayRejectReason {
+    /// The peer sent a message that is not a valid transaction, so it never reached the gateway.
+    UnparsableMessage,
+    /// The gateway rejected the transaction the peer sent with the given error.
+    SpecError(GatewaySpecError),
+}
+
+/// Emitted whenever the runner reports a peer, so other subsystems (e.g., peer scoring) can react.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PeerReportEvent {
+    pub peer: OpaquePeerId,
+    pub reason: GatewayRejectReason,
+    /// The hash of the rejected transaction, if the message was parsed into one.
+    pub tx_hash: Option<TransactionHash>,
+}
+
+// The outcome of a gateway request, with what is needed to resubmit it if it failed transiently.
+struct GatewayRequestOutcome {
+    gateway_input: GatewayInput,
+    // When the transaction first failed to be added, if this request is a retry.
+    first_failed_at: Option<Instant>,
+    result: GatewayClientResult<TransactionHash>,
+}
+
+type GatewayFuture = Abortable<BoxFuture<'static, (TransactionHash, GatewayRequestOutcome)>>;
+
+// The abort handles of the in-flight gateway requests, keyed by the hash of the transaction they
+// add. A request is removed from it once it is cancelled or its outcome is handled.
+type InflightRequests = Arc<Mutex<HashMap<TransactionHash, AbortHandle>>>;
+
+/// Cancels in-flight gateway requests of a [`MempoolP2pRunner`].
+#[derive(Clone, Debug)]
+pub struct InflightCancelHandle {
+    inflight_requests: InflightRequests,
+}
+
+impl InflightCancelHandle {
+    /// Cancels the in-flight gateway request adding the transaction with the given hash, so its
+    /// outcome is never handled. Returns whether such a request was in flight.
+    pub fn cancel(&self, tx_hash: TransactionHash) -> bool {
+        let abort_handle = self
+            .inflight_requests
+            .lock()
+            .expect("Inflight requests lock should not be poisoned.")
+            .remove(&tx_hash);
+        match abort_handle {
+            Some(abort_handle) => {
+                abort_handle.abort();
+                debug!("Cancelled the gateway request adding transaction {}.", tx_hash);
+                true
+            }
+            None => {
+                debug!("No gateway request adding transaction {} to cancel.", tx_hash);
+                false
+            }
+        }
+    }
+}
 
 pub struct MempoolP2pRunner {
-    network_manager: Option<NetworkManager>,
+    network_future: BoxFuture<'static, Result<(), NetworkError>>,
     broadcasted_topic_server: BroadcastTopicServer<RpcTransactionWrapper>,
     broadcast_topic_client: BroadcastTopicClient<RpcTransactionWrapper>,
     gateway_client: SharedGatewayClient,
+    // In-flight gateway requests, including cancelled ones until they are polled.
+    gateway_futures: FuturesUnordered<GatewayFuture>,
+    inflight_requests: InflightRequests,
+    max_concurrent_gateway_requests: usize,
+    gateway_requests_soft_limit: usize,
+    chain_id: ChainId,
+    // Hashes of recently received transactions, used to avoid forwarding duplicates.
+    seen_transactions: LruCache<TransactionHash, ()>,
+    // Transactions the gateway failed to add due to a transient error.
+    retry_queue: RetryQueue,
+    retry_interval: Duration,
+    peer_report_sender: Option<Sender<PeerReportEvent>>,
+    shutdown_signal: ShutdownSignal,
 }
 
 impl MempoolP2pRunner {
+    #[allow(clippy::too_many_arguments)]
     pub fn new(
-        network_manager: Option<NetworkManager>,
+        network_future: BoxFuture<'static, Result<(), NetworkError>>,
         broadcasted_topic_server: BroadcastTopicServer<RpcTransactionWrapper>,
         broadcast_topic_client: BroadcastTopicClient<RpcTransactionWrapper>,
         gateway_client: SharedGatewayClient,
+        max_concurrent_gateway_requests: usize,
+        gateway_requests_soft_limit: usize,
+        seen_transactions_cache_size: usize,
+        gateway_retry_config: GatewayRetryConfig,
+        chain_id: ChainId,
+        peer_report_sender: Option<Sender<PeerReportEvent>>,
     ) -> Self {
-        Self { network_manager, broadcasted_topic_server, broadcast_topic_client, gateway_client }
+        Self {
+            network_future,
+            broadcasted_topic_server,
+            broadcast_topic_client,
+            gateway_client,
+            gateway_futures: FuturesUnordered::new(),
+            inflight_requests: Arc::new(Mutex::new(HashMap::new())),
+            max_concurrent_gateway_requests,
+            gateway_requests_soft_limit,
+            chain_id,
+            seen_transactions: LruCache::new(
+                NonZeroUsize::new(seen_transactions_cache_size)
+                    .expect("seen_transactions_cache_size should be a positive integer."),
+            ),
+            retry_queue: RetryQueue::new(
+                gateway_retry_config.queue_capacity,
+                gateway_retry_config.max_age,
+            ),
+            retry_interval: gateway_retry_config.interval,
+            peer_report_sender,
+            shutdown_signal: ShutdownSignal::default(),
+        }
+    }
+
+    /// Sets the signal on which the runner stops gracefully. By default, the runner only stops when
+    /// the network does.
+    pub fn set_shutdown_signal(&mut self, shutdown_signal: ShutdownSignal) {
+        self.shutdown_signal = shutdown_signal;
+    }
+
+    /// Returns a handle through which in-flight gateway requests are cancelled.
+    pub fn cancel_handle(&self) -> InflightCancelHandle {
+        InflightCancelHandle { inflight_requests: self.inflight_requests.clone() }
+    }
+
+    // The number of in-flight gateway requests, not counting the cancelled ones.
+    fn n_inflight_requests(&self) -> usize {
+        self.inflight_requests.lock().expect("Inflight requests lock should not be poisoned.").len()
+    }
+
+    fn is_inflight(&self, tx_hash: &TransactionHash) -> bool {
+        self.inflight_requests
+            .lock()
+            .expect("Inflight requests lock should not be poisoned.")
+            .contains_key(tx_hash)
+    }
+
+    // Lets the in-flight transactions reach the gateway, before shutting down.
+    async fn drain_gateway_requests(&mut self) {
+        while let Some(finished_request) = self.gateway_futures.next().await {
+            self.handle_finished_gateway_request(finished_request).await;
+        }
+    }
+
+    // Handles all the gateway requests that already finished, without waiting for the others.
+    async fn handle_finished_gateway_requests(&mut self) {
+        while let Some(Some(finished_request)) = self.gateway_futures.next().now_or_never() {
+            self.handle_finished_gateway_request(finished_request).await;
+        }
+    }
+
+    // Handles the outcome of a finished gateway request, unless it was cancelled.
+    async fn handle_finished_gateway_request(
+        &mut self,
+        finished_request: Result<(TransactionHash, GatewayRequestOutcome), Aborted>,
+    ) {
+        let Ok((tx_hash, outcome)) = finished_request else {
+            return;
+        };
+        // A request that finished cannot be cancelled anymore.
+        self.inflight_requests
+            .lock()
+            .expect("Inflight requests lock should not be poisoned.")
+            .remove(&tx_hash);
+        Self::handle_gateway_outcome(
+            &mut self.broadcast_topic_client,
+            &mut self.peer_report_sender,
+            &mut self.retry_queue,
+            tx_hash,
+            outcome,
+        )
+        .await;
+    }
+
+    // Drops the transactions that failed for too long, and resubmits the others to the gateway as
+    // long as it is not saturated.
+    fn retry_transactions(&mut self) {
+        let n_expired = self.retry_queue.drop_expired();
+        if n_expired > 0 {
+            warn!(
+                "Dropped {} transactions that the gateway failed to add for too long.",
+                n_expired
+            );
+            counter!(MEMPOOL_P2P_RETRY_QUEUE_DROPPED_TOTAL, u64::try_from(n_expired).unwrap());
+        }
+        while self.n_inflight_requests() < self.max_concurrent_gateway_requests {
+            let Some(RetryEntry { tx_hash, gateway_input, first_failed_at }) =
+                self.retry_queue.pop()
+            else {
+                break;
+            };
+            if self.is_inflight(&tx_hash) {
+                continue;
+            }
+            self.submit_to_gateway(tx_hash, gateway_input, Some(first_failed_at));
+        }
+        self.report_retry_queue_metrics();
+    }
+
+    #[allow(clippy::as_conversions)] // FIXME: use int metrics so `as f64` may be removed.
+    fn report_retry_queue_metrics(&self) {
+        gauge!(MEMPOOL_P2P_RETRY_QUEUE_DEPTH, self.retry_queue.len() as f64);
+        gauge!(
+            MEMPOOL_P2P_RETRY_QUEUE_OLDEST_AGE_SECONDS,
+            self.retry_queue.oldest_age().unwrap_or_default().as_secs_f64()
+        );
+    }
+
+    fn forward_to_gateway(
+        &mut self,
+        rpc_tx: RpcTransaction,
+        broadcasted_message_metadata: BroadcastedMessageMetadata,
+    ) {
+        let tx_hash =
+            match Transaction::from(rpc_tx.clone()).calculate_transaction_hash(&self.chain_id) {
+                Ok(tx_hash) => tx_hash,
+                Err(e) => {
+                    warn!("Failed to calculate the hash of a transaction from network: {:?}.", e);
+                    return;
+                }
+            };
+        if self.seen_transactions.put(tx_hash, ()).is_some() {
+            debug!(
+                "Received a transaction that was recently seen. Not forwarding it to the gateway."
+            );
+            return;
+        }
+        if self.is_inflight(&tx_hash) {
+            debug!("Transaction {} is already being added to the gateway.", tx_hash);
+            return;
+        }
+
+        let gateway_input =
+            GatewayInput { rpc_tx, message_metadata: Some(broadcasted_message_metadata) };
+        self.submit_to_gateway(tx_hash, gateway_input, None);
+    }
+
+    fn submit_to_gateway(
+        &mut self,
+        tx_hash: TransactionHash,
+        gateway_input: GatewayInput,
+        first_failed_at: Option<Instant>,
+    ) {
+        let gateway_client = self.gateway_client.clone();
+        let gateway_future = async move {
+            let result = gateway_client.add_tx(gateway_input.clone()).await;
+            (tx_hash, GatewayRequestOutcome { gateway_input, first_failed_at, result })
+        }
+        .boxed();
+        let (abort_handle, abort_registration) = AbortHandle::new_pair();
+        self.inflight_requests
+            .lock()
+            .expect("Inflight requests lock should not be poisoned.")
+            .insert(tx_hash, abort_handle);
+        self.gateway_futures.push(Abortable::new(gateway_future, abort_registration));
+    }
+
+    async fn handle_gateway_outcome(
+        broadcast_topic_client: &mut BroadcastTopicClient<RpcTransactionWrapper>,
+        peer_report_sender: &mut Option<Sender<PeerReportEvent>>,
+        retry_queue: &mut RetryQueue,
+        tx_hash: TransactionHash,
+        outcome: GatewayRequestOutcome,
+    ) {
+        let GatewayRequestOutcome { gateway_input, first_failed_at, result } = outcome;
+        let Err(error) = result else {
+            return;
+        };
+        increment_counter!(MEMPOOL_P2P_GATEWAY_ADD_TX_ERRORS_TOTAL);
+        if Self::is_transient(&error) {
+            let entry = RetryEntry {
+                tx_hash,
+                gateway_input,
+                first_failed_at: first_failed_at.unwrap_or_else(Instant::now),
+            };
+            if !retry_queue.push(entry) {
+                warn!(
+                    "Dropped transaction {} that the gateway failed to add: {:?}.",
+                    tx_hash, error
+                );
+                increment_counter!(MEMPOOL_P2P_RETRY_QUEUE_DROPPED_TOTAL);
+            }
+            return;
+        }
+        if let GatewayClientError::GatewayError(GatewayError::GatewaySpecError {
+            source,
+            p2p_message_metadata: Some(p2p_message_metadata),
+        }) = error
+        {
+            Self::report_peer(
+                broadcast_topic_client,
+                peer_report_sender,
+                p2p_message_metadata,
+                GatewayRejectReason::SpecError(source),
+                Some(tx_hash),
+            )
+            .await;
+        }
+    }
+
+    // Whether the error is unrelated to the transaction, so adding it again may succeed.
+    fn is_transient(error: &GatewayClientError) -> bool {
+        matches!(
+            error,
+            GatewayClientError::ClientError(ClientError::CommunicationFailure(_))
+                | GatewayClientError::GatewayError(GatewayError::GatewaySpecError {
+                    source: GatewaySpecError::UnexpectedError { .. },
+                    ..
+                })
+        )
+    }
+
+    async fn report_peer(
+        broadcast_topic_client: &mut BroadcastTopicClient<RpcTransactionWrapper>,
+        peer_report_sender: &mut Option<Sender<PeerReportEvent>>,
+        broadcasted_message_metadata: BroadcastedMessageMetadata,
+        reason: GatewayRejectReason,
+        tx_hash: Option<TransactionHash>,
+    ) {
+        if let Some(sender) = peer_report_sender {
+            let event = PeerReportEvent {
+                peer: broadcasted_message_metadata.originator_id.clone(),
+                reason,
+                tx_hash,
+            };
+            // Don't let a slow subscriber stall the runner.
+            if let Err(e) = sender.try_send(event) {
+                warn!("Failed to emit a peer report event: {:?}", e);
+            }
+        }
+        match broadcast_topic_client.report_peer(broadcasted_message_metadata).await {
+            Ok(()) => increment_counter!(MEMPOOL_P2P_PEER_REPORTS_TOTAL),
+            Err(e) => warn!("Failed to report peer: {:?}", e),
+        }
     }
 }
 
 #[async_trait]
 impl ComponentStarter for MempoolP2pRunner {
     async fn start(&mut self) -> Result<(), ComponentError> {
-        let network_future = self
-            .network_manager
-            .take()
-            .expect("Network manager not found")
-            .run()
-            .map_err(|_| ComponentError::InternalComponentError);
-        pin_mut!(network_future);
-        let mut gateway_futures = FuturesUnordered::new();
+        let mut retry_interval = tokio::time::interval(self.retry_interval);
         loop {
+            // During a burst, prioritize the finished gateway requests over new transactions, so
+            // their handling is not delayed for as long as the burst lasts.
+            if self.n_inflight_requests() >= self.gateway_requests_soft_limit {
+                self.handle_finished_gateway_requests().await;
+            }
             tokio::select! {
-                // tokio::select! takes ownership of the futures, so we need to wrap with poll_fn
-                result = futures::future::poll_fn(|cx| network_future.poll_unpin(cx)) => {
-                    result?;
-                    panic!("Network stopped unexpectedly");
-                }
-                Some(result) = gateway_futures.next() => {
+                result = &mut self.network_future => {
                     match result {
-                        Ok(_) => {}
-                        Err(gateway_client_error) => {
-                            if let GatewayClientError::GatewayError(
-                                GatewayError::GatewaySpecError{p2p_message_metadata: Some(p2p_message_metadata), ..}
-                            ) = gateway_client_error {
-                                if let Err(e) = self.broadcast_topic_client.report_peer(p2p_message_metadata.clone()).await {
-                                    warn!("Failed to report peer: {:?}", e);
-                                }
-                            }
-                        }
+                        Ok(()) => error!("Network stopped unexpectedly."),
+                        Err(e) => error!("Network stopped with an error: {:?}.", e),
                     }
+                    self.drain_gateway_requests().await;
+                    return Err(ComponentError::InternalComponentError);
+                }
+                _ = self.shutdown_signal.wait() => {
+                    debug!("Shutting down, draining {} in-flight gateway requests.", self.n_inflight_requests());
+                    self.drain_gateway_requests().await;
+                    return Ok(());
+                }
+                Some(finished_request) = self.gateway_futures.next() => {
+                    self.handle_finished_gateway_request(finished_request).await;
+                }
+                _ = retry_interval.tick() => {
+                    self.retry_transactions();
                 }
-                Some((message_result, broadcasted_message_metadata)) = self.broadcasted_topic_server.next() => {
+                // Stop receiving transactions from the network while the gateway is saturated.
+                Some((message_result, broadcasted_message_metadata)) = self.broadcasted_topic_server.next(),
+                    if self.n_inflight_requests() < self.max_concurrent_gateway_requests => {
                     match message_result {
                         Ok(message) => {
-                            gateway_futures.push(self.gateway_client.add_tx(
-                                GatewayInput { rpc_tx: message.0, message_metadata: Some(broadcasted_message_metadata.clone()) }
-                            ));
+                            self.forward_to_gateway(message.0, broadcasted_message_metadata);
                         }
                         Err(e) => {
+                            increment_counter!(MEMPOOL_P2P_FAULTY_TXS_TOTAL);
                             warn!("Received a faulty transaction from network: {:?}. Attempting to report the sending peer", e);
-                            if let Err(e) = self.broadcast_topic_client.report_peer(broadcasted_message_metadata).await {
-                                warn!("Failed to report peer: {:?}", e);
-                            }
+                            Self::report_peer(
+                                &mut self.broadcast_topic_client,
+                                &mut self.peer_report_sender,
+                                broadcasted_message_metadata,
+                                GatewayRejectReason::UnparsableMessage,
+                                None,
+                            )
+                            .await;
                         }
                     }
                 }
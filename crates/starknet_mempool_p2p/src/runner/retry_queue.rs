@@ -0,0 +1,64 @@
+use std::collections::VecDeque;
+use std::time::Duration;
+
+use starknet_api::transaction::TransactionHash;
+use starknet_gateway_types::gateway_types::GatewayInput;
+use tokio::time::Instant;
+
+/// A transaction that the gateway failed to add due to a transient error, waiting to be
+/// resubmitted.
+#[derive(Clone, Debug)]
+pub(crate) struct RetryEntry {
+    pub tx_hash: TransactionHash,
+    pub gateway_input: GatewayInput,
+    pub first_failed_at: Instant,
+}
+
+/// A bounded FIFO of transactions waiting to be resubmitted to the gateway. Entries that failed
+/// for the first time more than `max_age` ago are no longer retried.
+pub(crate) struct RetryQueue {
+    entries: VecDeque<RetryEntry>,
+    capacity: usize,
+    max_age: Duration,
+}
+
+impl RetryQueue {
+    pub fn new(capacity: usize, max_age: Duration) -> Self {
+        Self { entries: VecDeque::new(), capacity, max_age }
+    }
+
+    /// Adds the entry to the back of the queue. Returns false, without adding it, if the queue is
+    /// full or the entry is already too old to be retried.
+    pub fn push(&mut self, entry: RetryEntry) -> bool {
+        if self.entries.len() >= self.capacity || self.is_expired(&entry) {
+            return false;
+        }
+        self.entries.push_back(entry);
+        true
+    }
+
+    pub fn pop(&mut self) -> Option<RetryEntry> {
+        self.entries.pop_front()
+    }
+
+    /// Removes the entries that are too old to be retried. Returns the number of removed entries.
+    pub fn drop_expired(&mut self) -> usize {
+        let len_before = self.entries.len();
+        let now = Instant::now();
+        self.entries.retain(|entry| now.duration_since(entry.first_failed_at) <= self.max_age);
+        len_before - self.entries.len()
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// The time since the first failure of the oldest entry in the queue.
+    pub fn oldest_age(&self) -> Option<Duration> {
+        self.entries.iter().map(|entry| entry.first_failed_at.elapsed()).max()
+    }
+
+    fn is_expired(&self, entry: &RetryEntry) -> bool {
+        entry.first_failed_at.elapsed() > self.max_age
+    }
+}
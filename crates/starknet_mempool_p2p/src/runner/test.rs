@@ -1,28 +1,102 @@
-use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
 use std::time::Duration;
 
 use async_trait::async_trait;
-use futures::channel::mpsc::Sender;
+use futures::channel::mpsc::{Receiver, Sender};
+use futures::future::BoxFuture;
 use futures::stream::StreamExt;
-use futures::SinkExt;
+use futures::{FutureExt, SinkExt};
+use metrics_exporter_prometheus::PrometheusBuilder;
 use papyrus_network::network_manager::test_utils::{
     mock_register_broadcast_topic,
     BroadcastNetworkMock,
     TestSubscriberChannels,
 };
-use papyrus_network::network_manager::{BroadcastTopicChannels, NetworkManager};
-use papyrus_network::NetworkConfig;
+use papyrus_network::network_manager::{BroadcastTopicChannels, NetworkError};
 use papyrus_network_types::network_types::BroadcastedMessageMetadata;
 use papyrus_protobuf::mempool::RpcTransactionWrapper;
-use papyrus_test_utils::{get_rng, GetTestInstance};
+use papyrus_test_utils::{get_rng, prometheus_is_contained, GetTestInstance};
+use prometheus_parse::Value::Counter;
+use starknet_api::core::ChainId;
 use starknet_api::rpc_transaction::RpcTransaction;
-use starknet_api::transaction::TransactionHash;
-use starknet_gateway_types::communication::{GatewayClient, GatewayClientResult};
+use starknet_api::test_utils::invoke::rpc_invoke_tx;
+use starknet_api::transaction::{Transaction, TransactionHash};
+use starknet_api::{invoke_tx_args, nonce};
+use starknet_gateway_types::communication::{
+    GatewayClient,
+    GatewayClientError,
+    GatewayClientResult,
+    SharedGatewayClient,
+};
+use starknet_gateway_types::errors::{GatewayError, GatewaySpecError};
 use starknet_gateway_types::gateway_types::GatewayInput;
 use starknet_sequencer_infra::component_definitions::ComponentStarter;
+use starknet_sequencer_infra::errors::ComponentError;
+use starknet_sequencer_infra::shutdown::ShutdownHandle;
+use tokio::sync::Notify;
 use tokio::time::sleep;
 
-use super::MempoolP2pRunner;
+use super::retry_queue::{RetryEntry, RetryQueue};
+use super::{GatewayRejectReason, MempoolP2pRunner, PeerReportEvent};
+use crate::config::GatewayRetryConfig;
+use crate::metrics::{
+    MEMPOOL_P2P_FAULTY_TXS_TOTAL,
+    MEMPOOL_P2P_GATEWAY_ADD_TX_ERRORS_TOTAL,
+    MEMPOOL_P2P_PEER_REPORTS_TOTAL,
+};
+
+const MAX_CONCURRENT_GATEWAY_REQUESTS: usize = 4;
+const GATEWAY_REQUESTS_SOFT_LIMIT: usize = 2;
+const SEEN_TRANSACTIONS_CACHE_SIZE: usize = 100;
+
+fn invoke_tx_with_nonce(nonce: usize) -> RpcTransactionWrapper {
+    RpcTransactionWrapper(rpc_invoke_tx(invoke_tx_args!(nonce: nonce!(nonce))))
+}
+
+// The arguments of the runner under test that tests override.
+struct TestRunnerConfig {
+    network_future: BoxFuture<'static, Result<(), NetworkError>>,
+    max_concurrent_gateway_requests: usize,
+    gateway_retry_config: GatewayRetryConfig,
+    peer_report_sender: Option<Sender<PeerReportEvent>>,
+}
+
+impl Default for TestRunnerConfig {
+    fn default() -> Self {
+        Self {
+            // A network future that never resolves, standing in for a running network.
+            network_future: futures::future::pending().boxed(),
+            max_concurrent_gateway_requests: MAX_CONCURRENT_GATEWAY_REQUESTS,
+            gateway_retry_config: GatewayRetryConfig::default(),
+            peer_report_sender: None,
+        }
+    }
+}
+
+// Creates a runner over a mock network, which is returned to drive the runner.
+fn setup_runner(
+    gateway_client: SharedGatewayClient,
+    config: TestRunnerConfig,
+) -> (MempoolP2pRunner, BroadcastNetworkMock<RpcTransactionWrapper>) {
+    let TestSubscriberChannels { mock_network, subscriber_channels } =
+        mock_register_broadcast_topic().expect("Failed to create mock network");
+    let BroadcastTopicChannels { broadcasted_messages_receiver, broadcast_topic_client } =
+        subscriber_channels;
+    let mempool_p2p_runner = MempoolP2pRunner::new(
+        config.network_future,
+        broadcasted_messages_receiver,
+        broadcast_topic_client,
+        gateway_client,
+        config.max_concurrent_gateway_requests,
+        GATEWAY_REQUESTS_SOFT_LIMIT,
+        SEEN_TRANSACTIONS_CACHE_SIZE,
+        config.gateway_retry_config,
+        ChainId::create_for_testing(),
+        config.peer_report_sender,
+    );
+    (mempool_p2p_runner, mock_network)
+}
 
 // TODO(eitan): Make it an automock
 #[derive(Clone)]
@@ -38,26 +112,128 @@ impl GatewayClient for MockGatewayClient {
     }
 }
 
+// A gateway client that holds each request for a while and records the peak number of requests
+// that were in flight at the same time.
+#[derive(Clone)]
+struct SlowGatewayClient {
+    in_flight: Arc<AtomicUsize>,
+    max_in_flight: Arc<AtomicUsize>,
+    add_tx_sender: Sender<RpcTransaction>,
+}
+
+#[async_trait]
+impl GatewayClient for SlowGatewayClient {
+    async fn add_tx(&self, gateway_input: GatewayInput) -> GatewayClientResult<TransactionHash> {
+        let in_flight = self.in_flight.fetch_add(1, Ordering::SeqCst) + 1;
+        self.max_in_flight.fetch_max(in_flight, Ordering::SeqCst);
+        sleep(Duration::from_millis(10)).await;
+        self.in_flight.fetch_sub(1, Ordering::SeqCst);
+        let _ = self.clone().add_tx_sender.send(gateway_input.rpc_tx).await;
+        Ok(TransactionHash::default())
+    }
+}
+
+// A gateway client that announces each request it receives and completes it only once released.
+#[derive(Clone)]
+struct GatedGatewayClient {
+    started_sender: Sender<()>,
+    release: Arc<Notify>,
+    completed: Arc<AtomicBool>,
+}
+
+#[async_trait]
+impl GatewayClient for GatedGatewayClient {
+    async fn add_tx(&self, _gateway_input: GatewayInput) -> GatewayClientResult<TransactionHash> {
+        let _ = self.clone().started_sender.send(()).await;
+        self.release.notified().await;
+        self.completed.store(true, Ordering::SeqCst);
+        Ok(TransactionHash::default())
+    }
+}
+
+// A gateway client that holds the first requests until released and then rejects them, blaming
+// the peer that sent them. Every later request announces how many peers were reported before it
+// reached the gateway.
+#[derive(Clone)]
+struct BurstGatewayClient {
+    n_held_requests: usize,
+    n_calls: Arc<AtomicUsize>,
+    started_sender: Sender<()>,
+    release: Arc<Notify>,
+    peer_report_receiver: Arc<Mutex<Receiver<PeerReportEvent>>>,
+    n_reported_peers: Arc<AtomicUsize>,
+    n_reported_peers_sender: Sender<usize>,
+}
+
+#[async_trait]
+impl GatewayClient for BurstGatewayClient {
+    async fn add_tx(&self, gateway_input: GatewayInput) -> GatewayClientResult<TransactionHash> {
+        if self.n_calls.fetch_add(1, Ordering::SeqCst) < self.n_held_requests {
+            let _ = self.started_sender.clone().send(()).await;
+            self.release.notified().await;
+            return Err(GatewayClientError::GatewayError(GatewayError::GatewaySpecError {
+                source: GatewaySpecError::ValidationFailure { data: "Invalid transaction".into() },
+                p2p_message_metadata: gateway_input.message_metadata,
+            }));
+        }
+        let n_new_reports = {
+            let mut peer_report_receiver = self.peer_report_receiver.lock().unwrap();
+            std::iter::from_fn(|| peer_report_receiver.try_next().ok().flatten()).count()
+        };
+        let n_reported_peers =
+            self.n_reported_peers.fetch_add(n_new_reports, Ordering::SeqCst) + n_new_reports;
+        let _ = self.n_reported_peers_sender.clone().send(n_reported_peers).await;
+        Ok(TransactionHash::default())
+    }
+}
+
+// A gateway client that rejects every transaction, blaming the peer that sent it.
+#[derive(Clone)]
+struct RejectingGatewayClient;
+
+#[async_trait]
+impl GatewayClient for RejectingGatewayClient {
+    async fn add_tx(&self, gateway_input: GatewayInput) -> GatewayClientResult<TransactionHash> {
+        Err(GatewayClientError::GatewayError(GatewayError::GatewaySpecError {
+            source: GatewaySpecError::ValidationFailure { data: "Invalid transaction".into() },
+            p2p_message_metadata: gateway_input.message_metadata,
+        }))
+    }
+}
+
+// A gateway client that fails to add each transaction a given number of times with an unexpected
+// error, and then forwards it.
+#[derive(Clone)]
+struct FlakyGatewayClient {
+    add_tx_sender: Sender<RpcTransaction>,
+    n_failures: usize,
+    n_calls: Arc<AtomicUsize>,
+}
+
+#[async_trait]
+impl GatewayClient for FlakyGatewayClient {
+    async fn add_tx(&self, gateway_input: GatewayInput) -> GatewayClientResult<TransactionHash> {
+        if self.n_calls.fetch_add(1, Ordering::SeqCst) < self.n_failures {
+            return Err(GatewayClientError::GatewayError(GatewayError::GatewaySpecError {
+                source: GatewaySpecError::UnexpectedError { data: "Internal server error".into() },
+                p2p_message_metadata: gateway_input.message_metadata,
+            }));
+        }
+        self.add_tx_sender.clone().send(gateway_input.rpc_tx).await.unwrap();
+        Ok(TransactionHash::default())
+    }
+}
+
 #[tokio::test]
 async fn start_component_receive_tx_happy_flow() {
-    let TestSubscriberChannels { mock_network, subscriber_channels } =
-        mock_register_broadcast_topic().expect("Failed to create mock network");
-    let BroadcastTopicChannels { broadcasted_messages_receiver, broadcast_topic_client } =
-        subscriber_channels;
+    let (add_tx_sender, mut add_tx_receiver) = futures::channel::mpsc::channel(1);
+    let mock_gateway_client = Arc::new(MockGatewayClient { add_tx_sender });
+    let (mut mempool_p2p_runner, mock_network) =
+        setup_runner(mock_gateway_client, TestRunnerConfig::default());
     let BroadcastNetworkMock {
         broadcasted_messages_sender: mut mock_broadcasted_messages_sender,
         ..
     } = mock_network;
-    // Creating a placeholder network manager with default config for init of a mempool receiver
-    let placeholder_network_manager = NetworkManager::new(NetworkConfig::default(), None);
-    let (add_tx_sender, mut add_tx_receiver) = futures::channel::mpsc::channel(1);
-    let mock_gateway_client = Arc::new(MockGatewayClient { add_tx_sender });
-    let mut mempool_p2p_runner = MempoolP2pRunner::new(
-        Some(placeholder_network_manager),
-        broadcasted_messages_receiver,
-        broadcast_topic_client,
-        mock_gateway_client,
-    );
     let message_metadata = BroadcastedMessageMetadata::get_test_instance(&mut get_rng());
     let expected_rpc_transaction =
         RpcTransactionWrapper(RpcTransaction::get_test_instance(&mut get_rng()));
@@ -77,4 +253,428 @@ async fn start_component_receive_tx_happy_flow() {
         }
     }
 }
+
+#[tokio::test]
+async fn start_component_returns_error_when_network_stops() {
+    let (add_tx_sender, _add_tx_receiver) = futures::channel::mpsc::channel(1);
+    let mock_gateway_client = Arc::new(MockGatewayClient { add_tx_sender });
+    let (mut mempool_p2p_runner, _mock_network) = setup_runner(
+        mock_gateway_client,
+        TestRunnerConfig {
+            // A network future that completes immediately, as if the network was shut down.
+            network_future: futures::future::ready(Ok(())).boxed(),
+            ..Default::default()
+        },
+    );
+
+    tokio::select! {
+        result = mempool_p2p_runner.start() => {
+            assert_eq!(result, Err(ComponentError::InternalComponentError));
+        }
+        _ = sleep(Duration::from_secs(5)) => {
+            panic!("Test timed out");
+        }
+    }
+}
+
+#[tokio::test]
+async fn start_component_bounds_concurrent_gateway_requests() {
+    const N_TRANSACTIONS: usize = 3 * MAX_CONCURRENT_GATEWAY_REQUESTS;
+
+    let (add_tx_sender, add_tx_receiver) = futures::channel::mpsc::channel(N_TRANSACTIONS);
+    let max_in_flight = Arc::new(AtomicUsize::new(0));
+    let slow_gateway_client = Arc::new(SlowGatewayClient {
+        in_flight: Arc::new(AtomicUsize::new(0)),
+        max_in_flight: max_in_flight.clone(),
+        add_tx_sender,
+    });
+    let (mut mempool_p2p_runner, mock_network) =
+        setup_runner(slow_gateway_client, TestRunnerConfig::default());
+    let BroadcastNetworkMock {
+        broadcasted_messages_sender: mut mock_broadcasted_messages_sender,
+        ..
+    } = mock_network;
+
+    // Flood the runner with more transactions than it may forward concurrently.
+    tokio::spawn(async move {
+        let mut rng = get_rng();
+        for nonce in 0..N_TRANSACTIONS {
+            let message_metadata = BroadcastedMessageMetadata::get_test_instance(&mut rng);
+            let rpc_transaction = invoke_tx_with_nonce(nonce);
+            mock_broadcasted_messages_sender
+                .send((rpc_transaction, message_metadata))
+                .await
+                .expect("Failed to send message");
+        }
+    });
+
+    tokio::select! {
+        _ = mempool_p2p_runner.start() => {panic!("Mempool receiver failed to start");}
+        received_transactions = add_tx_receiver.take(N_TRANSACTIONS).collect::<Vec<_>>() => {
+            assert_eq!(received_transactions.len(), N_TRANSACTIONS);
+        }
+        _ = sleep(Duration::from_secs(5)) => {
+            panic!("Test timed out");
+        }
+    }
+    assert!(max_in_flight.load(Ordering::SeqCst) <= MAX_CONCURRENT_GATEWAY_REQUESTS);
+}
+
+#[tokio::test]
+async fn start_component_handles_finished_requests_first_above_soft_limit() {
+    const N_NEW_TRANSACTIONS: usize = 2;
+
+    let (peer_report_sender, peer_report_receiver) =
+        futures::channel::mpsc::channel(GATEWAY_REQUESTS_SOFT_LIMIT);
+    let (started_sender, mut started_receiver) =
+        futures::channel::mpsc::channel(GATEWAY_REQUESTS_SOFT_LIMIT);
+    let (n_reported_peers_sender, n_reported_peers_receiver) =
+        futures::channel::mpsc::channel(N_NEW_TRANSACTIONS);
+    let release = Arc::new(Notify::new());
+    let burst_gateway_client = Arc::new(BurstGatewayClient {
+        n_held_requests: GATEWAY_REQUESTS_SOFT_LIMIT,
+        n_calls: Arc::new(AtomicUsize::new(0)),
+        started_sender,
+        release: release.clone(),
+        peer_report_receiver: Arc::new(Mutex::new(peer_report_receiver)),
+        n_reported_peers: Arc::new(AtomicUsize::new(0)),
+        n_reported_peers_sender,
+    });
+    let (mut mempool_p2p_runner, mock_network) = setup_runner(
+        burst_gateway_client,
+        TestRunnerConfig { peer_report_sender: Some(peer_report_sender), ..Default::default() },
+    );
+    let BroadcastNetworkMock {
+        broadcasted_messages_sender: mut mock_broadcasted_messages_sender,
+        ..
+    } = mock_network;
+
+    let test_flow = async {
+        let mut rng = get_rng();
+        // Reach the soft limit with requests the gateway holds.
+        for nonce in 0..GATEWAY_REQUESTS_SOFT_LIMIT {
+            let message_metadata = BroadcastedMessageMetadata::get_test_instance(&mut rng);
+            mock_broadcasted_messages_sender
+                .send((invoke_tx_with_nonce(nonce), message_metadata))
+                .await
+                .expect("Failed to send message");
+        }
+        (&mut started_receiver).take(GATEWAY_REQUESTS_SOFT_LIMIT).collect::<Vec<_>>().await;
+
+        // The held requests finish while new transactions arrive.
+        release.notify_waiters();
+        for nonce in GATEWAY_REQUESTS_SOFT_LIMIT..GATEWAY_REQUESTS_SOFT_LIMIT + N_NEW_TRANSACTIONS {
+            let message_metadata = BroadcastedMessageMetadata::get_test_instance(&mut rng);
+            mock_broadcasted_messages_sender
+                .send((invoke_tx_with_nonce(nonce), message_metadata))
+                .await
+                .expect("Failed to send message");
+        }
+        n_reported_peers_receiver.take(N_NEW_TRANSACTIONS).collect::<Vec<_>>().await
+    };
+
+    tokio::select! {
+        _ = mempool_p2p_runner.start() => {panic!("Mempool receiver failed to start");}
+        n_reported_peers = test_flow => {
+            // The finished requests are handled, i.e., their senders are reported, before the
+            // runner accepts another transaction.
+            assert_eq!(n_reported_peers.last(), Some(&GATEWAY_REQUESTS_SOFT_LIMIT));
+        }
+        _ = sleep(Duration::from_secs(5)) => {
+            panic!("Test timed out");
+        }
+    }
+}
+
+#[tokio::test]
+async fn start_component_updates_metrics_on_faulty_tx_and_gateway_error() {
+    let handle = PrometheusBuilder::new().install_recorder().unwrap();
+    let (mut mempool_p2p_runner, mock_network) =
+        setup_runner(Arc::new(RejectingGatewayClient), TestRunnerConfig::default());
+    let BroadcastNetworkMock {
+        broadcasted_messages_sender: mut mock_broadcasted_messages_sender,
+        mut reported_messages_receiver,
+        ..
+    } = mock_network;
+
+    // An empty message can't be parsed into a transaction.
+    let faulty_tx_metadata = BroadcastedMessageMetadata::get_test_instance(&mut get_rng());
+    mock_broadcasted_messages_sender
+        .get_mut()
+        .send((Vec::new(), faulty_tx_metadata))
+        .await
+        .expect("Failed to send message");
+    let rejected_tx_metadata = BroadcastedMessageMetadata::get_test_instance(&mut get_rng());
+    let rejected_tx = RpcTransactionWrapper(RpcTransaction::get_test_instance(&mut get_rng()));
+    mock_broadcasted_messages_sender
+        .send((rejected_tx, rejected_tx_metadata))
+        .await
+        .expect("Failed to send message");
+
+    tokio::select! {
+        _ = mempool_p2p_runner.start() => {panic!("Mempool receiver failed to start");}
+        reported_peers = (&mut reported_messages_receiver).take(2).collect::<Vec<_>>() => {
+            assert_eq!(reported_peers.len(), 2);
+        }
+        _ = sleep(Duration::from_secs(5)) => {
+            panic!("Test timed out");
+        }
+    }
+
+    // Other tests may report to the same recorder concurrently, so only lower bounds hold.
+    for (metric_name, min_value) in [
+        (MEMPOOL_P2P_FAULTY_TXS_TOTAL, 1.0),
+        (MEMPOOL_P2P_GATEWAY_ADD_TX_ERRORS_TOTAL, 1.0),
+        (MEMPOOL_P2P_PEER_REPORTS_TOTAL, 2.0),
+    ] {
+        let Some(Counter(value)) = prometheus_is_contained(handle.render(), metric_name, &[])
+        else {
+            panic!("{metric_name} is not a Counter");
+        };
+        assert!(value >= min_value, "{metric_name} is {value}, expected at least {min_value}.");
+    }
+}
+
+#[tokio::test]
+async fn start_component_emits_peer_report_event_on_gateway_rejection() {
+    let (peer_report_sender, mut peer_report_receiver) = futures::channel::mpsc::channel(1);
+    let chain_id = ChainId::create_for_testing();
+    let (mut mempool_p2p_runner, mock_network) = setup_runner(
+        Arc::new(RejectingGatewayClient),
+        TestRunnerConfig { peer_report_sender: Some(peer_report_sender), ..Default::default() },
+    );
+    let BroadcastNetworkMock {
+        broadcasted_messages_sender: mut mock_broadcasted_messages_sender,
+        ..
+    } = mock_network;
+    let message_metadata = BroadcastedMessageMetadata::get_test_instance(&mut get_rng());
+    let rpc_transaction = invoke_tx_with_nonce(0);
+    let tx_hash =
+        Transaction::from(rpc_transaction.0.clone()).calculate_transaction_hash(&chain_id).unwrap();
+
+    mock_broadcasted_messages_sender
+        .send((rpc_transaction, message_metadata.clone()))
+        .await
+        .expect("Failed to send message");
+
+    let expected_event = PeerReportEvent {
+        peer: message_metadata.originator_id,
+        reason: GatewayRejectReason::SpecError(GatewaySpecError::ValidationFailure {
+            data: "Invalid transaction".into(),
+        }),
+        tx_hash: Some(tx_hash),
+    };
+    tokio::select! {
+        _ = mempool_p2p_runner.start() => panic!("Mempool receiver failed to start"),
+        event = peer_report_receiver.next() => assert_eq!(event, Some(expected_event)),
+        _ = sleep(Duration::from_secs(5)) => panic!("Test timed out"),
+    }
+}
+
+#[tokio::test]
+async fn start_component_skips_recently_seen_tx() {
+    let (add_tx_sender, mut add_tx_receiver) = futures::channel::mpsc::channel(3);
+    let mock_gateway_client = Arc::new(MockGatewayClient { add_tx_sender });
+    let (mut mempool_p2p_runner, mock_network) = setup_runner(
+        mock_gateway_client,
+        TestRunnerConfig {
+            // Forward one transaction at a time, so they reach the gateway in the order they were
+            // received.
+            max_concurrent_gateway_requests: 1,
+            ..Default::default()
+        },
+    );
+    let BroadcastNetworkMock {
+        broadcasted_messages_sender: mut mock_broadcasted_messages_sender,
+        ..
+    } = mock_network;
+
+    // The same transaction is gossiped by two peers, followed by a different transaction.
+    let mut rng = get_rng();
+    let duplicated_tx = invoke_tx_with_nonce(0);
+    let other_tx = invoke_tx_with_nonce(1);
+    for rpc_tx in [duplicated_tx.clone(), duplicated_tx.clone(), other_tx.clone()] {
+        let message_metadata = BroadcastedMessageMetadata::get_test_instance(&mut rng);
+        mock_broadcasted_messages_sender
+            .send((rpc_tx, message_metadata))
+            .await
+            .expect("Failed to send message");
+    }
+
+    tokio::select! {
+        _ = mempool_p2p_runner.start() => {panic!("Mempool receiver failed to start");}
+        forwarded_txs = (&mut add_tx_receiver).take(2).collect::<Vec<_>>() => {
+            assert_eq!(forwarded_txs, vec![duplicated_tx.0, other_tx.0]);
+        }
+        _ = sleep(Duration::from_secs(5)) => {
+            panic!("Test timed out");
+        }
+    }
+}
+
+#[tokio::test]
+async fn start_component_cancels_inflight_tx() {
+    let (started_sender, mut started_receiver) = futures::channel::mpsc::channel(1);
+    let release = Arc::new(Notify::new());
+    let completed = Arc::new(AtomicBool::new(false));
+    let gateway_client = Arc::new(GatedGatewayClient {
+        started_sender,
+        release: release.clone(),
+        completed: completed.clone(),
+    });
+    let chain_id = ChainId::create_for_testing();
+    let (mut mempool_p2p_runner, mock_network) =
+        setup_runner(gateway_client, TestRunnerConfig::default());
+    let BroadcastNetworkMock {
+        broadcasted_messages_sender: mut mock_broadcasted_messages_sender,
+        ..
+    } = mock_network;
+    let cancel_handle = mempool_p2p_runner.cancel_handle();
+    let message_metadata = BroadcastedMessageMetadata::get_test_instance(&mut get_rng());
+    let rpc_transaction = invoke_tx_with_nonce(0);
+    let tx_hash =
+        Transaction::from(rpc_transaction.0.clone()).calculate_transaction_hash(&chain_id).unwrap();
+
+    mock_broadcasted_messages_sender
+        .send((rpc_transaction, message_metadata))
+        .await
+        .expect("Failed to send message");
+
+    // Run until the transaction is dispatched to the gateway.
+    tokio::select! {
+        _ = mempool_p2p_runner.start() => panic!("Mempool receiver failed to start"),
+        _ = started_receiver.next() => {}
+        _ = sleep(Duration::from_secs(5)) => panic!("Test timed out"),
+    }
+
+    // The request is cancelled while it is still blocked in the gateway, and only once.
+    assert!(cancel_handle.cancel(tx_hash));
+    assert!(!cancel_handle.cancel(tx_hash));
+    let _ = tokio::time::timeout(Duration::from_millis(100), mempool_p2p_runner.start()).await;
+
+    // Releasing the gateway does not complete the cancelled request.
+    release.notify_one();
+    let _ = tokio::time::timeout(Duration::from_millis(100), mempool_p2p_runner.start()).await;
+    assert!(!completed.load(Ordering::SeqCst));
+}
+
+#[tokio::test]
+async fn start_component_drains_inflight_tx_on_shutdown() {
+    let (started_sender, mut started_receiver) = futures::channel::mpsc::channel(1);
+    let release = Arc::new(Notify::new());
+    let completed = Arc::new(AtomicBool::new(false));
+    let gateway_client = Arc::new(GatedGatewayClient {
+        started_sender,
+        release: release.clone(),
+        completed: completed.clone(),
+    });
+    let (mut mempool_p2p_runner, mock_network) =
+        setup_runner(gateway_client, TestRunnerConfig::default());
+    let BroadcastNetworkMock {
+        broadcasted_messages_sender: mut mock_broadcasted_messages_sender,
+        ..
+    } = mock_network;
+    let shutdown_handle = ShutdownHandle::new();
+    mempool_p2p_runner.set_shutdown_signal(shutdown_handle.subscribe());
+    let message_metadata = BroadcastedMessageMetadata::get_test_instance(&mut get_rng());
+
+    mock_broadcasted_messages_sender
+        .send((invoke_tx_with_nonce(0), message_metadata))
+        .await
+        .expect("Failed to send message");
+
+    // Run until the transaction is dispatched to the gateway.
+    tokio::select! {
+        _ = mempool_p2p_runner.start() => panic!("Mempool receiver failed to start"),
+        _ = started_receiver.next() => {}
+        _ = sleep(Duration::from_secs(5)) => panic!("Test timed out"),
+    }
+
+    // The runner stops only after the in-flight request completes.
+    shutdown_handle.trigger();
+    release.notify_one();
+    tokio::select! {
+        result = mempool_p2p_runner.start() => assert_eq!(result, Ok(())),
+        _ = sleep(Duration::from_secs(5)) => panic!("Test timed out"),
+    }
+    assert!(completed.load(Ordering::SeqCst));
+}
+
+#[tokio::test]
+async fn start_component_retries_transiently_failed_tx() {
+    let (add_tx_sender, mut add_tx_receiver) = futures::channel::mpsc::channel(1);
+    let n_calls = Arc::new(AtomicUsize::new(0));
+    let gateway_client =
+        Arc::new(FlakyGatewayClient { add_tx_sender, n_failures: 2, n_calls: n_calls.clone() });
+    let gateway_retry_config = GatewayRetryConfig {
+        queue_capacity: 10,
+        max_age: Duration::from_secs(60),
+        interval: Duration::from_millis(10),
+    };
+    let (mut mempool_p2p_runner, mock_network) = setup_runner(
+        gateway_client,
+        TestRunnerConfig { gateway_retry_config, ..Default::default() },
+    );
+    let BroadcastNetworkMock {
+        broadcasted_messages_sender: mut mock_broadcasted_messages_sender,
+        ..
+    } = mock_network;
+    let message_metadata = BroadcastedMessageMetadata::get_test_instance(&mut get_rng());
+    let rpc_transaction = invoke_tx_with_nonce(0);
+
+    mock_broadcasted_messages_sender
+        .send((rpc_transaction.clone(), message_metadata))
+        .await
+        .expect("Failed to send message");
+
+    tokio::select! {
+        _ = mempool_p2p_runner.start() => panic!("Mempool receiver failed to start"),
+        actual_rpc_transaction = add_tx_receiver.next() => {
+            assert_eq!(actual_rpc_transaction, Some(rpc_transaction.0));
+        }
+        _ = sleep(Duration::from_secs(5)) => panic!("Test timed out"),
+    }
+    assert_eq!(n_calls.load(Ordering::SeqCst), 3);
+    assert_eq!(mempool_p2p_runner.retry_queue.len(), 0);
+}
+
+#[tokio::test(start_paused = true)]
+async fn retry_queue_is_bounded_and_drops_expired_entries() {
+    const CAPACITY: usize = 3;
+    const MAX_AGE: Duration = Duration::from_secs(10);
+    let mut retry_queue = RetryQueue::new(CAPACITY, MAX_AGE);
+    let entry = |nonce: usize| RetryEntry {
+        tx_hash: TransactionHash::default(),
+        gateway_input: GatewayInput {
+            rpc_tx: invoke_tx_with_nonce(nonce).0,
+            message_metadata: None,
+        },
+        first_failed_at: tokio::time::Instant::now(),
+    };
+
+    // Filling the queue.
+    assert!(retry_queue.push(entry(0)));
+    tokio::time::advance(Duration::from_secs(6)).await;
+    assert!(retry_queue.push(entry(1)));
+    assert!(retry_queue.push(entry(2)));
+    assert!(!retry_queue.push(entry(3)));
+    assert_eq!(retry_queue.len(), CAPACITY);
+    assert_eq!(retry_queue.oldest_age(), Some(Duration::from_secs(6)));
+
+    // Only the oldest entry ages out.
+    tokio::time::advance(Duration::from_secs(6)).await;
+    assert_eq!(retry_queue.drop_expired(), 1);
+    assert_eq!(retry_queue.len(), 2);
+    assert_eq!(retry_queue.oldest_age(), Some(Duration::from_secs(6)));
+
+    // Entries that are already too old are not queued.
+    let mut expired_entry = entry(4);
+    expired_entry.first_failed_at -= MAX_AGE + Duration::from_secs(1);
+    assert!(!retry_queue.push(expired_entry));
+
+    assert_eq!(retry_queue.pop().unwrap().gateway_input.rpc_tx, invoke_tx_with_nonce(1).0);
+    assert_eq!(retry_queue.pop().unwrap().gateway_input.rpc_tx, invoke_tx_with_nonce(2).0);
+    assert!(retry_queue.pop().is_none());
+}
+
 // TODO(eitan): Add test for when the gateway client fails to add the transaction
@@ -1,10 +1,13 @@
 use std::any::type_name;
+use std::collections::BTreeMap;
 use std::net::SocketAddr;
+use std::sync::{Arc, RwLock};
 
 use axum::http::StatusCode;
 use axum::routing::get;
-use axum::{async_trait, Router, Server};
+use axum::{async_trait, Json, Router, Server};
 use hyper::Error;
+use serde::Serialize;
 use starknet_sequencer_infra::component_definitions::ComponentStarter;
 use starknet_sequencer_infra::errors::ComponentError;
 use tracing::{info, instrument};
@@ -19,15 +22,68 @@ pub(crate) const MONITORING_PREFIX: &str = "monitoring";
 pub(crate) const ALIVE: &str = "alive";
 pub(crate) const READY: &str = "ready";
 pub(crate) const VERSION: &str = "nodeVersion";
+pub(crate) const VERSIONED_CONSTANTS_HASH: &str = "versionedConstantsHash";
+
+/// The status of a node component, as reported by the readiness endpoint.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ComponentStatus {
+    /// The component is not run by this node.
+    Disabled,
+    /// The component is run by another node, and is accessed through a remote client.
+    Remote,
+    /// The component is run by this node, and its server is running.
+    Up,
+    /// The component should be run by this node, but its server is not running.
+    Down,
+}
+
+/// The status of each node component, keyed by the component name.
+pub type ComponentStatuses = BTreeMap<&'static str, ComponentStatus>;
+
+/// The component statuses, shared between the node, which updates them as the component servers
+/// start and stop, and the monitoring endpoint, which reports them.
+#[derive(Clone, Debug, Default)]
+pub struct SharedComponentStatuses(Arc<RwLock<ComponentStatuses>>);
+
+impl SharedComponentStatuses {
+    pub fn new(component_statuses: ComponentStatuses) -> Self {
+        Self(Arc::new(RwLock::new(component_statuses)))
+    }
+
+    /// Returns the current status of each component.
+    pub fn get(&self) -> ComponentStatuses {
+        self.0.read().expect("Component statuses lock should not be poisoned.").clone()
+    }
+
+    pub fn set(&self, component: &'static str, status: ComponentStatus) {
+        self.0
+            .write()
+            .expect("Component statuses lock should not be poisoned.")
+            .insert(component, status);
+    }
+}
 
 pub struct MonitoringEndpoint {
     config: MonitoringEndpointConfig,
     version: &'static str,
+    // Hex-encoded content hash of the versioned constants used by the node.
+    versioned_constants_hash: String,
+    component_statuses: SharedComponentStatuses,
 }
 
 impl MonitoringEndpoint {
-    pub fn new(config: MonitoringEndpointConfig, version: &'static str) -> Self {
-        MonitoringEndpoint { config, version }
+    pub fn new(
+        config: MonitoringEndpointConfig,
+        version: &'static str,
+        versioned_constants_hash: String,
+        component_statuses: SharedComponentStatuses,
+    ) -> Self {
+        MonitoringEndpoint { config, version, versioned_constants_hash, component_statuses }
+    }
+
+    pub fn component_statuses(&self) -> ComponentStatuses {
+        self.component_statuses.get()
     }
 
     #[instrument(
@@ -35,6 +91,7 @@ impl MonitoringEndpoint {
         fields(
             config = %self.config,
             version = %self.version,
+            versioned_constants_hash = %self.versioned_constants_hash,
         ),
         level = "debug")]
     pub async fn run(&self) -> Result<(), Error> {
@@ -49,6 +106,8 @@ impl MonitoringEndpoint {
 
     fn app(&self) -> Router {
         let version = self.version.to_string();
+        let versioned_constants_hash = self.versioned_constants_hash.clone();
+        let component_statuses = self.component_statuses.clone();
 
         Router::new()
             .route(
@@ -57,20 +116,37 @@ impl MonitoringEndpoint {
             )
             .route(
                 format!("/{MONITORING_PREFIX}/{READY}").as_str(),
-                get(move || async { StatusCode::OK.to_string() }),
+                get(move || {
+                    let component_statuses = component_statuses.get();
+                    // The node is ready only if none of the components it should run are down.
+                    let ready_status_code = match component_statuses
+                        .values()
+                        .any(|status| *status == ComponentStatus::Down)
+                    {
+                        true => StatusCode::SERVICE_UNAVAILABLE,
+                        false => StatusCode::OK,
+                    };
+                    async move { (ready_status_code, Json(component_statuses)) }
+                }),
             )
             .route(
                 format!("/{MONITORING_PREFIX}/{VERSION}").as_str(),
                 get(move || async { version }),
             )
+            .route(
+                format!("/{MONITORING_PREFIX}/{VERSIONED_CONSTANTS_HASH}").as_str(),
+                get(move || async { versioned_constants_hash }),
+            )
     }
 }
 
 pub fn create_monitoring_endpoint(
     config: MonitoringEndpointConfig,
     version: &'static str,
+    versioned_constants_hash: String,
+    component_statuses: SharedComponentStatuses,
 ) -> MonitoringEndpoint {
-    MonitoringEndpoint::new(config, version)
+    MonitoringEndpoint::new(config, version, versioned_constants_hash, component_statuses)
 }
 
 #[async_trait]
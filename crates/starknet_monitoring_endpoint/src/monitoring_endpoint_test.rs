@@ -15,17 +15,33 @@ use tower::ServiceExt;
 use super::MonitoringEndpointConfig;
 use crate::monitoring_endpoint::{
     create_monitoring_endpoint,
+    ComponentStatus,
+    ComponentStatuses,
     MonitoringEndpoint,
+    SharedComponentStatuses,
     ALIVE,
     READY,
     VERSION,
+    VERSIONED_CONSTANTS_HASH,
 };
 use crate::test_utils::build_request;
 
 const TEST_VERSION: &str = "1.2.3-dev";
+const TEST_VERSIONED_CONSTANTS_HASH: &str = "0123abcd";
 
 fn setup_monitoring_endpoint() -> MonitoringEndpoint {
-    create_monitoring_endpoint(MonitoringEndpointConfig::default(), TEST_VERSION)
+    setup_monitoring_endpoint_with_statuses(ComponentStatuses::new())
+}
+
+fn setup_monitoring_endpoint_with_statuses(
+    component_statuses: ComponentStatuses,
+) -> MonitoringEndpoint {
+    create_monitoring_endpoint(
+        MonitoringEndpointConfig::default(),
+        TEST_VERSION,
+        TEST_VERSIONED_CONSTANTS_HASH.to_string(),
+        SharedComponentStatuses::new(component_statuses),
+    )
 }
 
 async fn request_app(app: Router, method: &str) -> Response<UnsyncBoxBody<Bytes, Error>> {
@@ -41,6 +57,15 @@ async fn test_node_version() {
     assert_eq!(&body[..], TEST_VERSION.as_bytes());
 }
 
+#[tokio::test]
+async fn test_versioned_constants_hash() {
+    let response = request_app(setup_monitoring_endpoint().app(), VERSIONED_CONSTANTS_HASH).await;
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let body = to_bytes(response.into_body()).await.unwrap();
+    assert_eq!(&body[..], TEST_VERSIONED_CONSTANTS_HASH.as_bytes());
+}
+
 #[tokio::test]
 async fn test_alive() {
     let response = request_app(setup_monitoring_endpoint().app(), ALIVE).await;
@@ -53,6 +78,59 @@ async fn test_ready() {
     assert_eq!(response.status(), StatusCode::OK);
 }
 
+#[tokio::test]
+async fn test_ready_reports_component_statuses() {
+    let component_statuses = ComponentStatuses::from([
+        ("batcher", ComponentStatus::Disabled),
+        ("gateway", ComponentStatus::Up),
+        ("mempool", ComponentStatus::Remote),
+    ]);
+    let response =
+        request_app(setup_monitoring_endpoint_with_statuses(component_statuses).app(), READY).await;
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let body = to_bytes(response.into_body()).await.unwrap();
+    assert_eq!(&body[..], br#"{"batcher":"disabled","gateway":"up","mempool":"remote"}"#);
+}
+
+#[tokio::test]
+async fn test_not_ready_when_a_component_is_down() {
+    let component_statuses = ComponentStatuses::from([
+        ("batcher", ComponentStatus::Down),
+        ("gateway", ComponentStatus::Up),
+    ]);
+    let response =
+        request_app(setup_monitoring_endpoint_with_statuses(component_statuses).app(), READY).await;
+    assert_eq!(response.status(), StatusCode::SERVICE_UNAVAILABLE);
+
+    let body = to_bytes(response.into_body()).await.unwrap();
+    assert_eq!(&body[..], br#"{"batcher":"down","gateway":"up"}"#);
+}
+
+#[tokio::test]
+async fn test_ready_reports_updated_component_statuses() {
+    let component_statuses = SharedComponentStatuses::new(ComponentStatuses::from([
+        ("batcher", ComponentStatus::Up),
+        ("gateway", ComponentStatus::Up),
+    ]));
+    let app = create_monitoring_endpoint(
+        MonitoringEndpointConfig::default(),
+        TEST_VERSION,
+        TEST_VERSIONED_CONSTANTS_HASH.to_string(),
+        component_statuses.clone(),
+    )
+    .app();
+    assert_eq!(request_app(app.clone(), READY).await.status(), StatusCode::OK);
+
+    // The batcher server stops after the endpoint started.
+    component_statuses.set("batcher", ComponentStatus::Down);
+    let response = request_app(app, READY).await;
+    assert_eq!(response.status(), StatusCode::SERVICE_UNAVAILABLE);
+
+    let body = to_bytes(response.into_body()).await.unwrap();
+    assert_eq!(&body[..], br#"{"batcher":"down","gateway":"up"}"#);
+}
+
 #[tokio::test]
 async fn test_endpoint_as_server() {
     spawn(async move { setup_monitoring_endpoint().run().await });
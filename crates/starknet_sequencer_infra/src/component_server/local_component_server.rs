@@ -13,6 +13,7 @@ use crate::component_definitions::{
 };
 use crate::component_server::{ComponentReplacer, ComponentServerStarter};
 use crate::errors::{ComponentServerError, ReplaceComponentError};
+use crate::shutdown::ShutdownSignal;
 
 /// The `LocalComponentServer` struct is a generic server that handles requests and responses for a
 /// specified component. It receives requests, processes them using the provided component, and
@@ -128,7 +129,7 @@ where
     async fn start(&mut self) -> Result<(), ComponentServerError> {
         info!("Starting LocalComponentServer for {}.", type_name::<Component>());
         self.component.start().await?;
-        request_response_loop(&mut self.rx, &mut self.component).await;
+        request_response_loop(&mut self.rx, &mut self.component, &mut self.shutdown_signal).await;
         info!("Finished LocalComponentServer for {}.", type_name::<Component>());
         Ok(())
     }
@@ -149,13 +150,17 @@ where
     async fn start(&mut self) -> Result<(), ComponentServerError> {
         let mut component = self.component.clone();
         let component_future = async move { component.start().await };
-        let request_response_future = request_response_loop(&mut self.rx, &mut self.component);
+        let request_response_future =
+            request_response_loop(&mut self.rx, &mut self.component, &mut self.shutdown_signal);
 
         tokio::select! {
             _res = component_future => {
                 error!("Component stopped.");
             }
             _res = request_response_future => {
+                if self.shutdown_signal.is_triggered() {
+                    return Ok(());
+                }
                 error!("Server stopped.");
             }
         };
@@ -172,6 +177,7 @@ where
 {
     component: Component,
     rx: Receiver<ComponentRequestAndResponseSender<Request, Response>>,
+    shutdown_signal: ShutdownSignal,
     _local_server_type: PhantomData<LocalServerType>,
 }
 
@@ -186,7 +192,18 @@ where
         component: Component,
         rx: Receiver<ComponentRequestAndResponseSender<Request, Response>>,
     ) -> Self {
-        Self { component, rx, _local_server_type: PhantomData }
+        Self {
+            component,
+            rx,
+            shutdown_signal: ShutdownSignal::default(),
+            _local_server_type: PhantomData,
+        }
+    }
+
+    /// Sets the signal on which the server stops receiving requests. The requests already sent to
+    /// the server are still handled before it stops.
+    pub fn set_shutdown_signal(&mut self, shutdown_signal: ShutdownSignal) {
+        self.shutdown_signal = shutdown_signal;
     }
 }
 
@@ -206,6 +223,7 @@ where
 async fn request_response_loop<Request, Response, Component>(
     rx: &mut Receiver<ComponentRequestAndResponseSender<Request, Response>>,
     component: &mut Component,
+    shutdown_signal: &mut ShutdownSignal,
 ) where
     Component: ComponentRequestHandler<Request, Response> + Send + Sync,
     Request: Send + Sync + Debug,
@@ -213,7 +231,21 @@ async fn request_response_loop<Request, Response, Component>(
 {
     info!("Starting server for component {}", type_name::<Component>());
 
-    while let Some(request_and_res_tx) = rx.recv().await {
+    let mut draining = false;
+    loop {
+        let request_and_res_tx = tokio::select! {
+            request_and_res_tx = rx.recv() => request_and_res_tx,
+            _ = shutdown_signal.wait(), if !draining => {
+                info!("Draining server for component {}", type_name::<Component>());
+                // Rejects new requests, while the ones already sent are still received.
+                rx.close();
+                draining = true;
+                continue;
+            }
+        };
+        let Some(request_and_res_tx) = request_and_res_tx else {
+            break;
+        };
         let request = request_and_res_tx.request;
         let tx = request_and_res_tx.tx;
         debug!("Component {} received request {:?}", type_name::<Component>(), request);
@@ -20,6 +20,7 @@ use crate::component_definitions::{
 use crate::component_server::ComponentServerStarter;
 use crate::errors::ComponentServerError;
 use crate::serde_utils::BincodeSerdeWrapper;
+use crate::shutdown::ShutdownSignal;
 
 /// The `RemoteComponentServer` struct is a generic server that handles requests and responses for a
 /// specified component. It receives requests, processes them using the provided component, and
@@ -111,6 +112,7 @@ where
 {
     socket: SocketAddr,
     local_client: LocalComponentClient<Request, Response>,
+    shutdown_signal: ShutdownSignal,
 }
 
 impl<Request, Response> RemoteComponentServer<Request, Response>
@@ -122,7 +124,13 @@ where
         local_client: LocalComponentClient<Request, Response>,
         config: RemoteServerConfig,
     ) -> Self {
-        Self { local_client, socket: config.socket }
+        Self { local_client, socket: config.socket, shutdown_signal: ShutdownSignal::default() }
+    }
+
+    /// Sets the signal on which the server stops accepting connections. The requests already
+    /// received are still handled before it stops.
+    pub fn set_shutdown_signal(&mut self, shutdown_signal: ShutdownSignal) {
+        self.shutdown_signal = shutdown_signal;
     }
 
     async fn remote_component_server_handler(
@@ -183,9 +191,11 @@ where
                 }))
             }
         });
+        let mut shutdown_signal = self.shutdown_signal.clone();
 
         Server::bind(&self.socket.clone())
             .serve(make_svc)
+            .with_graceful_shutdown(async move { shutdown_signal.wait().await })
             .await
             .map_err(|err| ComponentServerError::HttpServerStartError(err.to_string()))?;
         Ok(())
@@ -3,6 +3,7 @@ pub mod component_definitions;
 pub mod component_server;
 pub mod errors;
 pub mod serde_utils;
+pub mod shutdown;
 #[cfg(test)]
 pub mod test_utils;
 #[cfg(test)]
@@ -0,0 +1,68 @@
+use std::future::pending;
+use std::sync::Arc;
+
+use tokio::sync::watch;
+
+#[cfg(test)]
+#[path = "shutdown_test.rs"]
+pub mod shutdown_test;
+
+/// Broadcasts a shutdown signal to all the [`ShutdownSignal`]s subscribed to it. Clones of the
+/// handle trigger the same signal.
+#[derive(Clone, Debug)]
+pub struct ShutdownHandle {
+    sender: Arc<watch::Sender<bool>>,
+}
+
+impl ShutdownHandle {
+    pub fn new() -> Self {
+        let (sender, _receiver) = watch::channel(false);
+        Self { sender: Arc::new(sender) }
+    }
+
+    pub fn subscribe(&self) -> ShutdownSignal {
+        ShutdownSignal { receiver: self.sender.subscribe() }
+    }
+
+    /// Notifies the subscribed signals, including the ones subscribed after the trigger. Triggering
+    /// more than once has no further effect.
+    pub fn trigger(&self) {
+        self.sender.send_replace(true);
+    }
+
+    pub fn is_triggered(&self) -> bool {
+        *self.sender.borrow()
+    }
+}
+
+impl Default for ShutdownHandle {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// The receiving side of a [`ShutdownHandle`]. The default signal is never triggered.
+#[derive(Clone, Debug)]
+pub struct ShutdownSignal {
+    receiver: watch::Receiver<bool>,
+}
+
+impl ShutdownSignal {
+    /// Waits until the shutdown is triggered, or returns immediately if it already was. If all the
+    /// handles are dropped without triggering the shutdown, waits forever.
+    pub async fn wait(&mut self) {
+        if self.receiver.wait_for(|is_triggered| *is_triggered).await.is_err() {
+            pending::<()>().await;
+        }
+    }
+
+    pub fn is_triggered(&self) -> bool {
+        *self.receiver.borrow()
+    }
+}
+
+impl Default for ShutdownSignal {
+    fn default() -> Self {
+        ShutdownHandle::new().subscribe()
+    }
+}
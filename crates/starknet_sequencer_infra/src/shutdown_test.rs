@@ -0,0 +1,33 @@
+use std::time::Duration;
+
+use futures::FutureExt;
+use tokio::time::timeout;
+
+use crate::shutdown::{ShutdownHandle, ShutdownSignal};
+
+const TIMEOUT: Duration = Duration::from_millis(100);
+
+#[tokio::test]
+async fn signal_is_received_by_all_subscribers() {
+    let handle = ShutdownHandle::new();
+    let mut early_signal = handle.subscribe();
+    assert!(early_signal.wait().now_or_never().is_none());
+
+    handle.clone().trigger();
+    assert!(handle.is_triggered());
+
+    let mut late_signal = handle.subscribe();
+    timeout(TIMEOUT, early_signal.wait()).await.unwrap();
+    timeout(TIMEOUT, late_signal.wait()).await.unwrap();
+}
+
+#[tokio::test]
+async fn signal_without_trigger_never_fires() {
+    let mut default_signal = ShutdownSignal::default();
+    assert!(timeout(TIMEOUT, default_signal.wait()).await.is_err());
+
+    let handle = ShutdownHandle::new();
+    let mut signal = handle.subscribe();
+    drop(handle);
+    assert!(timeout(TIMEOUT, signal.wait()).await.is_err());
+}
@@ -1,11 +1,16 @@
+use std::time::Duration;
+
+use assert_matches::assert_matches;
 use async_trait::async_trait;
 use starknet_types_core::felt::Felt;
 use tokio::sync::mpsc::channel;
 use tokio::task;
+use tokio::time::timeout;
 
 use crate::component_client::{ClientError, ClientResult, LocalComponentClient};
 use crate::component_definitions::{ComponentClient, ComponentRequestAndResponseSender};
 use crate::component_server::{ComponentServerStarter, LocalComponentServer};
+use crate::shutdown::ShutdownHandle;
 use crate::tests::{
     test_a_b_functionality,
     ComponentA,
@@ -87,3 +92,38 @@ async fn test_setup() {
 
     test_a_b_functionality(a_client, b_client, expected_value).await;
 }
+
+#[tokio::test]
+async fn test_server_drains_requests_on_shutdown() {
+    let setup_value: ValueB = Felt::from(30);
+
+    let (tx_a, _rx_a) =
+        channel::<ComponentRequestAndResponseSender<ComponentARequest, ComponentAResponse>>(32);
+    let (tx_b, rx_b) =
+        channel::<ComponentRequestAndResponseSender<ComponentBRequest, ComponentBResponse>>(32);
+
+    let component_b = ComponentB::new(setup_value, Box::new(ComponentAClient::new(tx_a)));
+    let mut component_b_server = LocalComponentServer::new(component_b, rx_b);
+    let shutdown_handle = ShutdownHandle::new();
+    component_b_server.set_shutdown_signal(shutdown_handle.subscribe());
+
+    // A request sent before the shutdown is triggered.
+    let (res_tx, mut res_rx) = channel(1);
+    let request_and_res_tx =
+        ComponentRequestAndResponseSender { request: ComponentBRequest::BGetValue, tx: res_tx };
+    tx_b.send(request_and_res_tx).await.unwrap();
+    shutdown_handle.trigger();
+
+    timeout(Duration::from_secs(5), component_b_server.start())
+        .await
+        .expect("The server should stop once it handled its pending requests.")
+        .unwrap();
+
+    let response = res_rx.recv().await.unwrap();
+    assert_matches!(response, ComponentBResponse::BGetValue(value) if value == setup_value);
+    // Requests sent after the shutdown are rejected.
+    let (res_tx, _res_rx) = channel(1);
+    let request_and_res_tx =
+        ComponentRequestAndResponseSender { request: ComponentBRequest::BGetValue, tx: res_tx };
+    assert!(tx_b.send(request_and_res_tx).await.is_err());
+}
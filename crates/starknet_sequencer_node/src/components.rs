@@ -1,3 +1,14 @@
+#[cfg(test)]
+#[path = "components_test.rs"]
+mod components_test;
+
+use std::sync::Arc;
+
+use blockifier::versioned_constants::VersionedConstants;
+use futures::channel::mpsc::Receiver;
+use papyrus_config::presentation::get_config_presentation;
+use papyrus_config::ConfigError;
+use serde_json::{json, Map, Value};
 use starknet_batcher::batcher::{create_batcher, Batcher};
 use starknet_consensus_manager::consensus_manager::ConsensusManager;
 use starknet_gateway::gateway::{create_gateway, Gateway};
@@ -5,15 +16,20 @@ use starknet_http_server::http_server::{create_http_server, HttpServer};
 use starknet_mempool::communication::{create_mempool, MempoolCommunicationWrapper};
 use starknet_mempool_p2p::create_p2p_propagator_and_runner;
 use starknet_mempool_p2p::propagator::MempoolP2pPropagator;
-use starknet_mempool_p2p::runner::MempoolP2pRunner;
+use starknet_mempool_p2p::runner::{MempoolP2pRunner, PeerReportEvent};
 use starknet_monitoring_endpoint::monitoring_endpoint::{
     create_monitoring_endpoint,
+    ComponentStatus,
+    ComponentStatuses,
     MonitoringEndpoint,
+    SharedComponentStatuses,
 };
+use tracing::{info, warn};
 
 use crate::clients::SequencerNodeClients;
-use crate::config::component_execution_config::ComponentExecutionMode;
+use crate::config::component_execution_config::{ComponentExecutionConfig, ComponentExecutionMode};
 use crate::config::node_config::SequencerNodeConfig;
+use crate::shutdown::{NodeComponent, NodeShutdown};
 use crate::version::VERSION_FULL;
 
 pub struct SequencerNodeComponents {
@@ -25,26 +41,56 @@ pub struct SequencerNodeComponents {
     pub monitoring_endpoint: Option<MonitoringEndpoint>,
     pub mempool_p2p_propagator: Option<MempoolP2pPropagator>,
     pub mempool_p2p_runner: Option<MempoolP2pRunner>,
+    /// The events the mempool p2p runner emits whenever it reports a peer.
+    pub mempool_p2p_peer_reports: Option<Receiver<PeerReportEvent>>,
+    /// Reported by the monitoring endpoint, and updated as the component servers start and stop.
+    pub component_statuses: SharedComponentStatuses,
+    pub shutdown: NodeShutdown,
+}
+
+#[derive(Debug, thiserror::Error, PartialEq)]
+pub enum ComponentConfigError {
+    #[error("The {component} component requires a {client} client, but none is configured.")]
+    MissingClient { component: &'static str, client: &'static str },
 }
 
+/// Creates the components run by this node. Fails if a component's required client isn't
+/// available, i.e., the component it depends on is neither run locally nor accessed remotely. The
+/// components that observe the node shutdown are wired to the returned components' shutdown.
 pub fn create_node_components(
     config: &SequencerNodeConfig,
     clients: &SequencerNodeClients,
-) -> SequencerNodeComponents {
+) -> Result<SequencerNodeComponents, ComponentConfigError> {
+    let shutdown = NodeShutdown::new();
+
+    // The gateway and the batcher share a single instance, so that transactions are validated and
+    // executed under the same versioned constants.
+    let versioned_constants = Arc::new(VersionedConstants::get_versioned_constants(
+        config.batcher_config.block_builder_config.versioned_constants_overrides.clone(),
+    ));
     let batcher = match config.components.batcher.execution_mode {
         ComponentExecutionMode::LocalExecutionWithRemoteDisabled
         | ComponentExecutionMode::LocalExecutionWithRemoteEnabled => {
-            let mempool_client =
-                clients.get_mempool_shared_client().expect("Mempool Client should be available");
-            Some(create_batcher(config.batcher_config.clone(), mempool_client))
+            let mempool_client = clients.get_mempool_shared_client().ok_or(
+                ComponentConfigError::MissingClient { component: "batcher", client: "mempool" },
+            )?;
+            Some(create_batcher(
+                config.batcher_config.clone(),
+                mempool_client,
+                versioned_constants.clone(),
+            ))
         }
         ComponentExecutionMode::Disabled | ComponentExecutionMode::Remote => None,
     };
     let consensus_manager = match config.components.consensus_manager.execution_mode {
         ComponentExecutionMode::LocalExecutionWithRemoteDisabled
         | ComponentExecutionMode::LocalExecutionWithRemoteEnabled => {
-            let batcher_client =
-                clients.get_batcher_shared_client().expect("Batcher Client should be available");
+            let batcher_client = clients.get_batcher_shared_client().ok_or(
+                ComponentConfigError::MissingClient {
+                    component: "consensus_manager",
+                    client: "batcher",
+                },
+            )?;
             Some(ConsensusManager::new(config.consensus_manager_config.clone(), batcher_client))
         }
         ComponentExecutionMode::Disabled | ComponentExecutionMode::Remote => None,
@@ -52,14 +98,16 @@ pub fn create_node_components(
     let gateway = match config.components.gateway.execution_mode {
         ComponentExecutionMode::LocalExecutionWithRemoteDisabled
         | ComponentExecutionMode::LocalExecutionWithRemoteEnabled => {
-            let mempool_client =
-                clients.get_mempool_shared_client().expect("Mempool Client should be available");
+            let mempool_client = clients.get_mempool_shared_client().ok_or(
+                ComponentConfigError::MissingClient { component: "gateway", client: "mempool" },
+            )?;
 
             Some(create_gateway(
                 config.gateway_config.clone(),
                 config.rpc_state_reader_config.clone(),
                 config.compiler_config.clone(),
                 mempool_client,
+                versioned_constants.clone(),
             ))
         }
         ComponentExecutionMode::Disabled | ComponentExecutionMode::Remote => None,
@@ -67,28 +115,32 @@ pub fn create_node_components(
     let http_server = match config.components.http_server.execution_mode {
         ComponentExecutionMode::LocalExecutionWithRemoteDisabled
         | ComponentExecutionMode::LocalExecutionWithRemoteEnabled => {
-            let gateway_client =
-                clients.get_gateway_shared_client().expect("Gateway Client should be available");
+            let gateway_client = clients.get_gateway_shared_client().ok_or(
+                ComponentConfigError::MissingClient { component: "http_server", client: "gateway" },
+            )?;
 
             Some(create_http_server(config.http_server_config.clone(), gateway_client))
         }
         ComponentExecutionMode::Disabled | ComponentExecutionMode::Remote => None,
     };
 
-    let (mempool_p2p_propagator, mempool_p2p_runner) = match config
+    let (mempool_p2p_propagator, mempool_p2p_runner, mempool_p2p_peer_reports) = match config
         .components
         .mempool_p2p
         .execution_mode
     {
         ComponentExecutionMode::LocalExecutionWithRemoteDisabled
         | ComponentExecutionMode::LocalExecutionWithRemoteEnabled => {
-            let gateway_client =
-                clients.get_gateway_shared_client().expect("Gateway Client should be available");
-            let (mempool_p2p_propagator, mempool_p2p_runner) =
+            let gateway_client = clients.get_gateway_shared_client().ok_or(
+                ComponentConfigError::MissingClient { component: "mempool_p2p", client: "gateway" },
+            )?;
+            let (mempool_p2p_propagator, mut mempool_p2p_runner, mempool_p2p_peer_reports) =
                 create_p2p_propagator_and_runner(config.mempool_p2p_config.clone(), gateway_client);
-            (Some(mempool_p2p_propagator), Some(mempool_p2p_runner))
+            mempool_p2p_runner
+                .set_shutdown_signal(shutdown.component_signal(NodeComponent::MempoolP2pRunner));
+            (Some(mempool_p2p_propagator), Some(mempool_p2p_runner), Some(mempool_p2p_peer_reports))
         }
-        ComponentExecutionMode::Disabled | ComponentExecutionMode::Remote => (None, None),
+        ComponentExecutionMode::Disabled | ComponentExecutionMode::Remote => (None, None, None),
     };
 
     let mempool = match config.components.mempool.execution_mode {
@@ -96,22 +148,48 @@ pub fn create_node_components(
         | ComponentExecutionMode::LocalExecutionWithRemoteEnabled => {
             let mempool_p2p_propagator_client = clients
                 .get_mempool_p2p_propagator_shared_client()
-                .expect("Propagator Client should be available");
+                .ok_or(ComponentConfigError::MissingClient {
+                    component: "mempool",
+                    client: "mempool_p2p_propagator",
+                })?;
             let mempool = create_mempool(mempool_p2p_propagator_client);
             Some(mempool)
         }
         ComponentExecutionMode::Disabled | ComponentExecutionMode::Remote => None,
     };
 
-    let monitoring_endpoint = match config.components.monitoring_endpoint.execution_mode {
-        ComponentExecutionMode::LocalExecutionWithRemoteEnabled => Some(
-            create_monitoring_endpoint(config.monitoring_endpoint_config.clone(), VERSION_FULL),
+    let components = &config.components;
+    let component_statuses = SharedComponentStatuses::new(ComponentStatuses::from([
+        ("batcher", initial_component_status(&components.batcher.execution_mode)),
+        (
+            "consensus_manager",
+            initial_component_status(&components.consensus_manager.execution_mode),
         ),
+        ("gateway", initial_component_status(&components.gateway.execution_mode)),
+        ("http_server", initial_component_status(&components.http_server.execution_mode)),
+        ("mempool", initial_component_status(&components.mempool.execution_mode)),
+        ("mempool_p2p", initial_component_status(&components.mempool_p2p.execution_mode)),
+        (
+            "monitoring_endpoint",
+            initial_component_status(&components.monitoring_endpoint.execution_mode),
+        ),
+    ]));
+
+    let monitoring_endpoint_execution_mode = &config.components.monitoring_endpoint.execution_mode;
+    let monitoring_endpoint = match monitoring_endpoint_execution_mode {
+        ComponentExecutionMode::LocalExecutionWithRemoteEnabled => {
+            Some(create_monitoring_endpoint(
+                config.monitoring_endpoint_config.clone(),
+                VERSION_FULL,
+                hex::encode(versioned_constants.content_hash()),
+                component_statuses.clone(),
+            ))
+        }
         ComponentExecutionMode::LocalExecutionWithRemoteDisabled => None,
         ComponentExecutionMode::Disabled | ComponentExecutionMode::Remote => None,
     };
 
-    SequencerNodeComponents {
+    Ok(SequencerNodeComponents {
         batcher,
         consensus_manager,
         gateway,
@@ -120,5 +198,82 @@ pub fn create_node_components(
         monitoring_endpoint,
         mempool_p2p_propagator,
         mempool_p2p_runner,
+        mempool_p2p_peer_reports,
+        component_statuses,
+        shutdown,
+    })
+}
+
+/// Returns the status of a component given its execution mode, before the node runs. The components
+/// run by this node are down until their servers start.
+fn initial_component_status(execution_mode: &ComponentExecutionMode) -> ComponentStatus {
+    match execution_mode {
+        ComponentExecutionMode::Disabled => ComponentStatus::Disabled,
+        ComponentExecutionMode::Remote => ComponentStatus::Remote,
+        ComponentExecutionMode::LocalExecutionWithRemoteDisabled
+        | ComponentExecutionMode::LocalExecutionWithRemoteEnabled => ComponentStatus::Down,
+    }
+}
+
+/// Logs a single event summarizing the node components: the execution mode of each component and,
+/// for the components run by this node, their config. Private config parameters are not logged.
+pub fn log_components_summary(config: &SequencerNodeConfig) {
+    match components_summary(config) {
+        Ok(summary) => info!(components = %summary, "Node components summary."),
+        Err(err) => warn!("Failed to summarize the node components: {err}"),
     }
 }
+
+fn components_summary(config: &SequencerNodeConfig) -> Result<Value, ConfigError> {
+    let components = &config.components;
+    let summaries: [(&str, &ComponentExecutionConfig, Option<Value>); 7] = [
+        (
+            "batcher",
+            &components.batcher,
+            Some(get_config_presentation(&config.batcher_config, false)?),
+        ),
+        (
+            "consensus_manager",
+            &components.consensus_manager,
+            Some(get_config_presentation(&config.consensus_manager_config, false)?),
+        ),
+        (
+            "gateway",
+            &components.gateway,
+            Some(get_config_presentation(&config.gateway_config, false)?),
+        ),
+        (
+            "http_server",
+            &components.http_server,
+            Some(get_config_presentation(&config.http_server_config, false)?),
+        ),
+        ("mempool", &components.mempool, None),
+        (
+            "mempool_p2p",
+            &components.mempool_p2p,
+            Some(get_config_presentation(&config.mempool_p2p_config, false)?),
+        ),
+        (
+            "monitoring_endpoint",
+            &components.monitoring_endpoint,
+            Some(get_config_presentation(&config.monitoring_endpoint_config, false)?),
+        ),
+    ];
+
+    let summary = summaries
+        .into_iter()
+        .map(|(name, execution_config, component_config)| {
+            let mut component_summary = Map::from_iter([(
+                "execution_mode".to_string(),
+                json!(execution_config.execution_mode),
+            )]);
+            if let Some(component_config) =
+                component_config.filter(|_| execution_config.is_run_locally())
+            {
+                component_summary.insert("config".to_string(), component_config);
+            }
+            (name.to_string(), Value::Object(component_summary))
+        })
+        .collect();
+    Ok(Value::Object(summary))
+}
@@ -0,0 +1,178 @@
+use std::sync::Arc;
+
+use rstest::rstest;
+use starknet_monitoring_endpoint::monitoring_endpoint::{ComponentStatus, ComponentStatuses};
+use starknet_sequencer_infra::component_definitions::RemoteClientConfig;
+use tempfile::tempdir;
+use tracing_test::traced_test;
+
+use crate::clients::create_node_clients;
+use crate::communication::create_node_channels;
+use crate::components::{create_node_components, log_components_summary, ComponentConfigError};
+use crate::config::component_config::ComponentConfig;
+use crate::config::component_execution_config::{ComponentExecutionConfig, ComponentExecutionMode};
+use crate::config::node_config::SequencerNodeConfig;
+
+fn disabled_component_config() -> ComponentExecutionConfig {
+    ComponentExecutionConfig::disabled_config()
+}
+
+fn remote_component_config() -> ComponentExecutionConfig {
+    ComponentExecutionConfig {
+        execution_mode: ComponentExecutionMode::Remote,
+        local_server_config: None,
+        remote_client_config: Some(RemoteClientConfig::default()),
+        remote_server_config: None,
+    }
+}
+
+fn all_disabled_components_config() -> ComponentConfig {
+    ComponentConfig {
+        batcher: disabled_component_config(),
+        consensus_manager: disabled_component_config(),
+        gateway: disabled_component_config(),
+        http_server: disabled_component_config(),
+        mempool: disabled_component_config(),
+        mempool_p2p: disabled_component_config(),
+        monitoring_endpoint: disabled_component_config(),
+    }
+}
+
+#[test]
+fn gateway_and_batcher_share_versioned_constants() {
+    let batcher_storage_dir = tempdir().unwrap();
+    let mut config = SequencerNodeConfig::default();
+    config.batcher_config.storage.db_config.path_prefix = batcher_storage_dir.path().into();
+    config.batcher_config.storage.db_config.enforce_file_exists = false;
+    config.components.consensus_manager = disabled_component_config();
+    config.components.http_server = disabled_component_config();
+    config.components.mempool_p2p = disabled_component_config();
+    config.components.monitoring_endpoint = disabled_component_config();
+    // The batcher and the gateway only need a mempool client, so the mempool is not created.
+    config.components.mempool = remote_component_config();
+
+    let clients = create_node_clients(&config, &mut create_node_channels());
+    let components = create_node_components(&config, &clients).unwrap();
+
+    let batcher = components.batcher.expect("Batcher should be created.");
+    let gateway = components.gateway.expect("Gateway should be created.");
+    assert!(Arc::ptr_eq(
+        &gateway.stateful_tx_validator.versioned_constants,
+        &batcher.versioned_constants()
+    ));
+}
+
+#[test]
+fn monitoring_endpoint_reports_component_statuses() {
+    let mut config = SequencerNodeConfig::default();
+    config.components.batcher = disabled_component_config();
+    config.components.consensus_manager = disabled_component_config();
+    config.components.http_server = disabled_component_config();
+    config.components.mempool_p2p = disabled_component_config();
+    config.components.mempool = remote_component_config();
+    config.components.monitoring_endpoint.execution_mode =
+        ComponentExecutionMode::LocalExecutionWithRemoteEnabled;
+
+    let clients = create_node_clients(&config, &mut create_node_channels());
+    let components = create_node_components(&config, &clients).unwrap();
+
+    let monitoring_endpoint =
+        components.monitoring_endpoint.expect("Monitoring endpoint should be created.");
+    assert_eq!(
+        monitoring_endpoint.component_statuses(),
+        // The local components are down until their servers start.
+        ComponentStatuses::from([
+            ("batcher", ComponentStatus::Disabled),
+            ("consensus_manager", ComponentStatus::Disabled),
+            ("gateway", ComponentStatus::Down),
+            ("http_server", ComponentStatus::Disabled),
+            ("mempool", ComponentStatus::Remote),
+            ("mempool_p2p", ComponentStatus::Disabled),
+            ("monitoring_endpoint", ComponentStatus::Down),
+        ])
+    );
+}
+
+#[rstest]
+#[case::batcher_without_mempool(
+    |components: &mut ComponentConfig| components.batcher = ComponentExecutionConfig::default(),
+    "batcher",
+    "mempool"
+)]
+#[case::consensus_manager_without_batcher(
+    |components: &mut ComponentConfig| {
+        components.consensus_manager = ComponentExecutionConfig::default()
+    },
+    "consensus_manager",
+    "batcher"
+)]
+#[case::gateway_without_mempool(
+    |components: &mut ComponentConfig| components.gateway = ComponentExecutionConfig::default(),
+    "gateway",
+    "mempool"
+)]
+#[case::http_server_without_gateway(
+    |components: &mut ComponentConfig| components.http_server = ComponentExecutionConfig::default(),
+    "http_server",
+    "gateway"
+)]
+#[case::mempool_p2p_without_gateway(
+    |components: &mut ComponentConfig| components.mempool_p2p = ComponentExecutionConfig::default(),
+    "mempool_p2p",
+    "gateway"
+)]
+#[case::mempool_without_mempool_p2p_propagator(
+    |components: &mut ComponentConfig| components.mempool = ComponentExecutionConfig::default(),
+    "mempool",
+    "mempool_p2p_propagator"
+)]
+fn component_without_required_client_fails(
+    #[case] enable_component: fn(&mut ComponentConfig),
+    #[case] component: &'static str,
+    #[case] client: &'static str,
+) {
+    let mut config = SequencerNodeConfig {
+        components: all_disabled_components_config(),
+        ..SequencerNodeConfig::default()
+    };
+    // Only the component under test runs, so the component it depends on is unavailable.
+    enable_component(&mut config.components);
+
+    let clients = create_node_clients(&config, &mut create_node_channels());
+    let result = create_node_components(&config, &clients);
+
+    assert_eq!(result.err(), Some(ComponentConfigError::MissingClient { component, client }));
+}
+
+#[tokio::test]
+async fn gateway_ingress_profile_creates_only_ingress_components() {
+    let config = SequencerNodeConfig::gateway_ingress_profile();
+    assert_eq!(config.validate_component_graph(), Ok(()));
+
+    let clients = create_node_clients(&config, &mut create_node_channels());
+    let components = create_node_components(&config, &clients).unwrap();
+
+    assert!(components.gateway.is_some());
+    assert!(components.http_server.is_some());
+    assert!(components.mempool.is_some());
+    assert!(components.mempool_p2p_propagator.is_some());
+    assert!(components.mempool_p2p_runner.is_some());
+    assert!(components.mempool_p2p_peer_reports.is_some());
+    assert!(components.batcher.is_none());
+    assert!(components.consensus_manager.is_none());
+}
+
+#[traced_test]
+#[test]
+fn components_summary_lists_components_without_private_params() {
+    let mut config = SequencerNodeConfig::default();
+    config.components.batcher = disabled_component_config();
+
+    log_components_summary(&config);
+
+    assert!(logs_contain(r#""batcher":{"execution_mode":"Disabled"}"#));
+    assert!(logs_contain(r#""mempool":{"execution_mode":"LocalExecutionWithRemoteDisabled"}"#));
+    assert!(logs_contain(r#""network_buffer_size":10000"#));
+    // The p2p network secret key is a private param.
+    assert!(!logs_contain("secret_key"));
+}
@@ -5,7 +5,15 @@ use papyrus_config::{ParamPath, SerializedParam};
 use serde::{Deserialize, Serialize};
 use validator::Validate;
 
-use crate::config::component_execution_config::ComponentExecutionConfig;
+use crate::config::component_execution_config::{ComponentExecutionConfig, ComponentExecutionMode};
+
+/// A component that is run by the node, but depends on a component that is disabled.
+#[derive(Clone, Debug, thiserror::Error, PartialEq)]
+#[error("The {component} component depends on the {dependency} component, which is disabled.")]
+pub struct ComponentDependencyError {
+    pub component: &'static str,
+    pub dependency: &'static str,
+}
 
 /// The components configuration.
 #[derive(Clone, Debug, Serialize, Deserialize, Validate, PartialEq)]
@@ -40,6 +48,42 @@ impl Default for ComponentConfig {
     }
 }
 
+impl ComponentConfig {
+    /// The components of a lightweight transaction ingress: the http server, the gateway and the
+    /// mempool, along with the mempool p2p the mempool depends on. Block building and consensus are
+    /// disabled.
+    pub fn gateway_ingress() -> Self {
+        Self {
+            batcher: ComponentExecutionConfig::disabled_config(),
+            consensus_manager: ComponentExecutionConfig::disabled_config(),
+            ..Self::default()
+        }
+    }
+
+    /// Returns all the dependency violations among the components, i.e., every component that is
+    /// run by the node while a component it requires a client of is disabled. A dependency that is
+    /// run remotely is considered satisfied.
+    pub fn dependency_violations(&self) -> Vec<ComponentDependencyError> {
+        let dependencies = [
+            ("batcher", &self.batcher, "mempool", &self.mempool),
+            ("consensus_manager", &self.consensus_manager, "batcher", &self.batcher),
+            ("gateway", &self.gateway, "mempool", &self.mempool),
+            ("http_server", &self.http_server, "gateway", &self.gateway),
+            ("mempool", &self.mempool, "mempool_p2p", &self.mempool_p2p),
+            ("mempool_p2p", &self.mempool_p2p, "gateway", &self.gateway),
+        ];
+
+        dependencies
+            .into_iter()
+            .filter(|(_, component_config, _, dependency_config)| {
+                component_config.is_run_locally()
+                    && dependency_config.execution_mode == ComponentExecutionMode::Disabled
+            })
+            .map(|(component, _, dependency, _)| ComponentDependencyError { component, dependency })
+            .collect()
+    }
+}
+
 impl SerializeConfig for ComponentConfig {
     fn dump(&self) -> BTreeMap<ParamPath, SerializedParam> {
         let sub_configs = vec![
@@ -62,8 +62,28 @@ impl Default for ComponentExecutionConfig {
     }
 }
 
+impl ComponentExecutionConfig {
+    /// Whether the component is run by this node.
+    pub fn is_run_locally(&self) -> bool {
+        matches!(
+            self.execution_mode,
+            ComponentExecutionMode::LocalExecutionWithRemoteDisabled
+                | ComponentExecutionMode::LocalExecutionWithRemoteEnabled
+        )
+    }
+}
+
 /// Specific components default configurations.
 impl ComponentExecutionConfig {
+    pub fn disabled_config() -> Self {
+        Self {
+            execution_mode: ComponentExecutionMode::Disabled,
+            local_server_config: None,
+            remote_client_config: None,
+            remote_server_config: None,
+        }
+    }
+
     pub fn gateway_default_config() -> Self {
         Self {
             execution_mode: ComponentExecutionMode::LocalExecutionWithRemoteDisabled,
@@ -15,6 +15,7 @@ use starknet_sequencer_infra::component_definitions::{
 };
 use validator::Validate;
 
+use crate::config::component_config::ComponentDependencyError;
 use crate::config::component_execution_config::{ComponentExecutionConfig, ComponentExecutionMode};
 use crate::config::node_config::{
     SequencerNodeConfig,
@@ -57,6 +58,46 @@ fn test_valid_component_execution_config(
     assert_eq!(component_exe_config.validate(), Ok(()));
 }
 
+#[test]
+fn test_default_component_graph_is_valid() {
+    assert_eq!(SequencerNodeConfig::default().validate_component_graph(), Ok(()));
+}
+
+#[test]
+fn test_component_graph_reports_all_violations() {
+    let disabled_component_config = ComponentExecutionConfig {
+        execution_mode: ComponentExecutionMode::Disabled,
+        local_server_config: None,
+        remote_client_config: None,
+        remote_server_config: None,
+    };
+    let mut config = SequencerNodeConfig::default();
+    config.components.batcher = disabled_component_config.clone();
+    config.components.gateway = disabled_component_config;
+
+    assert_eq!(
+        config.validate_component_graph(),
+        Err(vec![
+            ComponentDependencyError { component: "consensus_manager", dependency: "batcher" },
+            ComponentDependencyError { component: "http_server", dependency: "gateway" },
+            ComponentDependencyError { component: "mempool_p2p", dependency: "gateway" },
+        ])
+    );
+}
+
+#[test]
+fn test_component_graph_with_remote_dependency_is_valid() {
+    let mut config = SequencerNodeConfig::default();
+    config.components.batcher = ComponentExecutionConfig {
+        execution_mode: ComponentExecutionMode::Remote,
+        local_server_config: None,
+        remote_client_config: Some(RemoteClientConfig::default()),
+        remote_server_config: None,
+    };
+
+    assert_eq!(config.validate_component_graph(), Ok(()));
+}
+
 /// Test the validation of the struct SequencerNodeConfig and that the default config file is up to
 /// date. To update the default config file, run:
 /// cargo run --bin sequencer_dump_config -q
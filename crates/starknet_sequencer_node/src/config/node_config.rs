@@ -1,12 +1,15 @@
 use std::collections::{BTreeMap, HashSet};
 use std::fs::File;
-use std::path::Path;
+use std::io;
+use std::path::PathBuf;
 use std::sync::LazyLock;
 use std::vec::Vec;
 
+use blockifier::versioned_constants::VersionedConstantsOverrides;
 use clap::Command;
 use papyrus_config::dumping::{
     append_sub_config_name,
+    ser_pointer_target_param,
     ser_pointer_target_required_param,
     set_pointing_param_paths,
     ConfigPointers,
@@ -25,8 +28,8 @@ use starknet_monitoring_endpoint::config::MonitoringEndpointConfig;
 use starknet_sierra_compile::config::SierraToCasmCompilationConfig;
 use validator::Validate;
 
-use crate::config::component_config::ComponentConfig;
-use crate::utils::get_absolute_path;
+use crate::config::component_config::{ComponentConfig, ComponentDependencyError};
+use crate::utils::try_get_absolute_path;
 use crate::version::VERSION_FULL;
 
 // The path of the default configuration file, provided as part of the crate.
@@ -79,7 +82,64 @@ pub static REQUIRED_PARAM_CONFIG_POINTERS: LazyLock<ConfigPointers> = LazyLock::
 });
 
 // Optional target parameters, i.e., target parameters with default values.
-pub static DEFAULT_PARAM_CONFIG_POINTERS: LazyLock<ConfigPointers> = LazyLock::new(Vec::new);
+pub static DEFAULT_PARAM_CONFIG_POINTERS: LazyLock<ConfigPointers> = LazyLock::new(|| {
+    let versioned_constants_overrides = VersionedConstantsOverrides::default();
+    vec![
+        (
+            ser_pointer_target_param(
+                "versioned_constants_overrides.invoke_tx_max_n_steps",
+                &versioned_constants_overrides.invoke_tx_max_n_steps,
+                "Maximum number of steps the invoke function is allowed to run.",
+            ),
+            set_pointing_param_paths(&[
+                "batcher_config.block_builder_config.versioned_constants_overrides.\
+                 invoke_tx_max_n_steps",
+                "gateway_config.stateful_tx_validator_config.versioned_constants_overrides.\
+                 invoke_tx_max_n_steps",
+            ]),
+        ),
+        (
+            ser_pointer_target_param(
+                "versioned_constants_overrides.max_recursion_depth",
+                &versioned_constants_overrides.max_recursion_depth,
+                "Maximum recursion depth for nested calls during blockifier validation.",
+            ),
+            set_pointing_param_paths(&[
+                "batcher_config.block_builder_config.versioned_constants_overrides.\
+                 max_recursion_depth",
+                "gateway_config.stateful_tx_validator_config.versioned_constants_overrides.\
+                 max_recursion_depth",
+            ]),
+        ),
+        (
+            ser_pointer_target_param(
+                "versioned_constants_overrides.validate_max_n_steps",
+                &versioned_constants_overrides.validate_max_n_steps,
+                "Maximum number of steps the validation function is allowed to run.",
+            ),
+            set_pointing_param_paths(&[
+                "batcher_config.block_builder_config.versioned_constants_overrides.\
+                 validate_max_n_steps",
+                "gateway_config.stateful_tx_validator_config.versioned_constants_overrides.\
+                 validate_max_n_steps",
+            ]),
+        ),
+        (
+            ser_pointer_target_param(
+                "versioned_constants_overrides.unsupported_syscalls_are_recoverable",
+                &versioned_constants_overrides.unsupported_syscalls_are_recoverable,
+                "If true, a syscall that is not supported fails the execution with an error, \
+                 instead of panicking.",
+            ),
+            set_pointing_param_paths(&[
+                "batcher_config.block_builder_config.versioned_constants_overrides.\
+                 unsupported_syscalls_are_recoverable",
+                "gateway_config.stateful_tx_validator_config.versioned_constants_overrides.\
+                 unsupported_syscalls_are_recoverable",
+            ]),
+        ),
+    ]
+});
 
 // All target parameters.
 pub static CONFIG_POINTERS: LazyLock<ConfigPointers> = LazyLock::new(|| {
@@ -92,7 +152,6 @@ pub static CONFIG_POINTERS: LazyLock<ConfigPointers> = LazyLock::new(|| {
 pub static CONFIG_NON_POINTERS_WHITELIST: LazyLock<Pointers> =
     LazyLock::new(HashSet::<ParamPath>::new);
 
-// TODO(yair): Make the GW and batcher execution config point to the same values.
 /// The configurations of the various components of the node.
 #[derive(Debug, Default, Deserialize, Serialize, Clone, PartialEq, Validate)]
 pub struct SequencerNodeConfig {
@@ -148,14 +207,30 @@ impl SequencerNodeConfig {
         config_file_name: Option<&str>,
     ) -> Result<Self, ConfigError> {
         let config_file_name = match config_file_name {
-            Some(file_name) => Path::new(file_name),
-            None => &get_absolute_path(DEFAULT_CONFIG_PATH),
+            Some(file_name) => PathBuf::from(file_name),
+            None => try_get_absolute_path(DEFAULT_CONFIG_PATH)
+                .map_err(|err| io::Error::new(io::ErrorKind::NotFound, err))?,
         };
 
         let default_config_file = File::open(config_file_name)?;
         load_and_process_config(default_config_file, node_command(), args)
     }
 
+    /// A config of a node that only ingests transactions, see [`ComponentConfig::gateway_ingress`].
+    pub fn gateway_ingress_profile() -> Self {
+        Self { components: ComponentConfig::gateway_ingress(), ..Self::default() }
+    }
+
+    /// Checks that every component run by the node has the components it depends on available,
+    /// before any of them is created. Returns all the violations at once.
+    pub fn validate_component_graph(&self) -> Result<(), Vec<ComponentDependencyError>> {
+        let violations = self.components.dependency_violations();
+        match violations.is_empty() {
+            true => Ok(()),
+            false => Err(violations),
+        }
+    }
+
     pub fn load_and_process(args: Vec<String>) -> Result<Self, ConfigError> {
         Self::load_and_process_config_file(args, None)
     }
@@ -3,6 +3,7 @@ pub mod communication;
 pub mod components;
 pub mod config;
 pub mod servers;
+pub mod shutdown;
 #[cfg(any(feature = "testing", test))]
 pub mod test_utils;
 pub mod utils;
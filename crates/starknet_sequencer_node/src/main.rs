@@ -7,6 +7,8 @@ use starknet_sequencer_infra::trace_util::configure_tracing;
 use starknet_sequencer_node::config::node_config::SequencerNodeConfig;
 use starknet_sequencer_node::servers::run_component_servers;
 use starknet_sequencer_node::utils::create_node_modules;
+use tokio::signal::ctrl_c;
+use tokio::signal::unix::{signal, SignalKind};
 use tracing::{error, info};
 
 #[tokio::main]
@@ -25,14 +27,29 @@ async fn main() -> anyhow::Result<()> {
         error!("{}", error);
         exit(1);
     }
+    if let Err(violations) = config.validate_component_graph() {
+        for violation in violations {
+            error!("{}", violation);
+        }
+        exit(1);
+    }
     info!("Finished validating configuration.");
 
     // Clients are currently unused, but should not be dropped.
-    let (_clients, servers) = create_node_modules(&config);
+    let (_clients, servers) = create_node_modules(&config)?;
+
+    let shutdown_handle = servers.shutdown_handle();
+    tokio::spawn(async move {
+        let mut sigterm = signal(SignalKind::terminate()).expect("Failed to listen to SIGTERM.");
+        tokio::select! {
+            _ = sigterm.recv() => info!("Received SIGTERM."),
+            _ = ctrl_c() => info!("Received SIGINT."),
+        }
+        shutdown_handle.trigger();
+    });
 
     info!("Starting components!");
     run_component_servers(servers).await?;
 
-    // TODO(Tsabary): Add graceful shutdown.
     Ok(())
 }
@@ -1,7 +1,13 @@
+#[cfg(test)]
+#[path = "servers_test.rs"]
+mod servers_test;
+
 use std::future::pending;
 use std::pin::Pin;
 
-use futures::{Future, FutureExt};
+use futures::channel::mpsc::Receiver;
+use futures::future::select_all;
+use futures::{Future, FutureExt, StreamExt};
 use starknet_batcher::communication::{LocalBatcherServer, RemoteBatcherServer};
 use starknet_consensus_manager::communication::ConsensusManagerServer;
 use starknet_gateway::communication::{LocalGatewayServer, RemoteGatewayServer};
@@ -11,8 +17,9 @@ use starknet_mempool_p2p::propagator::{
     LocalMempoolP2pPropagatorServer,
     RemoteMempoolP2pPropagatorServer,
 };
-use starknet_mempool_p2p::runner::MempoolP2pRunnerServer;
+use starknet_mempool_p2p::runner::{MempoolP2pRunnerServer, PeerReportEvent};
 use starknet_monitoring_endpoint::communication::MonitoringEndpointServer;
+use starknet_monitoring_endpoint::monitoring_endpoint::{ComponentStatus, SharedComponentStatuses};
 use starknet_sequencer_infra::component_server::{
     ComponentServerStarter,
     LocalComponentServer,
@@ -20,13 +27,15 @@ use starknet_sequencer_infra::component_server::{
     WrapperServer,
 };
 use starknet_sequencer_infra::errors::ComponentServerError;
-use tracing::error;
+use starknet_sequencer_infra::shutdown::ShutdownHandle;
+use tracing::{error, info};
 
 use crate::clients::SequencerNodeClients;
 use crate::communication::SequencerNodeCommunication;
 use crate::components::SequencerNodeComponents;
 use crate::config::component_execution_config::ComponentExecutionMode;
 use crate::config::node_config::SequencerNodeConfig;
+use crate::shutdown::{NodeComponent, NodeShutdown};
 
 // Component servers that can run locally.
 struct LocalServers {
@@ -55,55 +64,73 @@ pub struct RemoteServers {
 
 pub struct SequencerNodeServers {
     local_servers: LocalServers,
+    remote_servers: RemoteServers,
     wrapper_servers: WrapperServers,
+    mempool_p2p_peer_reports: Option<Receiver<PeerReportEvent>>,
+    component_statuses: SharedComponentStatuses,
+    shutdown: NodeShutdown,
+}
+
+impl SequencerNodeServers {
+    /// Returns the handle that triggers the graceful shutdown of the running servers.
+    pub fn shutdown_handle(&self) -> ShutdownHandle {
+        self.shutdown.node_handle()
+    }
 }
 
 /// A macro for creating a remote component server based on the component's execution mode.
-/// Returns a remote server if the component is configured with Remote execution mode; otherwise,
-/// returns None.
+/// Returns a remote server if the component is run locally and exposed to other nodes, i.e., its
+/// execution mode is LocalExecutionWithRemoteEnabled; otherwise, returns None.
 ///
 /// # Arguments
 ///
 /// * `$execution_mode` - A reference to the component's execution mode, of type
 ///   `&ComponentExecutionMode`.
-/// * `$local_client` - The local client to be used for the remote server initialization if the
-///   execution mode is `Remote`.
+/// * `$local_client` - The local client, through which the remote server forwards the requests it
+///   receives to the component.
 /// * `$config` - The configuration for the remote server.
+/// * `$shutdown_signal` - The signal on which the remote server stops.
 ///
 /// # Returns
 ///
 /// An `Option<Box<RemoteComponentServer<LocalClientType, RequestType, ResponseType>>>` containing
-/// the remote server if the execution mode is Remote, or None if the execution mode is Disabled,
-/// LocalExecutionWithRemoteEnabled or LocalExecutionWithRemoteDisabled.
+/// the remote server if the execution mode is LocalExecutionWithRemoteEnabled, or None if the
+/// execution mode is Disabled, Remote or LocalExecutionWithRemoteDisabled.
 ///
 /// # Example
 ///
 /// ```rust,ignore
 /// let batcher_remote_server = create_remote_server!(
 ///     &config.components.batcher.execution_mode,
-///     clients.get_gateway_local_client(),
-///     config.remote_server_config
+///     clients.get_batcher_local_client(),
+///     config.components.batcher.remote_server_config,
+///     shutdown.component_signal(NodeComponent::RemoteBatcher)
 /// );
 /// match batcher_remote_server {
 ///     Some(server) => println!("Remote server created: {:?}", server),
-///     None => println!("Remote server not created because the execution mode is not remote."),
+///     None => println!("Remote server not created because the component is not exposed."),
 /// }
 /// ```
 #[macro_export]
 macro_rules! create_remote_server {
-    ($execution_mode:expr, $local_client:expr, $config:expr) => {
+    ($execution_mode:expr, $local_client:expr, $config:expr, $shutdown_signal:expr) => {
         match *$execution_mode {
-            ComponentExecutionMode::Remote => {
-                let local_client = $local_client
-                    .expect("Error: local client must be initialized in Remote execution mode.");
-                let config = $config
-                    .as_ref()
-                    .expect("Error: config must be initialized in Remote execution mode.");
-
-                Some(Box::new(RemoteComponentServer::new(local_client, config.clone())))
+            ComponentExecutionMode::LocalExecutionWithRemoteEnabled => {
+                let local_client = $local_client.expect(
+                    "Error: local client must be initialized in LocalExecutionWithRemoteEnabled \
+                     execution mode.",
+                );
+                let config = $config.as_ref().expect(
+                    "Error: config must be initialized in LocalExecutionWithRemoteEnabled \
+                     execution mode.",
+                );
+
+                let mut server = RemoteComponentServer::new(local_client, config.clone());
+                server.set_shutdown_signal($shutdown_signal);
+                Some(Box::new(server))
             }
             ComponentExecutionMode::LocalExecutionWithRemoteDisabled
-            | ComponentExecutionMode::LocalExecutionWithRemoteEnabled
+            | ComponentExecutionMode::Remote
             | ComponentExecutionMode::Disabled => None,
         }
     };
@@ -119,6 +146,7 @@ macro_rules! create_remote_server {
 /// * $component - The component that will be taken to initialize the server if the execution mode
 ///   is enabled(LocalExecutionWithRemoteDisabled / LocalExecutionWithRemoteEnabled).
 /// * $Receiver - receiver side for the server.
+/// * $shutdown_signal - The signal on which the server stops.
 ///
 /// # Returns
 ///
@@ -132,7 +160,8 @@ macro_rules! create_remote_server {
 /// let batcher_server = create_local_server!(
 ///     &config.components.batcher.execution_mode,
 ///     components.batcher,
-///     communication.take_batcher_rx()
+///     communication.take_batcher_rx(),
+///     shutdown.component_signal(NodeComponent::Batcher)
 /// );
 /// match batcher_server {
 ///     Some(server) => println!("Server created: {:?}", server),
@@ -140,16 +169,18 @@ macro_rules! create_remote_server {
 /// }
 /// ```
 macro_rules! create_local_server {
-    ($execution_mode:expr, $component:expr, $receiver:expr) => {
+    ($execution_mode:expr, $component:expr, $receiver:expr, $shutdown_signal:expr) => {
         match *$execution_mode {
             ComponentExecutionMode::LocalExecutionWithRemoteDisabled
             | ComponentExecutionMode::LocalExecutionWithRemoteEnabled => {
-                Some(Box::new(LocalComponentServer::new(
+                let mut server = LocalComponentServer::new(
                     $component
                         .take()
                         .expect(concat!(stringify!($component), " is not initialized.")),
                     $receiver,
-                )))
+                );
+                server.set_shutdown_signal($shutdown_signal);
+                Some(Box::new(server))
             }
             ComponentExecutionMode::Disabled | ComponentExecutionMode::Remote => None,
         }
@@ -208,25 +239,30 @@ fn create_local_servers(
     communication: &mut SequencerNodeCommunication,
     components: &mut SequencerNodeComponents,
 ) -> LocalServers {
+    let shutdown = &components.shutdown;
     let batcher_server = create_local_server!(
         &config.components.batcher.execution_mode,
         components.batcher,
-        communication.take_batcher_rx()
+        communication.take_batcher_rx(),
+        shutdown.component_signal(NodeComponent::Batcher)
     );
     let gateway_server = create_local_server!(
         &config.components.gateway.execution_mode,
         components.gateway,
-        communication.take_gateway_rx()
+        communication.take_gateway_rx(),
+        shutdown.component_signal(NodeComponent::Gateway)
     );
     let mempool_server = create_local_server!(
         &config.components.mempool.execution_mode,
         components.mempool,
-        communication.take_mempool_rx()
+        communication.take_mempool_rx(),
+        shutdown.component_signal(NodeComponent::Mempool)
     );
     let mempool_p2p_propagator_server = create_local_server!(
         &config.components.mempool_p2p.execution_mode,
         components.mempool_p2p_propagator,
-        communication.take_mempool_p2p_propagator_rx()
+        communication.take_mempool_p2p_propagator_rx(),
+        shutdown.component_signal(NodeComponent::MempoolP2pPropagator)
     );
     LocalServers {
         batcher: batcher_server,
@@ -239,33 +275,38 @@ fn create_local_servers(
 pub fn create_remote_servers(
     config: &SequencerNodeConfig,
     clients: &SequencerNodeClients,
+    shutdown: &NodeShutdown,
 ) -> RemoteServers {
     let batcher_client = clients.get_batcher_local_client();
     let batcher_server = create_remote_server!(
         &config.components.batcher.execution_mode,
         batcher_client,
-        config.components.batcher.remote_server_config
+        config.components.batcher.remote_server_config,
+        shutdown.component_signal(NodeComponent::RemoteBatcher)
     );
 
     let gateway_client = clients.get_gateway_local_client();
     let gateway_server = create_remote_server!(
         &config.components.gateway.execution_mode,
         gateway_client,
-        config.components.gateway.remote_server_config
+        config.components.gateway.remote_server_config,
+        shutdown.component_signal(NodeComponent::RemoteGateway)
     );
 
     let mempool_client = clients.get_mempool_local_client();
     let mempool_server = create_remote_server!(
         &config.components.mempool.execution_mode,
         mempool_client,
-        config.components.mempool.remote_server_config
+        config.components.mempool.remote_server_config,
+        shutdown.component_signal(NodeComponent::RemoteMempool)
     );
 
     let mempool_p2p_propagator_client = clients.get_mempool_p2p_propagator_local_client();
     let mempool_p2p_propagator_server = create_remote_server!(
         &config.components.mempool_p2p.execution_mode,
         mempool_p2p_propagator_client,
-        config.components.mempool_p2p.remote_server_config
+        config.components.mempool_p2p.remote_server_config,
+        shutdown.component_signal(NodeComponent::RemoteMempoolP2pPropagator)
     );
     RemoteServers {
         batcher: batcher_server,
@@ -309,87 +350,203 @@ pub fn create_node_servers(
     config: &SequencerNodeConfig,
     communication: &mut SequencerNodeCommunication,
     components: SequencerNodeComponents,
+    clients: &SequencerNodeClients,
 ) -> SequencerNodeServers {
     let mut components = components;
     let local_servers = create_local_servers(config, communication, &mut components);
+    let remote_servers = create_remote_servers(config, clients, &components.shutdown);
     let wrapper_servers = create_wrapper_servers(config, &mut components);
 
-    SequencerNodeServers { local_servers, wrapper_servers }
+    SequencerNodeServers {
+        local_servers,
+        remote_servers,
+        wrapper_servers,
+        mempool_p2p_peer_reports: components.mempool_p2p_peer_reports,
+        component_statuses: components.component_statuses,
+        shutdown: components.shutdown,
+    }
 }
 
+/// Runs the servers until one of them stops, or until the node shutdown is triggered, in which case
+/// the servers are stopped gracefully, in [`SHUTDOWN_ORDER`](crate::shutdown::SHUTDOWN_ORDER).
 pub async fn run_component_servers(servers: SequencerNodeServers) -> anyhow::Result<()> {
-    // Batcher server.
-    let batcher_future = get_server_future(servers.local_servers.batcher);
-
-    // Consensus Manager server.
-    let consensus_manager_future = get_server_future(servers.wrapper_servers.consensus_manager);
-
-    // Gateway server.
-    let gateway_future = get_server_future(servers.local_servers.gateway);
+    let SequencerNodeServers {
+        local_servers,
+        remote_servers,
+        wrapper_servers,
+        mempool_p2p_peer_reports,
+        component_statuses,
+        shutdown,
+    } = servers;
+
+    let server_futures = [
+        // Batcher server.
+        get_stoppable_server_future(
+            NodeComponent::Batcher,
+            local_servers.batcher,
+            &shutdown,
+            &component_statuses,
+        ),
+        // Consensus Manager server.
+        get_stoppable_server_future(
+            NodeComponent::ConsensusManager,
+            wrapper_servers.consensus_manager,
+            &shutdown,
+            &component_statuses,
+        ),
+        // Gateway server.
+        get_stoppable_server_future(
+            NodeComponent::Gateway,
+            local_servers.gateway,
+            &shutdown,
+            &component_statuses,
+        ),
+        // HttpServer server.
+        get_stoppable_server_future(
+            NodeComponent::HttpServer,
+            wrapper_servers.http_server,
+            &shutdown,
+            &component_statuses,
+        ),
+        // Mempool server.
+        get_stoppable_server_future(
+            NodeComponent::Mempool,
+            local_servers.mempool,
+            &shutdown,
+            &component_statuses,
+        ),
+        // Sequencer Monitoring server.
+        get_stoppable_server_future(
+            NodeComponent::MonitoringEndpoint,
+            wrapper_servers.monitoring_endpoint,
+            &shutdown,
+            &component_statuses,
+        ),
+        // MempoolP2pPropagator server.
+        get_stoppable_server_future(
+            NodeComponent::MempoolP2pPropagator,
+            local_servers.mempool_p2p_propagator,
+            &shutdown,
+            &component_statuses,
+        ),
+        // MempoolP2pRunner server.
+        get_stoppable_server_future(
+            NodeComponent::MempoolP2pRunner,
+            wrapper_servers.mempool_p2p_runner,
+            &shutdown,
+            &component_statuses,
+        ),
+        // Remote Batcher server.
+        get_stoppable_server_future(
+            NodeComponent::RemoteBatcher,
+            remote_servers.batcher,
+            &shutdown,
+            &component_statuses,
+        ),
+        // Remote Gateway server.
+        get_stoppable_server_future(
+            NodeComponent::RemoteGateway,
+            remote_servers.gateway,
+            &shutdown,
+            &component_statuses,
+        ),
+        // Remote Mempool server.
+        get_stoppable_server_future(
+            NodeComponent::RemoteMempool,
+            remote_servers.mempool,
+            &shutdown,
+            &component_statuses,
+        ),
+        // Remote MempoolP2pPropagator server.
+        get_stoppable_server_future(
+            NodeComponent::RemoteMempoolP2pPropagator,
+            remote_servers.mempool_p2p_propagator,
+            &shutdown,
+            &component_statuses,
+        ),
+    ];
 
-    // HttpServer server.
-    let http_server_future = get_server_future(servers.wrapper_servers.http_server);
+    // Start servers.
+    let (components, mut server_handles): (Vec<_>, Vec<_>) = server_futures
+        .into_iter()
+        .map(|(component, server_future)| (component, tokio::spawn(server_future)))
+        .unzip();
+    if let Some(peer_reports) = mempool_p2p_peer_reports {
+        tokio::spawn(log_peer_reports(peer_reports));
+    }
 
-    // Mempool server.
-    let mempool_future = get_server_future(servers.local_servers.mempool);
+    let mut node_shutdown_signal = shutdown.node_signal();
+    let stopped_server = select_all(server_handles.iter_mut());
+    let stopped_server = tokio::select! {
+        (result, index, _) = stopped_server => Some((components[index], result)),
+        _ = node_shutdown_signal.wait() => None,
+    };
 
-    // Sequencer Monitoring server.
-    let monitoring_endpoint_future = get_server_future(servers.wrapper_servers.monitoring_endpoint);
+    let Some((component, result)) = stopped_server else {
+        info!("Shutting down the node.");
+        shutdown.shutdown_components(components.into_iter().zip(server_handles).collect()).await;
+        return Ok(());
+    };
+    error!("{component:?} server stopped.");
+    let result = result?;
+    error!("Servers ended with unexpected Ok.");
 
-    // MempoolP2pPropagator server.
-    let mempool_p2p_propagator_future =
-        get_server_future(servers.local_servers.mempool_p2p_propagator);
+    Ok(result?)
+}
 
-    // MempoolP2pRunner server.
-    let mempool_p2p_runner_future = get_server_future(servers.wrapper_servers.mempool_p2p_runner);
+// Logs the peers reported by the mempool p2p runner, until the runner is dropped.
+async fn log_peer_reports(peer_reports: Receiver<PeerReportEvent>) {
+    peer_reports
+        .for_each(|PeerReportEvent { peer, reason, tx_hash }| async move {
+            info!("Mempool p2p reported peer {peer:?}: {reason:?}, transaction {tx_hash:?}.");
+        })
+        .await;
+}
 
-    // Start servers.
-    let batcher_handle = tokio::spawn(batcher_future);
-    let consensus_manager_handle = tokio::spawn(consensus_manager_future);
-    let gateway_handle = tokio::spawn(gateway_future);
-    let http_server_handle = tokio::spawn(http_server_future);
-    let mempool_handle = tokio::spawn(mempool_future);
-    let monitoring_endpoint_handle = tokio::spawn(monitoring_endpoint_future);
-    let mempool_p2p_propagator_handle = tokio::spawn(mempool_p2p_propagator_future);
-    let mempool_p2p_runner_handle = tokio::spawn(mempool_p2p_runner_future);
-
-    let result = tokio::select! {
-        res = batcher_handle => {
-            error!("Batcher Server stopped.");
-            res?
-        }
-        res = consensus_manager_handle => {
-            error!("Consensus Manager Server stopped.");
-            res?
-        }
-        res = gateway_handle => {
-            error!("Gateway Server stopped.");
-            res?
-        }
-        res = http_server_handle => {
-            error!("Http Server stopped.");
-            res?
-        }
-        res = mempool_handle => {
-            error!("Mempool Server stopped.");
-            res?
-        }
-        res = monitoring_endpoint_handle => {
-            error!("Monitoring Endpoint Server stopped.");
-            res?
-        }
-        res = mempool_p2p_propagator_handle => {
-            error!("Mempool P2P Propagator Server stopped.");
-            res?
-        }
-        res = mempool_p2p_runner_handle => {
-            error!("Mempool P2P Runner Server stopped.");
-            res?
+// Returns the server's future, which ends once the component's shutdown is triggered. The servers
+// of components that observe the shutdown themselves are left to finish their in-flight work.
+fn get_stoppable_server_future(
+    component: NodeComponent,
+    server: Option<Box<impl ComponentServerStarter + Send + 'static>>,
+    shutdown: &NodeShutdown,
+    component_statuses: &SharedComponentStatuses,
+) -> (NodeComponent, Pin<Box<dyn Future<Output = Result<(), ComponentServerError>> + Send>>) {
+    let runs_server = server.is_some();
+    let mut shutdown_signal = shutdown.component_signal(component);
+    let server_future = match server {
+        Some(server) if component.observes_shutdown_signal() => get_server_future(Some(server)),
+        server => {
+            let server_future = get_server_future(server);
+            async move {
+                tokio::select! {
+                    result = server_future => result,
+                    _ = shutdown_signal.wait() => Ok(()),
+                }
+            }
+            .boxed()
         }
     };
-    error!("Servers ended with unexpected Ok.");
+    let server_future = match runs_server {
+        true => with_status_updates(component, server_future, component_statuses.clone()),
+        false => server_future,
+    };
+    (component, server_future)
+}
 
-    Ok(result?)
+// Reports the component as up while its server runs, and as down once the server stops.
+fn with_status_updates(
+    component: NodeComponent,
+    server_future: Pin<Box<dyn Future<Output = Result<(), ComponentServerError>> + Send>>,
+    component_statuses: SharedComponentStatuses,
+) -> Pin<Box<dyn Future<Output = Result<(), ComponentServerError>> + Send>> {
+    let status_name = component.status_name();
+    async move {
+        component_statuses.set(status_name, ComponentStatus::Up);
+        let result = server_future.await;
+        component_statuses.set(status_name, ComponentStatus::Down);
+        result
+    }
+    .boxed()
 }
 
 pub fn get_server_future(
@@ -0,0 +1,118 @@
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use starknet_api::block::BlockNumber;
+use starknet_batcher_types::batcher_types::StartHeightInput;
+use starknet_batcher_types::communication::{BatcherRequest, BatcherResponse};
+use starknet_monitoring_endpoint::monitoring_endpoint::{
+    ComponentStatus,
+    ComponentStatuses,
+    SharedComponentStatuses,
+};
+use starknet_sequencer_infra::component_definitions::{
+    ComponentRequestAndResponseSender,
+    LocalServerConfig,
+    RemoteClientConfig,
+    RemoteServerConfig,
+};
+use starknet_sequencer_infra::component_server::ComponentServerStarter;
+use starknet_sequencer_infra::errors::ComponentServerError;
+use starknet_sequencer_infra::test_utils::get_available_socket;
+use tokio::sync::mpsc::channel;
+use tokio::sync::Notify;
+use tokio::task;
+
+use crate::clients::create_node_clients;
+use crate::communication::create_node_channels;
+use crate::config::component_execution_config::{ComponentExecutionConfig, ComponentExecutionMode};
+use crate::config::node_config::SequencerNodeConfig;
+use crate::servers::{create_remote_servers, get_server_future, get_stoppable_server_future};
+use crate::shutdown::{NodeComponent, NodeShutdown};
+
+#[tokio::test]
+async fn remote_batcher_client_routes_requests_over_the_wire() {
+    const HEIGHT: BlockNumber = BlockNumber(5);
+    let socket = get_available_socket().await;
+
+    // The node that runs the batcher exposes it through a remote server.
+    let mut server_node_config = SequencerNodeConfig::default();
+    server_node_config.components.batcher = ComponentExecutionConfig {
+        execution_mode: ComponentExecutionMode::LocalExecutionWithRemoteEnabled,
+        local_server_config: Some(LocalServerConfig::default()),
+        remote_client_config: None,
+        remote_server_config: Some(RemoteServerConfig { socket }),
+    };
+    let mut server_node_channels = create_node_channels();
+    let server_node_clients = create_node_clients(&server_node_config, &mut server_node_channels);
+    let shutdown = NodeShutdown::new();
+    let remote_server =
+        create_remote_servers(&server_node_config, &server_node_clients, &shutdown).batcher;
+    assert!(remote_server.is_some(), "Remote batcher server should be created.");
+    task::spawn(get_server_future(remote_server));
+
+    // A loopback mock of the batcher, answering the requests forwarded by the remote server.
+    let mut batcher_rx = server_node_channels.take_batcher_rx();
+    let (received_heights_tx, mut received_heights_rx) = channel(1);
+    task::spawn(async move {
+        while let Some(ComponentRequestAndResponseSender { request, tx }) = batcher_rx.recv().await
+        {
+            let BatcherRequest::StartHeight(StartHeightInput { height }) = request else {
+                panic!("Unexpected request: {request:?}.");
+            };
+            received_heights_tx.send(height).await.unwrap();
+            tx.send(BatcherResponse::StartHeight(Ok(()))).await.unwrap();
+        }
+    });
+    // Ensure the servers start running.
+    task::yield_now().await;
+
+    // The node that only reaches the batcher remotely.
+    let mut client_node_config = SequencerNodeConfig::default();
+    client_node_config.components.batcher = ComponentExecutionConfig {
+        execution_mode: ComponentExecutionMode::Remote,
+        local_server_config: None,
+        remote_client_config: Some(RemoteClientConfig { socket, ..Default::default() }),
+        remote_server_config: None,
+    };
+    let client_node_clients = create_node_clients(&client_node_config, &mut create_node_channels());
+    assert!(client_node_clients.get_batcher_local_client().is_none());
+    let batcher_client =
+        client_node_clients.get_batcher_shared_client().expect("Batcher client should be created.");
+
+    batcher_client.start_height(StartHeightInput { height: HEIGHT }).await.unwrap();
+    assert_eq!(received_heights_rx.recv().await, Some(HEIGHT));
+}
+
+// A server that runs until it is told to stop.
+struct StoppableServer {
+    stop: Arc<Notify>,
+}
+
+#[async_trait]
+impl ComponentServerStarter for StoppableServer {
+    async fn start(&mut self) -> Result<(), ComponentServerError> {
+        self.stop.notified().await;
+        Err(ComponentServerError::ServerUnexpectedlyStopped)
+    }
+}
+
+#[tokio::test]
+async fn component_status_follows_its_server() {
+    let component_statuses =
+        SharedComponentStatuses::new(ComponentStatuses::from([("batcher", ComponentStatus::Down)]));
+    let stop = Arc::new(Notify::new());
+    let (_, server_future) = get_stoppable_server_future(
+        NodeComponent::Batcher,
+        Some(Box::new(StoppableServer { stop: stop.clone() })),
+        &NodeShutdown::new(),
+        &component_statuses,
+    );
+    let server = task::spawn(server_future);
+    // Ensure the server starts running.
+    task::yield_now().await;
+    assert_eq!(component_statuses.get()["batcher"], ComponentStatus::Up);
+
+    stop.notify_one();
+    assert!(server.await.unwrap().is_err());
+    assert_eq!(component_statuses.get()["batcher"], ComponentStatus::Down);
+}
@@ -0,0 +1,155 @@
+#[cfg(test)]
+#[path = "shutdown_test.rs"]
+mod shutdown_test;
+
+use std::collections::BTreeMap;
+use std::time::Duration;
+
+use starknet_sequencer_infra::errors::ComponentServerError;
+use starknet_sequencer_infra::shutdown::{ShutdownHandle, ShutdownSignal};
+use tokio::task::JoinHandle;
+use tokio::time::timeout;
+use tracing::{error, info};
+
+/// A component of the node that is stopped on shutdown.
+#[derive(Clone, Copy, Debug, Eq, Ord, PartialEq, PartialOrd)]
+pub enum NodeComponent {
+    Batcher,
+    ConsensusManager,
+    Gateway,
+    HttpServer,
+    Mempool,
+    MempoolP2pPropagator,
+    MempoolP2pRunner,
+    MonitoringEndpoint,
+    RemoteBatcher,
+    RemoteGateway,
+    RemoteMempool,
+    RemoteMempoolP2pPropagator,
+}
+
+impl NodeComponent {
+    /// Whether the component stops on its own once its shutdown is triggered, after finishing its
+    /// in-flight work: the local and remote servers drain the requests they already received. The
+    /// servers of the other components are stopped by the node.
+    pub fn observes_shutdown_signal(&self) -> bool {
+        !matches!(
+            self,
+            NodeComponent::ConsensusManager
+                | NodeComponent::HttpServer
+                | NodeComponent::MonitoringEndpoint
+        )
+    }
+
+    /// The name under which the component's status is reported. The remote servers report the
+    /// status of the component they expose.
+    pub fn status_name(&self) -> &'static str {
+        match self {
+            NodeComponent::Batcher | NodeComponent::RemoteBatcher => "batcher",
+            NodeComponent::ConsensusManager => "consensus_manager",
+            NodeComponent::Gateway | NodeComponent::RemoteGateway => "gateway",
+            NodeComponent::HttpServer => "http_server",
+            NodeComponent::Mempool | NodeComponent::RemoteMempool => "mempool",
+            NodeComponent::MempoolP2pPropagator
+            | NodeComponent::MempoolP2pRunner
+            | NodeComponent::RemoteMempoolP2pPropagator => "mempool_p2p",
+            NodeComponent::MonitoringEndpoint => "monitoring_endpoint",
+        }
+    }
+}
+
+/// The order in which the components are stopped. The components that receive work from outside
+/// the node, including the remote servers, are stopped first, so no new work reaches the components
+/// after them. A component is stopped before the components it calls, e.g., the mempool forwards
+/// transactions to the propagator while it drains. The batcher, which writes to storage, is stopped
+/// last.
+pub const SHUTDOWN_ORDER: [NodeComponent; 12] = [
+    NodeComponent::HttpServer,
+    NodeComponent::MonitoringEndpoint,
+    NodeComponent::MempoolP2pRunner,
+    NodeComponent::RemoteGateway,
+    NodeComponent::RemoteMempoolP2pPropagator,
+    NodeComponent::RemoteMempool,
+    NodeComponent::RemoteBatcher,
+    NodeComponent::ConsensusManager,
+    NodeComponent::Gateway,
+    NodeComponent::Mempool,
+    NodeComponent::MempoolP2pPropagator,
+    NodeComponent::Batcher,
+];
+
+pub type ServerHandle = JoinHandle<Result<(), ComponentServerError>>;
+
+/// The time a component is given to stop before its server is aborted.
+pub const DEFAULT_COMPONENT_SHUTDOWN_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Coordinates the graceful shutdown of the node: a single node-wide trigger stops the components
+/// one by one, in [`SHUTDOWN_ORDER`].
+#[derive(Clone, Debug)]
+pub struct NodeShutdown {
+    node_handle: ShutdownHandle,
+    component_handles: BTreeMap<NodeComponent, ShutdownHandle>,
+    component_shutdown_timeout: Duration,
+}
+
+impl NodeShutdown {
+    pub fn new() -> Self {
+        Self {
+            node_handle: ShutdownHandle::new(),
+            component_handles: SHUTDOWN_ORDER
+                .into_iter()
+                .map(|component| (component, ShutdownHandle::new()))
+                .collect(),
+            component_shutdown_timeout: DEFAULT_COMPONENT_SHUTDOWN_TIMEOUT,
+        }
+    }
+
+    pub fn set_component_shutdown_timeout(&mut self, component_shutdown_timeout: Duration) {
+        self.component_shutdown_timeout = component_shutdown_timeout;
+    }
+
+    /// Returns the handle that triggers the shutdown of the whole node.
+    pub fn node_handle(&self) -> ShutdownHandle {
+        self.node_handle.clone()
+    }
+
+    pub fn node_signal(&self) -> ShutdownSignal {
+        self.node_handle.subscribe()
+    }
+
+    /// Returns the signal on which the given component should stop.
+    pub fn component_signal(&self, component: NodeComponent) -> ShutdownSignal {
+        self.component_handles[&component].subscribe()
+    }
+
+    /// Stops the components in [`SHUTDOWN_ORDER`]: triggers the shutdown of each component, and
+    /// waits for its server to stop before moving on to the next one. The servers are expected to
+    /// stop once their component's signal is triggered; a server that does not stop within the
+    /// component shutdown timeout is aborted.
+    pub async fn shutdown_components(&self, mut servers: BTreeMap<NodeComponent, ServerHandle>) {
+        for component in SHUTDOWN_ORDER {
+            self.component_handles[&component].trigger();
+            let Some(mut server) = servers.remove(&component) else {
+                continue;
+            };
+            match timeout(self.component_shutdown_timeout, &mut server).await {
+                Ok(Ok(Ok(()))) => info!("{component:?} server stopped."),
+                Ok(Ok(Err(err))) => error!("{component:?} server stopped with an error: {err:?}."),
+                Ok(Err(err)) => error!("{component:?} server task failed: {err}."),
+                Err(_) => {
+                    error!(
+                        "{component:?} server did not stop within {:?}, aborting it.",
+                        self.component_shutdown_timeout
+                    );
+                    server.abort();
+                }
+            }
+        }
+    }
+}
+
+impl Default for NodeShutdown {
+    fn default() -> Self {
+        Self::new()
+    }
+}
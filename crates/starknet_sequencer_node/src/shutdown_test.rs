@@ -0,0 +1,84 @@
+use std::collections::BTreeMap;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use tokio::sync::oneshot;
+use tokio::time::timeout;
+
+use crate::shutdown::{NodeComponent, NodeShutdown, SHUTDOWN_ORDER};
+
+#[tokio::test]
+async fn components_are_stopped_in_shutdown_order() {
+    let shutdown = NodeShutdown::new();
+    let stopped_components = Arc::new(Mutex::new(Vec::new()));
+
+    // Mock servers that record when they receive their stop signal.
+    let servers: BTreeMap<_, _> = SHUTDOWN_ORDER
+        .into_iter()
+        .rev()
+        .map(|component| {
+            let mut signal = shutdown.component_signal(component);
+            let stopped_components = stopped_components.clone();
+            let server = tokio::spawn(async move {
+                signal.wait().await;
+                stopped_components.lock().unwrap().push(component);
+                Ok(())
+            });
+            (component, server)
+        })
+        .collect();
+
+    timeout(Duration::from_secs(5), shutdown.shutdown_components(servers))
+        .await
+        .expect("Shutdown timed out.");
+
+    assert_eq!(*stopped_components.lock().unwrap(), SHUTDOWN_ORDER);
+}
+
+#[test]
+fn components_are_stopped_before_the_components_they_call() {
+    let position = |component| SHUTDOWN_ORDER.iter().position(|c| *c == component).unwrap();
+    for (caller, callee) in [
+        (NodeComponent::Gateway, NodeComponent::Mempool),
+        (NodeComponent::Mempool, NodeComponent::MempoolP2pPropagator),
+        (NodeComponent::ConsensusManager, NodeComponent::Batcher),
+    ] {
+        assert!(position(caller) < position(callee), "{caller:?} should stop before {callee:?}.");
+    }
+}
+
+#[tokio::test]
+async fn component_is_not_stopped_before_its_predecessors() {
+    let shutdown = NodeShutdown::new();
+    let mut batcher_signal = shutdown.component_signal(NodeComponent::Batcher);
+    // The http server is the first to stop, and it never does.
+    let servers =
+        BTreeMap::from([(NodeComponent::HttpServer, tokio::spawn(std::future::pending()))]);
+
+    let result = timeout(Duration::from_millis(100), shutdown.shutdown_components(servers)).await;
+
+    assert!(result.is_err());
+    assert!(timeout(Duration::from_millis(100), batcher_signal.wait()).await.is_err());
+}
+
+#[tokio::test]
+async fn component_that_does_not_stop_is_aborted() {
+    let mut shutdown = NodeShutdown::new();
+    shutdown.set_component_shutdown_timeout(Duration::from_millis(100));
+    let mut batcher_signal = shutdown.component_signal(NodeComponent::Batcher);
+    // The http server is the first to stop, and it never does. The receiver is notified once the
+    // server is dropped.
+    let (http_server_alive_sender, http_server_alive_receiver) = oneshot::channel::<()>();
+    let http_server = tokio::spawn(async move {
+        let _http_server_alive_sender = http_server_alive_sender;
+        std::future::pending().await
+    });
+    let servers = BTreeMap::from([(NodeComponent::HttpServer, http_server)]);
+
+    timeout(Duration::from_secs(5), shutdown.shutdown_components(servers))
+        .await
+        .expect("Shutdown timed out.");
+
+    assert!(timeout(Duration::from_millis(100), http_server_alive_receiver).await.is_ok());
+    timeout(Duration::from_millis(100), batcher_signal.wait()).await.unwrap();
+}
@@ -1,30 +1,105 @@
-use std::env;
-use std::path::PathBuf;
+#[cfg(test)]
+#[path = "utils_test.rs"]
+mod utils_test;
+
+use std::path::{Path, PathBuf};
+use std::sync::LazyLock;
+use std::{env, fs, io};
 
 use crate::clients::{create_node_clients, SequencerNodeClients};
 use crate::communication::create_node_channels;
-use crate::components::create_node_components;
+use crate::components::{create_node_components, log_components_summary, ComponentConfigError};
 use crate::config::node_config::SequencerNodeConfig;
 use crate::servers::{create_node_servers, SequencerNodeServers};
 
 pub fn create_node_modules(
     config: &SequencerNodeConfig,
-) -> (SequencerNodeClients, SequencerNodeServers) {
+) -> Result<(SequencerNodeClients, SequencerNodeServers), ComponentConfigError> {
     let mut channels = create_node_channels();
     let clients = create_node_clients(config, &mut channels);
-    let components = create_node_components(config, &clients);
-    let servers = create_node_servers(config, &mut channels, components);
+    let components = create_node_components(config, &clients)?;
+    log_components_summary(config);
+    let servers = create_node_servers(config, &mut channels, components, &clients);
 
-    (clients, servers)
+    Ok((clients, servers))
+}
+
+/// The root of the cargo workspace, resolved once from `CARGO_MANIFEST_DIR`.
+static WORKSPACE_ROOT: LazyLock<Option<PathBuf>> = LazyLock::new(|| {
+    let manifest_dir = env::var("CARGO_MANIFEST_DIR").ok()?;
+    find_workspace_root(Path::new(&manifest_dir))
+});
+
+/// An environment variable that sets the project root when `CARGO_MANIFEST_DIR` isn't set, e.g., in
+/// deployed images that don't ship the project's `Cargo.lock`.
+pub const PROJECT_ROOT_ENV_VAR: &str = "SEQUENCER_PROJECT_ROOT";
+
+/// A file that only exists at the project root, used to find it when neither `CARGO_MANIFEST_DIR`
+/// nor [`PROJECT_ROOT_ENV_VAR`] is set.
+const PROJECT_ROOT_MARKER: &str = "Cargo.lock";
+
+#[derive(Debug, thiserror::Error)]
+pub enum PathResolutionError {
+    #[error("Failed to get the current working directory: {0}")]
+    CurrentDir(#[from] io::Error),
+    #[error(
+        "Neither CARGO_MANIFEST_DIR nor {PROJECT_ROOT_ENV_VAR} is set and no ancestor of \
+         {current_dir:?} contains {PROJECT_ROOT_MARKER}."
+    )]
+    ProjectRootNotFound { current_dir: PathBuf },
 }
 
 // TODO(Tsabary): consolidate with other get_absolute_path functions.
-/// Returns the absolute path from the project root.
+/// Returns the absolute path from the project root, or panics if the project root can't be
+/// determined. See [`try_get_absolute_path`].
 pub fn get_absolute_path(relative_path: &str) -> PathBuf {
-    let base_dir = env::var("CARGO_MANIFEST_DIR")
-        // Attempt to get the `CARGO_MANIFEST_DIR` environment variable and convert it to `PathBuf`. Ascend two directories ("../..") to get to the project root.
-        .map(|dir| PathBuf::from(dir).join("../.."))
-        // If `CARGO_MANIFEST_DIR` isn't set, fall back to the current working directory
-        .unwrap_or_else(|_| env::current_dir().expect("Failed to get current directory"));
-    base_dir.join(relative_path)
+    try_get_absolute_path(relative_path)
+        .unwrap_or_else(|err| panic!("Failed to resolve the path {relative_path:?}: {err}"))
+}
+
+/// Returns the absolute path from the project root. The project root is the [`workspace_root`] if
+/// it is known, then the value of [`PROJECT_ROOT_ENV_VAR`] if it is set, and otherwise the closest
+/// ancestor of the current working directory that contains the project's `Cargo.lock`.
+pub fn try_get_absolute_path(relative_path: &str) -> Result<PathBuf, PathResolutionError> {
+    let project_root = match workspace_root() {
+        Some(workspace_root) => workspace_root.to_path_buf(),
+        None => find_project_root(
+            env::var_os(PROJECT_ROOT_ENV_VAR).map(PathBuf::from),
+            &env::current_dir()?,
+        )?,
+    };
+    Ok(project_root.join(relative_path))
+}
+
+/// Returns the root of the cargo workspace, i.e., the closest ancestor of `CARGO_MANIFEST_DIR`
+/// whose `Cargo.toml` has a `[workspace]` section. Returns `None` if `CARGO_MANIFEST_DIR` isn't set
+/// or no such manifest exists.
+pub fn workspace_root() -> Option<&'static Path> {
+    WORKSPACE_ROOT.as_deref()
+}
+
+fn find_workspace_root(manifest_dir: &Path) -> Option<PathBuf> {
+    manifest_dir
+        .ancestors()
+        .find(|dir| {
+            fs::read_to_string(dir.join("Cargo.toml"))
+                .is_ok_and(|manifest| manifest.lines().any(|line| line.trim() == "[workspace]"))
+        })
+        .map(Path::to_path_buf)
+}
+
+fn find_project_root(
+    project_root_override: Option<PathBuf>,
+    current_dir: &Path,
+) -> Result<PathBuf, PathResolutionError> {
+    if let Some(project_root) = project_root_override {
+        return Ok(project_root);
+    }
+    current_dir
+        .ancestors()
+        .find(|dir| dir.join(PROJECT_ROOT_MARKER).is_file())
+        .map(Path::to_path_buf)
+        .ok_or_else(|| PathResolutionError::ProjectRootNotFound {
+            current_dir: current_dir.to_path_buf(),
+        })
 }
@@ -0,0 +1,80 @@
+use std::env;
+use std::fs::{self, create_dir_all, File};
+use std::path::PathBuf;
+
+use assert_matches::assert_matches;
+use tempfile::tempdir;
+
+use crate::utils::{
+    find_project_root,
+    find_workspace_root,
+    try_get_absolute_path,
+    workspace_root,
+    PathResolutionError,
+};
+
+#[test]
+fn absolute_path_with_manifest_dir() {
+    // Cargo sets `CARGO_MANIFEST_DIR` when running the tests.
+    let manifest_dir = PathBuf::from(env::var("CARGO_MANIFEST_DIR").unwrap());
+    let expected_workspace_root = manifest_dir.join("../..").canonicalize().unwrap();
+    assert_eq!(workspace_root().unwrap().canonicalize().unwrap(), expected_workspace_root);
+    assert_eq!(
+        try_get_absolute_path("config").unwrap().canonicalize().unwrap(),
+        expected_workspace_root.join("config")
+    );
+}
+
+#[test]
+fn workspace_root_at_any_crate_depth() {
+    let workspace_root = tempdir().unwrap();
+    fs::write(workspace_root.path().join("Cargo.toml"), "[workspace]\nmembers = [\"crates/*\"]\n")
+        .unwrap();
+
+    for crate_dir in ["shallow_crate", "crates/some_crate", "crates/group/nested_crate"] {
+        let manifest_dir = workspace_root.path().join(crate_dir);
+        create_dir_all(&manifest_dir).unwrap();
+        fs::write(manifest_dir.join("Cargo.toml"), "[package]\nname = \"some_crate\"\n").unwrap();
+
+        assert_eq!(find_workspace_root(&manifest_dir).unwrap(), workspace_root.path());
+    }
+    assert_eq!(find_workspace_root(workspace_root.path()).unwrap(), workspace_root.path());
+}
+
+#[test]
+fn workspace_root_not_found() {
+    let dir = tempdir().unwrap();
+    fs::write(dir.path().join("Cargo.toml"), "[package]\nname = \"some_crate\"\n").unwrap();
+    assert_eq!(find_workspace_root(dir.path()), None);
+}
+
+#[test]
+fn project_root_without_manifest_dir() {
+    let project_root = tempdir().unwrap();
+    File::create(project_root.path().join("Cargo.lock")).unwrap();
+    let nested_dir = project_root.path().join("crates/some_crate/src");
+    create_dir_all(&nested_dir).unwrap();
+
+    assert_eq!(find_project_root(None, &nested_dir).unwrap(), project_root.path());
+    assert_eq!(find_project_root(None, project_root.path()).unwrap(), project_root.path());
+}
+
+#[test]
+fn project_root_not_found_without_manifest_dir() {
+    let dir = tempdir().unwrap();
+    assert_matches!(
+        find_project_root(None, dir.path()),
+        Err(PathResolutionError::ProjectRootNotFound { current_dir }) if current_dir == dir.path()
+    );
+}
+
+#[test]
+fn project_root_from_override_without_marker() {
+    // Deployed images don't ship the project's `Cargo.lock`.
+    let project_root = tempdir().unwrap();
+    let working_dir = tempdir().unwrap();
+    assert_eq!(
+        find_project_root(Some(project_root.path().to_path_buf()), working_dir.path()).unwrap(),
+        project_root.path()
+    );
+}